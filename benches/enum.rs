@@ -0,0 +1,28 @@
+use boon::{Compiler, Schemas};
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde_json::{json, Value};
+
+// code-list style schema, such as country or currency codes.
+pub fn code_list(c: &mut Criterion) {
+    let codes: Vec<Value> = (0..500).map(|i| json!(format!("CODE-{i:04}"))).collect();
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler
+        .add_resource(
+            "http://tmp.com/codes.json",
+            json!({"items": {"enum": codes}}),
+        )
+        .unwrap();
+    let sch = compiler
+        .compile("http://tmp.com/codes.json", &mut schemas)
+        .unwrap();
+    let inst: Value = (0..100)
+        .map(|i| json!(format!("CODE-{:04}", i * 5)))
+        .collect();
+    c.bench_function("enum/code-list", |b| {
+        b.iter(|| schemas.validate(&inst, sch).unwrap())
+    });
+}
+
+criterion_group!(benches, code_list);
+criterion_main!(benches);
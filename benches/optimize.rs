@@ -0,0 +1,62 @@
+use boon::{Compiler, OptimizationLevel, Schemas};
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde_json::{json, Value};
+
+// hand made schema, with expensive subschema listed before a cheap
+// one, inside `not`. it shows best case of `Reorder`, not typical one.
+pub fn reorder(c: &mut Criterion) {
+    let multiples: Vec<Value> = (2..30).map(|i| json!({"multipleOf": i * 7})).collect();
+    let schema = json!({
+        "items": {
+            "not": {
+                "allOf": [
+                    {"anyOf": multiples},
+                    {"type": "string"}
+                ]
+            }
+        }
+    });
+    let inst: Value = (0..100).map(|i| json!(i * 11 + 1)).collect();
+    for (name, level) in [
+        ("optimize/none", OptimizationLevel::None),
+        ("optimize/reorder", OptimizationLevel::Reorder),
+        ("optimize/fold", OptimizationLevel::Fold),
+    ] {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        compiler.set_optimization_level(level);
+        compiler
+            .add_resource("http://tmp.com/schema.json", schema.clone())
+            .unwrap();
+        let sch = compiler
+            .compile("http://tmp.com/schema.json", &mut schemas)
+            .unwrap();
+        c.bench_function(name, |b| b.iter(|| schemas.validate(&inst, sch).unwrap()));
+    }
+}
+
+// small `$ref` target, applied to each item.
+pub fn inline(c: &mut Criterion) {
+    let schema = json!({
+        "$defs": {"pos": {"type": "integer", "minimum": 1}},
+        "items": {"$ref": "#/$defs/pos"}
+    });
+    let inst: Value = (1..100).collect();
+    for (name, inline) in [("inline/none", false), ("inline/small", true)] {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        if inline {
+            compiler.enable_ref_inlining(4);
+        }
+        compiler
+            .add_resource("http://tmp.com/schema.json", schema.clone())
+            .unwrap();
+        let sch = compiler
+            .compile("http://tmp.com/schema.json", &mut schemas)
+            .unwrap();
+        c.bench_function(name, |b| b.iter(|| schemas.validate(&inst, sch).unwrap()));
+    }
+}
+
+criterion_group!(benches, reorder, inline);
+criterion_main!(benches);
@@ -0,0 +1,33 @@
+use boon::{Compiler, Schemas};
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde_json::{json, Map, Value};
+
+// map-heavy schema, such as labels or headers keyed by name.
+pub fn labels(c: &mut Criterion) {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler
+        .add_resource(
+            "http://tmp.com/labels.json",
+            json!({
+                "items": {
+                    "propertyNames": {"pattern": "^[a-z][a-z0-9-]*$", "maxLength": 63},
+                    "additionalProperties": {"type": "string"}
+                }
+            }),
+        )
+        .unwrap();
+    let sch = compiler
+        .compile("http://tmp.com/labels.json", &mut schemas)
+        .unwrap();
+    let labels: Map<String, Value> = (0..50)
+        .map(|i| (format!("label-{i}"), json!("value")))
+        .collect();
+    let inst = Value::Array(vec![Value::Object(labels); 100]);
+    c.bench_function("propertyNames/labels", |b| {
+        b.iter(|| schemas.validate(&inst, sch).unwrap())
+    });
+}
+
+criterion_group!(benches, labels);
+criterion_main!(benches);
@@ -0,0 +1,52 @@
+use std::{collections::BTreeMap, error::Error, sync::Arc};
+
+use boon::UrlLoader;
+use serde_json::Value;
+
+// values given with --define, by name
+pub type Defines = Arc<BTreeMap<String, Value>>;
+
+// parses `NAME=VALUE` of --define. VALUE is json, if it parses
+// as json, otherwise string. so `max=10` is number, but `env=prod`
+// is string.
+pub fn parse(s: &str) -> Option<(String, Value)> {
+    let (name, value) = s.split_once('=')?;
+    if name.is_empty() {
+        return None;
+    }
+    let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_owned()));
+    Some((name.to_owned(), value))
+}
+
+// loads schema documents using `inner`, replacing placeholders
+// `{"x-boon-define": "NAME"}` with the value of NAME. the keyword is
+// namespaced, so that it is not mistaken for literal value in schema
+pub struct DefineLoader {
+    pub inner: Box<dyn UrlLoader>,
+    pub defines: Defines,
+}
+
+impl UrlLoader for DefineLoader {
+    fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+        let mut doc = self.inner.load(url)?;
+        substitute(&mut doc, &self.defines)?;
+        Ok(doc)
+    }
+}
+
+fn substitute(v: &mut Value, defines: &BTreeMap<String, Value>) -> Result<(), String> {
+    match v {
+        Value::Object(obj) => {
+            if let (1, Some(Value::String(name))) = (obj.len(), obj.get("x-boon-define")) {
+                let Some(value) = defines.get(name) else {
+                    return Err(format!("{name} is not defined, use --define {name}=VALUE"));
+                };
+                *v = value.clone();
+                return Ok(());
+            }
+            obj.values_mut().try_for_each(|v| substitute(v, defines))
+        }
+        Value::Array(arr) => arr.iter_mut().try_for_each(|v| substitute(v, defines)),
+        _ => Ok(()),
+    }
+}
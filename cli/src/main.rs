@@ -1,13 +1,36 @@
 use core::panic;
-use std::{env, error::Error, fs::File, io::BufReader, process, str::FromStr, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashSet},
+    env,
+    error::Error,
+    fs::{self, File},
+    io::BufReader,
+    path::Path,
+    process,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
 
-use boon::{Compiler, Draft, Schemas, SchemeUrlLoader, UrlLoader};
+use boon::{
+    Compiler, DocFormat, Draft, KeywordEval, KeywordHook, RetryLoader, SchemaIndex, Schemas,
+    SchemeUrlLoader, UrlLoader, ValidationError, ValidationOptions,
+};
 use getopts::Options;
 use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::Value;
 use ureq::Agent;
 use url::Url;
 
+use crate::{
+    define::{DefineLoader, Defines},
+    report::{Failure, Report},
+};
+
+mod define;
+mod render;
+mod report;
+
 fn main() {
     let opts = options();
     let matches = match opts.parse(env::args().skip(1)) {
@@ -25,34 +48,59 @@ fn main() {
         process::exit(0);
     }
 
+    // introspection --
+    let lists = [
+        "list-drafts",
+        "list-formats",
+        "list-encodings",
+        "list-media-types",
+    ];
+    if lists.iter().any(|name| matches.opt_present(name)) {
+        let compiler = Compiler::new();
+        if matches.opt_present("list-drafts") {
+            for d in Draft::all() {
+                let default = if d == Draft::default() {
+                    " (default)"
+                } else {
+                    ""
+                };
+                println!("{:<4}  {}{default}", d.version(), d.url());
+            }
+        }
+        if matches.opt_present("list-formats") {
+            compiler.formats().iter().for_each(|f| println!("{f}"));
+        }
+        if matches.opt_present("list-encodings") {
+            compiler
+                .content_encodings()
+                .iter()
+                .for_each(|e| println!("{e}"));
+        }
+        if matches.opt_present("list-media-types") {
+            compiler
+                .content_media_types()
+                .iter()
+                .for_each(|m| println!("{m}"));
+        }
+        process::exit(0);
+    }
+
     // draft --
     let mut draft = Draft::default();
     if let Some(v) = matches.opt_str("draft") {
-        let Ok(v) = usize::from_str(&v) else {
+        let Some(d) = usize::from_str(&v).ok().and_then(parse_draft) else {
             eprintln!("invalid draft: {v}");
             eprintln!();
             eprintln!("{}", opts.usage(BRIEF));
             process::exit(1);
         };
-        draft = match v {
-            4 => Draft::V4,
-            6 => Draft::V6,
-            7 => Draft::V7,
-            2019 => Draft::V2019_09,
-            2020 => Draft::V2020_12,
-            _ => {
-                eprintln!("invalid draft: {v}");
-                eprintln!();
-                eprintln!("{}", opts.usage(BRIEF));
-                process::exit(1);
-            }
-        };
+        draft = d;
     }
 
     // output --
     let output = matches.opt_str("output");
     if let Some(o) = &output {
-        if !matches!(o.as_str(), "simple" | "alt" | "flag" | "basic" | "detailed") {
+        if !OUTPUTS.contains(&o.as_str()) {
             eprintln!("invalid output: {o}");
             eprintln!();
             eprintln!("{}", opts.usage(BRIEF));
@@ -60,11 +108,109 @@ fn main() {
         }
     }
 
+    // doc --
+    let doc_format = match matches.opt_str("doc").as_deref() {
+        None => None,
+        Some("markdown") => Some(DocFormat::Markdown),
+        Some("html") => Some(DocFormat::Html),
+        Some(f) => {
+            eprintln!("invalid doc format: {f}");
+            eprintln!();
+            eprintln!("{}", opts.usage(BRIEF));
+            process::exit(1);
+        }
+    };
+
+    // report --
+    let mut targets = vec![];
+    for v in matches.opt_strs("report") {
+        let target = v
+            .split_once('=')
+            .filter(|(_, file)| !file.is_empty())
+            .and_then(|(format, file)| Some((report::Format::parse(format)?, file.to_owned())));
+        let Some(target) = target else {
+            eprintln!("invalid report: {v}");
+            eprintln!();
+            eprintln!("{}", opts.usage(BRIEF));
+            process::exit(1);
+        };
+        targets.push(target);
+    }
+    if !targets.is_empty() && doc_format.is_some() {
+        eprintln!("--report not allowed with --doc");
+        eprintln!();
+        eprintln!("{}", opts.usage(BRIEF));
+        process::exit(1);
+    }
+    let mut report = Report::new(targets);
+
+    // define --
+    let mut defines = BTreeMap::new();
+    for v in matches.opt_strs("define") {
+        let Some((name, value)) = define::parse(&v) else {
+            eprintln!("invalid define: {v}");
+            eprintln!();
+            eprintln!("{}", opts.usage(BRIEF));
+            process::exit(1);
+        };
+        defines.insert(name, value);
+    }
+    let defines = Arc::new(defines);
+
+    // retries --
+    let mut retries = 3;
+    if let Some(v) = matches.opt_str("retries") {
+        let Ok(v) = usize::from_str(&v) else {
+            eprintln!("invalid retries: {v}");
+            eprintln!();
+            eprintln!("{}", opts.usage(BRIEF));
+            process::exit(1);
+        };
+        retries = v;
+    }
+
     // flags --
     let quiet = matches.opt_present("quiet");
-    let assert_format = matches.opt_present("assert-format");
-    let assert_content = matches.opt_present("assert-content");
+    let settings = Settings {
+        draft,
+        output,
+        assert_format: matches.opt_present("assert-format"),
+        assert_content: matches.opt_present("assert-content"),
+        deny_unknown: matches.opt_present("deny-unknown-keywords"),
+        deny_unknown_formats: matches.opt_present("deny-unknown-formats"),
+    };
+    let output = settings.output.as_deref();
     let insecure = matches.opt_present("insecure");
+    let check_schema = matches.opt_present("check-schema");
+    let coverage = matches.opt_present("coverage");
+    let metaschema = matches.opt_str("metaschema");
+    let cacert = matches.opt_str("cacert");
+    let cacert = cacert.as_deref();
+
+    // manifest --
+    if let Some(manifest) = matches.opt_str("manifest") {
+        if !matches.free.is_empty()
+            || check_schema
+            || coverage
+            || doc_format.is_some()
+            || metaschema.is_some()
+        {
+            eprintln!("--manifest not allowed with SCHEMA, INSTANCE, --check-schema, --coverage, --doc or --metaschema");
+            eprintln!();
+            eprintln!("{}", opts.usage(BRIEF));
+            process::exit(1);
+        }
+        let entries = match load_manifest(&manifest, &settings) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("invalid manifest {manifest}: {e}");
+                process::exit(1);
+            }
+        };
+        let loader = || url_loader(cacert, insecure, retries, &defines);
+        let all_valid = run_manifest(&entries, quiet, &loader, &mut report);
+        exit(&report, if all_valid { 0 } else { 2 });
+    }
 
     // schema --
     let Some(schema) = matches.free.first() else {
@@ -73,100 +219,417 @@ fn main() {
         eprintln!("{}", opts.usage(BRIEF));
         process::exit(1);
     };
+    if (check_schema || doc_format.is_some()) && matches.free.len() > 1 {
+        eprintln!("INSTANCE not allowed with --check-schema or --doc");
+        eprintln!();
+        eprintln!("{}", opts.usage(BRIEF));
+        process::exit(1);
+    }
+    if coverage && (check_schema || doc_format.is_some()) {
+        eprintln!("--coverage not allowed with --check-schema or --doc");
+        eprintln!();
+        eprintln!("{}", opts.usage(BRIEF));
+        process::exit(1);
+    }
 
     // compile --
     let mut schemas = Schemas::new();
-    let mut compiler = Compiler::new();
-    let mut loader = SchemeUrlLoader::new();
-    loader.register("file", Box::new(FileUrlLoader));
-    let cacert = matches.opt_str("cacert");
-    let cacert = cacert.as_deref();
-    loader.register("http", Box::new(HttpUrlLoader::new(cacert, insecure)));
-    loader.register("https", Box::new(HttpUrlLoader::new(cacert, insecure)));
-    compiler.use_loader(Box::new(loader));
-    compiler.set_default_draft(draft);
-    if assert_format {
-        compiler.enable_format_assertions();
-    }
-    if assert_content {
-        compiler.enable_content_assertions();
+    let mut compiler = settings.compiler(url_loader(cacert, insecure, retries, &defines));
+    if let Some(metaschema) = &metaschema {
+        let meta = match compiler.compile(metaschema, &mut schemas) {
+            Ok(meta) => meta,
+            Err(e) => {
+                println!("metaschema {metaschema}: {}", render::failed());
+                if !quiet {
+                    println!("{e:#}");
+                }
+                report.add(metaschema, None, vec![Failure::compile(&e)]);
+                exit(&report, 2);
+            }
+        };
+        let doc = to_url(schema)
+            .and_then(|url| url_loader(cacert, insecure, retries, &defines).load(&url));
+        let doc = match doc {
+            Ok(doc) => doc,
+            Err(e) => {
+                let e = format!("error loading {schema}: {e}");
+                println!("schema {schema}: {}", render::failed());
+                if !quiet {
+                    println!("{e}");
+                }
+                report.add(schema, None, vec![Failure::load(e)]);
+                exit(&report, 2);
+            }
+        };
+        if let Err(e) = schemas.validate(&doc, meta) {
+            println!("schema {schema}: {}", render::failed());
+            if !quiet {
+                print_error(&e, output, schema, None);
+            }
+            report.add(schema, None, Failure::validation(&e));
+            exit(&report, 2);
+        }
     }
     let sch = match compiler.compile(schema, &mut schemas) {
         Ok(sch) => {
-            println!("schema {schema}: ok");
+            if let Some(format) = doc_format {
+                print!("{}", schemas.doc(sch, format));
+                process::exit(0);
+            }
+            println!("schema {schema}: {}", render::ok());
+            report.add(schema, None, vec![]);
             sch
         }
         Err(e) => {
-            println!("schema {schema}: failed");
+            println!("schema {schema}: {}", render::failed());
             if !quiet {
                 println!("{e:#}");
             }
-            process::exit(2);
+            report.add(schema, None, vec![Failure::compile(&e)]);
+            exit(&report, 2);
         }
     };
 
     // validate --
+    let mut options = ValidationOptions::new();
+    let exercised = Arc::new(Exercised::default());
+    if coverage {
+        options.set_keyword_hook(exercised.clone());
+    }
     let mut all_valid = true;
     for instance in &matches.free[1..] {
         if !quiet {
             println!();
         }
-        let rdr = match File::open(instance) {
-            Ok(rdr) => BufReader::new(rdr),
-            Err(e) => {
-                println!("instance {instance}: failed");
-                if !quiet {
-                    println!("error reading file {instance}: {e}");
-                }
-                all_valid = false;
-                continue;
+        let failures = validate_instance(&schemas, sch, instance, &options, quiet, output);
+        all_valid &= failures.is_empty();
+        report.add(schema, Some(instance), failures);
+    }
+    if coverage {
+        print_coverage(&schemas, sch, &exercised, quiet);
+    }
+    exit(&report, if all_valid { 0 } else { 2 });
+}
+
+// writes --report files, before exiting with `code`
+fn exit(report: &Report, code: i32) -> ! {
+    if let Err(e) = report.write() {
+        eprintln!("error writing report {e}");
+        process::exit(2);
+    }
+    process::exit(code);
+}
+
+// options to compile and validate, which can be overridden
+// by entries in --manifest
+#[derive(Clone)]
+struct Settings {
+    draft: Draft,
+    output: Option<String>,
+    assert_format: bool,
+    assert_content: bool,
+    deny_unknown: bool,
+    deny_unknown_formats: bool,
+}
+
+impl Settings {
+    fn compiler(&self, loader: SchemeUrlLoader) -> Compiler {
+        let mut compiler = Compiler::new();
+        compiler.use_loader(Box::new(loader));
+        compiler.set_default_draft(self.draft);
+        if self.assert_format {
+            compiler.enable_format_assertions();
+        }
+        if self.assert_content {
+            compiler.enable_content_assertions();
+        }
+        if self.deny_unknown {
+            compiler.deny_unknown_keywords();
+        }
+        if self.deny_unknown_formats {
+            compiler.deny_unknown_formats();
+        }
+        compiler
+    }
+}
+
+// validates `instance` file against `sch`, printing the result.
+// returns failures, which is empty if it is valid.
+fn validate_instance(
+    schemas: &Schemas,
+    sch: SchemaIndex,
+    instance: &str,
+    options: &ValidationOptions,
+    quiet: bool,
+    output: Option<&str>,
+) -> Vec<Failure> {
+    let text = read_text(instance);
+    let value = text.and_then(|text| Ok((parse_text::<Value>(instance, &text)?, text)));
+    let (value, text) = match value {
+        Ok(v) => v,
+        Err(e) => {
+            println!("instance {instance}: {}", render::failed());
+            if !quiet {
+                println!("{e}");
             }
-        };
-        let value: Result<Value, String> =
-            if instance.ends_with(".yaml") || instance.ends_with(".yml") {
-                serde_yaml::from_reader(rdr).map_err(|e| e.to_string())
-            } else {
-                serde_json::from_reader(rdr).map_err(|e| e.to_string())
+            return vec![Failure::load(e)];
+        }
+    };
+    match schemas.validate_with(&value, sch, options) {
+        Ok(_) => {
+            println!("instance {instance}: {}", render::ok());
+            vec![]
+        }
+        Err(e) => {
+            println!("instance {instance}: {}", render::failed());
+            if !quiet {
+                let source = (!is_yaml(instance)).then_some(text.as_str());
+                print_error(&e, output, instance, source);
+            }
+            Failure::validation(&e)
+        }
+    }
+}
+
+// reads json or yaml file, depending on its extension
+fn read_file<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    parse_text(path, &read_text(path)?)
+}
+
+fn read_text(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("error reading file {path}: {e}"))
+}
+
+// parses `text` of file at `path`, as json or yaml depending on its extension
+fn parse_text<T: DeserializeOwned>(path: &str, text: &str) -> Result<T, String> {
+    let value = if is_yaml(path) {
+        serde_yaml::from_str(text).map_err(|e| e.to_string())
+    } else {
+        serde_json::from_str(text).map_err(|e| e.to_string())
+    };
+    value.map_err(|e| format!("error parsing file {path}: {e}"))
+}
+
+fn is_yaml(path: &str) -> bool {
+    path.ends_with(".yaml") || path.ends_with(".yml")
+}
+
+// entry in --manifest file
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct ManifestEntry {
+    schema: String,
+    #[serde(default)]
+    instances: Vec<String>,
+    draft: Option<usize>,
+    output: Option<String>,
+    assert_format: Option<bool>,
+    assert_content: Option<bool>,
+}
+
+// schema and instances to validate, with paths resolved
+struct Validation {
+    schema: String,
+    instances: Vec<String>,
+    settings: Settings,
+}
+
+// loads entries of `manifest`, with `settings` overridden by each entry.
+// relative paths in entries are resolved against directory of `manifest`.
+fn load_manifest(manifest: &str, settings: &Settings) -> Result<Vec<Validation>, String> {
+    let entries: Vec<ManifestEntry> = read_file(manifest)?;
+    let dir = Path::new(manifest).parent().unwrap_or(Path::new(""));
+    let resolve = |loc: &str| match Url::parse(loc) {
+        Ok(url) if url.scheme().len() > 1 => loc.to_owned(),
+        _ => dir.join(loc).to_string_lossy().into_owned(),
+    };
+    let mut validations = vec![];
+    for (i, entry) in entries.into_iter().enumerate() {
+        let mut settings = settings.clone();
+        if let Some(v) = entry.draft {
+            let Some(d) = parse_draft(v) else {
+                return Err(format!("invalid draft {v} in entry {i}"));
             };
-        let value = match value {
-            Ok(v) => v,
-            Err(e) => {
-                println!("instance {instance}: failed");
-                if !quiet {
-                    println!("error parsing file {instance}: {e}");
-                }
-                all_valid = false;
-                continue;
+            settings.draft = d;
+        }
+        if let Some(o) = entry.output {
+            if !OUTPUTS.contains(&o.as_str()) {
+                return Err(format!("invalid output {o} in entry {i}"));
+            }
+            settings.output = Some(o);
+        }
+        settings.assert_format = entry.assert_format.unwrap_or(settings.assert_format);
+        settings.assert_content = entry.assert_content.unwrap_or(settings.assert_content);
+        validations.push(Validation {
+            schema: resolve(&entry.schema),
+            instances: entry.instances.iter().map(|inst| resolve(inst)).collect(),
+            settings,
+        });
+    }
+    Ok(validations)
+}
+
+// runs `validations`, followed by a summary of results.
+// returns false, if any schema or instance failed.
+fn run_manifest(
+    validations: &[Validation],
+    quiet: bool,
+    loader: &dyn Fn() -> SchemeUrlLoader,
+    report: &mut Report,
+) -> bool {
+    let options = ValidationOptions::new();
+    let (mut schemas_failed, mut instances_ok, mut instances_failed) = (0, 0, 0);
+    for (i, v) in validations.iter().enumerate() {
+        if !quiet && i > 0 {
+            println!();
+        }
+        let output = v.settings.output.as_deref();
+        let mut schemas = Schemas::new();
+        let mut compiler = v.settings.compiler(loader());
+        let sch = match compiler.compile(&v.schema, &mut schemas) {
+            Ok(sch) => {
+                println!("schema {}: {}", v.schema, render::ok());
+                report.add(&v.schema, None, vec![]);
+                sch
             }
-        };
-        match schemas.validate(&value, sch) {
-            Ok(_) => println!("instance {instance}: ok"),
             Err(e) => {
-                println!("instance {instance}: failed");
+                println!("schema {}: {}", v.schema, render::failed());
                 if !quiet {
-                    match &output {
-                        Some(out) => match out.as_str() {
-                            "simple" => println!("{e}"),
-                            "alt" => println!("{e:#}"),
-                            "flag" => println!("{:#}", e.flag_output()),
-                            "basic" => println!("{:#}", e.basic_output()),
-                            "detailed" => println!("{:#}", e.detailed_output()),
-                            _ => (),
-                        },
-                        None => println!("{e}"),
-                    }
+                    println!("{e:#}");
                 }
-                all_valid = false;
+                report.add(&v.schema, None, vec![Failure::compile(&e)]);
+                schemas_failed += 1;
                 continue;
             }
         };
+        for instance in &v.instances {
+            if !quiet {
+                println!();
+            }
+            let failures = validate_instance(&schemas, sch, instance, &options, quiet, output);
+            if failures.is_empty() {
+                instances_ok += 1;
+            } else {
+                instances_failed += 1;
+            }
+            report.add(&v.schema, Some(instance), failures);
+        }
     }
-    if !all_valid {
-        process::exit(2);
+    println!();
+    println!(
+        "schemas: {} ok, {schemas_failed} failed; instances: {instances_ok} ok, {instances_failed} failed",
+        validations.len() - schemas_failed,
+    );
+    schemas_failed == 0 && instances_failed == 0
+}
+
+// keyword locations evaluated by instances, see --coverage
+#[derive(Default)]
+struct Exercised(Mutex<HashSet<String>>);
+
+impl KeywordHook for Exercised {
+    fn before(&self, eval: &KeywordEval) {
+        let loc = format!("{}/{}", eval.schema_url, eval.keyword);
+        self.0.lock().unwrap().insert(loc);
+    }
+}
+
+fn print_coverage(schemas: &Schemas, sch: SchemaIndex, exercised: &Exercised, quiet: bool) {
+    let exercised = exercised.0.lock().unwrap();
+    let all = schemas.keyword_locations(sch);
+    let missed: Vec<_> = all.iter().filter(|loc| !exercised.contains(*loc)).collect();
+    let covered = all.len() - missed.len();
+    let percent = match all.len() {
+        0 => 100.0,
+        n => covered as f64 * 100.0 / n as f64,
+    };
+    println!();
+    println!(
+        "coverage: {covered}/{} keywords exercised ({percent:.1}%)",
+        all.len()
+    );
+    if !quiet && !missed.is_empty() {
+        println!("keywords never exercised:");
+        for loc in missed {
+            println!("  {loc}");
+        }
+    }
+}
+
+fn parse_draft(version: usize) -> Option<Draft> {
+    Draft::all().into_iter().find(|d| d.version() == version)
+}
+
+const OUTPUTS: [&str; 5] = ["simple", "alt", "flag", "basic", "detailed"];
+
+// prints `e` in `output` format. `source` is json text of
+// document at `path`, if available, used to show error locations.
+fn print_error(e: &ValidationError, output: Option<&str>, path: &str, source: Option<&str>) {
+    match output {
+        Some("alt") => println!("{e:#}"),
+        Some("flag") => println!("{:#}", e.flag_output()),
+        Some("basic") => println!("{:#}", e.basic_output()),
+        Some("detailed") => println!("{:#}", e.detailed_output()),
+        _ => println!("{}", render::simple(e, path, source)),
     }
 }
 
-const BRIEF: &str = "Usage: boon [OPTIONS] SCHEMA [INSTANCE...]";
+// loader of schema documents, with placeholders of --define replaced
+fn url_loader(
+    cacert: Option<&str>,
+    insecure: bool,
+    retries: usize,
+    defines: &Defines,
+) -> SchemeUrlLoader {
+    let define = |inner: Box<dyn UrlLoader>| {
+        let defines = defines.clone();
+        Box::new(DefineLoader { inner, defines })
+    };
+    let http = || {
+        let mut loader = RetryLoader::new(HttpUrlLoader::new(cacert, insecure));
+        loader.set_max_retries(retries);
+        loader.set_retry_if(is_transient);
+        define(Box::new(loader))
+    };
+    let mut loader = SchemeUrlLoader::new();
+    loader.register("file", define(Box::new(FileUrlLoader)));
+    loader.register("http", http());
+    loader.register("https", http());
+    loader
+}
+
+// retry on connection and io errors, including timeouts, server errors
+// and rate limiting. not on invalid url, unknown scheme or tls errors
+fn is_transient(e: &(dyn Error + 'static)) -> bool {
+    use ureq::ErrorKind::*;
+    match e.downcast_ref::<ureq::Error>() {
+        Some(ureq::Error::Status(code, _)) => *code >= 500 || *code == 429,
+        Some(ureq::Error::Transport(t)) => {
+            // tls errors, like invalid certificate, are io errors of kind InvalidData
+            let tls = std::iter::successors(Some(e), |&e| e.source())
+                .filter_map(|e| e.downcast_ref::<std::io::Error>())
+                .any(|e| e.kind() == std::io::ErrorKind::InvalidData);
+            matches!(t.kind(), ConnectionFailed | Io | ProxyConnect) && !tls
+        }
+        None => false,
+    }
+}
+
+// converts `loc`, which is either url or file path, to url.
+fn to_url(loc: &str) -> Result<String, Box<dyn Error>> {
+    if let Ok(url) = Url::parse(loc) {
+        if url.scheme().len() > 1 {
+            // not windows drive letter
+            return Ok(url.into());
+        }
+    }
+    let path = std::path::absolute(loc)?;
+    let url = Url::from_file_path(&path).map_err(|_| format!("invalid file path {loc}"))?;
+    Ok(url.into())
+}
+
+const BRIEF: &str = "Usage: boon [OPTIONS] SCHEMA [INSTANCE...]
+       boon [OPTIONS] --manifest <FILE>";
 
 fn options() -> Options {
     let mut opts = Options::new();
@@ -194,6 +657,56 @@ fn options() -> Options {
         "assert-content",
         "Enable content assertions with draft >= 7",
     );
+    opts.optflag(
+        "",
+        "deny-unknown-keywords",
+        "Fail if schema uses keywords not defined by its draft",
+    );
+    opts.optflag(
+        "",
+        "deny-unknown-formats",
+        "Fail if schema uses formats not supported",
+    );
+    opts.optflag(
+        "",
+        "check-schema",
+        "Only check SCHEMA, without validating any INSTANCE",
+    );
+    opts.optflag(
+        "",
+        "coverage",
+        "Report keywords of SCHEMA, which are never evaluated by any INSTANCE",
+    );
+    opts.optmulti(
+        "",
+        "define",
+        "Replace {\"x-boon-define\": \"NAME\"} in schemas with VALUE, parsed as json or else string. Can be repeated",
+        "<NAME=VALUE>",
+    );
+    opts.optopt(
+        "",
+        "doc",
+        "Print documentation of SCHEMA, instead of validating. Valid values markdown, html",
+        "<FMT>",
+    );
+    opts.optopt(
+        "",
+        "manifest",
+        "Validate schemas and instances listed in json or yaml FILE, instead of SCHEMA and INSTANCE",
+        "<FILE>",
+    );
+    opts.optopt(
+        "",
+        "metaschema",
+        "Also validate SCHEMA document against given metaschema, besides its '$schema'",
+        "<URL>",
+    );
+    opts.optmulti(
+        "",
+        "report",
+        "Also write results to FILE, for CI. Valid values of FMT junit, sarif. Can be repeated",
+        "<FMT=FILE>",
+    );
     opts.optopt(
         "",
         "cacert",
@@ -201,6 +714,24 @@ fn options() -> Options {
         "<FILE>",
     );
     opts.optflag("k", "insecure", "Use insecure TLS connection");
+    opts.optopt(
+        "",
+        "retries",
+        "Retry loading remote schemas on transient errors, upto N times (default 3)",
+        "<N>",
+    );
+    opts.optflag("", "list-drafts", "Print supported drafts and exit");
+    opts.optflag("", "list-formats", "Print supported formats and exit");
+    opts.optflag(
+        "",
+        "list-encodings",
+        "Print supported content encodings and exit",
+    );
+    opts.optflag(
+        "",
+        "list-media-types",
+        "Print supported content media types and exit",
+    );
     opts
 }
 
@@ -0,0 +1,232 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+    sync::OnceLock,
+};
+
+use boon::ValidationError;
+
+// tells whether output is colored, which is when stdout is
+// a terminal and NO_COLOR is not set. see https://no-color.org
+fn colored() -> bool {
+    static COLORED: OnceLock<bool> = OnceLock::new();
+    *COLORED.get_or_init(|| {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        !no_color && io::stdout().is_terminal()
+    })
+}
+
+// wraps `s` in ansi escape `code`, if output is colored
+fn paint(code: &str, s: &str) -> String {
+    if colored() {
+        format!("\x1b[{code}m{s}\x1b[0m")
+    } else {
+        s.to_owned()
+    }
+}
+
+pub fn ok() -> String {
+    paint("32", "ok")
+}
+
+pub fn failed() -> String {
+    paint("1;31", "failed")
+}
+
+// renders `e` in simple format, with instance locations highlighted.
+// if json `source` of the instance is given, errors are followed by
+// excerpts of it, with caret under the values they are about.
+// ends with the number of errors.
+pub fn simple(e: &ValidationError, path: &str, source: Option<&str>) -> String {
+    let mut out = String::new();
+    for line in e.to_string().lines() {
+        match line.find("at '").zip(line.find("': ")) {
+            Some((i, j)) if i < j => {
+                out.push_str(&line[..i]);
+                out.push_str(&paint("36", &line[i..=j]));
+                out.push_str(&line[j + 1..]);
+            }
+            _ => out.push_str(line),
+        }
+        out.push('\n');
+    }
+
+    let mut leaves = vec![];
+    collect_leaves(e, &mut leaves);
+    if let Some(source) = source {
+        // group messages by location, in order of first occurrence
+        let mut locs: Vec<(&str, Vec<&str>)> = vec![];
+        for (loc, msg) in &leaves {
+            match locs.iter_mut().find(|(l, _)| l == loc) {
+                Some((_, msgs)) => msgs.push(msg),
+                None => locs.push((loc, vec![msg])),
+            }
+        }
+        for (loc, msgs) in locs {
+            if let Some(offset) = locate(source, loc) {
+                out.push_str(&excerpt(path, source, offset, &msgs.join(", ")));
+            }
+        }
+    }
+
+    let n = leaves.len();
+    let s = if n == 1 { "" } else { "s" };
+    out.push_str(&paint("1;31", &format!("{n} error{s}")));
+    out
+}
+
+// collects (instance location, message) of errors without causes
+fn collect_leaves(e: &ValidationError, leaves: &mut Vec<(String, String)>) {
+    if e.causes.is_empty() {
+        leaves.push((e.instance_location.to_string(), e.kind.to_string()));
+    }
+    for cause in &e.causes {
+        collect_leaves(cause, leaves);
+    }
+}
+
+// renders line of `source` at byte `offset`, with `msg` at caret:
+//
+//   --> data.json:3:8
+//    |
+//  3 |   "a": 1,
+//    |        ^ must be string
+fn excerpt(path: &str, source: &str, offset: usize, msg: &str) -> String {
+    let start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let end = source[offset..]
+        .find('\n')
+        .map_or(source.len(), |i| offset + i);
+    let line = source[..offset].matches('\n').count() + 1;
+    let column = source[start..offset].chars().count() + 1;
+    let text = source[start..end].trim_end_matches('\r');
+
+    let num = line.to_string();
+    let pad = " ".repeat(num.len());
+    let gutter = paint("1;34", &format!("{pad} |"));
+    let caret = paint("1;31", &format!("{}^ {msg}", " ".repeat(column - 1)));
+    format!(
+        "{pad}{} {path}:{line}:{column}\n{gutter}\n{} {text}\n{gutter} {caret}\n",
+        paint("1;34", "-->"),
+        paint("1;34", &format!("{num} |")),
+    )
+}
+
+// returns byte offset of value at json-pointer `ptr` in json text `source`.
+// returns None, if `source` is not json, or has no such value.
+fn locate(source: &str, ptr: &str) -> Option<usize> {
+    let mut s = Scanner {
+        b: source.as_bytes(),
+        i: 0,
+    };
+    s.ws();
+    if ptr.is_empty() {
+        return Some(s.i);
+    }
+    for tok in ptr.strip_prefix('/')?.split('/') {
+        let tok = tok.replace("~1", "/").replace("~0", "~");
+        match s.peek()? {
+            b'{' => {
+                s.i += 1;
+                loop {
+                    s.ws();
+                    let (start, end) = s.string()?;
+                    let key: String = serde_json::from_slice(&s.b[start..end]).ok()?;
+                    s.ws();
+                    s.expect(b':')?;
+                    s.ws();
+                    if key == tok {
+                        break;
+                    }
+                    s.value()?;
+                    s.ws();
+                    s.expect(b',')?;
+                }
+            }
+            b'[' => {
+                s.i += 1;
+                let index: usize = tok.parse().ok()?;
+                for _ in 0..index {
+                    s.ws();
+                    s.value()?;
+                    s.ws();
+                    s.expect(b',')?;
+                }
+                s.ws();
+            }
+            _ => return None,
+        }
+    }
+    Some(s.i)
+}
+
+// skims json text, without building values
+struct Scanner<'a> {
+    b: &'a [u8],
+    i: usize,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.b.get(self.i).copied()
+    }
+
+    fn ws(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.i += 1;
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Option<()> {
+        (self.peek()? == b).then(|| self.i += 1)
+    }
+
+    // skips string, returning its range including quotes
+    fn string(&mut self) -> Option<(usize, usize)> {
+        let start = self.i;
+        self.expect(b'"')?;
+        loop {
+            match self.peek()? {
+                b'"' => break,
+                b'\\' => self.i += 2,
+                _ => self.i += 1,
+            }
+        }
+        self.i += 1;
+        Some((start, self.i))
+    }
+
+    fn value(&mut self) -> Option<()> {
+        match self.peek()? {
+            b'"' => {
+                self.string()?;
+            }
+            b'{' | b'[' => {
+                let mut depth = 0;
+                loop {
+                    match self.peek()? {
+                        b'"' => {
+                            self.string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => depth -= 1,
+                        _ => {}
+                    }
+                    self.i += 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                while self
+                    .peek()
+                    .is_some_and(|b| !matches!(b, b',' | b']' | b'}') && !b.is_ascii_whitespace())
+                {
+                    self.i += 1;
+                }
+            }
+        }
+        Some(())
+    }
+}
@@ -0,0 +1,212 @@
+use std::{collections::BTreeSet, fs, io};
+
+use boon::{CompileError, ValidationError};
+use serde_json::{json, Value};
+
+// format of --report
+#[derive(Clone, Copy)]
+pub enum Format {
+    Junit,
+    Sarif,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "junit" => Some(Self::Junit),
+            "sarif" => Some(Self::Sarif),
+            _ => None,
+        }
+    }
+}
+
+// single problem found in schema or instance
+pub struct Failure {
+    // keyword that failed, or `compile`/`load`
+    pub rule: String,
+    pub message: String,
+    // json-pointer within the instance, for validation failures
+    pub location: Option<String>,
+}
+
+impl Failure {
+    pub fn load(message: String) -> Self {
+        Self {
+            rule: "load".to_owned(),
+            message,
+            location: None,
+        }
+    }
+
+    pub fn compile(e: &CompileError) -> Self {
+        Self {
+            rule: "compile".to_owned(),
+            message: format!("{e:#}"),
+            location: None,
+        }
+    }
+
+    pub fn validation(e: &ValidationError) -> Vec<Self> {
+        e.ajv_output()
+            .into_iter()
+            .map(|e| Self {
+                rule: e.keyword.to_owned(),
+                message: e.message,
+                location: Some(e.instance_path),
+            })
+            .collect()
+    }
+}
+
+// outcome of compiling `schema`, or validating `instance` against it
+struct Outcome {
+    schema: String,
+    instance: Option<String>,
+    failures: Vec<Failure>,
+}
+
+impl Outcome {
+    fn document(&self) -> &str {
+        self.instance.as_deref().unwrap_or(&self.schema)
+    }
+}
+
+// collects outcomes, to be written to files given with --report
+pub struct Report {
+    targets: Vec<(Format, String)>,
+    outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    pub fn new(targets: Vec<(Format, String)>) -> Self {
+        Self {
+            targets,
+            outcomes: vec![],
+        }
+    }
+
+    pub fn add(&mut self, schema: &str, instance: Option<&str>, failures: Vec<Failure>) {
+        self.outcomes.push(Outcome {
+            schema: schema.to_owned(),
+            instance: instance.map(str::to_owned),
+            failures,
+        });
+    }
+
+    pub fn write(&self) -> io::Result<()> {
+        for (format, file) in &self.targets {
+            let content = match format {
+                Format::Junit => self.junit(),
+                Format::Sarif => format!("{:#}", self.sarif()),
+            };
+            fs::write(file, content)
+                .map_err(|e| io::Error::new(e.kind(), format!("{file}: {e}")))?;
+        }
+        Ok(())
+    }
+
+    // one testsuite per schema, with one testcase for the schema
+    // and for each of its instances.
+    fn junit(&self) -> String {
+        let count = |outcomes: &[Outcome]| {
+            let failed = outcomes.iter().filter(|o| !o.failures.is_empty()).count();
+            format!(r#"tests="{}" failures="{failed}""#, outcomes.len())
+        };
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"boon\" {}>\n",
+            count(&self.outcomes)
+        ));
+        for suite in self.outcomes.chunk_by(|a, b| a.schema == b.schema) {
+            let schema = escape_xml(&suite[0].schema);
+            xml.push_str(&format!(
+                "  <testsuite name=\"{schema}\" {}>\n",
+                count(suite)
+            ));
+            for o in suite {
+                let name = match &o.instance {
+                    Some(instance) => format!("instance {}", escape_xml(instance)),
+                    None => format!("schema {schema}"),
+                };
+                xml.push_str(&format!(
+                    "    <testcase classname=\"{schema}\" name=\"{name}\""
+                ));
+                let Some(first) = o.failures.first() else {
+                    xml.push_str("/>\n");
+                    continue;
+                };
+                xml.push_str(">\n");
+                xml.push_str(&format!(
+                    "      <failure type=\"{}\" message=\"{}\">",
+                    escape_xml(&first.rule),
+                    escape_xml(&first.message)
+                ));
+                for f in &o.failures {
+                    let line = match &f.location {
+                        Some(loc) => format!("at '{loc}': {}\n", f.message),
+                        None => format!("{}\n", f.message),
+                    };
+                    xml.push_str(&escape_xml(&line));
+                }
+                xml.push_str("</failure>\n    </testcase>\n");
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+
+    // see https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+    fn sarif(&self) -> Value {
+        let mut rules = BTreeSet::new();
+        let mut results = vec![];
+        for o in &self.outcomes {
+            for f in &o.failures {
+                rules.insert(f.rule.as_str());
+                let mut location = json!({
+                    "physicalLocation": {"artifactLocation": {"uri": o.document()}}
+                });
+                if let Some(loc) = f.location.as_ref().filter(|loc| !loc.is_empty()) {
+                    location["logicalLocations"] = json!([{"fullyQualifiedName": loc}]);
+                }
+                results.push(json!({
+                    "ruleId": f.rule,
+                    "level": "error",
+                    "message": {"text": f.message},
+                    "locations": [location],
+                }));
+            }
+        }
+        let rules: Vec<_> = rules.into_iter().map(|id| json!({"id": id})).collect();
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "boon",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/santhosh-tekuri/boon",
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }]
+        })
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,66 @@
+/*
+ * C ABI for boon, JSONSchema (draft 2020-12, draft 2019-09, draft-7,
+ * draft-6, draft-4) Validation.
+ *
+ * All strings are nul-terminated utf-8. Strings returned through
+ * `char **` out parameters are owned by the caller and must be
+ * released with boon_string_free.
+ */
+
+#ifndef BOON_H
+#define BOON_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* return values of boon_validate */
+#define BOON_VALID 0
+#define BOON_INVALID 1
+#define BOON_ERROR (-1)
+
+/* flags of boon_compile */
+#define BOON_ASSERT_FORMAT 1u
+#define BOON_ASSERT_CONTENT 2u
+
+/* compiled schema */
+typedef struct BoonSchema BoonSchema;
+
+/*
+ * Compiles schema at loc, which is file path or url.
+ *
+ * If schema is not NULL, it is used as json text of the schema instead
+ * of loading loc. draft is the version of draft used when $schema is
+ * missing, like 2020 or 7; 0 means latest. flags is bitwise or of
+ * BOON_ASSERT_* constants.
+ *
+ * Returns NULL on failure, setting *error to error message if error
+ * is not NULL.
+ */
+BoonSchema *boon_compile(const char *loc, const char *schema, int draft,
+                         unsigned int flags, char **error);
+
+/*
+ * Validates json text instance against schema.
+ *
+ * Returns BOON_VALID, BOON_INVALID or BOON_ERROR. If errors is not NULL,
+ * *errors is set to the basic output json for BOON_INVALID and to error
+ * message for BOON_ERROR.
+ */
+int boon_validate(const BoonSchema *schema, const char *instance,
+                  char **errors);
+
+/* Releases schema returned by boon_compile. NULL is ignored. */
+void boon_schema_free(BoonSchema *schema);
+
+/* Releases string returned by this library. NULL is ignored. */
+void boon_string_free(char *s);
+
+/* Returns version of this library, as static string. */
+const char *boon_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* BOON_H */
@@ -0,0 +1,203 @@
+/*!
+C ABI for boon. See `include/boon.h` for the declarations.
+
+All strings are nul-terminated utf-8. Strings returned through `char **`
+out parameters are owned by the caller and must be released with
+[`boon_string_free`].
+*/
+
+use std::{
+    ffi::{c_char, c_int, c_uint, CStr, CString},
+    ptr,
+};
+
+use boon::{Compiler, Draft, InstanceError, SchemaIndex, Schemas};
+use serde_json::Value;
+
+/// Instance is valid.
+pub const BOON_VALID: c_int = 0;
+/// Instance is not valid.
+pub const BOON_INVALID: c_int = 1;
+/// Instance is not valid json, or arguments are invalid.
+pub const BOON_ERROR: c_int = -1;
+
+/// Enable format assertions.
+pub const BOON_ASSERT_FORMAT: c_uint = 1;
+/// Enable content assertions.
+pub const BOON_ASSERT_CONTENT: c_uint = 2;
+
+/// Compiled schema. Opaque to C.
+pub struct BoonSchema {
+    schemas: Schemas,
+    sch: SchemaIndex,
+}
+
+/**
+Compiles schema at `loc`, which is file path or url.
+
+If `schema` is not null, it is used as json text of the schema
+instead of loading `loc`. `draft` is the version of draft used when
+`$schema` is missing, like `2020` or `7`; `0` means latest. `flags`
+is bitwise or of `BOON_ASSERT_*` constants.
+
+Returns null on failure, setting `*error` to error message if
+`error` is not null.
+
+# Safety
+
+`loc` and `schema` must be null or valid nul-terminated strings.
+`error` must be null or valid to write.
+*/
+#[no_mangle]
+pub unsafe extern "C" fn boon_compile(
+    loc: *const c_char,
+    schema: *const c_char,
+    draft: c_int,
+    flags: c_uint,
+    error: *mut *mut c_char,
+) -> *mut BoonSchema {
+    match compile(loc, schema, draft, flags) {
+        Ok(schema) => Box::into_raw(Box::new(schema)),
+        Err(msg) => {
+            set_string(error, msg);
+            ptr::null_mut()
+        }
+    }
+}
+
+unsafe fn compile(
+    loc: *const c_char,
+    schema: *const c_char,
+    draft: c_int,
+    flags: c_uint,
+) -> Result<BoonSchema, String> {
+    let loc = to_str(loc, "loc")?;
+    let mut compiler = Compiler::new();
+    if draft != 0 {
+        let Some(draft) = Draft::all()
+            .into_iter()
+            .find(|d| d.version() as c_int == draft)
+        else {
+            return Err(format!("unsupported draft version {draft}"));
+        };
+        compiler.set_default_draft(draft);
+    }
+    if flags & BOON_ASSERT_FORMAT != 0 {
+        compiler.enable_format_assertions();
+    }
+    if flags & BOON_ASSERT_CONTENT != 0 {
+        compiler.enable_content_assertions();
+    }
+    if !schema.is_null() {
+        let schema = to_str(schema, "schema")?;
+        let json: Value =
+            serde_json::from_str(schema).map_err(|e| format!("error parsing schema: {e}"))?;
+        compiler
+            .add_resource(loc, json)
+            .map_err(|e| e.to_string())?;
+    }
+    let mut schemas = Schemas::new();
+    let sch = compiler
+        .compile(loc, &mut schemas)
+        .map_err(|e| format!("{e:#}"))?;
+    Ok(BoonSchema { schemas, sch })
+}
+
+/**
+Validates json text `instance` against `schema`.
+
+Returns `BOON_VALID`, `BOON_INVALID` or `BOON_ERROR`. If `errors` is
+not null, `*errors` is set to the [basic output] json for `BOON_INVALID`
+and to error message for `BOON_ERROR`.
+
+[basic output]: https://json-schema.org/draft/2020-12/json-schema-core.html#name-basic
+
+# Safety
+
+`schema` must be returned by [`boon_compile`] and not yet freed.
+`instance` must be null or valid nul-terminated string. `errors` must be
+null or valid to write.
+*/
+#[no_mangle]
+pub unsafe extern "C" fn boon_validate(
+    schema: *const BoonSchema,
+    instance: *const c_char,
+    errors: *mut *mut c_char,
+) -> c_int {
+    let Some(schema) = schema.as_ref() else {
+        set_string(errors, "schema is null".to_owned());
+        return BOON_ERROR;
+    };
+    let instance = match to_str(instance, "instance") {
+        Ok(instance) => instance,
+        Err(msg) => {
+            set_string(errors, msg);
+            return BOON_ERROR;
+        }
+    };
+    match schema.schemas.validate_str(instance, schema.sch) {
+        Ok(()) => BOON_VALID,
+        Err(InstanceError::Invalid(e)) => {
+            set_string(errors, e.basic_output().to_string());
+            BOON_INVALID
+        }
+        Err(e) => {
+            set_string(errors, e.to_string());
+            BOON_ERROR
+        }
+    }
+}
+
+/**
+Releases schema returned by [`boon_compile`]. Does nothing if `schema` is null.
+
+# Safety
+
+`schema` must be null or returned by [`boon_compile`] and not yet freed.
+*/
+#[no_mangle]
+pub unsafe extern "C" fn boon_schema_free(schema: *mut BoonSchema) {
+    if !schema.is_null() {
+        drop(Box::from_raw(schema));
+    }
+}
+
+/**
+Releases string returned by this library. Does nothing if `s` is null.
+
+# Safety
+
+`s` must be null or returned by this library and not yet freed.
+*/
+#[no_mangle]
+pub unsafe extern "C" fn boon_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Returns version of this library, as static string.
+#[no_mangle]
+pub extern "C" fn boon_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+// helpers --
+
+unsafe fn to_str<'a>(s: *const c_char, name: &str) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err(format!("{name} is null"));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| format!("{name} is not valid utf-8"))
+}
+
+unsafe fn set_string(dst: *mut *mut c_char, s: String) {
+    if dst.is_null() {
+        return;
+    }
+    // json and error messages do not contain nul, but be safe
+    let s = CString::new(s.replace('\0', "\\u0000")).unwrap_or_default();
+    *dst = s.into_raw();
+}
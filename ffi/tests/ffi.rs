@@ -0,0 +1,76 @@
+use std::{
+    ffi::{c_char, CStr, CString},
+    ptr,
+};
+
+use boon_ffi::*;
+use serde_json::Value;
+
+unsafe fn take(s: *mut c_char) -> String {
+    assert!(!s.is_null());
+    let owned = CStr::from_ptr(s).to_str().unwrap().to_owned();
+    boon_string_free(s);
+    owned
+}
+
+#[test]
+fn test_compile_validate() {
+    let loc = CString::new("http://example.com/schema.json").unwrap();
+    let schema = CString::new(r#"{"properties": {"a": {"type": "integer"}}}"#).unwrap();
+    unsafe {
+        let mut error = ptr::null_mut();
+        let sch = boon_compile(loc.as_ptr(), schema.as_ptr(), 0, 0, &mut error);
+        assert!(!sch.is_null());
+        assert!(error.is_null());
+
+        let valid = CString::new(r#"{"a": 1}"#).unwrap();
+        assert_eq!(
+            boon_validate(sch, valid.as_ptr(), ptr::null_mut()),
+            BOON_VALID
+        );
+
+        let invalid = CString::new(r#"{"a": "x"}"#).unwrap();
+        let mut errors = ptr::null_mut();
+        assert_eq!(
+            boon_validate(sch, invalid.as_ptr(), &mut errors),
+            BOON_INVALID
+        );
+        let output: Value = serde_json::from_str(&take(errors)).unwrap();
+        assert_eq!(output["errors"][0]["instanceLocation"], "/a");
+
+        let malformed = CString::new("{").unwrap();
+        let mut errors = ptr::null_mut();
+        assert_eq!(
+            boon_validate(sch, malformed.as_ptr(), &mut errors),
+            BOON_ERROR
+        );
+        assert!(take(errors).starts_with("error parsing instance"));
+
+        boon_schema_free(sch);
+    }
+}
+
+#[test]
+fn test_compile_error() {
+    let loc = CString::new("http://example.com/schema.json").unwrap();
+    unsafe {
+        let schema = CString::new(r#"{"type": 1}"#).unwrap();
+        let mut error = ptr::null_mut();
+        let sch = boon_compile(loc.as_ptr(), schema.as_ptr(), 0, 0, &mut error);
+        assert!(sch.is_null());
+        assert!(!take(error).is_empty());
+
+        let mut error = ptr::null_mut();
+        let sch = boon_compile(loc.as_ptr(), schema.as_ptr(), 5, 0, &mut error);
+        assert!(sch.is_null());
+        assert_eq!(take(error), "unsupported draft version 5");
+
+        assert!(boon_compile(ptr::null(), ptr::null(), 0, 0, ptr::null_mut()).is_null());
+    }
+}
+
+#[test]
+fn test_version() {
+    let version = unsafe { CStr::from_ptr(boon_version()) };
+    assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+}
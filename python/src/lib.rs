@@ -0,0 +1,284 @@
+/*!
+Python bindings for boon.
+
+```python
+import boon
+
+schemas = boon.Schemas()
+compiler = boon.Compiler()
+compiler.add_resource("http://example.com/schema.json", {"type": "object"})
+sch = compiler.compile("http://example.com/schema.json", schemas)
+try:
+    schemas.validate([1, 2], sch)
+except boon.ValidationError as e:
+    for unit in e.errors:
+        print(unit["instanceLocation"], unit["error"])
+```
+*/
+
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple},
+};
+use serde_json::{Map, Number, Value};
+
+create_exception!(
+    boon,
+    CompileError,
+    PyException,
+    "Schema compilation failed."
+);
+create_exception!(
+    boon,
+    ValidationError,
+    PyException,
+    "Instance is not valid. The attribute `errors` holds the basic output units."
+);
+
+/// Compiles json schemas. Mirrors `boon::Compiler`.
+#[pyclass(unsendable, module = "boon")]
+struct Compiler(boon::Compiler);
+
+#[pymethods]
+impl Compiler {
+    #[new]
+    fn new() -> Self {
+        Self(boon::Compiler::new())
+    }
+
+    /// Sets the draft used for schemas without `$schema`,
+    /// by version like `2020` or `7`.
+    fn set_default_draft(&mut self, version: usize) -> PyResult<()> {
+        let Some(draft) = boon::Draft::all()
+            .into_iter()
+            .find(|d| d.version() == version)
+        else {
+            return Err(PyValueError::new_err(format!(
+                "unsupported draft version {version}"
+            )));
+        };
+        self.0.set_default_draft(draft);
+        Ok(())
+    }
+
+    fn enable_format_assertions(&mut self) {
+        self.0.enable_format_assertions();
+    }
+
+    fn enable_content_assertions(&mut self) {
+        self.0.enable_content_assertions();
+    }
+
+    /// Adds schema resource `json` (a python object) at `loc`.
+    fn add_resource(&mut self, loc: &str, json: &Bound<'_, PyAny>) -> PyResult<()> {
+        let json = to_value(json)?;
+        self.0.add_resource(loc, json).map_err(compile_error)
+    }
+
+    /// Compiles schema at `loc` into `schemas`.
+    fn compile(&mut self, loc: &str, schemas: &mut Schemas) -> PyResult<SchemaIndex> {
+        let sch = self.0.compile(loc, &mut schemas.0).map_err(compile_error)?;
+        Ok(SchemaIndex(sch))
+    }
+}
+
+/// Identifier to compiled schema.
+#[pyclass(frozen, eq, hash, module = "boon")]
+#[derive(Clone, Copy, PartialEq, Hash)]
+struct SchemaIndex(boon::SchemaIndex);
+
+/// Collection of compiled schemas. Mirrors `boon::Schemas`.
+#[pyclass(module = "boon")]
+#[derive(Default)]
+struct Schemas(boon::Schemas);
+
+#[pymethods]
+impl Schemas {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn contains(&self, sch: SchemaIndex) -> bool {
+        self.0.contains(sch.0)
+    }
+
+    /// Validates python object `instance`, raising `ValidationError` if invalid.
+    fn validate(
+        &self,
+        py: Python<'_>,
+        instance: &Bound<'_, PyAny>,
+        sch: SchemaIndex,
+    ) -> PyResult<()> {
+        self.check(sch)?;
+        let instance = to_value(instance)?;
+        match self.0.validate(&instance, sch.0) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(validation_error(py, &e)?),
+        }
+    }
+
+    /// Validates json text `instance`, raising `ValueError` if it is not
+    /// valid json and `ValidationError` if invalid.
+    fn validate_str(&self, py: Python<'_>, instance: &str, sch: SchemaIndex) -> PyResult<()> {
+        self.check(sch)?;
+        match self.0.validate_str(instance, sch.0) {
+            Ok(()) => Ok(()),
+            Err(boon::InstanceError::Invalid(e)) => Err(validation_error(py, &e)?),
+            Err(e) => Err(PyValueError::new_err(e.to_string())),
+        }
+    }
+
+    /// Returns basic output units of validating `instance`,
+    /// empty list if valid.
+    fn errors<'py>(
+        &self,
+        py: Python<'py>,
+        instance: &Bound<'py, PyAny>,
+        sch: SchemaIndex,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.check(sch)?;
+        let instance = to_value(instance)?;
+        match self.0.validate(&instance, sch.0) {
+            Ok(()) => Ok(PyList::empty(py).into_any()),
+            Err(e) => output_units(py, &e),
+        }
+    }
+
+    fn is_valid(&self, instance: &Bound<'_, PyAny>, sch: SchemaIndex) -> PyResult<bool> {
+        self.check(sch)?;
+        let instance = to_value(instance)?;
+        Ok(self.0.validate(&instance, sch.0).is_ok())
+    }
+}
+
+impl Schemas {
+    // avoid panic on index from other Schemas
+    fn check(&self, sch: SchemaIndex) -> PyResult<()> {
+        if self.0.contains(sch.0) {
+            Ok(())
+        } else {
+            Err(PyValueError::new_err("schema index not found in schemas"))
+        }
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "boon")]
+fn boon_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Compiler>()?;
+    m.add_class::<Schemas>()?;
+    m.add_class::<SchemaIndex>()?;
+    m.add("CompileError", m.py().get_type::<CompileError>())?;
+    m.add("ValidationError", m.py().get_type::<ValidationError>())?;
+    Ok(())
+}
+
+// errors --
+
+fn compile_error(e: boon::CompileError) -> PyErr {
+    CompileError::new_err(e.to_string())
+}
+
+fn validation_error(py: Python<'_>, e: &boon::ValidationError) -> PyResult<PyErr> {
+    let err = ValidationError::new_err(e.to_string());
+    err.value(py).setattr("errors", output_units(py, e)?)?;
+    Ok(err)
+}
+
+fn output_units<'py>(py: Python<'py>, e: &boon::ValidationError) -> PyResult<Bound<'py, PyAny>> {
+    let output =
+        serde_json::to_value(e.basic_output()).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    match output.get("errors") {
+        Some(units) => to_py(py, units),
+        None => to_py(py, &Value::Array(vec![output])),
+    }
+}
+
+// conversions --
+
+fn to_value(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if obj.is_none() {
+        return Ok(Value::Null);
+    }
+    // bool must be checked before int, since bool is subclass of int
+    if let Ok(b) = obj.downcast::<PyBool>() {
+        return Ok(Value::Bool(b.is_true()));
+    }
+    if obj.is_instance_of::<PyInt>() {
+        if let Ok(i) = obj.extract::<i64>() {
+            return Ok(i.into());
+        }
+        if let Ok(u) = obj.extract::<u64>() {
+            return Ok(u.into());
+        }
+        // big integer
+        let s = obj.str()?;
+        return serde_json::from_str::<Number>(&s.to_cow()?)
+            .map(Value::Number)
+            .map_err(|e| PyValueError::new_err(e.to_string()));
+    }
+    if let Ok(f) = obj.downcast::<PyFloat>() {
+        let Some(n) = Number::from_f64(f.value()) else {
+            return Err(PyValueError::new_err("nan and inf are not valid json"));
+        };
+        return Ok(Value::Number(n));
+    }
+    if let Ok(s) = obj.downcast::<PyString>() {
+        return Ok(Value::String(s.to_cow()?.into_owned()));
+    }
+    if let Ok(dict) = obj.downcast::<PyDict>() {
+        let mut map = Map::with_capacity(dict.len());
+        for (k, v) in dict {
+            let Ok(k) = k.downcast::<PyString>() else {
+                return Err(PyTypeError::new_err("object keys must be str"));
+            };
+            map.insert(k.to_cow()?.into_owned(), to_value(&v)?);
+        }
+        return Ok(Value::Object(map));
+    }
+    if let Ok(list) = obj.downcast::<PyList>() {
+        return list.iter().map(|v| to_value(&v)).collect();
+    }
+    if let Ok(tuple) = obj.downcast::<PyTuple>() {
+        return tuple.iter().map(|v| to_value(&v)).collect();
+    }
+    Err(PyTypeError::new_err(format!(
+        "{} is not json compatible",
+        obj.get_type().name()?
+    )))
+}
+
+fn to_py<'py>(py: Python<'py>, v: &Value) -> PyResult<Bound<'py, PyAny>> {
+    let obj = match v {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_pyobject(py)?.into_any()
+            } else if let Some(u) = n.as_u64() {
+                u.into_pyobject(py)?.into_any()
+            } else {
+                PyFloat::new(py, n.as_f64().unwrap_or(f64::NAN)).into_any()
+            }
+        }
+        Value::String(s) => PyString::new(py, s).into_any(),
+        Value::Array(arr) => {
+            let list = PyList::empty(py);
+            for item in arr {
+                list.append(to_py(py, item)?)?;
+            }
+            list.into_any()
+        }
+        Value::Object(obj) => {
+            let dict = PyDict::new(py);
+            for (k, v) in obj {
+                dict.set_item(k, to_py(py, v)?)?;
+            }
+            dict.into_any()
+        }
+    };
+    Ok(obj)
+}
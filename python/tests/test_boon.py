@@ -0,0 +1,58 @@
+import unittest
+
+import boon
+
+URL = "http://example.com/schema.json"
+
+
+def compile(schema):
+    schemas = boon.Schemas()
+    compiler = boon.Compiler()
+    compiler.add_resource(URL, schema)
+    return schemas, compiler.compile(URL, schemas)
+
+
+class TestBoon(unittest.TestCase):
+    def test_validate(self):
+        schemas, sch = compile({"properties": {"a": {"type": "integer"}}})
+        schemas.validate({"a": 1}, sch)
+        schemas.validate_str('{"a": 1}', sch)
+        self.assertTrue(schemas.is_valid({"a": 10**30}, sch))
+        self.assertFalse(schemas.is_valid({"a": True}, sch))
+
+        with self.assertRaises(boon.ValidationError) as cm:
+            schemas.validate({"a": "x"}, sch)
+        [unit] = cm.exception.errors
+        self.assertEqual(unit["instanceLocation"], "/a")
+        self.assertEqual(unit["keywordLocation"], "/properties/a/type")
+        self.assertEqual(schemas.errors({"a": "x"}, sch), cm.exception.errors)
+        self.assertEqual(schemas.errors({"a": 1}, sch), [])
+
+        with self.assertRaises(ValueError):
+            schemas.validate_str("{", sch)
+        with self.assertRaises(TypeError):
+            schemas.validate({"a": object()}, sch)
+
+    def test_compile_error(self):
+        with self.assertRaises(boon.CompileError):
+            compile({"type": 1})
+
+    def test_default_draft(self):
+        schemas = boon.Schemas()
+        compiler = boon.Compiler()
+        compiler.set_default_draft(4)
+        compiler.add_resource(URL, {"maximum": 10, "exclusiveMaximum": True})
+        sch = compiler.compile(URL, schemas)
+        self.assertFalse(schemas.is_valid(10, sch))
+        with self.assertRaises(ValueError):
+            compiler.set_default_draft(5)
+
+    def test_foreign_index(self):
+        _, sch = compile({})
+        self.assertFalse(boon.Schemas().contains(sch))
+        with self.assertRaises(ValueError):
+            boon.Schemas().validate(1, sch)
+
+
+if __name__ == "__main__":
+    unittest.main()
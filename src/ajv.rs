@@ -0,0 +1,252 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{ser::SerializeMap, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{util::*, ErrorKind, ValidationError};
+
+/**
+Error object in the format reported by [ajv](https://ajv.js.org/api.html#error-objects).
+
+Use [`ValidationError::ajv_output`] to convert validation error into
+list of ajv error objects.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct AjvError {
+    /// json-pointer to the failing value within instance, like `/items/0`
+    pub instance_path: String,
+    /// json-pointer to the failing keyword within schema, like `#/properties/a/type`
+    pub schema_path: String,
+    /// The failing keyword, like `type`
+    pub keyword: &'static str,
+    /// Keyword specific parameters, like `{"type": "string"}`
+    pub params: Map<String, Value>,
+    /// Error message, like `must be string`
+    pub message: String,
+}
+
+impl Serialize for AjvError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(5))?;
+        map.serialize_entry("instancePath", &self.instance_path)?;
+        map.serialize_entry("schemaPath", &self.schema_path)?;
+        map.serialize_entry("keyword", self.keyword)?;
+        map.serialize_entry("params", &self.params)?;
+        map.serialize_entry("message", &self.message)?;
+        map.end()
+    }
+}
+
+impl Display for AjvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.instance_path.is_empty() {
+            write!(f, "data {}", self.message)
+        } else {
+            write!(f, "data{} {}", self.instance_path, self.message)
+        }
+    }
+}
+
+impl ValidationError<'_, '_> {
+    /**
+    Returns errors as ajv error objects, as reported by ajv
+    with `allErrors: true`.
+
+    Errors of subschemas are listed before the error of their parent
+    keyword, for example errors of each `anyOf` branch are followed by
+    `must match a schema in anyOf`. `required` and `additionalProperties`
+    errors are reported once for each property, like ajv does.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # let mut schemas = Schemas::new();
+    # let mut compiler = Compiler::new();
+    # compiler.add_resource("http://example.com/schema.json", json!({"required": ["a"]})).unwrap();
+    # let sch = compiler.compile("http://example.com/schema.json", &mut schemas).unwrap();
+    let instance = json!({});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let errors = err.ajv_output();
+    assert_eq!(errors[0].message, "must have required property 'a'");
+    ```
+    */
+    pub fn ajv_output(&self) -> Vec<AjvError> {
+        let mut errors = vec![];
+        self.collect_ajv(&mut errors);
+        errors
+    }
+
+    fn collect_ajv(&self, errors: &mut Vec<AjvError>) {
+        use ErrorKind::*;
+        if !matches!(self.kind, ContentSchema) {
+            for cause in &self.causes {
+                cause.collect_ajv(errors);
+            }
+        }
+
+        let keyword = match &self.kind {
+            Group | Schema { .. } | Reference { .. } | AllOf => return,
+            FalseSchema => "false schema",
+            Custom { kind, .. } if matches!(**kind, Group) => "errorMessage",
+            kind => kind.keyword_path().map_or("", |p| p.keyword),
+        };
+        let (_, frag) = split(self.schema_url);
+        let schema_path = match &self.kind {
+            FalseSchema => format!("#{frag}"),
+            _ => format!("#{frag}/{keyword}"),
+        };
+        let mut push = |params: Value, message: String| {
+            let Value::Object(params) = params else {
+                unreachable!("params must be object");
+            };
+            errors.push(AjvError {
+                instance_path: self.instance_location.to_string(),
+                schema_path: schema_path.clone(),
+                keyword,
+                params,
+                message,
+            });
+        };
+
+        match &self.kind {
+            Required { want } => {
+                for p in want {
+                    push(
+                        json!({"missingProperty": p}),
+                        format!("must have required property '{p}'"),
+                    );
+                }
+            }
+            AdditionalProperties { got } => {
+                for p in got {
+                    push(
+                        json!({"additionalProperty": p}),
+                        "must NOT have additional properties".to_owned(),
+                    );
+                }
+            }
+            Dependency { prop, missing } | DependentRequired { prop, missing } => {
+                let deps = missing.join(", ");
+                for p in missing {
+                    push(
+                        json!({"property": prop, "missingProperty": p, "depsCount": missing.len(), "deps": deps}),
+                        format!("must have property {p} when property {prop} is present"),
+                    );
+                }
+            }
+            kind => {
+                let (params, message) = ajv_params(kind);
+                push(params, message);
+            }
+        }
+    }
+}
+
+impl ErrorKind<'_, '_> {
+    // params of OutputUnit. see OutputUnit::with_params
+    pub(crate) fn params(&self) -> Map<String, Value> {
+        use ErrorKind::*;
+        let params = match self {
+            Type { want, .. } => {
+                let want: Vec<_> = want.iter().map(|t| t.to_string()).collect();
+                json!({ "type": want })
+            }
+            Required { want } => json!({ "missingProperties": want }),
+            AdditionalProperties { got } => json!({ "additionalProperties": got }),
+            Dependency { prop, missing } | DependentRequired { prop, missing } => {
+                json!({"property": prop, "missingProperties": missing})
+            }
+            Custom { kind, .. } | Redacted { kind, .. } => return kind.params(),
+            kind => ajv_params(kind).0,
+        };
+        let Value::Object(params) = params else {
+            unreachable!("params must be object");
+        };
+        params
+    }
+}
+
+fn ajv_params(kind: &ErrorKind) -> (Value, String) {
+    use ErrorKind::*;
+    let limit = |want: &usize, cmp: &str, what: &str| {
+        (
+            json!({"limit": want}),
+            format!("must NOT have {cmp} than {want} {what}"),
+        )
+    };
+    let compare = |want, cmp| {
+        (
+            json!({"comparison": cmp, "limit": want}),
+            format!("must be {cmp} {want}"),
+        )
+    };
+    match kind {
+        FalseSchema => (json!({}), "boolean schema is false".to_owned()),
+        Type { want, .. } => {
+            let want = join_iter(want.iter(), ",");
+            (json!({"type": want}), format!("must be {want}"))
+        }
+        Enum { want } => (
+            json!({"allowedValues": want}),
+            "must be equal to one of the allowed values".to_owned(),
+        ),
+        Const { want } => (
+            json!({"allowedValue": want}),
+            "must be equal to constant".to_owned(),
+        ),
+        Format { want, .. } => (
+            json!({"format": want}),
+            format!("must match format \"{want}\""),
+        ),
+        Pattern { want, .. } => (
+            json!({"pattern": want}),
+            format!("must match pattern \"{want}\""),
+        ),
+        MinProperties { want, .. } => limit(want, "fewer", "properties"),
+        MaxProperties { want, .. } => limit(want, "more", "properties"),
+        MinItems { want, .. } => limit(want, "fewer", "items"),
+        MaxItems { want, .. } => limit(want, "more", "items"),
+        MinLength { want, .. } => limit(want, "fewer", "characters"),
+        MaxLength { want, .. } => limit(want, "more", "characters"),
+        Minimum { want, .. } => compare(*want, ">="),
+        Maximum { want, .. } => compare(*want, "<="),
+        ExclusiveMinimum { want, .. } => compare(*want, ">"),
+        ExclusiveMaximum { want, .. } => compare(*want, "<"),
+        MultipleOf { want, .. } => (
+            json!({"multipleOf": want}),
+            format!("must be multiple of {want}"),
+        ),
+        UniqueItems { got: [i, j] } => (
+            json!({"i": j, "j": i}),
+            format!("must NOT have duplicate items (items ## {j} and {i} are identical)"),
+        ),
+        Contains => (
+            json!({"minContains": 1}),
+            "must contain at least 1 valid item(s)".to_owned(),
+        ),
+        MinContains { want, .. } => (
+            json!({"minContains": want}),
+            format!("must contain at least {want} valid item(s)"),
+        ),
+        MaxContains { want, .. } => (
+            json!({"maxContains": want}),
+            format!("must contain at most {want} valid item(s)"),
+        ),
+        PropertyName { prop } => (
+            json!({"propertyName": prop}),
+            "property name must be valid".to_owned(),
+        ),
+        Not { .. } => (json!({}), "must NOT be valid".to_owned()),
+        AnyOf => (json!({}), "must match a schema in anyOf".to_owned()),
+        OneOf(passing) => (
+            json!({"passingSchemas": passing.map(|(i, j)| [i, j])}),
+            "must match exactly one schema in oneOf".to_owned(),
+        ),
+        Custom { message, kind } => (ajv_params(kind).0, message.to_string()),
+        Redacted { kind, .. } => ajv_params(kind),
+        kind => (json!({}), kind.to_string()),
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use crate::{CompileError, Compiler, SchemaIndex, Schemas};
+
+impl Schemas {
+    /**
+    Names schema identified by `sch_index` as `name`, so that it can be
+    looked up using [`Schemas::by_alias`]. Returns the schema previously
+    named `name`, if any.
+
+    [`SchemaIndex`] depends on the order in which schemas are compiled.
+    Aliases give stable names to schemas, for example in generated code,
+    which can be persisted using [`Schemas::alias_map`] and restored
+    using [`Compiler::compile_aliases`].
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/customer.json", json!({"required": ["id"]}))?;
+    let sch = compiler.compile("http://a.com/customer.json", &mut schemas)?;
+    schemas.alias(sch, "Customer");
+    assert_eq!(schemas.by_alias("Customer"), Some(sch));
+
+    // in another process
+    let map = schemas.alias_map();
+    let mut other = Schemas::new();
+    compiler.compile_aliases(&map, &mut other)?;
+    let sch = other.by_alias("Customer").unwrap();
+    assert!(other.validate(&json!({}), sch).is_err());
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn alias(&mut self, sch_index: SchemaIndex, name: &str) -> Option<SchemaIndex> {
+        if !self.contains(sch_index) {
+            panic!("Schemas::alias: schema index out of bounds");
+        }
+        self.aliases.insert(name.to_owned(), sch_index)
+    }
+
+    /// Returns the schema named `name` using [`Schemas::alias`].
+    pub fn by_alias(&self, name: &str) -> Option<SchemaIndex> {
+        self.aliases.get(name).copied()
+    }
+
+    /**
+    Returns aliases, mapped to absolute locations of their schemas,
+    like `http://a.com/s.json#/$defs/customer`.
+
+    Unlike [`SchemaIndex`], locations do not depend on the order of
+    compilation. So the map can be serialized, for example along with
+    generated code, and used with [`Compiler::compile_aliases`] to get
+    the same schemas under same names.
+    */
+    pub fn alias_map(&self) -> BTreeMap<String, String> {
+        self.aliases
+            .iter()
+            .map(|(name, idx)| (name.clone(), self.get(*idx).loc.clone()))
+            .collect()
+    }
+}
+
+impl Compiler {
+    /**
+    Compiles locations in `aliases` into `target`, naming each compiled
+    schema with its alias. see [`Schemas::alias_map`]
+
+    # Errors
+
+    returns [`CompileError`], if any location fails to compile. Aliases
+    compiled before the failure are kept.
+    */
+    pub fn compile_aliases(
+        &mut self,
+        aliases: &BTreeMap<String, String>,
+        target: &mut Schemas,
+    ) -> Result<(), CompileError> {
+        for (name, loc) in aliases {
+            let sch = self.compile(loc, target)?;
+            target.alias(sch, name);
+        }
+        Ok(())
+    }
+}
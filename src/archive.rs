@@ -0,0 +1,219 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    error::Error,
+    fs::File,
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use percent_encoding::percent_decode_str;
+use serde_json::Value;
+use url::Url;
+
+use crate::{CompileError, Compiler, UrlLoader};
+
+/**
+Loads json documents from zip and tar archives, using urls like
+`zip:///opt/bundle.zip!/schemas/root.json` or `tar://bundle.tar.gz!/root.json`.
+
+The part between scheme and `!/` is path of the archive, relative
+to current directory unless it starts with `/`. The part after `!`
+is path of the document inside the archive, so that relative
+references between documents in the archive resolve as expected.
+Tar archives may be gzip compressed.
+
+Each archive is read once, on first load from it, and its files are
+kept in memory for subsequent loads.
+
+Scheme `zip` is supported with feature `zip`, and `tar` with feature
+`tar`, along with feature `fs`. This loader is registered for them
+by default. see [`Compiler::add_archive`]
+*/
+#[derive(Default)]
+pub struct ArchiveLoader {
+    // by scheme and archive path
+    archives: RefCell<HashMap<(String, PathBuf), Files>>,
+}
+
+// content of files in archive, by path
+type Files = HashMap<String, Vec<u8>>;
+
+impl ArchiveLoader {
+    /// Creates loader, with no archive read yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl UrlLoader for ArchiveLoader {
+    fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+        let Some((scheme, rest)) = url.split_once("://") else {
+            return Err(format!("{url} is not archive url").into());
+        };
+        let Some((archive, entry)) = rest.split_once("!/") else {
+            return Err(format!("{url} has no '!/' separating archive and entry").into());
+        };
+        let archive = if archive.starts_with('/') {
+            Url::parse(&format!("file://{archive}"))?
+                .to_file_path()
+                .map_err(|_| format!("invalid archive path in {url}"))?
+        } else {
+            PathBuf::from(percent_decode_str(archive).decode_utf8()?.as_ref())
+        };
+        let entry = percent_decode_str(entry).decode_utf8()?;
+        let mut archives = self.archives.borrow_mut();
+        let key = (scheme.to_owned(), archive);
+        let files = match archives.get(&key) {
+            Some(files) => files,
+            None => {
+                let mut files = HashMap::new();
+                for_each_file(scheme, &key.1, |name, content| {
+                    files.insert(name.to_owned(), content.to_vec());
+                    true
+                })?;
+                archives.entry(key.clone()).or_insert(files)
+            }
+        };
+        match files.get(entry.as_ref()) {
+            Some(content) => Ok(serde_json::from_slice(content)?),
+            None => Err(format!("{entry} not found in {}", key.1.display()).into()),
+        }
+    }
+}
+
+impl Compiler {
+    /**
+    Adds all json documents in zip or tar archive at `path`, as schema
+    resources. Documents whose name does not end with `.json` are
+    ignored. Tar archives may be gzip compressed.
+
+    Each document is identified by url of the form
+    `zip://<abs-path>!/<entry>`, which can be used to compile it. see
+    [`ArchiveLoader`]
+
+    ```no_run
+    # use boon::*;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_archive("/opt/bundle.zip")?;
+    let mut schemas = Schemas::new();
+    let sch = compiler.compile("zip:///opt/bundle.zip!/schemas/root.json", &mut schemas)?;
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError::LoadUrlError`], if the archive cannot be read
+    or any of its json documents cannot be parsed.
+    */
+    pub fn add_archive(&mut self, path: impl AsRef<Path>) -> Result<(), CompileError> {
+        let path = path.as_ref();
+        let name = path.to_string_lossy();
+        let scheme = if name.ends_with(".zip") {
+            "zip"
+        } else if name.ends_with(".tar") || name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            "tar"
+        } else {
+            return Err(CompileError::LoadUrlError {
+                url: name.into_owned(),
+                src: "unsupported archive type".into(),
+            });
+        };
+        let base = std::path::absolute(path)
+            .ok()
+            .and_then(|p| Url::from_file_path(p).ok())
+            .map(|url| format!("{scheme}{}", &url.as_str()["file".len()..]))
+            .ok_or_else(|| CompileError::LoadUrlError {
+                url: name.to_string(),
+                src: "invalid archive path".into(),
+            })?;
+
+        let mut docs = vec![];
+        let mut parse_err = None;
+        let result = for_each_file(scheme, path, |entry, content| {
+            if !entry.ends_with(".json") {
+                return true;
+            }
+            let url = format!("{base}!/{entry}");
+            match serde_json::from_slice::<Value>(content) {
+                Ok(doc) => docs.push((url, doc)),
+                Err(e) => parse_err = Some((url, e)),
+            }
+            parse_err.is_none()
+        });
+        if let Err(src) = result {
+            return Err(CompileError::LoadUrlError { url: base, src });
+        }
+        if let Some((url, e)) = parse_err {
+            return Err(CompileError::LoadUrlError { url, src: e.into() });
+        }
+        for (url, doc) in docs {
+            self.add_resource(&url, doc)?;
+        }
+        Ok(())
+    }
+}
+
+// calls `f` with path and content of each file in archive,
+// until it returns false.
+fn for_each_file(
+    scheme: &str,
+    archive: &Path,
+    mut f: impl FnMut(&str, &[u8]) -> bool,
+) -> Result<(), Box<dyn Error>> {
+    let file = BufReader::new(File::open(archive)?);
+    match scheme {
+        #[cfg(feature = "zip")]
+        "zip" => {
+            let mut zip = zip::ZipArchive::new(file)?;
+            for i in 0..zip.len() {
+                let mut entry = zip.by_index(i)?;
+                if !entry.is_file() {
+                    continue;
+                }
+                let name = entry_name(entry.name()).to_owned();
+                let mut content = vec![];
+                entry.read_to_end(&mut content)?;
+                if !f(&name, &content) {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        #[cfg(feature = "tar")]
+        "tar" => {
+            let mut file = file;
+            let gzip = {
+                use std::io::BufRead;
+                file.fill_buf()?.starts_with(&[0x1f, 0x8b])
+            };
+            let reader: Box<dyn Read> = if gzip {
+                Box::new(flate2::read::GzDecoder::new(file))
+            } else {
+                Box::new(file)
+            };
+            let mut tar = tar::Archive::new(reader);
+            for entry in tar.entries()? {
+                let mut entry = entry?;
+                if !entry.header().entry_type().is_file() {
+                    continue;
+                }
+                let name = entry.path()?.to_string_lossy().into_owned();
+                let mut content = vec![];
+                entry.read_to_end(&mut content)?;
+                if !f(entry_name(&name), &content) {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        _ => Err(format!("unsupported archive scheme {scheme}").into()),
+    }
+}
+
+// archive tools may prefix entries with `./`
+fn entry_name(name: &str) -> &str {
+    name.trim_start_matches("./").trim_start_matches('/')
+}
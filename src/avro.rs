@@ -0,0 +1,568 @@
+/*!
+Conversion between [Avro] schemas and JSON Schema.
+
+[`to_json_schema`] converts Avro schema into 2020-12 json schema, to
+validate json renditions of Avro data. [`from_json_schema`] converts
+simple json schemas back into Avro.
+
+```
+# use boon::{avro, Compiler, Schemas};
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let avro = json!({
+    "type": "record",
+    "name": "User",
+    "namespace": "com.example",
+    "fields": [
+        {"name": "name", "type": "string"},
+        {"name": "age", "type": ["null", "int"], "default": null}
+    ]
+});
+let schema = avro::to_json_schema(&avro, avro::Encoding::Plain)?;
+
+let mut schemas = Schemas::new();
+let mut compiler = Compiler::new();
+let sch = compiler.compile_value("http://a.com/user.json", schema, &mut schemas)?;
+assert!(schemas.validate(&json!({"name": "john", "age": 20}), sch).is_ok());
+assert!(schemas.validate(&json!({"name": "john", "age": "20"}), sch).is_err());
+# Ok(())
+# }
+```
+
+[Avro]: https://avro.apache.org/docs/1.11.1/specification/
+*/
+
+use std::{collections::HashSet, error::Error, fmt::Display};
+
+use serde_json::{json, Map, Value};
+
+use crate::util::escape;
+
+/// Error returned by [`to_json_schema`] and [`from_json_schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvroError {
+    /// Json-pointer to the offending construct, in the input schema.
+    pub ptr: String,
+    /// Describes the construct which is invalid or not supported.
+    pub reason: String,
+}
+
+impl Display for AvroError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {:?}", self.reason, self.ptr)
+    }
+}
+
+impl Error for AvroError {}
+
+fn fail<T>(ptr: &str, reason: impl Display) -> Result<T, AvroError> {
+    Err(AvroError {
+        ptr: ptr.to_owned(),
+        reason: reason.to_string(),
+    })
+}
+
+/// How Avro data is rendered as json. see [`to_json_schema`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Values as is. Union value is the value of its branch.
+    Plain,
+    /// Avro [json encoding]. Union value, unless `null`, is an object with
+    /// single member, whose name is the type of its branch.
+    ///
+    /// [json encoding]: https://avro.apache.org/docs/1.11.1/specification/#json-encoding
+    Avro,
+}
+
+// avro -> json schema --
+
+/**
+Converts Avro schema `avro`, given in its json form, into 2020-12 json
+schema that accepts json renditions of the Avro data, in given `encoding`.
+
+- `record` is `object` with `additionalProperties: false`. Fields
+  without `default` are `required`.
+- `int` and `long` are `integer` limited to their range. `float` and
+  `double` are `number`.
+- `bytes` and `fixed` are `string`. `fixed` has exact length.
+- `enum` is `enum` of its symbols.
+- named types are in `$defs`, keyed by their fullname, so that
+  recursive types work.
+- `doc` is `description`, and `default` of fields is `default`.
+- logical types are ignored, except `uuid` which is `format: uuid`.
+
+# Errors
+
+returns [`AvroError`] if `avro` is not a valid Avro schema.
+*/
+pub fn to_json_schema(avro: &Value, encoding: Encoding) -> Result<Value, AvroError> {
+    let mut cv = ToJson {
+        encoding,
+        defs: Map::new(),
+    };
+    let mut sch = cv.convert(avro, "", "")?;
+    if !cv.defs.is_empty() {
+        sch["$defs"] = Value::Object(cv.defs);
+    }
+    sch["$schema"] = json!("https://json-schema.org/draft/2020-12/schema");
+    Ok(sch)
+}
+
+struct ToJson {
+    encoding: Encoding,
+    defs: Map<String, Value>, // fullname -> converted named type
+}
+
+impl ToJson {
+    // `ns` is the enclosing namespace
+    fn convert(&mut self, v: &Value, ptr: &str, ns: &str) -> Result<Value, AvroError> {
+        match v {
+            Value::String(name) => self.convert_name(name, ptr, ns),
+            Value::Array(branches) => {
+                let mut seen = HashSet::new();
+                let mut any_of = vec![];
+                for (i, branch) in branches.iter().enumerate() {
+                    let ptr = format!("{ptr}/{i}");
+                    if matches!(branch, Value::Array(_)) {
+                        return fail(&ptr, "union must not contain union");
+                    }
+                    let name = self.branch_name(branch, ns);
+                    if !seen.insert(name.clone()) {
+                        return fail(&ptr, format!("union has duplicate {name}"));
+                    }
+                    let sch = self.convert(branch, &ptr, ns)?;
+                    any_of.push(match (self.encoding, name.as_str()) {
+                        (Encoding::Plain, _) | (_, "null") => sch,
+                        (Encoding::Avro, _) => json!({
+                            "type": "object",
+                            "properties": {&name: sch},
+                            "required": [name],
+                            "additionalProperties": false
+                        }),
+                    });
+                }
+                Ok(json!({"anyOf": any_of}))
+            }
+            Value::Object(obj) => {
+                let Some(t) = obj.get("type") else {
+                    return fail(ptr, "type is missing");
+                };
+                let mut sch = match t.as_str() {
+                    Some("record" | "error" | "enum" | "fixed") => {
+                        self.convert_named(obj, ptr, ns)?
+                    }
+                    Some("array") => {
+                        let Some(items) = obj.get("items") else {
+                            return fail(ptr, "items is missing");
+                        };
+                        let items = self.convert(items, &format!("{ptr}/items"), ns)?;
+                        json!({"type": "array", "items": items})
+                    }
+                    Some("map") => {
+                        let Some(values) = obj.get("values") else {
+                            return fail(ptr, "values is missing");
+                        };
+                        let values = self.convert(values, &format!("{ptr}/values"), ns)?;
+                        json!({"type": "object", "additionalProperties": values})
+                    }
+                    _ => self.convert(t, &format!("{ptr}/type"), ns)?,
+                };
+                if obj.get("logicalType").and_then(Value::as_str) == Some("uuid") {
+                    sch["format"] = json!("uuid");
+                }
+                Ok(sch)
+            }
+            _ => fail(ptr, "schema must be string, array or object"),
+        }
+    }
+
+    fn convert_name(&mut self, name: &str, ptr: &str, ns: &str) -> Result<Value, AvroError> {
+        let sch = match name {
+            "null" => json!({"type": "null"}),
+            "boolean" => json!({"type": "boolean"}),
+            "int" => json!({"type": "integer", "minimum": i32::MIN, "maximum": i32::MAX}),
+            "long" => json!({"type": "integer", "minimum": i64::MIN, "maximum": i64::MAX}),
+            "float" | "double" => json!({"type": "number"}),
+            "bytes" | "string" => json!({"type": "string"}),
+            _ => {
+                let Some(fullname) = self.resolve(name, ns) else {
+                    return fail(ptr, format!("unknown type {name:?}"));
+                };
+                json!({"$ref": format!("#/$defs/{}", escape(&fullname))})
+            }
+        };
+        Ok(sch)
+    }
+
+    // fullname of named type `name`, if defined
+    fn resolve(&self, name: &str, ns: &str) -> Option<String> {
+        if !name.contains('.') && !ns.is_empty() {
+            let fullname = format!("{ns}.{name}");
+            if self.defs.contains_key(&fullname) {
+                return Some(fullname);
+            }
+        }
+        self.defs.contains_key(name).then(|| name.to_owned())
+    }
+
+    // name used to tag union branch, in avro json encoding
+    fn branch_name(&self, branch: &Value, ns: &str) -> String {
+        let (name, ns) = match branch {
+            Value::String(name) => (name.as_str(), ns),
+            Value::Object(obj) => match obj.get("type").and_then(Value::as_str) {
+                Some("record" | "error" | "enum" | "fixed") => {
+                    let name = obj.get("name").and_then(Value::as_str).unwrap_or_default();
+                    let ns = obj.get("namespace").and_then(Value::as_str).unwrap_or(ns);
+                    (name, ns)
+                }
+                Some(t) => (t, ns),
+                None => ("", ns),
+            },
+            _ => ("", ns),
+        };
+        match name {
+            "null" | "boolean" | "int" | "long" | "float" | "double" | "bytes" | "string"
+            | "array" | "map" => name.to_owned(),
+            _ => self.resolve(name, ns).unwrap_or_else(|| fullname(name, ns)),
+        }
+    }
+
+    // registers named type in $defs, and returns $ref to it
+    fn convert_named(
+        &mut self,
+        obj: &Map<String, Value>,
+        ptr: &str,
+        ns: &str,
+    ) -> Result<Value, AvroError> {
+        let Some(Value::String(name)) = obj.get("name") else {
+            return fail(ptr, "name is missing");
+        };
+        let ns = match obj.get("namespace") {
+            Some(Value::String(ns)) => ns.as_str(),
+            _ => ns,
+        };
+        let fullname = fullname(name, ns);
+        if self.defs.contains_key(&fullname) {
+            return fail(ptr, format!("{fullname} is already defined"));
+        }
+        let ns = fullname
+            .rsplit_once('.')
+            .map_or("", |(ns, _)| ns)
+            .to_owned();
+        // placeholder, for recursive references
+        self.defs.insert(fullname.clone(), Value::Bool(true));
+        let mut sch = match obj.get("type").and_then(Value::as_str) {
+            Some("enum") => Self::enum_(obj, ptr)?,
+            Some("fixed") => Self::fixed(obj, ptr)?,
+            _ => self.record(obj, ptr, &ns)?,
+        };
+        sch["title"] = json!(name.rsplit('.').next().unwrap_or(name));
+        if let Some(Value::String(doc)) = obj.get("doc") {
+            sch["description"] = json!(doc);
+        }
+        self.defs.insert(fullname.clone(), sch);
+        Ok(json!({"$ref": format!("#/$defs/{}", escape(&fullname))}))
+    }
+
+    fn record(
+        &mut self,
+        obj: &Map<String, Value>,
+        ptr: &str,
+        ns: &str,
+    ) -> Result<Value, AvroError> {
+        let Some(Value::Array(fields)) = obj.get("fields") else {
+            return fail(ptr, "fields is missing");
+        };
+        let mut props = Map::new();
+        let mut required = vec![];
+        for (i, field) in fields.iter().enumerate() {
+            let ptr = format!("{ptr}/fields/{i}");
+            let Some(Value::String(name)) = field.get("name") else {
+                return fail(&ptr, "name is missing");
+            };
+            let Some(t) = field.get("type") else {
+                return fail(&ptr, "type is missing");
+            };
+            let mut sch = self.convert(t, &format!("{ptr}/type"), ns)?;
+            if let Some(Value::String(doc)) = field.get("doc") {
+                sch["description"] = json!(doc);
+            }
+            match field.get("default") {
+                Some(default) => sch["default"] = default.clone(),
+                None => required.push(name.clone()),
+            }
+            props.insert(name.clone(), sch);
+        }
+        Ok(json!({
+            "type": "object",
+            "properties": props,
+            "required": required,
+            "additionalProperties": false
+        }))
+    }
+
+    fn enum_(obj: &Map<String, Value>, ptr: &str) -> Result<Value, AvroError> {
+        let Some(Value::Array(symbols)) = obj.get("symbols") else {
+            return fail(ptr, "symbols is missing");
+        };
+        Ok(json!({"enum": symbols}))
+    }
+
+    fn fixed(obj: &Map<String, Value>, ptr: &str) -> Result<Value, AvroError> {
+        let Some(size) = obj.get("size").and_then(Value::as_u64) else {
+            return fail(ptr, "size is missing");
+        };
+        Ok(json!({"type": "string", "minLength": size, "maxLength": size}))
+    }
+}
+
+fn fullname(name: &str, ns: &str) -> String {
+    if name.contains('.') || ns.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{ns}.{name}")
+    }
+}
+
+// json schema -> avro --
+
+/**
+Converts simple json schema `schema` into Avro schema. Records get names
+prefixed with `name`.
+
+Following json schemas are supported:
+- `type` of `null`, `boolean`, `integer`, `number` or `string`. Integer
+  is `int` if `minimum` and `maximum` are within its range, else `long`.
+  List of types is union.
+- `enum` of strings, which are valid Avro names.
+- `array` with `items` schema.
+- `object` with `properties` is `record`. Fields that are not `required`,
+  are union with `null`, having `null` as default.
+- `object` with only `additionalProperties` schema is `map`.
+- `anyOf` or `oneOf` is union.
+
+Other keywords like `minLength`, which have no Avro equivalent, are ignored.
+`description` and `title` are used as `doc`.
+
+# Errors
+
+returns [`AvroError`] if `schema` uses constructs not listed above, like
+`$ref`, or if the result is not valid Avro, like union of two arrays.
+*/
+pub fn from_json_schema(schema: &Value, name: &str) -> Result<Value, AvroError> {
+    let mut names = HashSet::new();
+    from_json(schema, "", name, &mut names)
+}
+
+// `name` is used, if `sch` is record
+fn from_json(
+    sch: &Value,
+    ptr: &str,
+    name: &str,
+    names: &mut HashSet<String>,
+) -> Result<Value, AvroError> {
+    let obj = match sch {
+        Value::Bool(_) => return fail(ptr, "boolean schema is not supported"),
+        Value::Object(obj) => obj,
+        _ => return fail(ptr, "schema must be an object"),
+    };
+    for kw in [
+        "$ref",
+        "$dynamicRef",
+        "allOf",
+        "not",
+        "if",
+        "patternProperties",
+    ] {
+        if obj.contains_key(kw) {
+            return fail(
+                &format!("{ptr}/{}", escape(kw)),
+                format!("{kw} is not supported"),
+            );
+        }
+    }
+
+    let branches = ["anyOf", "oneOf"]
+        .iter()
+        .find_map(|kw| Some((kw, obj.get(*kw)?.as_array()?)));
+    if let Some((kw, branches)) = branches {
+        let mut union = vec![];
+        for (i, branch) in branches.iter().enumerate() {
+            let ptr = format!("{ptr}/{kw}/{i}");
+            let name = format!("{name}{i}");
+            push_branch(&mut union, from_json(branch, &ptr, &name, names)?, &ptr)?;
+        }
+        return Ok(Value::Array(union));
+    }
+
+    if let Some(e) = obj.get("enum") {
+        let symbols = e.as_array().map(Vec::as_slice).unwrap_or_default();
+        if symbols.is_empty() || !symbols.iter().all(|s| s.as_str().is_some_and(is_name)) {
+            return fail(&format!("{ptr}/enum"), "enum must have names as symbols");
+        }
+        let name = unique_name(name, names);
+        return Ok(with_doc(
+            json!({"type": "enum", "name": name, "symbols": e}),
+            obj,
+        ));
+    }
+
+    let types = match obj.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(arr)) => arr.iter().filter_map(Value::as_str).collect(),
+        _ => return fail(ptr, "type is missing"),
+    };
+    let mut union = vec![];
+    for t in &types {
+        let avro = match *t {
+            "null" | "boolean" | "string" => json!(t),
+            "number" => json!("double"),
+            "integer" => {
+                let in_range = |kw: &str| {
+                    obj.get(kw)
+                        .and_then(Value::as_i64)
+                        .is_some_and(|n| i32::try_from(n).is_ok())
+                };
+                match in_range("minimum") && in_range("maximum") {
+                    true => json!("int"),
+                    false => json!("long"),
+                }
+            }
+            "array" => {
+                let Some(items) = obj.get("items") else {
+                    return fail(ptr, "array must have items");
+                };
+                let items = from_json(
+                    items,
+                    &format!("{ptr}/items"),
+                    &format!("{name}Item"),
+                    names,
+                )?;
+                json!({"type": "array", "items": items})
+            }
+            "object" => record_or_map(obj, ptr, name, names)?,
+            t => {
+                return fail(
+                    &format!("{ptr}/type"),
+                    format!("type {t:?} is not supported"),
+                )
+            }
+        };
+        push_branch(&mut union, avro, &format!("{ptr}/type"))?;
+    }
+    match union.len() {
+        1 => Ok(with_doc(union.remove(0), obj)),
+        _ => Ok(Value::Array(union)),
+    }
+}
+
+fn record_or_map(
+    obj: &Map<String, Value>,
+    ptr: &str,
+    name: &str,
+    names: &mut HashSet<String>,
+) -> Result<Value, AvroError> {
+    let props = obj.get("properties").and_then(Value::as_object);
+    if props.is_none() {
+        if let Some(values) = obj.get("additionalProperties").filter(|v| v.is_object()) {
+            let ptr = format!("{ptr}/additionalProperties");
+            let values = from_json(values, &ptr, &format!("{name}Value"), names)?;
+            return Ok(json!({"type": "map", "values": values}));
+        }
+    }
+    let required: Vec<_> = match obj.get("required") {
+        Some(Value::Array(arr)) => arr.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    let record_name = unique_name(name, names);
+    let mut fields = vec![];
+    for (pname, psch) in props.into_iter().flatten() {
+        let ptr = format!("{ptr}/properties/{}", escape(pname));
+        if !is_name(pname) {
+            return fail(&ptr, format!("property {pname:?} is not valid avro name"));
+        }
+        let field_name = format!("{record_name}{}", capitalize(pname));
+        let t = from_json(psch, &ptr, &field_name, names)?;
+        let mut field = json!({"name": pname});
+        if required.contains(&pname.as_str()) {
+            field["type"] = t;
+        } else {
+            let mut union = vec![json!("null")];
+            match t {
+                Value::Array(branches) => {
+                    for b in branches {
+                        push_branch(&mut union, b, &ptr)?;
+                    }
+                }
+                t => push_branch(&mut union, t, &ptr)?,
+            }
+            field["type"] = Value::Array(union);
+            field["default"] = Value::Null;
+        }
+        if let Some(Value::String(doc)) = psch.get("description") {
+            field["doc"] = json!(doc);
+        }
+        fields.push(field);
+    }
+    Ok(json!({"type": "record", "name": record_name, "fields": fields}))
+}
+
+// adds `branch` to `union`, failing if union already has branch of same kind
+fn push_branch(union: &mut Vec<Value>, branch: Value, ptr: &str) -> Result<(), AvroError> {
+    let kind = |v: &Value| match v {
+        Value::Object(obj) => match obj.get("type").and_then(Value::as_str) {
+            Some("record" | "enum" | "fixed") => obj.get("name").cloned().unwrap_or_default(),
+            _ => obj.get("type").cloned().unwrap_or_default(),
+        },
+        v => v.clone(),
+    };
+    let branches = match branch {
+        Value::Array(branches) => branches,
+        branch => vec![branch],
+    };
+    for branch in branches {
+        if union.iter().any(|b| kind(b) == kind(&branch)) {
+            if kind(&branch) == "null" {
+                continue;
+            }
+            return fail(ptr, format!("union has duplicate {}", kind(&branch)));
+        }
+        union.push(branch);
+    }
+    Ok(())
+}
+
+fn with_doc(mut avro: Value, obj: &Map<String, Value>) -> Value {
+    let doc = obj.get("description").or_else(|| obj.get("title"));
+    if let (Some(Value::String(doc)), Value::Object(avro)) = (doc, &mut avro) {
+        avro.insert("doc".to_owned(), json!(doc));
+    }
+    avro
+}
+
+fn unique_name(name: &str, names: &mut HashSet<String>) -> String {
+    let mut unique = name.to_owned();
+    let mut i = 1;
+    while !names.insert(unique.clone()) {
+        i += 1;
+        unique = format!("{name}{i}");
+    }
+    unique
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// [A-Za-z_][A-Za-z0-9_]*
+fn is_name(s: &str) -> bool {
+    let mut bytes = s.bytes();
+    bytes
+        .next()
+        .is_some_and(|b| b.is_ascii_alphabetic() || b == b'_')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
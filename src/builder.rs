@@ -0,0 +1,235 @@
+use std::{error::Error, sync::Arc};
+
+use serde_json::Value;
+
+use crate::{
+    Compiler, Decoder, Dialect, Draft, Format, MediaType, Metrics, OptimizationLevel, UrlLoader,
+    UrlMapper,
+};
+
+/**
+Fluent alternative to configuring [`Compiler`] using its setters.
+
+Each method calls the [`Compiler`] method of same name, so see
+there for details.
+
+```
+# use boon::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let mut compiler = Compiler::builder()
+    .default_draft(Draft::V7)
+    .enable_format_assertions()
+    .deny_unknown_keywords()
+    .build();
+
+let mut schemas = Schemas::new();
+compiler.add_resource("http://a.com/s.json", json!({"format": "ipv4"}))?;
+let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+assert_eq!(schemas.draft(sch), Draft::V7);
+assert!(schemas.validate(&json!("1.2.3"), sch).is_err());
+# Ok(())
+# }
+```
+*/
+#[derive(Default)]
+pub struct CompilerBuilder {
+    c: Compiler,
+}
+
+impl CompilerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// see [`Compiler::set_default_draft`]
+    pub fn default_draft(mut self, d: Draft) -> Self {
+        self.c.set_default_draft(d);
+        self
+    }
+
+    /// see [`Compiler::set_dialect`]
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.c.set_dialect(dialect);
+        self
+    }
+
+    /// see [`Compiler::enable_format_assertions`]
+    pub fn enable_format_assertions(mut self) -> Self {
+        self.c.enable_format_assertions();
+        self
+    }
+
+    /// see [`Compiler::enable_content_assertions`]
+    pub fn enable_content_assertions(mut self) -> Self {
+        self.c.enable_content_assertions();
+        self
+    }
+
+    /// see [`Compiler::enable_lazy_compilation`]
+    pub fn enable_lazy_compilation(mut self) -> Self {
+        self.c.enable_lazy_compilation();
+        self
+    }
+
+    /// see [`Compiler::enable_resource_dedup`]
+    pub fn enable_resource_dedup(mut self) -> Self {
+        self.c.enable_resource_dedup();
+        self
+    }
+
+    /// see [`Compiler::set_one_of_discriminator`]
+    pub fn one_of_discriminator(mut self, prop: &str) -> Self {
+        self.c.set_one_of_discriminator(prop);
+        self
+    }
+
+    /// see [`Compiler::enable_openapi_discriminator`]
+    pub fn enable_openapi_discriminator(mut self) -> Self {
+        self.c.enable_openapi_discriminator();
+        self
+    }
+
+    /// see [`Compiler::deny_unknown_keywords`]
+    pub fn deny_unknown_keywords(mut self) -> Self {
+        self.c.deny_unknown_keywords();
+        self
+    }
+
+    /// see [`Compiler::deny_unknown_formats`]
+    pub fn deny_unknown_formats(mut self) -> Self {
+        self.c.deny_unknown_formats();
+        self
+    }
+
+    /// see [`Compiler::deny_dangling_dynamic_refs`]
+    pub fn deny_dangling_dynamic_refs(mut self) -> Self {
+        self.c.deny_dangling_dynamic_refs();
+        self
+    }
+
+    /// see [`Compiler::enable_custom_error_messages`]
+    pub fn enable_custom_error_messages(mut self) -> Self {
+        self.c.enable_custom_error_messages();
+        self
+    }
+
+    /// see [`Compiler::enable_schema_hints`]
+    pub fn enable_schema_hints(mut self) -> Self {
+        self.c.enable_schema_hints();
+        self
+    }
+
+    /// see [`Compiler::enable_kubernetes_extensions`]
+    pub fn enable_kubernetes_extensions(mut self) -> Self {
+        self.c.enable_kubernetes_extensions();
+        self
+    }
+
+    /// see [`Compiler::set_strict_integers`]
+    pub fn strict_integers(mut self, strict: bool) -> Self {
+        self.c.set_strict_integers(strict);
+        self
+    }
+
+    /// see [`Compiler::set_optimization_level`]
+    pub fn optimization_level(mut self, level: OptimizationLevel) -> Self {
+        self.c.set_optimization_level(level);
+        self
+    }
+
+    /// see [`Compiler::enable_ref_inlining`]
+    pub fn enable_ref_inlining(mut self, max_keywords: usize) -> Self {
+        self.c.enable_ref_inlining(max_keywords);
+        self
+    }
+
+    /// see [`Compiler::set_max_schema_depth`]
+    pub fn max_schema_depth(mut self, max: usize) -> Self {
+        self.c.set_max_schema_depth(max);
+        self
+    }
+
+    /// see [`Compiler::set_metrics`]
+    pub fn metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.c.set_metrics(metrics);
+        self
+    }
+
+    /// see [`Compiler::use_loader`]
+    pub fn loader(mut self, url_loader: Box<dyn UrlLoader>) -> Self {
+        self.c.use_loader(url_loader);
+        self
+    }
+
+    /// see [`Compiler::use_url_mapper`]
+    pub fn url_mapper(mut self, mapper: UrlMapper) -> Self {
+        self.c.use_url_mapper(mapper);
+        self
+    }
+
+    /// see [`Compiler::set_load_extensions`]
+    pub fn load_extensions(mut self, extensions: &[&'static str]) -> Self {
+        self.c.set_load_extensions(extensions);
+        self
+    }
+
+    /// see [`Compiler::require_preloaded_resources`]
+    pub fn require_preloaded_resources(mut self) -> Self {
+        self.c.require_preloaded_resources();
+        self
+    }
+
+    /// see [`Compiler::deny_duplicate_keys`]
+    pub fn deny_duplicate_keys(mut self) -> Self {
+        self.c.deny_duplicate_keys();
+        self
+    }
+
+    /// see [`Compiler::register_format`]
+    pub fn format(mut self, format: Format) -> Self {
+        self.c.register_format(format);
+        self
+    }
+
+    /// see [`Compiler::register_format_fn`]
+    pub fn format_fn<F>(mut self, name: &'static str, func: F) -> Self
+    where
+        F: Fn(&Value) -> Result<(), Box<dyn Error>> + Send + Sync + 'static,
+    {
+        self.c.register_format_fn(name, func);
+        self
+    }
+
+    /// see [`Compiler::override_format`]
+    pub fn override_format<F>(mut self, name: &'static str, func: F) -> Self
+    where
+        F: Fn(&Value) -> Result<(), Box<dyn Error>> + Send + Sync + 'static,
+    {
+        self.c.override_format(name, func);
+        self
+    }
+
+    /// see [`Compiler::unregister_format`]
+    pub fn unregister_format(mut self, name: &'static str) -> Self {
+        self.c.unregister_format(name);
+        self
+    }
+
+    /// see [`Compiler::register_content_encoding`]
+    pub fn content_encoding(mut self, decoder: Decoder) -> Self {
+        self.c.register_content_encoding(decoder);
+        self
+    }
+
+    /// see [`Compiler::register_content_media_type`]
+    pub fn content_media_type(mut self, media_type: MediaType) -> Self {
+        self.c.register_content_media_type(media_type);
+        self
+    }
+
+    /// Returns the configured [`Compiler`].
+    pub fn build(self) -> Compiler {
+        self.c
+    }
+}
@@ -1,10 +1,24 @@
-use std::{cmp::Ordering, collections::HashMap, error::Error, fmt::Display};
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicUsize, Ordering as AtomicOrdering},
+        Arc,
+    },
+    time::Instant,
+};
 
 use regex::Regex;
 use serde_json::{Map, Value};
 use url::Url;
 
-use crate::{content::*, draft::*, ecma, formats::*, root::*, roots::*, util::*, *};
+use crate::{
+    content::*, draft::*, ecma, formats::*, kubernetes::check_structural, loader::MetaSchema,
+    root::*, roots::*, util::*, *,
+};
 
 /// Supported draft versions
 #[non_exhaustive]
@@ -39,16 +53,50 @@ impl Draft {
     ```
     */
     pub fn from_url(url: &str) -> Option<Draft> {
-        match crate::draft::Draft::from_url(url) {
-            Some(draft) => match draft.version {
-                4 => Some(Draft::V4),
-                6 => Some(Draft::V6),
-                7 => Some(Draft::V7),
-                2019 => Some(Draft::V2019_09),
-                2020 => Some(Draft::V2020_12),
-                _ => None,
-            },
-            None => None,
+        crate::draft::Draft::from_url(url).and_then(|draft| Self::from_version(draft.version))
+    }
+
+    /// Returns all drafts supported, oldest first.
+    pub fn all() -> [Draft; 5] {
+        [
+            Draft::V4,
+            Draft::V6,
+            Draft::V7,
+            Draft::V2019_09,
+            Draft::V2020_12,
+        ]
+    }
+
+    /// Returns version number of this draft, like `7` or `2020`.
+    pub fn version(&self) -> usize {
+        self.internal().version
+    }
+
+    /// Returns url of the metaschema of this draft.
+    pub fn url(&self) -> &'static str {
+        self.internal().url
+    }
+
+    /**
+    Tells whether this draft is enabled by its cargo feature, like
+    `draft7` or `draft2020`. All drafts are enabled by default.
+
+    Disable default features, to reduce binary size by not bundling
+    metaschemas of drafts you do not use. Compiling schema of disabled
+    draft fails with [`CompileError::DraftNotEnabled`].
+    */
+    pub fn is_enabled(&self) -> bool {
+        self.internal().enabled()
+    }
+
+    pub(crate) fn from_version(version: usize) -> Option<Draft> {
+        match version {
+            4 => Some(Draft::V4),
+            6 => Some(Draft::V6),
+            7 => Some(Draft::V7),
+            2019 => Some(Draft::V2019_09),
+            2020 => Some(Draft::V2020_12),
+            _ => None,
         }
     }
 
@@ -70,15 +118,100 @@ impl Default for Draft {
     }
 }
 
+/// Optimizations applied to compiled schemas.
+///
+/// see [`Compiler::set_optimization_level`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OptimizationLevel {
+    /// Subschemas are evaluated in the order they appear in schema.
+    #[default]
+    None,
+    /// `allOf` subschemas are evaluated cheapest first, so that
+    /// boolean checks, like `not` and `if`, fail as early as possible.
+    /// Other keywords are not reordered.
+    ///
+    /// Order of errors reported within `allOf` may differ from
+    /// the order of subschemas.
+    Reorder,
+    /// In addition to `Reorder`, trivially true subschemas, like `true`
+    /// and `{}`, are dropped from `$ref`, `allOf`, `then`, `else`,
+    /// `propertyNames`, `dependentSchemas` and `contentSchema`. And
+    /// subschema `{"allOf": [S]}` is replaced with `S` where it is used.
+    ///
+    /// This reduces subschemas evaluated, for schemas generated by tools.
+    /// Subschemas no longer referred are removed from [`Schemas`], so their
+    /// locations are compiled again, if asked later. Use lower level, to see
+    /// every subschema in traces while debugging.
+    Fold,
+}
+
+/// Language in which schemas are written.
+///
+/// see [`Compiler::set_dialect`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// JSON Schema, whose draft is given by `$schema`.
+    #[default]
+    JsonSchema,
+    /// JSON Type Definition, as per [rfc8927].
+    ///
+    /// [rfc8927]: https://www.rfc-editor.org/rfc/rfc8927
+    Jtd,
+}
+
+/// Kind of [`SchemaAnchor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnchorKind {
+    /// Schema resource, which is the document itself or identified by `$id`.
+    Resource,
+    /// `$anchor`, or `id`/`$id` with plain name fragment in drafts before 2019-09.
+    Anchor,
+    /// `$dynamicAnchor`.
+    DynamicAnchor,
+}
+
+/// Resource or anchor in a schema document.
+///
+/// see [`Compiler::anchors`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaAnchor {
+    /// Whether it is a resource or an anchor, and kind of anchor.
+    pub kind: AnchorKind,
+    /// Absolute url identifying it, like `http://a.com/s.json#node`.
+    pub url: String,
+    /// Json-pointer of the subschema in the document, like `/$defs/node`.
+    pub ptr: String,
+    /// Whether an anchor with same url is found elsewhere in the
+    /// resource, which fails compilation with [`CompileError::DuplicateAnchor`].
+    pub duplicate: bool,
+}
+
 /// JsonSchema compiler.
 #[derive(Default)]
 pub struct Compiler {
     roots: Roots,
     assert_format: bool,
     assert_content: bool,
-    formats: HashMap<&'static str, Format>,
-    decoders: HashMap<&'static str, Decoder>,
-    media_types: HashMap<&'static str, MediaType>,
+    formats: HashMap<&'static str, Option<DynFormat>>, // None means unregistered
+    decoders: HashMap<&'static str, DynDecoder>,
+    media_types: HashMap<&'static str, DynMediaType>,
+    lazy: bool,
+    discriminator: Option<String>,
+    openapi_discriminator: bool,
+    deny_unknown_keywords: bool,
+    deny_unknown_formats: bool,
+    deny_dangling_dynamic_refs: bool,
+    diagnostics: Vec<CompileError>,
+    error_messages: bool,
+    schema_hints: bool,
+    kubernetes: bool,
+    strict_integers: Option<bool>,
+    optimization: OptimizationLevel,
+    inline_limit: Option<usize>, // see Compiler::enable_ref_inlining
+    inlined_refs: usize,
+    regexes: RefCell<HashMap<String, Arc<Regex>>>, // keyed by converted pattern
+    metrics: Option<Arc<dyn Metrics>>,
+    content_index: Option<HashMap<u64, Vec<Url>>>, // Some if dedup enabled
 }
 
 impl Compiler {
@@ -86,6 +219,11 @@ impl Compiler {
         Self::default()
     }
 
+    /// Returns [`CompilerBuilder`] to configure a new compiler.
+    pub fn builder() -> CompilerBuilder {
+        CompilerBuilder::new()
+    }
+
     /**
     Overrides the draft used to compile schemas without
     explicit `$schema` field.
@@ -129,23 +267,574 @@ impl Compiler {
         self.assert_content = true;
     }
 
+    /**
+    Defers compilation of `$ref` targets in other documents, until
+    they are needed for validation.
+
+    This reduces startup cost, when schemas refer large graph of
+    documents, most of which are rarely used. Use
+    [`Compiler::validate_lazily`] to compile the referenced schemas
+    on demand. [`Schemas::validate`] fails with [`ErrorKind::Uncompiled`]
+    if it hits a reference which is not compiled yet.
+
+    Note that errors in deferred documents are reported only when
+    they are compiled.
+    */
+    pub fn enable_lazy_compilation(&mut self) {
+        self.lazy = true;
+    }
+
+    /**
+    Dispatches `oneOf` on object instances directly to the subschema
+    whose property `prop` matches that of the instance.
+
+    A subschema matches the tag value, if it, or the schemas it refers
+    to using `$ref` or `allOf`, constrain `prop` to that value using
+    `const` or `enum`, or if it is a `$ref` whose target location ends
+    with the tag value. Other subschemas are not evaluated. If no
+    subschema matches the tag, all of them are evaluated as usual.
+
+    OpenAPI `discriminator` keyword, if enabled using
+    [`Compiler::enable_openapi_discriminator`], takes precedence
+    over this.
+    */
+    pub fn set_one_of_discriminator(&mut self, prop: &str) {
+        self.discriminator = Some(prop.to_owned());
+    }
+
+    /**
+    Dispatches `oneOf`/`anyOf` using OpenAPI `discriminator` keyword,
+    as described in [`Compiler::set_one_of_discriminator`]. Tags listed
+    in its `mapping` dispatch to the subschema referring to the mapped
+    schema.
+
+    By default, `discriminator` is treated as annotation, as JSON Schema
+    requires.
+    */
+    pub fn enable_openapi_discriminator(&mut self) {
+        self.openapi_discriminator = true;
+    }
+
+    /**
+    Fails compilation with [`CompileError::UnknownKeyword`] if a schema
+    uses keywords, which are not defined by its draft.
+
+    This catches typos like `exclusiveMinmum`, which are otherwise
+    silently ignored. OpenAPI `discriminator` is also accepted if
+    [`Compiler::enable_openapi_discriminator`], and so is `errorMessage`
+    if [`Compiler::enable_custom_error_messages`],
+    `x-boon` if [`Compiler::enable_schema_hints`], and `nullable` and
+    `x-kubernetes-*` if [`Compiler::enable_kubernetes_extensions`].
+
+    Note that keywords from custom vocabularies are rejected too.
+    */
+    pub fn deny_unknown_keywords(&mut self) {
+        self.deny_unknown_keywords = true;
+    }
+
+    /**
+    Fails compilation with [`CompileError::UnknownFormat`] if a schema
+    uses `format` with no registered implementation.
+
+    This catches typos like `date-tiem`, which are otherwise silently
+    ignored. Formats in schemas where `format` is ignored are not checked.
+    see [`Schemas::format_mode`]
+    */
+    pub fn deny_unknown_formats(&mut self) {
+        self.deny_unknown_formats = true;
+    }
+
+    /**
+    Fails compilation with [`CompileError::DanglingDynamicRef`] if
+    `$dynamicRef` or `$recursiveRef` of a schema can never resolve
+    dynamically.
+
+    `$dynamicRef` with anchor resolves dynamically, only if its initial
+    target has `$dynamicAnchor` with same name, and `$recursiveRef` only
+    if its initial target has `"$recursiveAnchor": true`. Otherwise they
+    silently behave like `$ref`, which usually is a typo in anchor name.
+    Without this, such errors are collected in [`Compiler::diagnostics`].
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.deny_dangling_dynamic_refs();
+    let result = compiler.compile_value("http://a.com/tree.json", json!({
+        "$dynamicAnchor": "nodes",
+        "$anchor": "node",
+        "items": {"$dynamicRef": "#node"}
+    }), &mut schemas);
+    assert!(matches!(result, Err(CompileError::DanglingDynamicRef { .. })));
+    ```
+    */
+    pub fn deny_dangling_dynamic_refs(&mut self) {
+        self.deny_dangling_dynamic_refs = true;
+    }
+
+    /**
+    Returns problems found so far during compilation, which do not fail it.
+
+    Currently these are [`CompileError::DanglingDynamicRef`], unless
+    [`Compiler::deny_dangling_dynamic_refs`] is used.
+    */
+    pub fn diagnostics(&self) -> &[CompileError] {
+        &self.diagnostics
+    }
+
+    /**
+    Reports messages given by `errorMessage` keyword of a schema,
+    instead of generated ones, like [ajv-errors] does.
+
+    `errorMessage` is either a string, which replaces all errors of
+    the schema including those of its subschemas with single error, or
+    an object mapping keywords of the schema to message for their error.
+    Errors are reported as [`ErrorKind::Custom`]. Messages are used as is,
+    without any placeholders. Invalid `errorMessage` values are ignored.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_custom_error_messages();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "type": "string",
+        "minLength": 8,
+        "errorMessage": {"minLength": "password is too short"}
+    }))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let instance = json!("secret");
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    assert_eq!(err.causes[0].kind.to_string(), "password is too short");
+    # Ok(())
+    # }
+    ```
+
+    [ajv-errors]: https://github.com/ajv-validator/ajv-errors
+    */
+    pub fn enable_custom_error_messages(&mut self) {
+        self.error_messages = true;
+    }
+
+    /**
+    Reads validation hints given by `x-boon` keyword of a schema, so
+    that schema authors can tune validation without forking the schema.
+
+    `x-boon` is an object with following optional members:
+    - `skip`: keywords of the schema, which are ignored as if absent.
+      For example to skip expensive `uniqueItems` on large arrays.
+    - `warn`: keywords of the schema, whose errors do not fail validation.
+      Such errors are reported as warnings by [`Schemas::validate_with_warnings`],
+      and are ignored by other validate methods.
+
+    Hints apply only to the schema carrying them, not to its subschemas.
+    In `warn`, errors from subschemas, like those of `properties`, are
+    not affected. Invalid hints are ignored.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_schema_hints();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "properties": {"name": {"type": "string"}},
+        "additionalProperties": false,
+        "x-boon": {"warn": ["additionalProperties"]}
+    }))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let instance = json!({"name": "john", "age": 20});
+    let options = ValidationOptions::default();
+    let warnings = schemas.validate_with_warnings(&instance, sch, &options).unwrap();
+    assert_eq!(warnings.len(), 1);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn enable_schema_hints(&mut self) {
+        self.schema_hints = true;
+    }
+
+    /**
+    Compiles schemas as Kubernetes [structural schemas], used by
+    CustomResourceDefinitions, so that custom resources are validated
+    as the API server does.
+
+    - `x-kubernetes-int-or-string: true` allows integer or string.
+    - `nullable: true` allows `null` in addition to `type`.
+    - `x-kubernetes-preserve-unknown-fields` and `x-kubernetes-embedded-resource`
+      are honored by [`Schemas::prune_unknown_fields`].
+    - schema passed to [`Compiler::compile`] fails with
+      [`CompileError::NonStructural`] if it is not structural.
+
+    CRD schemas are OpenAPI v3.0 schemas, so use `Draft::V4` as default
+    draft. `x-kubernetes-validations` are not evaluated, since they use
+    [CEL] expressions.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.set_default_draft(Draft::V4);
+    compiler.enable_kubernetes_extensions();
+    compiler.add_resource("http://a.com/crd.json", json!({
+        "type": "object",
+        "properties": {
+            "spec": {
+                "type": "object",
+                "properties": {"port": {"x-kubernetes-int-or-string": true}}
+            }
+        }
+    }))?;
+    let sch = compiler.compile("http://a.com/crd.json", &mut schemas)?;
+
+    let mut cr = json!({"spec": {"port": "http", "replicas": 1}});
+    assert_eq!(schemas.prune_unknown_fields(&mut cr, sch), ["/spec/replicas"]);
+    assert!(schemas.validate(&cr, sch).is_ok());
+    assert!(schemas.validate(&json!({"spec": {"port": true}}), sch).is_err());
+    # Ok(())
+    # }
+    ```
+
+    [structural schemas]: https://kubernetes.io/docs/tasks/extend-kubernetes/custom-resources/custom-resource-definitions/#specifying-a-structural-schema
+    [CEL]: https://kubernetes.io/docs/reference/using-api/cel/
+    */
+    pub fn enable_kubernetes_extensions(&mut self) {
+        self.kubernetes = true;
+    }
+
+    /**
+    Sets the language in which resources are written. Resources added
+    or loaded after this call, are interpreted in `dialect`.
+
+    [`Dialect::Jtd`] resources are converted into equivalent 2020-12
+    schemas, and validated with same engine. So schema locations in
+    errors refer to the converted schema, where `definitions` is
+    `$defs`, `elements` is `items`, `values` is `additionalProperties`,
+    and `optionalProperties` are merged into `properties`. Invalid JTD
+    schema fails with [`CompileError::InvalidJtdSchema`]. `timestamp`
+    is always asserted.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.set_dialect(Dialect::Jtd);
+    compiler.add_resource("http://a.com/user.jtd.json", json!({
+        "properties": {"name": {"type": "string"}, "age": {"type": "uint8"}},
+        "optionalProperties": {"tags": {"elements": {"type": "string"}}}
+    }))?;
+    let sch = compiler.compile("http://a.com/user.jtd.json", &mut schemas)?;
+    assert!(schemas.validate(&json!({"name": "a", "age": 20}), sch).is_ok());
+    assert!(schemas.validate(&json!({"name": "a", "age": 256}), sch).is_err());
+    assert!(schemas.validate(&json!({"name": "a", "age": 20, "x": 1}), sch).is_err());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.roots.loader.set_dialect(dialect);
+    }
+
+    /**
+    Controls whether numbers with zero fractional part, like `1.0`,
+    are accepted by `"type": "integer"`.
+
+    # Default Behavior
+
+    - for draft-04: not accepted
+    - for draft-06 and later: accepted
+    */
+    pub fn set_strict_integers(&mut self, strict: bool) {
+        self.strict_integers = Some(strict);
+    }
+
+    /**
+    Sets the optimizations applied to compiled schemas.
+
+    Optimizations only change how fast instances are validated,
+    not whether they are valid. They do not change the order in which
+    keywords of a schema are checked, which is fixed: `type`, `const`,
+    `enum` and `format` are checked first, then `$ref`, type specific
+    keywords and applicators like `allOf`.
+    */
+    pub fn set_optimization_level(&mut self, level: OptimizationLevel) {
+        self.optimization = level;
+    }
+
+    /**
+    Inlines `$ref` targets having at most `max_keywords` keywords into
+    the referring schema, so that validation does not go through the
+    reference. Use [`Compiler::inlined_refs`] to find how many are inlined.
+
+    A target is inlined only if it has no subschemas, so that it cannot
+    depend on dynamic scope, and its keywords do not interact with those
+    of the referring schema, for example `additionalProperties` with
+    `properties`. `$ref` in `oneOf`/`anyOf` with discriminator is not inlined.
+
+    Errors of inlined keywords are reported at the referring schema,
+    without `$ref` in their keyword location.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_ref_inlining(4);
+    compiler.compile_value("http://a.com/s.json", json!({
+        "$defs": {"pos": {"type": "integer", "minimum": 1}},
+        "properties": {"a": {"$ref": "#/$defs/pos"}}
+    }), &mut schemas)?;
+    assert_eq!(compiler.inlined_refs(), 1);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn enable_ref_inlining(&mut self, max_keywords: usize) {
+        self.inline_limit = Some(max_keywords);
+    }
+
+    /// Returns number of `$ref`s inlined so far.
+    /// see [`Compiler::enable_ref_inlining`]
+    pub fn inlined_refs(&self) -> usize {
+        self.inlined_refs
+    }
+
+    /**
+    Limits the nesting depth of subschemas in schema documents. Root
+    schema has depth 1, and each subschema adds 1 to the depth of its
+    parent.
+
+    Schema exceeding the limit fails compilation with
+    [`CompileError::MaxDepthExceeded`]. Schema documents are traversed
+    without recursion, so this is meant to reject machine-generated
+    schemas that are too deep to be validated.
+
+    By default, there is no limit.
+    */
+    pub fn set_max_schema_depth(&mut self, max: usize) {
+        self.roots.max_depth = Some(max);
+    }
+
+    /// Reports calls to [`Compiler::compile`] to `metrics`.
+    /// see [`Schemas::set_metrics`] to report validations.
+    pub fn set_metrics(&mut self, metrics: Arc<dyn Metrics>) {
+        self.metrics = Some(metrics);
+    }
+
     /// Overrides default [`UrlLoader`] used to load schema resources
     pub fn use_loader(&mut self, url_loader: Box<dyn UrlLoader>) {
         self.roots.loader.use_loader(url_loader);
     }
 
+    /// Uses given [`UrlMapper`] to rewrite urls before loading them.
+    pub fn use_url_mapper(&mut self, mapper: UrlMapper) {
+        self.roots.loader.use_mapper(mapper);
+    }
+
+    /**
+    Sets the extensions to try, in order, when loading a url.
+
+    Each extension is appended to the url path and the first one
+    that loads successfully is used. Use `""` to try the url as is.
+    This is useful when `$ref` values omit the file extension, for
+    example `meta/core` stored on disk as `meta/core.json`.
+
+    ```
+    # use boon::*;
+    # let mut compiler = Compiler::new();
+    compiler.set_load_extensions(&["", ".json", ".yaml"]);
+    ```
+
+    By default the url is loaded as is.
+    */
+    pub fn set_load_extensions(&mut self, extensions: &[&'static str]) {
+        self.roots.loader.set_extensions(extensions);
+    }
+
+    /**
+    Denies loading of resources, so that only the ones added with
+    [`Compiler::add_resource`] and the like, and standard metaschemas
+    are used.
+
+    This is useful when all resources are supplied upfront. Without it,
+    a typo in `$ref` tries to load the url using [`UrlLoader`], failing
+    with confusing errors like file not found. With it, compilation fails
+    fast with [`CompileError::UnresolvedReference`] caused by
+    [`CompileError::NotPreloaded`].
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.require_preloaded_resources();
+    compiler.add_resource("http://a.com/person.json", json!({"type": "object"}))?;
+    compiler.add_resource("http://a.com/team.json", json!({
+        "items": {"$ref": "persn.json"}
+    }))?;
+    let Err(CompileError::UnresolvedReference { src, .. }) =
+        compiler.compile("http://a.com/team.json", &mut schemas)
+    else {
+        panic!("compilation must fail");
+    };
+    assert!(matches!(*src, CompileError::NotPreloaded { .. }));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn require_preloaded_resources(&mut self) {
+        self.roots.loader.require_preloaded();
+    }
+
+    /**
+    Fails compilation with [`CompileError::DuplicateKey`], if any object
+    in a schema document has duplicate keys. By default, `serde_json`
+    silently keeps the last of them.
+
+    This applies to documents loaded by the builtin `file` and `data`
+    url loaders. Custom [`UrlLoader`]s can parse with [`parse_strict`],
+    whose [`InstanceError::DuplicateKey`] is reported the same way.
+    Documents given to [`Compiler::add_resource`] are already parsed,
+    so they are not checked.
+
+    ```
+    # use boon::*;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.deny_duplicate_keys();
+    let url = "data:application/json,{\"type\": \"string\", \"type\": \"number\"}";
+    let result = compiler.compile(url, &mut schemas);
+    assert!(matches!(result, Err(CompileError::DuplicateKey { ref key, .. }) if key == "type"));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn deny_duplicate_keys(&mut self) {
+        self.roots.loader.deny_duplicate_keys();
+    }
+
     /**
     Registers custom `format`
 
     # Note
 
-    - `regex` format cannot be overridden
+    - `regex` format cannot be overridden, use [`Compiler::override_format`]
     -  format assertions are disabled for draft >= 2019-09.
        see [`Compiler::enable_format_assertions`]
     */
     pub fn register_format(&mut self, format: Format) {
         if format.name != "regex" {
-            self.formats.insert(format.name, format);
+            self.formats.insert(format.name, Some(format.into()));
+        }
+    }
+
+    /**
+    Registers custom `format` named `name`, whose validation is done by
+    closure `func`.
+
+    Unlike [`Compiler::register_format`], `func` can capture state,
+    such as a lookup table.
+
+    ```
+    # use boon::*;
+    # use serde_json::Value;
+    let codes = ["IN", "US"];
+    let mut compiler = Compiler::new();
+    compiler.register_format_fn("country", move |v: &Value| {
+        let Value::String(s) = v else {
+            return Ok(()); // applicable only on strings
+        };
+        if !codes.contains(&s.as_str()) {
+            Err("unknown country code")?
+        }
+        Ok(())
+    });
+    ```
+
+    same notes as in [`Compiler::register_format`] apply.
+    */
+    pub fn register_format_fn<F>(&mut self, name: &'static str, func: F)
+    where
+        F: Fn(&Value) -> Result<(), Box<dyn Error>> + Send + Sync + 'static,
+    {
+        if name != "regex" {
+            let func = Arc::new(func);
+            self.formats.insert(name, Some(DynFormat { name, func }));
+        }
+    }
+
+    /**
+    Uses `func` to validate format `name`, replacing the built-in or
+    previously registered format of that name, including `regex`.
+
+    Formats are looked up in the following order, first match wins:
+
+    1. [`ValidationOptions::override_format`], at validation time,
+       for formats known at compile time
+    2. formats registered or overridden with this compiler
+    3. built-in formats
+
+    ```
+    # use boon::*;
+    # use serde_json::{json, Value};
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.override_format("email", |v: &Value| match v {
+        Value::String(s) if !s.ends_with("@example.com") => Err("must be example.com email")?,
+        _ => Ok(()),
+    });
+    compiler.add_resource("http://a.com/s.json", json!({"format": "email"}))?;
+    let mut schemas = Schemas::new();
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    assert!(schemas.validate(&json!("a@gmail.com"), sch).is_err());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn override_format<F>(&mut self, name: &'static str, func: F)
+    where
+        F: Fn(&Value) -> Result<(), Box<dyn Error>> + Send + Sync + 'static,
+    {
+        let func = Arc::new(func);
+        self.formats.insert(name, Some(DynFormat { name, func }));
+    }
+
+    /**
+    Removes format `name`, whether built-in or registered, so that
+    it is treated as unknown format.
+
+    Unknown formats are ignored, unless [`Compiler::deny_unknown_formats`]
+    is used.
+    */
+    pub fn unregister_format(&mut self, name: &'static str) {
+        self.formats.insert(name, None);
+    }
+
+    /// Returns names of formats known to this compiler, including
+    /// built-in and registered ones, sorted.
+    pub fn formats(&self) -> Vec<&'static str> {
+        let mut names = sorted_names(FORMATS.keys().chain(self.formats.keys()));
+        names.retain(|name| self.format(name).is_some());
+        names
+    }
+
+    fn format(&self, name: &str) -> Option<DynFormat> {
+        match self.formats.get(name) {
+            Some(format) => format.clone(),
+            None => FORMATS.get(name).map(|&f| f.into()),
         }
     }
 
@@ -156,7 +845,27 @@ impl Compiler {
     see [`Compiler::enable_content_assertions`]
     */
     pub fn register_content_encoding(&mut self, decoder: Decoder) {
-        self.decoders.insert(decoder.name, decoder);
+        self.decoders.insert(decoder.name, decoder.into());
+    }
+
+    /**
+    Registers custom `contentEncoding` named `name`, whose decoding is
+    done by closure `func`, which can capture state.
+
+    see [`Compiler::register_content_encoding`]
+    */
+    pub fn register_content_encoding_fn<F>(&mut self, name: &'static str, func: F)
+    where
+        F: Fn(&str) -> Result<Vec<u8>, Box<dyn Error>> + Send + Sync + 'static,
+    {
+        let func = Arc::new(func);
+        self.decoders.insert(name, DynDecoder { name, func });
+    }
+
+    /// Returns names of content encodings known to this compiler,
+    /// including built-in and registered ones, sorted.
+    pub fn content_encodings(&self) -> Vec<&'static str> {
+        sorted_names(DECODERS.keys().chain(self.decoders.keys()))
     }
 
     /**
@@ -166,7 +875,71 @@ impl Compiler {
     see [`Compiler::enable_content_assertions`]
     */
     pub fn register_content_media_type(&mut self, media_type: MediaType) {
-        self.media_types.insert(media_type.name, media_type);
+        self.media_types.insert(media_type.name, media_type.into());
+    }
+
+    /**
+    Registers custom `contentMediaType` named `name`, whose check is
+    done by closure `func`, which can capture state.
+
+    see [`MediaType`] for the meaning of `json_compatible` and
+    arguments of `func`.
+    */
+    pub fn register_content_media_type_fn<F>(
+        &mut self,
+        name: &'static str,
+        json_compatible: bool,
+        func: F,
+    ) where
+        F: Fn(&[u8], bool) -> Result<Option<Value>, Box<dyn Error>> + Send + Sync + 'static,
+    {
+        let func = Arc::new(func);
+        self.media_types.insert(
+            name,
+            DynMediaType {
+                name,
+                json_compatible,
+                func,
+            },
+        );
+    }
+
+    /// Returns names of content media types known to this compiler,
+    /// including built-in and registered ones, sorted.
+    pub fn content_media_types(&self) -> Vec<&'static str> {
+        sorted_names(MEDIA_TYPES.keys().chain(self.media_types.keys()))
+    }
+
+    /**
+    Detects resources added by [`Compiler::add_resource`] with same
+    content, and compiles them only once.
+
+    Compiling such resource or referring it, gives the schema compiled
+    for the resource added first with same content. Resources are
+    deduplicated only if their meaning does not depend on their url,
+    i.e. if they have absolute `$id`, or have no `$ref`/`$id` at all.
+    Content is compared using stable hash. see [`Schemas::fingerprint`]
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_resource_dedup();
+    compiler.add_resource("http://a.com/v1/name.json", json!({"type": "string"}))?;
+    compiler.add_resource("http://a.com/v2/name.json", json!({"type": "string"}))?;
+    let v1 = compiler.compile("http://a.com/v1/name.json", &mut schemas)?;
+    let v2 = compiler.compile("http://a.com/v2/name.json", &mut schemas)?;
+    assert_eq!(v1, v2);
+    # Ok(())
+    # }
+    ```
+
+    Default Behavior is disabled.
+    */
+    pub fn enable_resource_dedup(&mut self) {
+        self.content_index.get_or_insert_with(HashMap::new);
     }
 
     /**
@@ -177,10 +950,125 @@ impl Compiler {
 
     # Errors
 
-    returns [`CompileError`] if url parsing failed.
+    returns [`CompileError`] if url parsing failed, or if `json` is not
+    valid in [`Dialect::Jtd`].
     */
     pub fn add_resource(&mut self, loc: &str, json: Value) -> Result<(), CompileError> {
         let uf = UrlFrag::absolute(loc)?;
+        let json = self.roots.loader.convert(&uf.url, json)?;
+        if let Some(index) = &mut self.content_index {
+            if self.roots.loader.get_doc(&uf.url).is_none() && is_location_independent(&json) {
+                let urls = index.entry(stable_hash(&json)).or_default();
+                let loader = &self.roots.loader;
+                let same = urls.iter().find(|url| {
+                    !self.roots.drafts.contains_key(url) && loader.get_doc(url) == Some(&json)
+                });
+                if let Some(same) = same {
+                    debug!("{} has same content as {same}", uf.url);
+                    self.roots.aliases.insert(uf.url, same.clone());
+                    return Ok(());
+                }
+                urls.push(uf.url.clone());
+            }
+        }
+        self.roots.loader.add_doc(uf.url, json);
+        Ok(())
+    }
+
+    /**
+    Same as [`Compiler::add_resource`], but the resource is interpreted
+    using `draft` if it has no `$schema`, instead of the default draft.
+
+    This is useful when schemas of different drafts are compiled together,
+    for example legacy draft-04 files referenced from 2020-12 schemas.
+    Resources that have `$schema` always use the draft it specifies.
+
+    # Errors
+
+    returns [`CompileError`] if url parsing failed.
+    */
+    pub fn add_resource_with_draft(
+        &mut self,
+        loc: &str,
+        json: Value,
+        draft: Draft,
+    ) -> Result<(), CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        let json = self.roots.loader.convert(&uf.url, json)?;
+        self.roots.drafts.insert(uf.url.clone(), draft.internal());
+        self.roots.loader.add_doc(uf.url, json);
+        Ok(())
+    }
+
+    /**
+    Registers custom metaschema `json` identified by `url`.
+
+    Schemas whose `$schema` is `url` are interpreted using given `draft`
+    and are validated against this metaschema, instead of failing
+    with [`CompileError::UnsupportedDraft`].
+
+    The argument `vocabularies` lists custom vocabulary uris which are
+    accepted even though boon does not implement them. Their keywords
+    are treated as annotations. Any other unknown vocabulary that is
+    required by the metaschema results in [`CompileError::UnsupportedVocabulary`].
+
+    The metaschema is compiled immediately, so it must be self-contained
+    or refer only to standard metaschemas. The compiled metaschema is
+    dropped along with the compiler.
+
+    # Example
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_metaschema(
+        "http://example.com/dialect",
+        json!({
+            "$schema": "http://example.com/dialect",
+            "$vocabulary": {
+                "https://json-schema.org/draft/2020-12/vocab/core": true,
+                "https://json-schema.org/draft/2020-12/vocab/applicator": true,
+                "http://example.com/vocab/ui": true
+            },
+            "allOf": [{"$ref": "https://json-schema.org/draft/2020-12/schema"}],
+            "properties": { "x-ui": {"type": "string"} }
+        }),
+        Draft::V2020_12,
+        &["http://example.com/vocab/ui"],
+    )?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn add_metaschema(
+        &mut self,
+        url: &str,
+        json: Value,
+        draft: Draft,
+        vocabularies: &[&str],
+    ) -> Result<(), CompileError> {
+        let uf = UrlFrag::absolute(url)?;
+        let draft = draft.internal();
+        let vocabs = draft.get_vocabs(&uf.url, &json, vocabularies)?;
+
+        // compile metaschema itself
+        let mut c = Compiler::new();
+        c.roots.default_draft = draft;
+        c.enable_format_assertions();
+        let meta = |validator| MetaSchema {
+            draft,
+            vocabs: vocabs.clone(),
+            validator,
+        };
+        c.roots.loader.add_metaschema(uf.url.clone(), meta(None));
+        c.add_resource(uf.url.as_str(), json.clone())?;
+        let mut schemas = Schemas::new();
+        let idx = c.compile(uf.url.as_str(), &mut schemas)?;
+        self.roots
+            .loader
+            .add_metaschema(uf.url.clone(), meta(Some((Arc::new(schemas), idx))));
         self.roots.loader.add_doc(uf.url, json);
         Ok(())
     }
@@ -189,26 +1077,236 @@ impl Compiler {
     Compile given `loc` into `target` and return an identifier to the compiled
     schema.
 
-    the argument `loc` can be file path or url with optional fragment.
-    examples: `http://example.com/schema.json#/defs/address`,
-              `samples/schema_file.json#defs/address`
+    the argument `loc` can be file path or url with optional fragment.
+    examples: `http://example.com/schema.json#/defs/address`,
+              `samples/schema_file.json#defs/address`
+
+    if `loc` is already compiled, it simply returns the same [`SchemaIndex`]
+     */
+    pub fn compile(
+        &mut self,
+        loc: &str,
+        target: &mut Schemas,
+    ) -> Result<SchemaIndex, CompileError> {
+        let start = self.metrics.as_ref().map(|_| Instant::now());
+        let mut cached = false;
+        let result = UrlFrag::absolute(loc)
+            // resolve anchor
+            .and_then(|uf| self.roots.resolve_fragment(uf))
+            .and_then(|up| {
+                if self.kubernetes {
+                    self.check_structural(&up)?;
+                }
+                cached = target.get_by_loc(&up).is_some();
+                self.do_compile(up, target)
+            });
+        if let Ok(idx) = result {
+            target.roots.insert(idx);
+        }
+        if let (Some(metrics), Some(start)) = (&self.metrics, start) {
+            let r = result.as_ref().map(|_| ());
+            metrics.compiled(loc, cached, r, start.elapsed());
+        }
+        if let Err(bug @ CompileError::Bug(_)) = &result {
+            debug_assert!(false, "{bug}");
+        }
+        result
+    }
+
+    /**
+    Adds `json` as resource identified by `loc`, and compiles it into
+    `target`. This is shorthand for [`Compiler::add_resource`] followed
+    by [`Compiler::compile`].
+
+    The argument `loc` can be file path or url. any fragment in `loc` is
+    ignored. Relative references in `json` are resolved against `loc`,
+    unless it has `$id`. If resource with same `loc` was already added or
+    loaded, `json` is ignored, and that resource is compiled.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile_value(
+        "http://a.com/person.json",
+        json!({"properties": {"name": {"type": "string"}}}),
+        &mut schemas,
+    )?;
+    assert!(schemas.validate(&json!({"name": 1}), sch).is_err());
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if url parsing failed or `json` fails to compile.
+    */
+    pub fn compile_value(
+        &mut self,
+        loc: &str,
+        json: Value,
+        target: &mut Schemas,
+    ) -> Result<SchemaIndex, CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        self.add_resource(uf.url.as_str(), json)?;
+        self.compile(uf.url.as_str(), target)
+    }
+
+    /**
+    Same as [`Compiler::compile_value`], but `json` is identified by
+    a generated url like `urn:boon:anonymous:1`, which is unique within
+    the process.
+
+    Use this when the schema has no natural location. Such schema
+    can refer to its own subschemas, or to urls which are absolute or
+    relative to its `$id`.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile_anonymous(
+        json!({"$defs": {"pos": {"minimum": 0}}, "items": {"$ref": "#/$defs/pos"}}),
+        &mut schemas,
+    )?;
+    assert!(schemas.validate(&json!([1, -1]), sch).is_err());
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if `json` fails to compile.
+    */
+    pub fn compile_anonymous(
+        &mut self,
+        json: Value,
+        target: &mut Schemas,
+    ) -> Result<SchemaIndex, CompileError> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, AtomicOrdering::Relaxed) + 1;
+        self.compile_value(&format!("urn:boon:anonymous:{n}"), json, target)
+    }
+
+    /**
+    Lists schema resources and anchors in the document at `loc`,
+    loading it if necessary, ordered by their location in the document.
+
+    Resources are the document itself, and subschemas with `$id`.
+    Anchors are listed with the url of their resource, like the
+    `$ref` referring to them would resolve to. This is useful for tools
+    like editors, and for finding out what is in a document that is
+    failing with [`CompileError::DuplicateAnchor`]: each of duplicate
+    anchors is listed, with [`SchemaAnchor::duplicate`] set. The
+    document is not validated against its metaschema.
+
+    The argument `loc` can be file path or url. any fragment in `loc` is ignored.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "$defs": {
+            "node": {"$dynamicAnchor": "node"},
+            "addr": {"$id": "addr.json", "$defs": {"zip": {"$anchor": "zip"}}}
+        }
+    }))?;
+    let anchors: Vec<_> = compiler
+        .anchors("http://a.com/s.json")?
+        .into_iter()
+        .map(|a| (a.kind, a.url, a.ptr))
+        .collect();
+    assert_eq!(anchors, [
+        (AnchorKind::Resource, "http://a.com/s.json".to_owned(), "".to_owned()),
+        (AnchorKind::Resource, "http://a.com/addr.json".to_owned(), "/$defs/addr".to_owned()),
+        (AnchorKind::Anchor, "http://a.com/addr.json#zip".to_owned(), "/$defs/addr/$defs/zip".to_owned()),
+        (AnchorKind::DynamicAnchor, "http://a.com/s.json#node".to_owned(), "/$defs/node".to_owned()),
+    ]);
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if the document cannot be loaded, or has
+    invalid ids or anchors, or duplicate ids.
+    */
+    pub fn anchors(&mut self, loc: &str) -> Result<Vec<SchemaAnchor>, CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        let url = self.roots.canonical(uf.url);
+        let doc = self.roots.loader.load(&url)?;
+        let draft = self.roots.get_draft(&url, doc)?;
+        let mut resources = HashMap::new();
+        draft.collect_resources(
+            doc,
+            &url,
+            "".into(),
+            &url,
+            &mut resources,
+            self.roots.max_depth,
+        )?;
+        let mut list = vec![];
+        for res in resources.values() {
+            list.push(SchemaAnchor {
+                kind: AnchorKind::Resource,
+                url: res.id.to_string(),
+                ptr: res.ptr.to_string(),
+                duplicate: false,
+            });
+            let duplicates = res.duplicate_anchors.iter().map(|(a, ptr)| (a, ptr));
+            for (anchor, ptr) in res.anchors.iter().chain(duplicates) {
+                list.push(SchemaAnchor {
+                    kind: match res.dynamic_anchors.contains(anchor) {
+                        true => AnchorKind::DynamicAnchor,
+                        false => AnchorKind::Anchor,
+                    },
+                    url: UrlFrag::format(&res.id, &anchor.to_string()),
+                    ptr: ptr.to_string(),
+                    duplicate: res.duplicate_anchors.iter().any(|(a, _)| a == anchor),
+                });
+            }
+        }
+        list.sort_by(|a, b| (&a.ptr, a.kind).cmp(&(&b.ptr, b.kind)));
+        Ok(list)
+    }
+
+    /**
+    Validates `v` with schema identified by `sch_index`, compiling
+    the deferred `$ref` targets it hits along the way.
+
+    see [`Compiler::enable_lazy_compilation`]
 
-    if `loc` is already compiled, it simply returns the same [`SchemaIndex`]
-     */
-    pub fn compile(
-        &mut self,
-        loc: &str,
-        target: &mut Schemas,
-    ) -> Result<SchemaIndex, CompileError> {
-        let uf = UrlFrag::absolute(loc)?;
-        // resolve anchor
-        let up = self.roots.resolve_fragment(uf)?;
+    # Errors
 
-        let result = self.do_compile(up, target);
-        if let Err(bug @ CompileError::Bug(_)) = &result {
-            debug_assert!(false, "{bug}");
+    returns [`CompileError`] if any deferred schema fails to compile.
+    */
+    pub fn validate_lazily<'s, 'v>(
+        &mut self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        schemas: &'s mut Schemas,
+    ) -> Result<Result<(), ValidationError<'s, 'v>>, CompileError> {
+        loop {
+            let pending = schemas.pending_refs(v, sch_index);
+            if pending.is_empty() {
+                break;
+            }
+            for idx in pending {
+                let Some(loc) = schemas.get(idx).lazy_ref.clone() else {
+                    continue;
+                };
+                let target = self.compile(&loc, schemas)?;
+                schemas.resolve_lazy_ref(idx, target);
+            }
         }
-        result
+        let schemas: &'s Schemas = schemas;
+        Ok(schemas.validate(v, sch_index))
     }
 
     fn do_compile(
@@ -233,16 +1331,62 @@ impl Compiler {
             };
             let doc = self.roots.loader.load(&root.url)?;
             let v = up.lookup(doc)?;
-            let sch = self.compile_value(target, v, &up.clone(), root, &mut queue)?;
+            let sch = self.compile_schema(target, v, &up.clone(), root, &mut queue)?;
             compiled.push(sch);
             self.roots.insert(&mut queue.roots);
         }
 
+        let mut dangling = dangling_dynamic_refs(target, &compiled);
+        if self.deny_dangling_dynamic_refs && !dangling.is_empty() {
+            return Err(dangling.swap_remove(0));
+        }
+        self.diagnostics.extend(dangling);
+
+        let start = target.next_index();
         target.insert(queue.schemas, compiled);
+        propagate_evaluated(target, start);
+        if let Some(limit) = self.inline_limit {
+            self.inlined_refs += inline_refs(target, start, limit);
+        }
+        if self.optimization >= OptimizationLevel::Fold {
+            fold(target, start);
+        }
+        if self.optimization >= OptimizationLevel::Reorder {
+            reorder_all_of(target, start);
+        }
+        if self.optimization >= OptimizationLevel::Fold {
+            // reclaim folded subschemas, which are no longer referred
+            let old = (0..start).map(SchemaIndex);
+            target.retain_reachable(old.chain([index]));
+        }
         Ok(index)
     }
 
-    fn compile_value(
+    // see Compiler::enable_kubernetes_extensions
+    fn check_structural(&mut self, up: &UrlPtr) -> Result<(), CompileError> {
+        self.roots.ensure_subschema(up)?;
+        let doc = self.roots.loader.load(&up.url)?;
+        let v = up.lookup(doc)?;
+        check_structural(v, up.ptr.as_str()).map_err(|(ptr, reason)| CompileError::NonStructural {
+            loc: format!("{}#{}", up.url, Fragment::encode(&ptr)),
+            reason,
+        })
+    }
+
+    // compiles converted pattern `ecma`, sharing regex with
+    // other schemas using same pattern.
+    fn regex(&self, ecma: &str) -> Result<Arc<Regex>, regex::Error> {
+        if let Some(regex) = self.regexes.borrow().get(ecma) {
+            return Ok(Arc::clone(regex));
+        }
+        let regex = Arc::new(Regex::new(ecma)?);
+        self.regexes
+            .borrow_mut()
+            .insert(ecma.to_owned(), Arc::clone(&regex));
+        Ok(regex)
+    }
+
+    fn compile_schema(
         &self,
         schemas: &Schemas,
         v: &Value,
@@ -252,6 +1396,28 @@ impl Compiler {
     ) -> Result<Schema, CompileError> {
         let mut s = Schema::new(up.to_string());
         s.draft_version = root.draft.version;
+        s.hash = stable_hash(v);
+        s.strict_integer = self.strict_integers.unwrap_or(s.draft_version < 6);
+        s.vocabs = root
+            .draft
+            .all_vocabs
+            .iter()
+            .copied()
+            .filter(|name| root.has_vocab(name))
+            .collect();
+        s.format_mode = if self.assert_format
+            || self.roots.loader.dialect == Dialect::Jtd
+            || root.has_vocab(match s.draft_version.cmp(&2019) {
+                Ordering::Less => "core",
+                Ordering::Equal => "format",
+                Ordering::Greater => "format-assertion",
+            }) {
+            FormatMode::Assertion
+        } else if root.meta_vocabs.is_none() || root.has_vocab("format-annotation") {
+            FormatMode::Annotation
+        } else {
+            FormatMode::Ignore
+        };
 
         // we know it is already in queue, we just want to get its index
         let len = queue.schemas.len();
@@ -283,12 +1449,20 @@ impl Compiler {
 
         match v {
             Value::Object(obj) => {
+                let mut obj = Cow::Borrowed(obj);
+                if self.schema_hints {
+                    let skip = hint_keywords(&obj, "skip");
+                    if !skip.is_empty() {
+                        obj.to_mut().retain(|kw, _| !skip.contains(kw));
+                    }
+                    s.warn_keywords = hint_keywords(&obj, "warn");
+                }
                 if obj.is_empty() {
                     s.boolean = Some(true);
                 } else {
                     ObjCompiler {
                         c: self,
-                        obj,
+                        obj: &obj,
                         up,
                         schemas,
                         root,
@@ -312,11 +1486,525 @@ impl Compiler {
         } else {
             s.prefix_items.len()
         };
+        s.name_only = match v {
+            Value::Object(obj) => obj.keys().all(|kw| NAME_KEYWORDS.contains(&kw.as_str())),
+            Value::Bool(_) => true,
+            _ => false,
+        } && s.format.is_none();
 
         Ok(s)
     }
 }
 
+// returns keywords listed in `x-boon` hint `name`. see Compiler::enable_schema_hints
+fn hint_keywords(obj: &Map<String, Value>, name: &str) -> Vec<String> {
+    let Some(Value::Array(arr)) = obj.get("x-boon").and_then(|hints| hints.get(name)) else {
+        return vec![];
+    };
+    arr.iter()
+        .filter_map(|kw| Some(kw.as_str()?.to_owned()))
+        .collect()
+}
+
+// keywords of `propertyNames` subschema, which validator checks directly
+// on property name, without allocating `Value::String`.
+const NAME_KEYWORDS: [&str; 15] = [
+    "type",
+    "const",
+    "enum",
+    "pattern",
+    "minLength",
+    "maxLength",
+    "format", // only if not asserted
+    "title",
+    "description",
+    "$comment",
+    "default",
+    "examples",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+];
+
+fn sorted_names<'a>(names: impl Iterator<Item = &'a &'static str>) -> Vec<&'static str> {
+    let mut names: Vec<_> = names.copied().collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+// returns errors for `$dynamicRef` and `$recursiveRef` in `compiled`, whose
+// initial target does not have matching dynamic anchor.
+// see Compiler::deny_dangling_dynamic_refs
+fn dangling_dynamic_refs(target: &Schemas, compiled: &[Schema]) -> Vec<CompileError> {
+    let start = target.next_index();
+    let get = |idx: SchemaIndex| match idx.0.checked_sub(start) {
+        Some(i) => &compiled[i],
+        None => target.get(idx),
+    };
+    let mut errors = vec![];
+    for s in compiled {
+        if let Some(DynamicRef {
+            sch,
+            anchor: Some(anchor),
+        }) = &s.dynamic_ref
+        {
+            let initial = get(*sch);
+            if initial.dynamic_anchor.as_ref() != Some(anchor) {
+                let res = get(initial.resource);
+                let names = res.dynamic_anchors.keys().cloned();
+                errors.push(CompileError::DanglingDynamicRef {
+                    loc: format!("{}/$dynamicRef", s.loc),
+                    initial: initial.loc.clone(),
+                    suggestions: similar(anchor, names),
+                });
+            }
+        }
+        if let Some(sch) = s.recursive_ref {
+            let initial = get(sch);
+            if !initial.recursive_anchor {
+                errors.push(CompileError::DanglingDynamicRef {
+                    loc: format!("{}/$recursiveRef", s.loc),
+                    initial: initial.loc.clone(),
+                    suggestions: vec![],
+                });
+            }
+        }
+    }
+    errors
+}
+
+// marks schemas, whose applicators always evaluate all properties/items
+// of a valid instance, so that validator need not track them.
+//
+// newly compiled schemas start from index `start`. because of `$ref`
+// cycles, repeats until nothing changes.
+fn propagate_evaluated(schemas: &mut Schemas, start: usize) {
+    // false schema never validates, so its annotations never count
+    fn all_props(s: &Schema) -> bool {
+        s.all_props_evaluated || s.boolean == Some(false)
+    }
+    fn all_items(s: &Schema) -> bool {
+        s.all_items_evaluated || s.boolean == Some(false)
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in start..schemas.next_index() {
+            let get = |i: &SchemaIndex| schemas.get(*i);
+            let s = get(&SchemaIndex(i));
+            if s.all_props_evaluated && s.all_items_evaluated {
+                continue;
+            }
+
+            // applicators, which are applied to every valid instance
+            let always = s.ref_.iter().chain(&s.all_of).map(get);
+            let always = always.chain(
+                s.dependent_schemas
+                    .iter()
+                    .filter(|(pname, _)| s.required.contains(pname))
+                    .map(|(_, i)| get(i)),
+            );
+            // applicators, one of which is applied to every valid instance
+            let mut any = vec![];
+            for branches in [&s.any_of, &s.one_of] {
+                if !branches.is_empty() {
+                    any.push(branches.iter().map(get).collect::<Vec<_>>());
+                }
+            }
+            if let (Some(_), Some(then), Some(else_)) = (s.if_, s.then, s.else_) {
+                any.push(vec![get(&then), get(&else_)]);
+            }
+
+            let props = s.all_props_evaluated
+                || always.clone().any(all_props)
+                || any.iter().any(|b| b.iter().all(|s| all_props(s)));
+            let items = s.all_items_evaluated
+                || always.clone().any(all_items)
+                || any.iter().any(|b| b.iter().all(|s| all_items(s)));
+            let num_items = always
+                .map(|s| s.num_items_evaluated)
+                .chain(any.iter().map(|b| {
+                    let n = b.iter().map(|s| s.num_items_evaluated).min();
+                    n.unwrap_or_default()
+                }))
+                .fold(s.num_items_evaluated, usize::max);
+
+            let s = schemas.get_mut(SchemaIndex(i));
+            if props != s.all_props_evaluated
+                || items != s.all_items_evaluated
+                || num_items != s.num_items_evaluated
+            {
+                s.all_props_evaluated = props;
+                s.all_items_evaluated = items;
+                s.num_items_evaluated = num_items;
+                changed = true;
+            }
+        }
+    }
+}
+
+// keywords whose evaluation depends on their siblings. inlined
+// schema must not share a group with the referring schema.
+const SIBLING_GROUPS: [&[&str]; 3] = [
+    &["properties", "patternProperties", "additionalProperties"],
+    &["items", "additionalItems"],
+    &["contentEncoding", "contentMediaType"],
+];
+
+// replaces `$ref` of newly compiled schemas with keywords of its target.
+// returns number of refs inlined. see Compiler::enable_ref_inlining
+fn inline_refs(schemas: &mut Schemas, start: usize, limit: usize) -> usize {
+    // discriminator matches branches by location of their $ref
+    let mut tagged = HashSet::new();
+    let mut stack = vec![];
+    for i in start..schemas.next_index() {
+        let s = schemas.get(SchemaIndex(i));
+        if let Some(d) = &s.discriminator {
+            stack.extend(&s.one_of);
+            if d.any_of {
+                stack.extend(&s.any_of);
+            }
+        }
+    }
+    while let Some(sch) = stack.pop() {
+        if tagged.insert(sch) {
+            let s = schemas.get(sch);
+            stack.extend(s.ref_.iter().chain(&s.all_of));
+        }
+    }
+
+    let mut inlined = 0;
+    for i in start..schemas.next_index() {
+        let s = schemas.get(SchemaIndex(i));
+        let Some(ref_) = s.ref_ else {
+            continue;
+        };
+        let t = schemas.get(ref_);
+        if tagged.contains(&s.idx) || !can_inline(s, t, limit) {
+            continue;
+        }
+        let mut s = s.clone();
+        merge_keywords(&mut s, t);
+        *schemas.get_mut(SchemaIndex(i)) = s;
+        inlined += 1;
+    }
+    inlined
+}
+
+// tells whether keywords of `$ref` target `t` can be moved into `s`
+fn can_inline(s: &Schema, t: &Schema, limit: usize) -> bool {
+    let t_kws: Vec<_> = crate::validator::keywords(t)
+        .into_iter()
+        .map(|(kw, _)| kw)
+        .collect();
+    let s_kws: Vec<_> = crate::validator::keywords(s)
+        .into_iter()
+        .map(|(kw, _)| kw)
+        .collect();
+    // without subschemas, `t` cannot depend on dynamic scope
+    let leaf = t.applied_subschemas().is_empty() && t.lazy_ref.is_none();
+    let plain = t.boolean.is_none()
+        && !t.deprecated
+        && t.error_message.is_none()
+        && t.warn_keywords.is_empty()
+        && s.error_message.is_none()
+        && s.warn_keywords.is_empty()
+        && t.draft_version == s.draft_version
+        && t.strict_integer == s.strict_integer;
+    let has_format = |s: &Schema| s.format.is_some() || s.format_annotation.is_some();
+    let disjoint = t_kws.iter().all(|kw| !s_kws.contains(kw))
+        && SIBLING_GROUPS.iter().all(|group| {
+            !t_kws.iter().any(|kw| group.contains(kw)) || !s_kws.iter().any(|kw| group.contains(kw))
+        })
+        && !(has_format(s) && has_format(t));
+    leaf && plain && disjoint && t_kws.len() <= limit
+}
+
+// moves keywords of subschema-free `t` into `s`, replacing its `$ref`.
+// caller must ensure that they have no keywords in common.
+fn merge_keywords(s: &mut Schema, t: &Schema) {
+    macro_rules! merge {
+        ($($field:ident),+ $(,)?) => {
+            $(if s.$field.is_none() {
+                s.$field = t.$field.clone();
+            })+
+        };
+    }
+    s.ref_ = None;
+    // fingerprint no longer reaches `t`
+    let mut h = StableHasher::default();
+    h.write_u64(s.hash);
+    h.write_u64(t.hash);
+    s.hash = h.finish();
+    if s.types.is_empty() {
+        s.types = t.types;
+    }
+    if s.required.is_empty() {
+        s.required = t.required.clone();
+    }
+    if s.dependent_required.is_empty() {
+        s.dependent_required = t.dependent_required.clone();
+    }
+    if s.dependencies.is_empty() {
+        s.dependencies = t.dependencies.clone();
+    }
+    s.unique_items |= t.unique_items;
+    merge!(
+        enum_,
+        constant,
+        format,
+        format_annotation,
+        min_properties,
+        max_properties,
+        additional_properties,
+        min_items,
+        max_items,
+        additional_items,
+        min_length,
+        max_length,
+        pattern,
+        content_encoding,
+        content_media_type,
+        minimum,
+        maximum,
+        exclusive_minimum,
+        exclusive_maximum,
+        multiple_of,
+    );
+}
+
+// drops references to trivially true subschemas, and replaces
+// references to `{"allOf": [S]}` with `S`, until nothing changes.
+// see OptimizationLevel::Fold
+fn fold(schemas: &mut Schemas, start: usize) {
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in start..schemas.next_index() {
+            if !foldable(schemas, schemas.get(SchemaIndex(i))) {
+                continue;
+            }
+            let mut s = schemas.get(SchemaIndex(i)).clone();
+            if fold_schema(schemas, &mut s) {
+                *schemas.get_mut(SchemaIndex(i)) = s;
+                changed = true;
+            }
+        }
+    }
+}
+
+// tells whether fold_schema would change `s`, so that
+// only those schemas are cloned
+fn foldable(schemas: &Schemas, s: &Schema) -> bool {
+    s.keyed_subschemas().into_iter().any(|(kw, sch)| {
+        let kw = kw.split('/').next().unwrap_or_default();
+        let droppable = match kw {
+            "$ref" => s.draft_version >= 2019,
+            "allOf" | "then" | "else" | "propertyNames" | "contentSchema" => true,
+            "dependentSchemas" | "dependencies" => true,
+            _ => false,
+        };
+        let aliased = !matches!(
+            kw,
+            "$resource"
+                | "$dynamicAnchor"
+                | "$recursiveRef"
+                | "$dynamicRef"
+                | "anyOf"
+                | "oneOf"
+                | "discriminator"
+        );
+        let t = schemas.get(sch);
+        (aliased && alias_of(t).is_some()) || (droppable && is_trivially_true(t))
+    })
+}
+
+// returns true, if `s` is changed
+fn fold_schema(schemas: &Schemas, s: &mut Schema) -> bool {
+    let changed = Cell::new(false);
+    let alias = |sch: &mut SchemaIndex| {
+        while let Some(target) = alias_of(schemas.get(*sch)) {
+            *sch = target;
+            changed.set(true);
+        }
+    };
+    // returns false, if `sch` can be dropped
+    let keep = |sch: &mut SchemaIndex| {
+        alias(sch);
+        let keep = !is_trivially_true(schemas.get(*sch));
+        if !keep {
+            changed.set(true);
+        }
+        keep
+    };
+    let alias_opt = |opt: &mut Option<SchemaIndex>| {
+        if let Some(sch) = opt {
+            alias(sch);
+        }
+    };
+    let keep_opt = |opt: &mut Option<SchemaIndex>| {
+        if opt.as_mut().is_some_and(|sch| !keep(sch)) {
+            *opt = None;
+        }
+    };
+    let alias_additional = |additional: &mut Option<Additional>| {
+        if let Some(Additional::SchemaRef(sch)) = additional {
+            alias(sch);
+        }
+    };
+
+    if s.draft_version >= 2019 {
+        keep_opt(&mut s.ref_);
+    } else if let Some(sch) = &mut s.ref_ {
+        alias(sch); // siblings of $ref are ignored, so it cannot be dropped
+    }
+    s.all_of.retain_mut(|sch| keep(sch));
+    alias_opt(&mut s.not);
+    alias_opt(&mut s.if_);
+    keep_opt(&mut s.then);
+    keep_opt(&mut s.else_);
+    keep_opt(&mut s.property_names);
+    keep_opt(&mut s.content_schema);
+    s.dependent_schemas.retain_mut(|(_, sch)| keep(sch));
+    s.dependencies.retain_mut(|(_, dep)| match dep {
+        Dependency::SchemaRef(sch) => keep(sch),
+        Dependency::Props(_) => true,
+    });
+
+    // these contribute to evaluated properties and items, even if true
+    s.properties.values_mut().for_each(alias);
+    s.pattern_properties
+        .iter_mut()
+        .for_each(|(_, sch)| alias(sch));
+    alias_additional(&mut s.additional_properties);
+    alias_opt(&mut s.unevaluated_properties);
+    match &mut s.items {
+        Some(Items::SchemaRef(sch)) => alias(sch),
+        Some(Items::SchemaRefs(list)) => list.iter_mut().for_each(alias),
+        None => {}
+    }
+    alias_additional(&mut s.additional_items);
+    s.prefix_items.iter_mut().for_each(alias);
+    alias_opt(&mut s.items2020);
+    alias_opt(&mut s.contains);
+    alias_opt(&mut s.unevaluated_items);
+    changed.get()
+}
+
+// tells whether `s` validates any value, without side effects
+fn is_trivially_true(s: &Schema) -> bool {
+    match s.boolean {
+        Some(b) => b,
+        None => {
+            crate::validator::keywords(s).is_empty()
+                && s.format_annotation.is_none()
+                && !s.deprecated
+        }
+    }
+}
+
+// returns `S`, if `s` is `{"allOf": [S]}`
+fn alias_of(s: &Schema) -> Option<SchemaIndex> {
+    let [member] = s.all_of[..] else {
+        return None;
+    };
+    let only_all_of = crate::validator::keywords(s) == [("allOf", None)];
+    let plain = s.idx != s.resource
+        && s.dynamic_anchor.is_none()
+        && !s.recursive_anchor
+        && s.format_annotation.is_none()
+        && !s.deprecated
+        && s.error_message.is_none()
+        && s.warn_keywords.is_empty()
+        && s.title.is_none()
+        && s.description.is_none()
+        && !s.preserve_unknown_fields
+        && !s.embedded_resource;
+    (only_all_of && plain).then_some(member)
+}
+
+// sorts `allOf` subschemas of newly compiled schemas by estimated cost.
+fn reorder_all_of(schemas: &mut Schemas, start: usize) {
+    let mut costs = vec![None; schemas.next_index()];
+    for i in start..schemas.next_index() {
+        if schemas.get(SchemaIndex(i)).all_of.len() < 2 {
+            continue;
+        }
+        let mut all_of = schemas.get(SchemaIndex(i)).all_of.clone();
+        all_of.sort_by_cached_key(|sch| cost(schemas, *sch, &mut costs));
+        schemas.get_mut(SchemaIndex(i)).all_of = all_of;
+    }
+}
+
+// estimated cost of evaluating schema `sch`.
+//
+// keywords applying subschemas in place add cost of those subschemas.
+// other keywords have fixed cost, higher for `format` and regexes.
+fn cost(schemas: &Schemas, sch: SchemaIndex, costs: &mut Vec<Option<usize>>) -> usize {
+    let i = sch.0;
+    if let Some(c) = costs[i] {
+        return c;
+    }
+    let s = schemas.get(sch);
+    if s.boolean.is_some() {
+        return 1;
+    }
+    costs[i] = Some(CYCLE_COST); // in case of `$ref` cycle
+
+    let simple = [
+        !s.types.is_empty(),
+        s.constant.is_some(),
+        s.enum_.is_some(),
+        s.min_properties.is_some() || s.max_properties.is_some(),
+        !s.required.is_empty(),
+        s.min_items.is_some() || s.max_items.is_some(),
+        s.min_length.is_some() || s.max_length.is_some(),
+        s.minimum.is_some() || s.maximum.is_some(),
+        s.exclusive_minimum.is_some() || s.exclusive_maximum.is_some(),
+        s.multiple_of.is_some(),
+    ];
+    let children = [
+        !s.properties.is_empty(),
+        s.additional_properties.is_some(),
+        s.property_names.is_some(),
+        s.items.is_some() || s.items2020.is_some(),
+        !s.prefix_items.is_empty(),
+        s.contains.is_some(),
+        s.unique_items,
+        s.unevaluated_properties.is_some() || s.unevaluated_items.is_some(),
+    ];
+    let mut c = simple.iter().filter(|b| **b).count()
+        + 4 * children.iter().filter(|b| **b).count()
+        + 4 * usize::from(s.format.is_some())
+        + 8 * usize::from(s.pattern.is_some())
+        + 8 * s.pattern_properties.len()
+        + CYCLE_COST
+            * usize::from(
+                s.lazy_ref.is_some() || s.recursive_ref.is_some() || s.dynamic_ref.is_some(),
+            );
+    let in_place = s
+        .ref_
+        .iter()
+        .chain(&s.all_of)
+        .chain(&s.any_of)
+        .chain(&s.one_of);
+    let in_place = in_place
+        .chain(&s.not)
+        .chain(&s.if_)
+        .chain(&s.then)
+        .chain(&s.else_);
+    for sch in in_place.chain(s.dependent_schemas.iter().map(|(_, sch)| sch)) {
+        c = c.saturating_add(cost(schemas, *sch, costs));
+    }
+    costs[i] = Some(c);
+    c
+}
+
+// cost of schemas, which cannot be estimated at compile time.
+const CYCLE_COST: usize = 64;
+
 struct ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
     c: &'c Compiler,
     obj: &'v Map<String, Value>,
@@ -329,6 +2017,9 @@ struct ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
 // compile supported drafts
 impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
     fn compile_obj(&mut self, s: &mut Schema) -> Result<(), CompileError> {
+        if self.c.deny_unknown_keywords {
+            self.check_keywords()?;
+        }
         self.compile_draft4(s)?;
         if self.draft_version() >= 6 {
             self.compile_draft6(s)?;
@@ -342,13 +2033,64 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
         if self.draft_version() >= 2020 {
             self.compile_draft2020(s)?;
         }
+        if self.c.error_messages {
+            s.error_message = self.compile_error_message();
+        }
+        if self.c.kubernetes {
+            self.compile_kubernetes(s);
+        }
+        Ok(())
+    }
+
+    // see Compiler::enable_kubernetes_extensions
+    fn compile_kubernetes(&mut self, s: &mut Schema) {
+        if self.bool("x-kubernetes-int-or-string") && s.types.is_empty() {
+            s.types.add(Type::Integer);
+            s.types.add(Type::String);
+        }
+        if self.bool("nullable") && !s.types.is_empty() {
+            s.types.add(Type::Null);
+        }
+        s.preserve_unknown_fields = self.bool("x-kubernetes-preserve-unknown-fields");
+        s.embedded_resource = self.bool("x-kubernetes-embedded-resource");
+    }
+
+    fn check_keywords(&self) -> Result<(), CompileError> {
+        let keywords = &self.root.draft.keywords;
+        for kw in self.obj.keys() {
+            let known = match kw.as_str() {
+                "discriminator" => self.c.openapi_discriminator,
+                "errorMessage" => self.c.error_messages,
+                "x-boon" => self.c.schema_hints,
+                "nullable" => self.c.kubernetes,
+                kw if kw.starts_with("x-kubernetes-") => self.c.kubernetes,
+                kw => keywords.contains(&kw),
+            };
+            if !known {
+                return Err(CompileError::UnknownKeyword {
+                    loc: self.up.format(kw),
+                    keyword: kw.clone(),
+                    suggestions: similar(kw, keywords.iter().map(|kw| kw.to_string())),
+                });
+            }
+        }
         Ok(())
     }
 
     fn compile_draft4(&mut self, s: &mut Schema) -> Result<(), CompileError> {
+        if let Some(Value::String(title)) = self.value("title") {
+            s.title = Some(title.clone());
+        }
+        if let Some(Value::String(description)) = self.value("description") {
+            s.description = Some(description.clone());
+        }
+
         if self.has_vocab("core") {
-            s.ref_ = self.enqueue_ref("$ref")?;
-            if s.ref_.is_some() && self.draft_version() < 2019 {
+            s.lazy_ref = self.defer_ref("$ref")?;
+            if s.lazy_ref.is_none() {
+                s.ref_ = self.enqueue_ref("$ref")?;
+            }
+            if (s.ref_.is_some() || s.lazy_ref.is_some()) && self.draft_version() < 2019 {
                 // All other properties in a "$ref" object MUST be ignored
                 return Ok(());
             }
@@ -359,6 +2101,9 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
             s.any_of = self.enqueue_arr("anyOf");
             s.one_of = self.enqueue_arr("oneOf");
             s.not = self.enqueue_prop("not");
+            if !s.one_of.is_empty() || !s.any_of.is_empty() {
+                s.discriminator = self.compile_discriminator()?;
+            }
 
             if self.draft_version() < 2020 {
                 match self.value("items") {
@@ -375,18 +2120,20 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
                 let mut v = vec![];
                 if let Some(Value::Object(obj)) = self.value("patternProperties") {
                     for pname in obj.keys() {
-                        let ecma =
-                            ecma::convert(pname).map_err(|src| CompileError::InvalidRegex {
+                        let ecma = ecma::to_rust_regex(pname).map_err(|src| {
+                            CompileError::InvalidRegex {
                                 url: self.up.format("patternProperties"),
                                 regex: pname.to_owned(),
-                                src,
-                            })?;
-                        let regex =
-                            Regex::new(ecma.as_ref()).map_err(|e| CompileError::InvalidRegex {
+                                src: src.into(),
+                            }
+                        })?;
+                        let regex = self.c.regex(ecma.as_ref()).map_err(|e| {
+                            CompileError::InvalidRegex {
                                 url: self.up.format("patternProperties"),
                                 regex: ecma.into_owned(),
                                 src: e.into(),
-                            })?;
+                            }
+                        })?;
                         let ptr = self.up.ptr.append2("patternProperties", pname);
                         let sch = self.enqueue_schema(ptr);
                         v.push((regex, sch));
@@ -434,14 +2181,7 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
             }
 
             if let Some(Value::Array(e)) = self.value("enum") {
-                let mut types = Types::default();
-                for item in e {
-                    types.add(Type::of(item));
-                }
-                s.enum_ = Some(Enum {
-                    types,
-                    values: e.clone(),
-                });
+                s.enum_ = Some(Enum::new(e.clone()));
             }
 
             s.multiple_of = self.num("multipleOf");
@@ -468,8 +2208,12 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
             s.min_length = self.usize("minLength");
 
             if let Some(Value::String(p)) = self.value("pattern") {
-                let p = ecma::convert(p).map_err(CompileError::Bug)?;
-                s.pattern = Some(Regex::new(p.as_ref()).map_err(|e| CompileError::Bug(e.into()))?);
+                let p = ecma::to_rust_regex(p).map_err(|e| CompileError::Bug(e.into()))?;
+                s.pattern = Some(
+                    self.c
+                        .regex(p.as_ref())
+                        .map_err(|e| CompileError::Bug(e.into()))?,
+                );
             }
 
             s.max_items = self.usize("maxItems");
@@ -485,20 +2229,23 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
         }
 
         // format --
-        if self.c.assert_format
-            || self.has_vocab(match self.draft_version().cmp(&2019) {
-                Ordering::Less => "core",
-                Ordering::Equal => "format",
-                Ordering::Greater => "format-assertion",
-            })
-        {
-            if let Some(Value::String(format)) = self.value("format") {
-                s.format = self
-                    .c
-                    .formats
-                    .get(format.as_str())
-                    .or_else(|| FORMATS.get(format.as_str()))
-                    .cloned();
+        if let Some(Value::String(name)) = self.value("format") {
+            let format = self.c.format(name);
+            if format.is_none()
+                && self.c.deny_unknown_formats
+                && s.format_mode != FormatMode::Ignore
+            {
+                let names = self.c.formats().into_iter();
+                return Err(CompileError::UnknownFormat {
+                    loc: self.up.format("format"),
+                    format: name.clone(),
+                    suggestions: similar(name, names.map(|name| name.to_string())),
+                });
+            }
+            match s.format_mode {
+                FormatMode::Assertion => s.format = format,
+                FormatMode::Annotation => s.format_annotation = format,
+                FormatMode::Ignore => {}
             }
         }
 
@@ -506,6 +2253,10 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
     }
 
     fn compile_draft6(&mut self, s: &mut Schema) -> Result<(), CompileError> {
+        if let Some(Value::Array(examples)) = self.value("examples") {
+            s.examples = examples.clone();
+        }
+
         if self.has_vocab("applicator") {
             s.contains = self.enqueue_prop("contains");
             s.property_names = self.enqueue_prop("propertyNames");
@@ -519,6 +2270,10 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
     }
 
     fn compile_draft7(&mut self, s: &mut Schema) -> Result<(), CompileError> {
+        if let Some(Value::String(comment)) = self.value("$comment") {
+            s.comment = Some(comment.clone());
+        }
+
         if self.has_vocab("applicator") {
             s.if_ = self.enqueue_prop("if");
             if s.if_.is_some() {
@@ -537,8 +2292,8 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
                     .c
                     .decoders
                     .get(encoding.as_str())
-                    .or_else(|| DECODERS.get(encoding.as_str()))
-                    .cloned();
+                    .cloned()
+                    .or_else(|| DECODERS.get(encoding.as_str()).map(|&d| d.into()));
             }
 
             if let Some(Value::String(media_type)) = self.value("contentMediaType") {
@@ -546,8 +2301,8 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
                     .c
                     .media_types
                     .get(media_type.as_str())
-                    .or_else(|| MEDIA_TYPES.get(media_type.as_str()))
-                    .cloned();
+                    .cloned()
+                    .or_else(|| MEDIA_TYPES.get(media_type.as_str()).map(|&mt| mt.into()));
             }
         }
 
@@ -559,6 +2314,7 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
             s.recursive_ref = self.enqueue_ref("$recursiveRef")?;
             s.recursive_anchor = self.bool("$recursiveAnchor");
         }
+        s.deprecated = self.bool("deprecated");
 
         if self.has_vocab("validation") {
             if s.contains.is_some() {
@@ -588,6 +2344,7 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
 
         if self.c.assert_content
             && s.content_media_type
+                .as_ref()
                 .map(|mt| mt.json_compatible)
                 .unwrap_or(false)
         {
@@ -680,15 +2437,160 @@ impl ObjCompiler<'_, '_, '_, '_, '_, '_> {
         let Some(Value::String(ref_)) = self.obj.get(pname) else {
             return Ok(None);
         };
-        let base_url = self.root.base_url(&self.up.ptr);
+        let root = self.root;
+        let base_url = root.base_url(&self.up.ptr);
         let abs_ref = UrlFrag::join(base_url, ref_)?;
+        trace!(
+            "{}: {pname} {ref_:?} with base {base_url} is {abs_ref}",
+            self.up
+        );
+        match self.resolve_ref(abs_ref.clone()) {
+            Ok(sch) => Ok(Some(sch)),
+            Err(
+                e @ (CompileError::LoadUrlError { .. }
+                | CompileError::UnsupportedUrlScheme { .. }
+                | CompileError::NotPreloaded { .. }
+                | CompileError::AnchorNotFound { .. }
+                | CompileError::InvalidJsonPointer(_)
+                | CompileError::JsonPointerNotFound(_)),
+            ) => Err(CompileError::UnresolvedReference {
+                loc: self.up.format(pname),
+                reference: ref_.clone(),
+                base: base_url.to_string(),
+                resolved: abs_ref.to_string(),
+                suggestions: self.ref_suggestions(&abs_ref),
+                src: Box::new(e),
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    // in lazy mode, returns location of remote `$ref` target
+    // which is not yet compiled or queued.
+    fn defer_ref(&self, pname: &str) -> Result<Option<String>, CompileError> {
+        if !self.c.lazy {
+            return Ok(None);
+        }
+        let Some(Value::String(ref_)) = self.obj.get(pname) else {
+            return Ok(None);
+        };
+        let abs_ref = UrlFrag::join(self.root.base_url(&self.up.ptr), ref_)?;
+        if !matches!(self.root.resolve(&abs_ref), Ok(None)) {
+            return Ok(None); // local ref
+        }
+        if let Fragment::JsonPointer(ptr) = &abs_ref.frag {
+            let up = UrlPtr {
+                url: abs_ref.url.clone(),
+                ptr: ptr.clone(),
+            };
+            if self.schemas.get_by_loc(&up).is_some() || self.queue.schemas.contains(&up) {
+                return Ok(None);
+            }
+        }
+        Ok(Some(abs_ref.to_string()))
+    }
+
+    fn resolve_ref(&mut self, abs_ref: UrlFrag) -> Result<SchemaIndex, CompileError> {
         if let Some(resolved_ref) = self.root.resolve(&abs_ref)? {
             // local ref
-            return Ok(Some(self.enqueue_schema(resolved_ref.ptr)));
+            trace!("{abs_ref} resolved to {resolved_ref}");
+            let doc = self.c.roots.loader.load(&self.root.url)?;
+            resolved_ref.lookup(doc)?;
+            return Ok(self.enqueue_schema(resolved_ref.ptr));
         }
         // remote ref
         let up = self.queue.resolve_anchor(abs_ref, &self.c.roots)?;
-        Ok(Some(self.queue.enqueue_schema(self.schemas, up)))
+        trace!("remote ref resolved to {up}");
+        let doc = self.c.roots.loader.load(&up.url)?;
+        up.lookup(doc)?;
+        Ok(self.queue.enqueue_schema(self.schemas, up))
+    }
+
+    // suggests similar anchors/defs, or similar document urls
+    // if target document could not be loaded.
+    fn ref_suggestions(&self, abs_ref: &UrlFrag) -> Vec<String> {
+        let loader = &self.c.roots.loader;
+        let Some(doc) = loader.get_doc(&abs_ref.url) else {
+            let urls = loader.doc_urls().into_iter().map(|u| u.to_string());
+            return similar(abs_ref.url.as_str(), urls);
+        };
+
+        // resource identified by abs_ref.url
+        let root = self
+            .c
+            .roots
+            .get(&abs_ref.url)
+            .or_else(|| self.queue.roots.get(&abs_ref.url))
+            .unwrap_or(self.root);
+        let res = root.resources.values().find(|res| res.id == abs_ref.url);
+
+        let mut candidates = vec![];
+        if let Some(res) = res {
+            candidates.extend(res.anchors.keys().map(|a| a.to_string()));
+        }
+        let res_ptr = res.map(|res| res.ptr.as_str()).unwrap_or_default();
+        if let Ok(Value::Object(obj)) = JsonPointer::from(res_ptr).lookup(doc, &root.url) {
+            for kw in ["$defs", "definitions"] {
+                if let Some(Value::Object(defs)) = obj.get(kw) {
+                    candidates.extend(
+                        defs.keys()
+                            .map(|k| format!("/{kw}/{}", JsonPointer::escape(k))),
+                    );
+                }
+            }
+        }
+        similar(abs_ref.frag.as_str(), candidates)
+            .into_iter()
+            .map(|frag| UrlFrag::format(&abs_ref.url, &frag))
+            .collect()
+    }
+
+    fn compile_error_message(&self) -> Option<ErrorMessage> {
+        match self.value("errorMessage")? {
+            Value::String(msg) => Some(ErrorMessage::All(msg.clone())),
+            Value::Object(obj) => {
+                let messages = obj
+                    .iter()
+                    .filter_map(|(kw, msg)| Some((kw.clone(), msg.as_str()?.to_owned())))
+                    .collect();
+                Some(ErrorMessage::Keywords(messages))
+            }
+            _ => None,
+        }
+    }
+
+    // OpenAPI `discriminator`, falling back to the one set in compiler.
+    fn compile_discriminator(&mut self) -> Result<Option<Discriminator>, CompileError> {
+        let obj = match self.value("discriminator") {
+            Some(Value::Object(obj)) if self.c.openapi_discriminator => obj,
+            _ => {
+                return Ok(self.c.discriminator.as_ref().map(|prop| Discriminator {
+                    prop: prop.clone(),
+                    mapping: HashMap::new(),
+                    any_of: false,
+                }))
+            }
+        };
+        let Some(Value::String(prop)) = obj.get("propertyName") else {
+            return Ok(None);
+        };
+        let mut mapping = HashMap::new();
+        if let Some(Value::Object(obj)) = obj.get("mapping") {
+            let root = self.root;
+            let base_url = root.base_url(&self.up.ptr);
+            for (tag, ref_) in obj {
+                let Value::String(ref_) = ref_ else {
+                    continue;
+                };
+                let abs_ref = UrlFrag::join(base_url, ref_)?;
+                mapping.insert(tag.clone(), self.resolve_ref(abs_ref)?);
+            }
+        }
+        Ok(Some(Discriminator {
+            prop: prop.clone(),
+            mapping,
+            any_of: true,
+        }))
     }
 
     fn enquue_additional(&mut self, pname: &'static str) -> Option<Additional> {
@@ -756,9 +2658,22 @@ pub enum CompileError {
     /// Failed loading `url`.
     LoadUrlError { url: String, src: Box<dyn Error> },
 
+    /// Document at `url` has duplicate `key` in an object, ending at
+    /// `line` and `column`. see [`Compiler::deny_duplicate_keys`]
+    DuplicateKey {
+        url: String,
+        key: String,
+        line: usize,
+        column: usize,
+    },
+
     /// no [`UrlLoader`] registered for the `url`
     UnsupportedUrlScheme { url: String },
 
+    /// `url` is not added to compiler, and loading it is denied.
+    /// see [`Compiler::require_preloaded_resources`]
+    NotPreloaded { url: String },
+
     /// Error in parsing `$schema` url.
     InvalidMetaSchemaUrl { url: String, src: Box<dyn Error> },
 
@@ -768,15 +2683,31 @@ pub enum CompileError {
     /// Cycle in resolving `$schema` in `url`.
     MetaSchemaCycle { url: String },
 
+    /// `url` uses `draft`, whose cargo feature is not enabled.
+    /// see [`Draft::is_enabled`]
+    DraftNotEnabled { url: String, draft: Draft },
+
     /// `url` is not valid against metaschema.
     ValidationError {
         url: String,
         src: ValidationError<'static, 'static>,
     },
 
+    /// `url` is not valid against `metaschema`, registered with
+    /// [`Compiler::add_metaschema`]. `src` describes the validation error.
+    MetaSchemaValidationError {
+        url: String,
+        metaschema: String,
+        src: Box<dyn Error>,
+    },
+
     /// Error in parsing id at `loc`
     ParseIdError { loc: String },
 
+    /// Subschema at `loc` is nested deeper than `max_depth`.
+    /// see [`Compiler::set_max_schema_depth`]
+    MaxDepthExceeded { loc: String, max_depth: usize },
+
     /// Error in parsing anchor at `loc`
     ParseAnchorError { loc: String },
 
@@ -805,6 +2736,51 @@ pub enum CompileError {
     /// anchor in `reference` not found in `url`.
     AnchorNotFound { url: String, reference: String },
 
+    /// `reference` at `loc` could not be resolved.
+    ///
+    /// `base` is the base uri against which `reference` is resolved
+    /// to `resolved`. `suggestions` lists similar anchors, definitions
+    /// or documents that are known to the compiler.
+    UnresolvedReference {
+        loc: String,
+        reference: String,
+        base: String,
+        resolved: String,
+        suggestions: Vec<String>,
+        src: Box<CompileError>,
+    },
+
+    /// `keyword` at `loc` is not defined by the draft.
+    ///
+    /// `suggestions` lists similar keywords defined by the draft.
+    /// see [`Compiler::deny_unknown_keywords`]
+    UnknownKeyword {
+        loc: String,
+        keyword: String,
+        suggestions: Vec<String>,
+    },
+
+    /// `format` at `loc` has no registered implementation.
+    ///
+    /// `suggestions` lists similar formats known to the compiler.
+    /// see [`Compiler::deny_unknown_formats`]
+    UnknownFormat {
+        loc: String,
+        format: String,
+        suggestions: Vec<String>,
+    },
+
+    /// `$dynamicRef` or `$recursiveRef` at `loc` can never resolve dynamically,
+    /// since its initial target `initial` has no matching dynamic anchor.
+    ///
+    /// `suggestions` lists similar `$dynamicAnchor` names in resource of `initial`.
+    /// see [`Compiler::deny_dangling_dynamic_refs`]
+    DanglingDynamicRef {
+        loc: String,
+        initial: String,
+        suggestions: Vec<String>,
+    },
+
     /// Unsupported vocabulary `vocabulary` in `url`.
     UnsupportedVocabulary { url: String, vocabulary: String },
 
@@ -815,6 +2791,14 @@ pub enum CompileError {
         src: Box<dyn Error>,
     },
 
+    /// JSON Type Definition at `loc` is not valid.
+    /// see [`Compiler::set_dialect`]
+    InvalidJtdSchema { loc: String, reason: String },
+
+    /// Schema at `loc` is not a Kubernetes structural schema.
+    /// see [`Compiler::enable_kubernetes_extensions`]
+    NonStructural { loc: String, reason: String },
+
     /// Encountered bug in compiler implementation. Please report
     /// this as an issue for this crate.
     Bug(Box<dyn Error>),
@@ -827,6 +2811,8 @@ impl Error for CompileError {
             Self::LoadUrlError { src, .. } => Some(src.as_ref()),
             Self::InvalidMetaSchemaUrl { src, .. } => Some(src.as_ref()),
             Self::ValidationError { src, .. } => Some(src),
+            Self::MetaSchemaValidationError { src, .. } => Some(src.as_ref()),
+            Self::UnresolvedReference { src, .. } => Some(src.as_ref()),
             Self::Bug(src) => Some(src.as_ref()),
             _ => None,
         }
@@ -851,6 +2837,17 @@ impl Display for CompileError {
                 }
             }
             Self::UnsupportedUrlScheme { url } => write!(f, "unsupported scheme in {url}"),
+            Self::NotPreloaded { url } => write!(f, "{url} is not preloaded"),
+            Self::DuplicateKey {
+                url,
+                key,
+                line,
+                column,
+            } => write!(
+                f,
+                "duplicate key {} in {url} at line {line} column {column}",
+                quote(key)
+            ),
             Self::InvalidMetaSchemaUrl { url, src } => {
                 if f.alternate() {
                     write!(f, "invalid $schema in {url}: {src}")
@@ -862,6 +2859,12 @@ impl Display for CompileError {
             Self::MetaSchemaCycle { url } => {
                 write!(f, "cycle in resolving $schema in {url}")
             }
+            Self::DraftNotEnabled { url, draft } => write!(
+                f,
+                "{url} uses draft {}, which is not enabled. enable cargo feature `draft{}`",
+                draft.version(),
+                draft.version()
+            ),
             Self::ValidationError { url, src } => {
                 if f.alternate() {
                     write!(f, "{url} is not valid against metaschema: {src}")
@@ -869,7 +2872,24 @@ impl Display for CompileError {
                     write!(f, "{url} is not valid against metaschema")
                 }
             }
+            Self::MetaSchemaValidationError {
+                url,
+                metaschema,
+                src,
+            } => {
+                if f.alternate() {
+                    write!(
+                        f,
+                        "{url} is not valid against metaschema {metaschema}: {src}"
+                    )
+                } else {
+                    write!(f, "{url} is not valid against metaschema {metaschema}")
+                }
+            }
             Self::ParseIdError { loc } => write!(f, "error in parsing id at {loc}"),
+            Self::MaxDepthExceeded { loc, max_depth } => {
+                write!(f, "schema at {loc} is nested deeper than {max_depth}")
+            }
             Self::ParseAnchorError { loc } => write!(f, "error in parsing anchor at {loc}"),
             Self::DuplicateId {
                 url,
@@ -896,6 +2916,59 @@ impl Display for CompileError {
                     "anchor in reference {reference} is not found in schema {url}"
                 )
             }
+            Self::UnresolvedReference {
+                loc,
+                reference,
+                base,
+                resolved,
+                suggestions,
+                src,
+            } => {
+                write!(f, "unresolved reference {} at {loc}", quote(reference))?;
+                if f.alternate() {
+                    write!(f, " (resolved to {resolved} against {base}): {src:#}")?;
+                }
+                if !suggestions.is_empty() {
+                    write!(f, ", did you mean {}?", join_iter(suggestions, " or "))?;
+                }
+                Ok(())
+            }
+            Self::UnknownKeyword {
+                loc,
+                keyword,
+                suggestions,
+            } => {
+                write!(f, "unknown keyword {} at {loc}", quote(keyword))?;
+                if !suggestions.is_empty() {
+                    write!(f, ", did you mean {}?", join_iter(suggestions, " or "))?;
+                }
+                Ok(())
+            }
+            Self::UnknownFormat {
+                loc,
+                format,
+                suggestions,
+            } => {
+                write!(f, "unknown format {} at {loc}", quote(format))?;
+                if !suggestions.is_empty() {
+                    write!(f, ", did you mean {}?", join_iter(suggestions, " or "))?;
+                }
+                Ok(())
+            }
+            Self::DanglingDynamicRef {
+                loc,
+                initial,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "{loc} never resolves dynamically, since {initial} has no matching dynamic anchor"
+                )?;
+                if !suggestions.is_empty() {
+                    write!(f, ", did you mean {}?", join_iter(suggestions, " or "))?;
+                }
+                Ok(())
+            }
             Self::UnsupportedVocabulary { url, vocabulary } => {
                 write!(f, "unsupported vocabulary {vocabulary} in {url}")
             }
@@ -906,6 +2979,12 @@ impl Display for CompileError {
                     write!(f, "invalid regex {} at {url}", quote(regex))
                 }
             }
+            Self::InvalidJtdSchema { loc, reason } => {
+                write!(f, "invalid json type definition at {loc}: {reason}")
+            }
+            Self::NonStructural { loc, reason } => {
+                write!(f, "schema at {loc} is not structural: {reason}")
+            }
             Self::Bug(src) => {
                 write!(
                     f,
@@ -952,6 +3031,10 @@ impl Queue {
         uf: UrlFrag,
         roots: &Roots,
     ) -> Result<UrlPtr, CompileError> {
+        let uf = UrlFrag {
+            url: roots.canonical(uf.url),
+            frag: uf.frag,
+        };
         match uf.frag {
             Fragment::JsonPointer(ptr) => Ok(UrlPtr { url: uf.url, ptr }),
             Fragment::Anchor(_) => {
@@ -975,11 +3058,37 @@ impl Queue {
         }
         if let Some(qindex) = self.schemas.iter().position(|e| *e == up) {
             // already queued for compilation
-            return SchemaIndex(schemas.size() + qindex);
+            return SchemaIndex(schemas.next_index() + qindex);
         }
 
         // new compilation request
         self.schemas.push(up);
-        SchemaIndex(schemas.size() + self.schemas.len() - 1)
+        SchemaIndex(schemas.next_index() + self.schemas.len() - 1)
+    }
+}
+
+// whether meaning of schema document `doc` does not depend on its url
+fn is_location_independent(doc: &Value) -> bool {
+    if let Some(Value::String(id)) = doc.get("$id").or_else(|| doc.get("id")) {
+        return Url::parse(id).is_ok();
+    }
+    let mut stack = vec![doc];
+    while let Some(v) = stack.pop() {
+        match v {
+            Value::Object(obj) => {
+                for (k, v) in obj {
+                    if matches!(
+                        k.as_str(),
+                        "$ref" | "$dynamicRef" | "$recursiveRef" | "$id" | "id"
+                    ) {
+                        return false;
+                    }
+                    stack.push(v);
+                }
+            }
+            Value::Array(arr) => stack.extend(arr),
+            _ => {}
+        }
     }
+    true
 }
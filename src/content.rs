@@ -1,4 +1,4 @@
-use std::{collections::HashMap, error::Error};
+use std::{collections::HashMap, error::Error, sync::Arc};
 
 use base64::Engine;
 use once_cell::sync::Lazy;
@@ -18,6 +18,24 @@ pub struct Decoder {
     pub func: fn(s: &str) -> Result<Vec<u8>, Box<dyn Error>>,
 }
 
+pub(crate) type DecoderFn = dyn Fn(&str) -> Result<Vec<u8>, Box<dyn Error>> + Send + Sync;
+
+// decoder whose func may capture state.
+#[derive(Clone)]
+pub(crate) struct DynDecoder {
+    pub(crate) name: &'static str,
+    pub(crate) func: Arc<DecoderFn>,
+}
+
+impl From<Decoder> for DynDecoder {
+    fn from(decoder: Decoder) -> Self {
+        Self {
+            name: decoder.name,
+            func: Arc::new(decoder.func),
+        }
+    }
+}
+
 pub(crate) static DECODERS: Lazy<HashMap<&'static str, Decoder>> = Lazy::new(|| {
     let mut m = HashMap::<&'static str, Decoder>::new();
     m.insert(
@@ -60,6 +78,27 @@ pub struct MediaType {
     pub func: fn(bytes: &[u8], deserialize: bool) -> Result<Option<Value>, Box<dyn Error>>,
 }
 
+pub(crate) type MediaTypeFn =
+    dyn Fn(&[u8], bool) -> Result<Option<Value>, Box<dyn Error>> + Send + Sync;
+
+// media type whose func may capture state.
+#[derive(Clone)]
+pub(crate) struct DynMediaType {
+    pub(crate) name: &'static str,
+    pub(crate) json_compatible: bool,
+    pub(crate) func: Arc<MediaTypeFn>,
+}
+
+impl From<MediaType> for DynMediaType {
+    fn from(mt: MediaType) -> Self {
+        Self {
+            name: mt.name,
+            json_compatible: mt.json_compatible,
+            func: Arc::new(mt.func),
+        }
+    }
+}
+
 pub(crate) static MEDIA_TYPES: Lazy<HashMap<&'static str, MediaType>> = Lazy::new(|| {
     let mut m = HashMap::<&'static str, MediaType>::new();
     m.insert(
@@ -80,3 +119,36 @@ fn check_json(bytes: &[u8], deserialize: bool) -> Result<Option<Value>, Box<dyn
     serde_json::from_slice::<IgnoredAny>(bytes)?;
     Ok(None)
 }
+
+// nesting depth of json text `bytes`, measured without deserializing,
+// as in ValidationOptions::set_max_content_depth. scalars have depth 1.
+pub(crate) fn json_depth(bytes: &[u8]) -> usize {
+    let (mut depth, mut max) = (0, 0);
+    let (mut in_str, mut escaped) = (false, false);
+    for &b in bytes {
+        if in_str {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_str = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b']' | b'}' => depth = usize::saturating_sub(depth, 1),
+            b',' | b':' => {}
+            b if b.is_ascii_whitespace() => {}
+            _ => {
+                // start of value
+                max = usize::max(max, depth + 1);
+                match b {
+                    b'"' => in_str = true,
+                    b'[' | b'{' => depth += 1,
+                    _ => {}
+                }
+            }
+        }
+    }
+    max
+}
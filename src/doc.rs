@@ -0,0 +1,333 @@
+use std::{collections::HashMap, fmt::Write};
+
+use serde_json::Value;
+
+use crate::{util::*, validator::constraints, Additional, Items, Schema, SchemaIndex, Schemas};
+
+/// Format of documentation rendered by [`Schemas::doc`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocFormat {
+    Markdown,
+    Html,
+}
+
+impl Schemas {
+    /**
+    Renders human-readable documentation of schema identified by
+    `sch_index`, in given `format`.
+
+    The documentation has a section for the schema, and for each
+    subschema reachable from it, with its title, description, type,
+    constraints, examples and a table of properties. `$ref` is
+    resolved, linking to the section of the referenced schema.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/person.json", json!({
+        "title": "Person",
+        "type": "object",
+        "properties": {
+            "name": {"type": "string", "description": "full name"},
+            "age": {"type": "integer", "minimum": 0}
+        },
+        "required": ["name"]
+    }))?;
+    let sch = compiler.compile("http://a.com/person.json", &mut schemas)?;
+    let doc = schemas.doc(sch, DocFormat::Markdown);
+    assert!(doc.contains("## Person"));
+    assert!(doc.contains("| [`name`](#s2) | `string` | yes | full name |"));
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    */
+    pub fn doc(&self, sch_index: SchemaIndex, format: DocFormat) -> String {
+        if !self.contains(sch_index) {
+            panic!("Schemas::doc: schema index out of bounds");
+        }
+        let mut doc = Doc {
+            schemas: self,
+            ids: HashMap::new(),
+            queue: vec![],
+        };
+        doc.link(sch_index);
+        let mut sections = vec![];
+        while let Some(&idx) = doc.queue.get(sections.len()) {
+            sections.push(doc.section(idx));
+        }
+        match format {
+            DocFormat::Markdown => markdown(&sections),
+            DocFormat::Html => html(&sections),
+        }
+    }
+}
+
+// sections are rendered in breadth first order, starting with root.
+struct Doc<'s> {
+    schemas: &'s Schemas,
+    ids: HashMap<SchemaIndex, usize>, // schema => section number
+    queue: Vec<SchemaIndex>,
+}
+
+struct Section<'s> {
+    title: &'s str,
+    url: &'s str,
+    description: Option<&'s str>,
+    types: String,
+    constraints: Vec<String>,
+    links: Vec<(&'static str, Vec<usize>)>,
+    properties: Vec<Property<'s>>,
+    examples: &'s [Value],
+}
+
+struct Property<'s> {
+    name: &'s str,
+    types: String,
+    required: bool,
+    description: Option<&'s str>,
+    link: Option<usize>,
+}
+
+impl<'s> Doc<'s> {
+    // follows schemas having nothing but `$ref`
+    fn resolve(&self, mut idx: SchemaIndex) -> &'s Schema {
+        let mut seen = vec![];
+        loop {
+            let s = self.schemas.get(idx);
+            match pure_ref(s) {
+                Some(ref_) if !seen.contains(&ref_) => {
+                    seen.push(idx);
+                    idx = ref_;
+                }
+                _ => return s,
+            }
+        }
+    }
+
+    // returns section number of schema, queueing it if new.
+    // boolean schemas get no section.
+    fn link(&mut self, idx: SchemaIndex) -> Option<usize> {
+        let s = self.resolve(idx);
+        if s.boolean.is_some() {
+            return None;
+        }
+        if let Some(&id) = self.ids.get(&s.idx) {
+            return Some(id);
+        }
+        let id = self.queue.len();
+        self.queue.push(s.idx);
+        self.ids.insert(s.idx, id);
+        Some(id)
+    }
+
+    fn links(&mut self, list: &[SchemaIndex]) -> Vec<usize> {
+        list.iter().filter_map(|&idx| self.link(idx)).collect()
+    }
+
+    fn section(&mut self, idx: SchemaIndex) -> Section<'s> {
+        let s = self.schemas.get(idx);
+        let mut links = vec![];
+        let mut add = |kw, ids: Vec<usize>| {
+            if !ids.is_empty() {
+                links.push((kw, ids));
+            }
+        };
+        add("allOf", self.links(&s.all_of));
+        add("anyOf", self.links(&s.any_of));
+        add("oneOf", self.links(&s.one_of));
+        add("not", self.links(&Vec::from_iter(s.not)));
+        add("if", self.links(&Vec::from_iter(s.if_)));
+        add("then", self.links(&Vec::from_iter(s.then)));
+        add("else", self.links(&Vec::from_iter(s.else_)));
+        let pattern_properties: Vec<_> = s.pattern_properties.iter().map(|(_, sch)| *sch).collect();
+        add("patternProperties", self.links(&pattern_properties));
+        if let Some(Additional::SchemaRef(sch)) = s.additional_properties {
+            add("additionalProperties", self.links(&[sch]));
+        }
+        match &s.items {
+            Some(Items::SchemaRef(sch)) => add("items", self.links(&[*sch])),
+            Some(Items::SchemaRefs(list)) => add("items", self.links(list)),
+            None => {}
+        }
+        if let Some(Additional::SchemaRef(sch)) = s.additional_items {
+            add("additionalItems", self.links(&[sch]));
+        }
+        add("prefixItems", self.links(&s.prefix_items));
+        add("items", self.links(&Vec::from_iter(s.items2020)));
+        add("contains", self.links(&Vec::from_iter(s.contains)));
+        add("$ref", self.links(&Vec::from_iter(s.ref_)));
+
+        let mut names: Vec<_> = s.properties.keys().collect();
+        names.sort();
+        let properties = names
+            .into_iter()
+            .map(|name| {
+                let sch = s.properties[name];
+                Property {
+                    name,
+                    types: self.types(sch),
+                    required: s.required.contains(name),
+                    description: self.schemas.get(sch).description.as_deref(),
+                    link: self.link(sch),
+                }
+            })
+            .collect();
+
+        let constraints = constraints(s)
+            .into_iter()
+            .filter(|c| !c.starts_with("type ") && !c.starts_with("properties "))
+            .collect();
+        Section {
+            title: s.title.as_deref().unwrap_or(&s.loc),
+            url: &s.loc,
+            description: s.description.as_deref(),
+            types: self.types(idx),
+            constraints,
+            links,
+            properties,
+            examples: &s.examples,
+        }
+    }
+
+    fn types(&self, idx: SchemaIndex) -> String {
+        let s = self.resolve(idx);
+        match s.boolean {
+            Some(true) => "any".to_owned(),
+            Some(false) => "never".to_owned(),
+            None if !s.types.is_empty() => join_iter(s.types.iter(), " or "),
+            None => "any".to_owned(),
+        }
+    }
+}
+
+// returns target of `$ref`, if it is the only keyword in `s`
+fn pure_ref(s: &Schema) -> Option<SchemaIndex> {
+    let ref_ = s.ref_?;
+    let refs = s.applied_subschemas().len();
+    (refs == 1 && s.types.is_empty() && constraints(s).is_empty()).then_some(ref_)
+}
+
+fn markdown(sections: &[Section]) -> String {
+    // keep table rows on single line
+    fn cell(s: &str) -> String {
+        s.replace('|', "\\|").replace('\n', " ")
+    }
+
+    let mut out = String::new();
+    for (id, sec) in sections.iter().enumerate() {
+        if id > 0 {
+            out.push('\n');
+        }
+        _ = writeln!(out, "<a id=\"s{id}\"></a>");
+        _ = writeln!(out, "## {}\n", sec.title);
+        _ = writeln!(out, "`{}`\n", sec.url);
+        if let Some(desc) = sec.description {
+            _ = writeln!(out, "{desc}\n");
+        }
+        _ = writeln!(out, "- **type:** `{}`", sec.types);
+        for c in &sec.constraints {
+            _ = writeln!(out, "- `{c}`");
+        }
+        for (kw, ids) in &sec.links {
+            let links: Vec<_> = ids
+                .iter()
+                .map(|&id| format!("[{}](#s{id})", sections[id].title))
+                .collect();
+            _ = writeln!(out, "- **{kw}:** {}", links.join(", "));
+        }
+        if !sec.examples.is_empty() {
+            _ = writeln!(out, "\n**examples:**\n");
+            for ex in sec.examples {
+                _ = writeln!(out, "```json\n{ex:#}\n```");
+            }
+        }
+        if !sec.properties.is_empty() {
+            _ = writeln!(out, "\n### Properties\n");
+            _ = writeln!(out, "| Name | Type | Required | Description |");
+            _ = writeln!(out, "| --- | --- | --- | --- |");
+            for p in &sec.properties {
+                let name = match p.link {
+                    Some(id) => format!("[`{}`](#s{id})", cell(p.name)),
+                    None => format!("`{}`", cell(p.name)),
+                };
+                _ = writeln!(
+                    out,
+                    "| {name} | `{}` | {} | {} |",
+                    p.types,
+                    if p.required { "yes" } else { "no" },
+                    cell(p.description.unwrap_or_default())
+                );
+            }
+        }
+    }
+    out
+}
+
+fn html(sections: &[Section]) -> String {
+    fn esc(s: &str) -> String {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    let mut out = String::new();
+    for (id, sec) in sections.iter().enumerate() {
+        _ = writeln!(out, "<section id=\"s{id}\">");
+        _ = writeln!(out, "<h2>{}</h2>", esc(sec.title));
+        _ = writeln!(out, "<p><code>{}</code></p>", esc(sec.url));
+        if let Some(desc) = sec.description {
+            _ = writeln!(out, "<p>{}</p>", esc(desc));
+        }
+        _ = writeln!(out, "<ul>");
+        _ = writeln!(out, "<li><b>type:</b> <code>{}</code></li>", sec.types);
+        for c in &sec.constraints {
+            _ = writeln!(out, "<li><code>{}</code></li>", esc(c));
+        }
+        for (kw, ids) in &sec.links {
+            let links: Vec<_> = ids
+                .iter()
+                .map(|&id| format!("<a href=\"#s{id}\">{}</a>", esc(sections[id].title)))
+                .collect();
+            _ = writeln!(out, "<li><b>{kw}:</b> {}</li>", links.join(", "));
+        }
+        _ = writeln!(out, "</ul>");
+        if !sec.examples.is_empty() {
+            _ = writeln!(out, "<p><b>examples:</b></p>");
+            for ex in sec.examples {
+                _ = writeln!(out, "<pre>{}</pre>", esc(&format!("{ex:#}")));
+            }
+        }
+        if !sec.properties.is_empty() {
+            _ = writeln!(out, "<h3>Properties</h3>");
+            _ = writeln!(out, "<table>");
+            _ = writeln!(
+                out,
+                "<tr><th>Name</th><th>Type</th><th>Required</th><th>Description</th></tr>"
+            );
+            for p in &sec.properties {
+                let name = match p.link {
+                    Some(id) => format!("<a href=\"#s{id}\"><code>{}</code></a>", esc(p.name)),
+                    None => format!("<code>{}</code>", esc(p.name)),
+                };
+                _ = writeln!(
+                    out,
+                    "<tr><td>{name}</td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                    p.types,
+                    if p.required { "yes" } else { "no" },
+                    esc(p.description.unwrap_or_default())
+                );
+            }
+            _ = writeln!(out, "</table>");
+        }
+        _ = writeln!(out, "</section>");
+    }
+    out
+}
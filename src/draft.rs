@@ -36,11 +36,50 @@ pub(crate) static DRAFT4: Lazy<Draft> = Lazy::new(|| Draft {
     vocab_prefix: "",
     all_vocabs: vec![],
     default_vocabs: vec![],
+    keywords: vec![
+        "$schema",
+        "id",
+        "$ref",
+        "definitions",
+        "title",
+        "description",
+        "default",
+        "multipleOf",
+        "maximum",
+        "exclusiveMaximum",
+        "minimum",
+        "exclusiveMinimum",
+        "maxLength",
+        "minLength",
+        "pattern",
+        "additionalItems",
+        "items",
+        "maxItems",
+        "minItems",
+        "uniqueItems",
+        "maxProperties",
+        "minProperties",
+        "required",
+        "additionalProperties",
+        "properties",
+        "patternProperties",
+        "dependencies",
+        "enum",
+        "type",
+        "allOf",
+        "anyOf",
+        "oneOf",
+        "not",
+        "format",
+    ],
 });
 
 pub(crate) static DRAFT6: Lazy<Draft> = Lazy::new(|| {
     let mut subschemas = DRAFT4.subschemas.clone();
     subschemas.extend([("propertyNames", POS_SELF), ("contains", POS_SELF)]);
+    let mut keywords = DRAFT4.keywords.clone();
+    keywords.retain(|kw| *kw != "id");
+    keywords.extend(["$id", "const", "contains", "propertyNames", "examples"]);
     Draft {
         version: 6,
         id: "$id",
@@ -49,12 +88,24 @@ pub(crate) static DRAFT6: Lazy<Draft> = Lazy::new(|| {
         vocab_prefix: "",
         all_vocabs: vec![],
         default_vocabs: vec![],
+        keywords,
     }
 });
 
 pub(crate) static DRAFT7: Lazy<Draft> = Lazy::new(|| {
     let mut subschemas = DRAFT6.subschemas.clone();
     subschemas.extend([("if", POS_SELF), ("then", POS_SELF), ("else", POS_SELF)]);
+    let mut keywords = DRAFT6.keywords.clone();
+    keywords.extend([
+        "$comment",
+        "if",
+        "then",
+        "else",
+        "readOnly",
+        "writeOnly",
+        "contentEncoding",
+        "contentMediaType",
+    ]);
     Draft {
         version: 7,
         id: "$id",
@@ -63,6 +114,7 @@ pub(crate) static DRAFT7: Lazy<Draft> = Lazy::new(|| {
         vocab_prefix: "",
         all_vocabs: vec![],
         default_vocabs: vec![],
+        keywords,
     }
 });
 
@@ -75,6 +127,22 @@ pub(crate) static DRAFT2019: Lazy<Draft> = Lazy::new(|| {
         ("unevaluatedItems", POS_SELF),
         ("contentSchema", POS_SELF),
     ]);
+    let mut keywords = DRAFT7.keywords.clone();
+    keywords.extend([
+        "$anchor",
+        "$recursiveRef",
+        "$recursiveAnchor",
+        "$vocabulary",
+        "$defs",
+        "dependentRequired",
+        "dependentSchemas",
+        "unevaluatedProperties",
+        "unevaluatedItems",
+        "maxContains",
+        "minContains",
+        "contentSchema",
+        "deprecated",
+    ]);
     Draft {
         version: 2019,
         id: "$id",
@@ -90,12 +158,16 @@ pub(crate) static DRAFT2019: Lazy<Draft> = Lazy::new(|| {
             "content",
         ],
         default_vocabs: vec!["core", "applicator", "validation"],
+        keywords,
     }
 });
 
 pub(crate) static DRAFT2020: Lazy<Draft> = Lazy::new(|| {
     let mut subschemas = DRAFT2019.subschemas.clone();
     subschemas.extend([("prefixItems", POS_ITEM)]);
+    let mut keywords = DRAFT2019.keywords.clone();
+    keywords.retain(|kw| !kw.starts_with("$recursive"));
+    keywords.extend(["prefixItems", "$dynamicRef", "$dynamicAnchor"]);
     Draft {
         version: 2020,
         id: "$id",
@@ -113,6 +185,7 @@ pub(crate) static DRAFT2020: Lazy<Draft> = Lazy::new(|| {
             "content",
         ],
         default_vocabs: vec!["core", "applicator", "unevaluated", "validation"],
+        keywords,
     }
 });
 
@@ -123,6 +196,32 @@ pub(crate) fn latest() -> &'static Draft {
     crate::Draft::default().internal()
 }
 
+/**
+Removes `$comment`, `description` and `examples` from schema document
+`sch` and its subschemas, for example to reduce the size of schemas
+served over network. These keywords do not affect validation.
+
+Subschemas are located using the draft specified by `$schema`,
+defaulting to latest draft. Members of `properties`, `const`, `enum`
+etc. with these names are left untouched.
+
+```
+# use boon::*;
+# use serde_json::json;
+let mut sch = json!({
+    "$comment": "root",
+    "properties": {
+        "description": {"description": "desc", "type": "string"}
+    }
+});
+strip_annotations(&mut sch);
+assert_eq!(sch, json!({"properties": {"description": {"type": "string"}}}));
+```
+*/
+pub fn strip_annotations(sch: &mut Value) {
+    Draft::of(sch).strip_annotations(sch);
+}
+
 // --
 
 pub(crate) struct Draft {
@@ -133,6 +232,7 @@ pub(crate) struct Draft {
     pub(crate) vocab_prefix: &'static str,    // prefix used for vocabulary
     pub(crate) all_vocabs: Vec<&'static str>, // names of supported vocabs
     pub(crate) default_vocabs: Vec<&'static str>, // names of default vocabs
+    pub(crate) keywords: Vec<&'static str>,   // keywords defined by draft
 }
 
 impl Draft {
@@ -158,6 +258,27 @@ impl Draft {
         }
     }
 
+    // draft specified by `$schema` of document `sch`, defaulting to latest
+    pub(crate) fn of(sch: &Value) -> &'static Draft {
+        match sch.get("$schema") {
+            Some(Value::String(url)) => Draft::from_url(url).unwrap_or_else(latest),
+            _ => latest(),
+        }
+    }
+
+    // whether cargo feature of this draft is enabled
+    #[allow(clippy::match_like_matches_macro)] // arms differ by features
+    pub(crate) fn enabled(&self) -> bool {
+        match self.version {
+            4 => cfg!(feature = "draft4"),
+            6 => cfg!(feature = "draft6"),
+            7 => cfg!(feature = "draft7"),
+            2019 => cfg!(feature = "draft2019"),
+            2020 => cfg!(feature = "draft2020"),
+            _ => false,
+        }
+    }
+
     fn get_schema(&self) -> Option<SchemaIndex> {
         let url = match self.version {
             2020 => "https://json-schema.org/draft/2020-12/schema",
@@ -199,10 +320,17 @@ impl Draft {
         Some(id).filter(|id| !id.is_empty())
     }
 
+    // `known` lists additional vocabularies which are accepted
+    // even though they are not implemented.
+    //
+    // supported vocabularies are enabled, even if they are optional.
+    // unsupported optional vocabularies are ignored, so that their
+    // keywords are treated as annotations.
     pub(crate) fn get_vocabs(
         &self,
         url: &Url,
         doc: &Value,
+        known: &[&str],
     ) -> Result<Option<Vec<String>>, CompileError> {
         if self.version < 2019 {
             return Ok(None);
@@ -217,18 +345,18 @@ impl Draft {
 
         let mut vocabs = vec![];
         for (vocab, reqd) in obj {
-            if let Value::Bool(true) = reqd {
-                let name = vocab
-                    .strip_prefix(self.vocab_prefix)
-                    .filter(|name| self.all_vocabs.contains(name));
-                if let Some(name) = name {
-                    vocabs.push(name.to_owned()); // todo: avoid alloc
-                } else {
-                    return Err(CompileError::UnsupportedVocabulary {
-                        url: url.as_str().to_owned(),
-                        vocabulary: vocab.to_owned(),
-                    });
-                }
+            let name = vocab
+                .strip_prefix(self.vocab_prefix)
+                .filter(|name| self.all_vocabs.contains(name));
+            if let Some(name) = name {
+                vocabs.push(name.to_owned()); // todo: avoid alloc
+            } else if reqd == &Value::Bool(true) && !known.contains(&vocab.as_str()) {
+                return Err(CompileError::UnsupportedVocabulary {
+                    url: url.as_str().to_owned(),
+                    vocabulary: vocab.to_owned(),
+                });
+            } else {
+                debug!("{url}: vocabulary {vocab} is not supported, its keywords are ignored");
             }
         }
         Ok(Some(vocabs))
@@ -247,23 +375,17 @@ impl Draft {
             return Ok(());
         };
 
+        // duplicates are collected, rather than failing, so that
+        // Compiler::anchors can list them
         let mut add_anchor = |anchor: Anchor| match res.anchors.entry(anchor) {
             Entry::Occupied(entry) => {
-                if entry.get() == sch_ptr {
-                    // anchor with same root_ptr already exists
-                    return Ok(());
+                // anchor with same root_ptr may already exist
+                if entry.get() != sch_ptr {
+                    let anchor = entry.key().clone();
+                    res.duplicate_anchors.push((anchor, sch_ptr.to_owned()));
                 }
-                Err(CompileError::DuplicateAnchor {
-                    url: url.as_str().to_owned(),
-                    anchor: entry.key().to_string(),
-                    ptr1: entry.get().to_string(),
-                    ptr2: sch_ptr.to_string(),
-                })
-            }
-            entry => {
-                entry.or_insert(sch_ptr.to_owned());
-                Ok(())
             }
+            entry => _ = entry.or_insert(sch_ptr.to_owned()),
         };
 
         if self.version < 2019 {
@@ -277,19 +399,19 @@ impl Draft {
                     return Err(CompileError::ParseAnchorError { loc });
                 };
                 if let Fragment::Anchor(anchor) = frag {
-                    add_anchor(anchor)?;
+                    add_anchor(anchor);
                 };
                 return Ok(());
             }
         }
         if self.version >= 2019 {
             if let Some(Value::String(anchor)) = obj.get("$anchor") {
-                add_anchor(anchor.as_str().into())?;
+                add_anchor(anchor.as_str().into());
             }
         }
         if self.version >= 2020 {
             if let Some(Value::String(anchor)) = obj.get("$dynamicAnchor") {
-                add_anchor(anchor.as_str().into())?;
+                add_anchor(anchor.as_str().into());
                 res.dynamic_anchors.insert(anchor.as_str().into());
             }
         }
@@ -297,6 +419,9 @@ impl Draft {
     }
 
     // error is json-ptr to invalid id
+    // collects resources and their anchors from `sch` and its subschemas,
+    // without recursion. fails if subschemas are nested deeper than `max_depth`,
+    // where `sch` has depth 1.
     pub(crate) fn collect_resources(
         &self,
         sch: &Value,
@@ -304,91 +429,163 @@ impl Draft {
         sch_ptr: JsonPointer, // ptr of json
         url: &Url,
         resources: &mut HashMap<JsonPointer, Resource>,
+        max_depth: Option<usize>,
     ) -> Result<(), CompileError> {
-        if resources.contains_key(&sch_ptr) {
-            // resources are already collected
-            return Ok(());
-        }
-        if let Value::Bool(_) = sch {
-            if sch_ptr.is_empty() {
-                // root resource
-                resources.insert(sch_ptr.clone(), Resource::new(sch_ptr, base.clone()));
+        let mut bases = vec![base.clone()];
+        // (schema, index into bases, ptr, depth)
+        let mut stack = vec![(sch, 0, sch_ptr, 1)];
+        while let Some((sch, mut base, sch_ptr, depth)) = stack.pop() {
+            if resources.contains_key(&sch_ptr) {
+                // resources are already collected
+                continue;
+            }
+            if let Some(max_depth) = max_depth {
+                if depth > max_depth {
+                    let loc = UrlFrag::format(url, sch_ptr.as_str());
+                    return Err(CompileError::MaxDepthExceeded { loc, max_depth });
+                }
+            }
+            if let Value::Bool(_) = sch {
+                if sch_ptr.is_empty() {
+                    // root resource
+                    let res = Resource::new(sch_ptr.clone(), bases[base].clone());
+                    resources.insert(sch_ptr, res);
+                }
+                continue;
             }
-            return Ok(());
-        }
 
-        let Value::Object(obj) = sch else {
-            return Ok(());
-        };
+            let Value::Object(obj) = sch else {
+                continue;
+            };
 
-        let mut base = base;
-        let tmp;
-        let res = if let Some(id) = self.get_id(obj) {
-            let Ok(id) = UrlFrag::join(base, id) else {
-                let loc = UrlFrag::format(url, sch_ptr.as_str());
-                return Err(CompileError::ParseIdError { loc });
+            let res = if let Some(id) = self.get_id(obj) {
+                let Ok(id) = UrlFrag::join(&bases[base], id) else {
+                    let loc = UrlFrag::format(url, sch_ptr.as_str());
+                    return Err(CompileError::ParseIdError { loc });
+                };
+                bases.push(id.url);
+                base = bases.len() - 1;
+                Some(Resource::new(sch_ptr.clone(), bases[base].clone()))
+            } else if sch_ptr.is_empty() {
+                // root resource
+                Some(Resource::new(sch_ptr.clone(), bases[base].clone()))
+            } else {
+                None
             };
-            tmp = id.url;
-            base = &tmp;
-            Some(Resource::new(sch_ptr.clone(), base.clone()))
-        } else if sch_ptr.is_empty() {
-            // root resource
-            Some(Resource::new(sch_ptr.clone(), base.clone()))
-        } else {
-            None
-        };
-        if let Some(res) = res {
-            if let Some(dup) = resources.values_mut().find(|res| res.id == *base) {
-                return Err(CompileError::DuplicateId {
-                    url: url.to_string(),
-                    id: base.to_string(),
-                    ptr1: res.ptr.to_string(),
-                    ptr2: dup.ptr.to_string(),
-                });
+            let base_url = &bases[base];
+            if let Some(res) = res {
+                if let Some(dup) = resources.values_mut().find(|res| res.id == *base_url) {
+                    return Err(CompileError::DuplicateId {
+                        url: url.to_string(),
+                        id: base_url.to_string(),
+                        ptr1: res.ptr.to_string(),
+                        ptr2: dup.ptr.to_string(),
+                    });
+                }
+                resources.insert(sch_ptr.clone(), res);
+            }
+
+            // collect anchors into base resource
+            if let Some(res) = resources.values_mut().find(|res| res.id == *base_url) {
+                self.collect_anchors(sch, &sch_ptr, res, url)?;
+            } else {
+                debug_assert!(false, "base resource must exist");
             }
-            resources.insert(sch_ptr.clone(), res);
-        }
 
-        // collect anchors into base resource
-        if let Some(res) = resources.values_mut().find(|res| res.id == *base) {
-            self.collect_anchors(sch, &sch_ptr, res, url)?;
-        } else {
-            debug_assert!(false, "base resource must exist");
+            // push in reverse, so that subschemas are visited in order
+            let len = stack.len();
+            for (&kw, &pos) in &self.subschemas {
+                let Some(v) = obj.get(kw) else {
+                    continue;
+                };
+                if pos & POS_SELF != 0 {
+                    let ptr = sch_ptr.append(kw);
+                    stack.push((v, base, ptr, depth + 1));
+                }
+                if pos & POS_ITEM != 0 {
+                    if let Value::Array(arr) = v {
+                        for (i, item) in arr.iter().enumerate() {
+                            let ptr = sch_ptr.append2(kw, &i.to_string());
+                            stack.push((item, base, ptr, depth + 1));
+                        }
+                    }
+                }
+                if pos & POS_PROP != 0 {
+                    if let Value::Object(obj) = v {
+                        for (pname, pvalue) in obj {
+                            let ptr = sch_ptr.append2(kw, pname);
+                            stack.push((pvalue, base, ptr, depth + 1));
+                        }
+                    }
+                }
+            }
+            stack[len..].reverse();
         }
+        Ok(())
+    }
 
-        for (&kw, &pos) in &self.subschemas {
-            let Some(v) = obj.get(kw) else {
+    fn strip_annotations(&self, sch: &mut Value) {
+        let mut stack = vec![sch];
+        while let Some(sch) = stack.pop() {
+            let Value::Object(obj) = sch else {
                 continue;
             };
-            if pos & POS_SELF != 0 {
-                let ptr = sch_ptr.append(kw);
-                self.collect_resources(v, base, ptr, url, resources)?;
+            for kw in ["$comment", "description", "examples"] {
+                obj.remove(kw);
             }
-            if pos & POS_ITEM != 0 {
-                if let Value::Array(arr) = v {
-                    for (i, item) in arr.iter().enumerate() {
-                        let ptr = sch_ptr.append2(kw, &i.to_string());
-                        self.collect_resources(item, base, ptr, url, resources)?;
+            for (kw, v) in obj.iter_mut() {
+                let Some(&pos) = self.subschemas.get(kw.as_str()) else {
+                    continue;
+                };
+                if pos & POS_PROP != 0 {
+                    if let Value::Object(obj) = v {
+                        stack.extend(obj.values_mut());
+                    }
+                } else if pos & POS_ITEM != 0 && v.is_array() {
+                    if let Value::Array(arr) = v {
+                        stack.extend(arr.iter_mut());
                     }
+                } else if pos & POS_SELF != 0 {
+                    stack.push(v);
                 }
             }
-            if pos & POS_PROP != 0 {
-                if let Value::Object(obj) = v {
-                    for (pname, pvalue) in obj {
-                        let ptr = sch_ptr.append2(kw, pname);
-                        self.collect_resources(pvalue, base, ptr, url, resources)?;
+        }
+    }
+
+    // json-pointers of `sch` and its subschemas, parent before its children
+    pub(crate) fn subschema_ptrs(&self, sch: &Value) -> Vec<String> {
+        let mut ptrs = vec![];
+        let mut stack = vec![(sch, String::new())];
+        while let Some((sch, ptr)) = stack.pop() {
+            let Value::Object(obj) = sch else {
+                continue;
+            };
+            for (kw, v) in obj {
+                let Some(&pos) = self.subschemas.get(kw.as_str()) else {
+                    continue;
+                };
+                let ptr = format!("{ptr}/{}", escape(kw));
+                match v {
+                    Value::Object(obj) if pos & POS_PROP != 0 => {
+                        for (name, v) in obj {
+                            stack.push((v, format!("{ptr}/{}", escape(name))));
+                        }
+                    }
+                    Value::Array(arr) if pos & POS_ITEM != 0 => {
+                        for (i, v) in arr.iter().enumerate() {
+                            stack.push((v, format!("{ptr}/{i}")));
+                        }
                     }
+                    _ if pos & POS_SELF != 0 => stack.push((v, ptr)),
+                    _ => {}
                 }
             }
+            ptrs.push(ptr);
         }
-        Ok(())
+        ptrs
     }
 
     pub(crate) fn is_subschema(&self, ptr: &str) -> bool {
-        if ptr.is_empty() {
-            return true;
-        }
-
         fn split(mut ptr: &str) -> (&str, &str) {
             ptr = &ptr[1..]; // rm `/` prefix
             if let Some(i) = ptr.find('/') {
@@ -398,28 +595,32 @@ impl Draft {
             }
         }
 
-        let (tok, ptr) = split(ptr);
-
-        if let Some(&pos) = self.subschemas.get(tok) {
-            if pos & POS_SELF != 0 && self.is_subschema(ptr) {
+        // remaining ptrs to be checked
+        let mut stack = vec![ptr];
+        while let Some(ptr) = stack.pop() {
+            if ptr.is_empty() {
                 return true;
             }
+            let (tok, ptr) = split(ptr);
+            let Some(&pos) = self.subschemas.get(tok) else {
+                continue;
+            };
+            if pos & POS_SELF != 0 {
+                stack.push(ptr);
+            }
             if !ptr.is_empty() {
                 if pos & POS_PROP != 0 {
                     let (_, ptr) = split(ptr);
-                    if self.is_subschema(ptr) {
-                        return true;
-                    }
+                    stack.push(ptr);
                 }
                 if pos & POS_ITEM != 0 {
                     let (tok, ptr) = split(ptr);
-                    if usize::from_str(tok).is_ok() && self.is_subschema(ptr) {
-                        return true;
+                    if usize::from_str(tok).is_ok() {
+                        stack.push(ptr);
                     }
                 }
             }
         }
-
         false
     }
 }
@@ -428,11 +629,11 @@ fn load_std_metaschemas() -> Result<Schemas, CompileError> {
     let mut schemas = Schemas::new();
     let mut compiler = Compiler::new();
     compiler.enable_format_assertions();
-    compiler.compile("https://json-schema.org/draft/2020-12/schema", &mut schemas)?;
-    compiler.compile("https://json-schema.org/draft/2019-09/schema", &mut schemas)?;
-    compiler.compile("http://json-schema.org/draft-07/schema", &mut schemas)?;
-    compiler.compile("http://json-schema.org/draft-06/schema", &mut schemas)?;
-    compiler.compile("http://json-schema.org/draft-04/schema", &mut schemas)?;
+    for draft in crate::Draft::all().into_iter().rev() {
+        if draft.is_enabled() {
+            compiler.compile(draft.url(), &mut schemas)?;
+        }
+    }
     Ok(schemas)
 }
 
@@ -511,7 +712,7 @@ mod tests {
         };
         let mut got = HashMap::new();
         DRAFT4
-            .collect_resources(&json, &url, "".into(), &url, &mut got)
+            .collect_resources(&json, &url, "".into(), &url, &mut got, None)
             .unwrap();
         let got = got
             .iter()
@@ -547,7 +748,7 @@ mod tests {
         .unwrap();
         let mut resources = HashMap::new();
         DRAFT2020
-            .collect_resources(&json, &url, "".into(), &url, &mut resources)
+            .collect_resources(&json, &url, "".into(), &url, &mut resources, None)
             .unwrap();
         assert!(resources.get("").unwrap().anchors.is_empty());
         assert_eq!(resources.get("/$defs/s2").unwrap().anchors, {
@@ -1,11 +1,59 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt::Display, ops::Range};
 
 use regex_syntax::ast::parse::Parser;
 use regex_syntax::ast::{self, *};
 
-// covert ecma regex to rust regex if possible
-// see https://262.ecma-international.org/11.0/#sec-regexp-regular-expression-objects
-pub(crate) fn convert(pattern: &str) -> Result<Cow<str>, Box<dyn std::error::Error>> {
+/// Error returned by [`to_rust_regex`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EcmaError {
+    /// The pattern in which the error is found.
+    ///
+    /// This may differ from the input pattern, if some translations
+    /// are already applied.
+    pub pattern: String,
+    /// Describes the construct which is invalid or not supported.
+    pub reason: String,
+    /// Byte offsets of the construct within `pattern`.
+    pub span: Range<usize>,
+}
+
+impl EcmaError {
+    fn new(pattern: &str, reason: impl Display, span: &Span) -> Self {
+        Self {
+            pattern: pattern.to_owned(),
+            reason: reason.to_string(),
+            span: span.start.offset..span.end.offset,
+        }
+    }
+}
+
+impl Display for EcmaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Range { start, end } = self.span;
+        write!(f, "{} at {start}..{end}", self.reason)
+    }
+}
+
+impl std::error::Error for EcmaError {}
+
+/**
+Converts ECMA-262 regular expression `pattern`, as used by json-schema,
+into equivalent pattern supported by the `regex` crate.
+
+For example `\d` is translated to `[0-9]`, since it matches only ascii
+digits in ECMA-262.
+
+```
+# use boon::ecma::to_rust_regex;
+assert_eq!(to_rust_regex(r"^\d+$").unwrap(), "^[0-9]+$");
+
+let err = to_rust_regex(r"a\ab").unwrap_err();
+assert_eq!(err.span, 1..3);
+```
+
+see <https://262.ecma-international.org/11.0/#sec-regexp-regular-expression-objects>
+*/
+pub fn to_rust_regex(pattern: &str) -> Result<Cow<'_, str>, EcmaError> {
     let mut pattern = Cow::Borrowed(pattern);
 
     let mut ast = loop {
@@ -15,7 +63,7 @@ pub(crate) fn convert(pattern: &str) -> Result<Cow<str>, Box<dyn std::error::Err
                 if let Some(s) = fix_error(&e) {
                     pattern = Cow::Owned(s);
                 } else {
-                    Err(e)?;
+                    return Err(EcmaError::new(e.pattern(), e.kind(), e.span()));
                 }
             }
         }
@@ -116,7 +164,7 @@ impl Translator<'_> {
 
 impl Visitor for Translator<'_> {
     type Output = Option<String>;
-    type Err = &'static str;
+    type Err = EcmaError;
 
     fn finish(self) -> Result<Self::Output, Self::Err> {
         Ok(self.out)
@@ -140,10 +188,12 @@ impl Visitor for Translator<'_> {
             Ast::Literal(ref literal) => {
                 if let Literal {
                     kind: LiteralKind::Special(SpecialLiteralKind::Bell),
+                    span,
                     ..
                 } = literal.as_ref()
                 {
-                    return Err("\\a is not an ECMA 262 control escape");
+                    let reason = "\\a is not an ECMA 262 control escape";
+                    return Err(EcmaError::new(self.pat, reason, span));
                 }
             }
             _ => (),
@@ -168,7 +218,7 @@ mod tests {
             (r"ab[a-z\D]ef", r#"ab[a-z[^0-9]]ef"#),  // \D inside classSet
         ];
         for (input, want) in tests {
-            match convert(input) {
+            match to_rust_regex(input) {
                 Ok(got) => {
                     if got.as_ref() != want {
                         panic!("convert({input:?}): got: {got:?}, want: {want:?}");
@@ -189,7 +239,7 @@ mod tests {
             r"abc\adef", // \a is not valid
         ];
         for input in tests {
-            if convert(input).is_ok() {
+            if to_rust_regex(input).is_ok() {
                 panic!("convert({input:?}) mut fail");
             }
         }
@@ -0,0 +1,76 @@
+use include_dir::Dir;
+use serde_json::Value;
+use url::Url;
+
+use crate::{CompileError, Compiler};
+
+impl Compiler {
+    /**
+    Adds all json documents in directory `dir`, embedded using
+    [`include_dir!`], as schema resources. Documents whose name does
+    not end with `.json` are ignored.
+
+    Each document is identified by `base_url` followed by its path
+    relative to `dir`. So documents can refer each other using relative
+    urls, and the resulting binary needs no file system access.
+
+    ```ignore
+    use include_dir::{include_dir, Dir};
+
+    static SCHEMAS: Dir = include_dir!("$CARGO_MANIFEST_DIR/schemas");
+
+    let mut compiler = Compiler::new();
+    compiler.add_embedded(&SCHEMAS, "https://example.com/schemas/")?;
+    let mut schemas = Schemas::new();
+    let sch = compiler.compile("https://example.com/schemas/common/address.json", &mut schemas)?;
+    ```
+
+    This method is available with feature `include_dir`.
+
+    # Errors
+
+    returns [`CompileError::ParseUrlError`], if `base_url` is not valid
+    base url, and [`CompileError::LoadUrlError`], if any json document
+    cannot be parsed.
+
+    [`include_dir!`]: https://docs.rs/include_dir/latest/include_dir/macro.include_dir.html
+    */
+    pub fn add_embedded(&mut self, dir: &Dir, base_url: &str) -> Result<(), CompileError> {
+        let base = Url::parse(base_url)
+            .map_err(|e| e.to_string())
+            .and_then(|url| match url.cannot_be_a_base() {
+                true => Err("cannot be a base url".to_owned()),
+                false => Ok(url),
+            })
+            .map_err(|e| CompileError::ParseUrlError {
+                url: base_url.to_owned(),
+                src: e.into(),
+            })?;
+
+        let mut dirs = vec![dir];
+        while let Some(d) = dirs.pop() {
+            dirs.extend(d.dirs());
+            for file in d.files() {
+                let Ok(path) = file.path().strip_prefix(dir.path()) else {
+                    continue;
+                };
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    continue;
+                }
+                let mut url = base.clone();
+                if let Ok(mut segments) = url.path_segments_mut() {
+                    segments.pop_if_empty();
+                    segments.extend(path.iter().map(|c| c.to_string_lossy()));
+                }
+                let doc: Value = serde_json::from_slice(file.contents()).map_err(|e| {
+                    CompileError::LoadUrlError {
+                        url: url.to_string(),
+                        src: e.into(),
+                    }
+                })?;
+                self.add_resource(url.as_str(), doc)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use std::{error::Error as StdError, fmt::Display, io::Read};
+
+use serde_json::Value;
+
+use crate::{util::quote, CompileError, InstanceError, SchemaIndex, Schemas, ValidationError};
+
+/**
+Error type, that any error of this crate converts into.
+
+[`ValidationError`] and [`InstanceError`] borrow the schema and the
+instance, which makes them hard to return from functions. They are
+converted into owned [`InvalidError`], so that applications can
+use `?` with a single error type.
+
+```
+# use boon::*;
+# use serde_json::json;
+fn check(instance: &serde_json::Value) -> boon::Result<()> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile_value("http://a.com/s.json", json!({"type": "string"}), &mut schemas)?;
+    schemas.validate(instance, sch)?;
+    Ok(())
+}
+
+assert!(check(&json!("x")).is_ok());
+assert!(matches!(check(&json!(1)), Err(Error::Invalid(_))));
+```
+*/
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum Error {
+    /// Schema failed to compile.
+    Compile(CompileError),
+    /// The instance is not valid against the schema.
+    Invalid(InvalidError),
+    /// The instance is not valid json.
+    Parse(serde_json::Error),
+    /// An object in the instance has duplicate `key`, at given position.
+    DuplicateKey {
+        key: String,
+        line: usize,
+        column: usize,
+    },
+    /// The instance could not be read.
+    Io(std::io::Error),
+}
+
+/// Result type, whose error defaults to [`enum@Error`].
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/**
+Owned form of [`ValidationError`]. see [`Error::Invalid`]
+
+Use `{:#}` to format it along with schema locations, same as
+[`ValidationError`].
+*/
+#[derive(Debug, Clone)]
+pub struct InvalidError {
+    /// The url of the schema, against which instance is not valid.
+    pub schema_url: String,
+    /// The errors, in [basic output] format.
+    ///
+    /// [basic output]: https://json-schema.org/draft/2020-12/json-schema-core.html#name-basic
+    pub output: Value,
+    message: String,
+    detailed: String,
+}
+
+impl From<ValidationError<'_, '_>> for InvalidError {
+    fn from(e: ValidationError) -> Self {
+        Self {
+            schema_url: e.schema_url.to_owned(),
+            output: serde_json::to_value(e.basic_output()).unwrap_or_default(),
+            message: format!("{e}"),
+            detailed: format!("{e:#}"),
+        }
+    }
+}
+
+impl Display for InvalidError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.alternate() {
+            true => f.write_str(&self.detailed),
+            false => f.write_str(&self.message),
+        }
+    }
+}
+
+impl StdError for InvalidError {}
+
+impl From<CompileError> for Error {
+    fn from(e: CompileError) -> Self {
+        Self::Compile(e)
+    }
+}
+
+impl From<ValidationError<'_, '_>> for Error {
+    fn from(e: ValidationError) -> Self {
+        Self::Invalid(e.into())
+    }
+}
+
+impl From<InstanceError<'_>> for Error {
+    fn from(e: InstanceError) -> Self {
+        match e {
+            InstanceError::Parse(e) => e.into(),
+            InstanceError::DuplicateKey { key, line, column } => {
+                Self::DuplicateKey { key, line, column }
+            }
+            InstanceError::Invalid(e) => e.into(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        match e.is_io() {
+            true => Self::Io(e.into()),
+            false => Self::Parse(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Compile(e) => e.fmt(f),
+            Self::Invalid(e) => e.fmt(f),
+            Self::Parse(e) => write!(f, "error parsing instance: {e}"),
+            Self::DuplicateKey { key, line, column } => write!(
+                f,
+                "duplicate key {} at line {line} column {column}",
+                quote(key)
+            ),
+            Self::Io(e) => write!(f, "error reading instance: {e}"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Compile(e) => e.source(),
+            Self::Parse(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::Invalid(_) | Self::DuplicateKey { .. } => None,
+        }
+    }
+}
+
+impl Schemas {
+    /**
+    Reads json instance from `rdr` and validates it with schema
+    identified by `sch_index`.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> boon::Result<()> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile_value("http://a.com/s.json", json!({"type": "array"}), &mut schemas)?;
+    schemas.validate_reader(&b"[1, 2]"[..], sch)?;
+    assert!(matches!(schemas.validate_reader(&b"[1, "[..], sch), Err(Error::Parse(_))));
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`Error::Io`] if reading fails, [`Error::Parse`] if it is not
+    valid json, and [`Error::Invalid`] if it is not valid against the schema.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    */
+    pub fn validate_reader(&self, rdr: impl Read, sch_index: SchemaIndex) -> Result<()> {
+        let v: Value = serde_json::from_reader(rdr)?;
+        self.validate(&v, sch_index)?;
+        Ok(())
+    }
+}
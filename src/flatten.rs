@@ -0,0 +1,274 @@
+use serde_json::{Map, Value};
+
+use crate::{draft::Draft, util::*};
+
+/**
+Merges members of `allOf` into their parent schema, in schema document
+`sch` and its subschemas, for example to generate forms or sdks from
+the schema.
+
+A member is merged only if the result validates exactly the same
+instances. For example `properties` and `required` are combined,
+`type` and `enum` are intersected, and the stricter of bounds like
+`minimum` and `maxLength` is kept. Members using other keywords, like
+`$ref`, `$id` or `additionalProperties`, are left in `allOf`. `allOf`
+is removed once all its members are merged.
+
+Subschemas are located using the draft specified by `$schema`,
+defaulting to latest draft. Note that references to subschemas inside
+`allOf` by json-pointer break, once they are merged.
+
+```
+# use boon::*;
+# use serde_json::json;
+let mut sch = json!({
+    "type": "object",
+    "allOf": [
+        {"properties": {"name": {"type": "string"}}, "required": ["name"]},
+        {"properties": {"age": {"type": "integer"}}, "required": ["age"]}
+    ]
+});
+flatten_all_of(&mut sch);
+assert_eq!(sch, json!({
+    "type": "object",
+    "properties": {"name": {"type": "string"}, "age": {"type": "integer"}},
+    "required": ["name", "age"]
+}));
+```
+*/
+pub fn flatten_all_of(sch: &mut Value) {
+    let draft = Draft::of(sch);
+    // children are flattened before their parent
+    for ptr in draft.subschema_ptrs(sch).into_iter().rev() {
+        if let Some(Value::Object(obj)) = sch.pointer_mut(&ptr) {
+            flatten(draft, obj);
+        }
+    }
+}
+
+fn flatten(draft: &Draft, obj: &mut Map<String, Value>) {
+    if draft.version < 2019 && obj.contains_key("$ref") {
+        return; // siblings of $ref are ignored
+    }
+    let Some(Value::Array(members)) = obj.remove("allOf") else {
+        return;
+    };
+    let mut unmerged = vec![];
+    for m in members {
+        match m {
+            Value::Bool(true) => {}
+            Value::Object(m) if can_merge(draft, obj, &m) => merge(draft, obj, m),
+            _ => unmerged.push(m),
+        }
+    }
+    // unmerged members of merged members
+    if let Some(Value::Array(arr)) = obj.remove("allOf") {
+        unmerged.extend(arr);
+    }
+    if !unmerged.is_empty() {
+        obj.insert("allOf".into(), Value::Array(unmerged));
+    }
+}
+
+// keywords whose value is kept from parent, if it has one
+const ANNOTATIONS: [&str; 8] = [
+    "title",
+    "description",
+    "$comment",
+    "default",
+    "examples",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+];
+
+// keywords independent of their siblings, merged only if equal
+const INDEPENDENT: [&str; 14] = [
+    "const",
+    "multipleOf",
+    "pattern",
+    "format",
+    "uniqueItems",
+    "not",
+    "anyOf",
+    "oneOf",
+    "propertyNames",
+    "dependencies",
+    "dependentRequired",
+    "dependentSchemas",
+    "contentEncoding",
+    "contentMediaType",
+];
+
+// keywords whose stricter value is kept
+const LOWER_BOUNDS: [&str; 5] = [
+    "minimum",
+    "exclusiveMinimum",
+    "minLength",
+    "minItems",
+    "minProperties",
+];
+const UPPER_BOUNDS: [&str; 5] = [
+    "maximum",
+    "exclusiveMaximum",
+    "maxLength",
+    "maxItems",
+    "maxProperties",
+];
+
+fn can_merge(draft: &Draft, dst: &Map<String, Value>, src: &Map<String, Value>) -> bool {
+    src.iter().all(|(kw, v)| {
+        // draft4 exclusiveMinimum is boolean, modifying minimum
+        if draft.version == 4
+            && matches!(kw.as_str(), "minimum" | "maximum")
+            && (dst.contains_key("exclusiveMinimum") || dst.contains_key("exclusiveMaximum"))
+        {
+            return false;
+        }
+        let Some(dv) = dst.get(kw) else {
+            return match kw.as_str() {
+                "properties" | "patternProperties" => !dst.contains_key("additionalProperties"),
+                "items" => is_schema(v) && !has_tuple(dst),
+                "allOf" | "type" | "enum" | "required" => true,
+                kw => ANNOTATIONS.contains(&kw) || INDEPENDENT.contains(&kw) || is_bound(draft, kw),
+            };
+        };
+        match kw.as_str() {
+            "properties" | "patternProperties" => {
+                !dst.contains_key("additionalProperties") && dv.is_object() && v.is_object()
+            }
+            "items" => is_schema(dv) && is_schema(v) && !has_tuple(dst) && !has_tuple(src),
+            "type" => !intersect_types(dv, v).is_empty(),
+            "enum" => match (dv, v) {
+                (Value::Array(d), Value::Array(s)) => {
+                    d.iter().any(|e| s.iter().any(|x| equals(e, x)))
+                }
+                _ => false,
+            },
+            "required" | "allOf" => dv.is_array() && v.is_array(),
+            kw if is_bound(draft, kw) => dv.is_number() && v.is_number(),
+            kw => ANNOTATIONS.contains(&kw) || (INDEPENDENT.contains(&kw) && equals(dv, v)),
+        }
+    })
+}
+
+fn merge(draft: &Draft, dst: &mut Map<String, Value>, src: Map<String, Value>) {
+    for (kw, v) in src {
+        let Some(dv) = dst.get_mut(&kw) else {
+            dst.insert(kw, v);
+            continue;
+        };
+        match (kw.as_str(), dv, v) {
+            ("properties" | "patternProperties", Value::Object(d), Value::Object(s)) => {
+                for (name, v) in s {
+                    match d.get_mut(&name) {
+                        Some(dv) => *dv = merge_schemas(draft, dv.take(), v),
+                        None => {
+                            d.insert(name, v);
+                        }
+                    }
+                }
+            }
+            ("items", dv, v) => *dv = merge_schemas(draft, dv.take(), v),
+            ("type", dv, v) => {
+                let types = intersect_types(dv, &v);
+                *dv = match types.as_slice() {
+                    [t] => Value::String(t.to_string()),
+                    _ => types.iter().map(|&t| Value::from(t)).collect(),
+                };
+            }
+            ("enum", Value::Array(d), Value::Array(s)) => {
+                d.retain(|e| s.iter().any(|x| equals(e, x)));
+            }
+            ("required" | "allOf", Value::Array(d), Value::Array(s)) => {
+                for v in s {
+                    if !d.iter().any(|e| equals(e, &v)) {
+                        d.push(v);
+                    }
+                }
+            }
+            (kw, dv, v) if is_bound(draft, kw) => {
+                let (Some(d), Some(s)) = (dv.as_f64(), v.as_f64()) else {
+                    continue;
+                };
+                let lower = LOWER_BOUNDS.contains(&kw);
+                if (lower && s > d) || (!lower && s < d) {
+                    *dv = v;
+                }
+            }
+            _ => {} // annotations and equal values, keep parent's
+        }
+    }
+}
+
+// schema, that is valid iff both `a` and `b` are valid
+fn merge_schemas(draft: &Draft, a: Value, b: Value) -> Value {
+    match (a, b) {
+        (Value::Bool(true), v) | (v, Value::Bool(true)) => v,
+        (Value::Bool(false), _) | (_, Value::Bool(false)) => Value::Bool(false),
+        (Value::Object(mut a), Value::Object(b)) => {
+            if !(draft.version < 2019 && a.contains_key("$ref")) && can_merge(draft, &a, &b) {
+                merge(draft, &mut a, b);
+                flatten(draft, &mut a);
+                Value::Object(a)
+            } else {
+                let mut obj = Map::new();
+                obj.insert(
+                    "allOf".into(),
+                    vec![Value::Object(a), Value::Object(b)].into(),
+                );
+                Value::Object(obj)
+            }
+        }
+        (a, b) => {
+            let mut obj = Map::new();
+            obj.insert("allOf".into(), vec![a, b].into());
+            Value::Object(obj)
+        }
+    }
+}
+
+fn is_bound(draft: &Draft, kw: &str) -> bool {
+    if draft.version == 4 && kw.starts_with("exclusiveM") {
+        return false; // boolean in draft4
+    }
+    LOWER_BOUNDS.contains(&kw) || UPPER_BOUNDS.contains(&kw)
+}
+
+fn is_schema(v: &Value) -> bool {
+    v.is_object() || v.is_boolean()
+}
+
+// whether `items` depends on position of array items
+fn has_tuple(obj: &Map<String, Value>) -> bool {
+    obj.contains_key("prefixItems")
+        || obj.contains_key("additionalItems")
+        || obj.get("items").is_some_and(Value::is_array)
+}
+
+fn intersect_types<'a>(a: &'a Value, b: &'a Value) -> Vec<&'a str> {
+    fn types(v: &Value) -> Vec<&str> {
+        match v {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(arr) => arr.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        }
+    }
+    let (a, b) = (types(a), types(b));
+    let mut result = vec![];
+    for &t in &a {
+        let t = if b.contains(&t) {
+            t
+        } else if (t == "number" && b.contains(&"integer"))
+            || (t == "integer" && b.contains(&"number"))
+        {
+            "integer"
+        } else {
+            continue;
+        };
+        if !result.contains(&t) {
+            result.push(t);
+        }
+    }
+    result
+}
@@ -0,0 +1,308 @@
+use std::collections::HashSet;
+
+use percent_encoding::percent_decode_str;
+use serde_json::{Map, Number, Value};
+
+use crate::{Additional, Error, Items, SchemaIndex, Schemas, Type};
+
+impl Schemas {
+    /**
+    Converts `application/x-www-form-urlencoded` text, like http query
+    string, into json instance, guided by schema identified by `sch_index`.
+    see [`Schemas::decode_pairs`]
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "properties": {
+            "page": {"type": "integer"},
+            "tags": {"type": "array"},
+            "filter": {"properties": {"active": {"type": "boolean"}}}
+        }
+    }))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let v = schemas.decode_form("page=2&tags=a&filter[active]=true&q=hello+world", sch);
+    assert_eq!(v, json!({
+        "page": 2,
+        "tags": ["a"],
+        "filter": {"active": true},
+        "q": "hello world"
+    }));
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn decode_form(&self, form: &str, sch_index: SchemaIndex) -> Value {
+        let pairs = form.split('&').filter(|pair| !pair.is_empty()).map(|pair| {
+            match pair.split_once('=') {
+                Some((k, v)) => (form_decode(k), form_decode(v)),
+                None => (form_decode(pair), String::new()),
+            }
+        });
+        self.decode_pairs(pairs, sch_index)
+    }
+
+    /**
+    Converts decoded key-value pairs of html form or query string into
+    json object, guided by schema identified by `sch_index`.
+
+    - key `a[b][c]` sets member `c` of object `b` in object `a`. Keys
+      with more than 32 brackets are used as is, like `a[b` is.
+    - repeated keys, or keys ending with `[]` like `a[]`, give an array.
+    - key having array schema gives an array, even with single value.
+      Object with keys `0`, `1`.. having array schema gives an array too.
+    - value is converted to type in schema, if it is not `string`. for
+      example `"1"` to `1` for `integer`, and `"true"` to `true` for
+      `boolean`. Empty value is converted to `null` for `null` only.
+      Values that cannot be converted, are left as strings.
+
+    Schemas are located through `properties`, `patternProperties`,
+    `additionalProperties`, `items` and `prefixItems`, following `$ref`
+    and applicators like `allOf`. Keys with no schema are left as strings.
+    */
+    pub fn decode_pairs<K, V>(
+        &self,
+        pairs: impl IntoIterator<Item = (K, V)>,
+        sch_index: SchemaIndex,
+    ) -> Value
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        if !self.contains(sch_index) {
+            panic!("Schemas::decode_pairs: schema index out of bounds");
+        }
+        let mut root = Raw::Object(vec![]);
+        for (key, value) in pairs {
+            root.insert(&parse_key(key.as_ref()), value.as_ref().to_owned());
+        }
+        self.convert(root, &Guide::new(self, [sch_index]))
+    }
+
+    /**
+    Converts `form` using [`Schemas::decode_form`], and validates the
+    result with schema identified by `sch_index`. Returns the converted
+    instance if it is valid.
+
+    # Errors
+
+    returns [`Error::Invalid`] if the converted instance is invalid.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_form(&self, form: &str, sch_index: SchemaIndex) -> crate::Result<Value> {
+        let v = self.decode_form(form, sch_index);
+        if let Err(e) = self.validate(&v, sch_index) {
+            return Err(Error::from(e));
+        }
+        Ok(v)
+    }
+
+    fn convert(&self, raw: Raw, guide: &Guide) -> Value {
+        let is_array = guide.has_type(Type::Array) && !guide.has_type(Type::Object);
+        match raw {
+            Raw::Values(values) if is_array || values.len() > 1 => values
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| guide.item(i).coerce(v))
+                .collect(),
+            Raw::Values(mut values) => guide.coerce(values.pop().unwrap_or_default()),
+            Raw::Object(members) if is_array && members.iter().all(|(k, _)| is_index(k)) => {
+                let mut items: Vec<_> = members
+                    .into_iter()
+                    .filter_map(|(k, raw)| Some((k.parse::<usize>().ok()?, raw)))
+                    .collect();
+                items.sort_by_key(|(i, _)| *i);
+                items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (_, raw))| self.convert(raw, &guide.item(i)))
+                    .collect()
+            }
+            Raw::Object(members) => {
+                let mut obj = Map::new();
+                for (name, raw) in members {
+                    let v = self.convert(raw, &guide.prop(&name));
+                    obj.insert(name, v);
+                }
+                Value::Object(obj)
+            }
+        }
+    }
+}
+
+// `+` means space in form encoding
+fn form_decode(s: &str) -> String {
+    let s = s.replace('+', " ");
+    percent_decode_str(&s).decode_utf8_lossy().into_owned()
+}
+
+// maximum nesting of brackets in a key. keys from untrusted
+// query string, must not nest deep enough to overflow the stack
+const MAX_KEY_DEPTH: usize = 32;
+
+// parses `a[b][]` into ["a", "b", ""]
+fn parse_key(key: &str) -> Vec<&str> {
+    let Some(open) = key.find('[') else {
+        return vec![key];
+    };
+    let mut tokens = vec![&key[..open]];
+    let mut rest = &key[open..];
+    while let Some(tail) = rest.strip_prefix('[') {
+        let Some(close) = tail.find(']') else {
+            break;
+        };
+        tokens.push(&tail[..close]);
+        rest = &tail[close + 1..];
+    }
+    if !rest.is_empty() || tokens.len() > MAX_KEY_DEPTH + 1 {
+        // malformed or too deep brackets, use key as is
+        return vec![key];
+    }
+    tokens
+}
+
+fn is_index(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+// key-value pairs before conversion
+enum Raw {
+    Values(Vec<String>),
+    Object(Vec<(String, Raw)>),
+}
+
+impl Raw {
+    fn insert(&mut self, tokens: &[&str], value: String) {
+        match (self, tokens) {
+            (Raw::Values(values), [] | [""]) => values.push(value),
+            (Raw::Object(members), [tok, rest @ ..]) if !tok.is_empty() => {
+                let i = match members.iter().position(|(name, _)| name == tok) {
+                    Some(i) => i,
+                    None => {
+                        let raw = match rest {
+                            [] | [""] => Raw::Values(vec![]),
+                            _ => Raw::Object(vec![]),
+                        };
+                        members.push((tok.to_string(), raw));
+                        members.len() - 1
+                    }
+                };
+                members[i].1.insert(rest, value);
+            }
+            _ => {} // conflicting keys like `a=1&a[b]=2`, first one wins
+        }
+    }
+}
+
+// subschemas applicable at a location in instance
+struct Guide<'s> {
+    schemas: &'s Schemas,
+    list: Vec<SchemaIndex>,
+}
+
+impl<'s> Guide<'s> {
+    // includes schemas reachable by `$ref` and applicators
+    fn new(schemas: &'s Schemas, list: impl IntoIterator<Item = SchemaIndex>) -> Self {
+        let mut seen = HashSet::new();
+        let mut queue: Vec<_> = list.into_iter().collect();
+        let mut list = vec![];
+        while let Some(idx) = queue.pop() {
+            if !seen.insert(idx) {
+                continue;
+            }
+            list.push(idx);
+            let s = schemas.get(idx);
+            queue.extend(s.ref_);
+            queue.extend(s.all_of.iter().chain(&s.any_of).chain(&s.one_of));
+            queue.extend([s.then, s.else_].into_iter().flatten());
+        }
+        Self { schemas, list }
+    }
+
+    fn has_type(&self, t: Type) -> bool {
+        self.list
+            .iter()
+            .any(|&idx| self.schemas.get(idx).types.contains(t))
+    }
+
+    fn prop(&self, name: &str) -> Guide<'s> {
+        let mut list = vec![];
+        for &idx in &self.list {
+            let s = self.schemas.get(idx);
+            let mut matched = false;
+            if let Some(&sch) = s.properties.get(name) {
+                list.push(sch);
+                matched = true;
+            }
+            for (regex, sch) in &s.pattern_properties {
+                if regex.is_match(name) {
+                    list.push(*sch);
+                    matched = true;
+                }
+            }
+            if let (false, Some(Additional::SchemaRef(sch))) = (matched, &s.additional_properties) {
+                list.push(*sch);
+            }
+        }
+        Guide::new(self.schemas, list)
+    }
+
+    fn item(&self, i: usize) -> Guide<'s> {
+        let mut list = vec![];
+        for &idx in &self.list {
+            let s = self.schemas.get(idx);
+            match &s.items {
+                Some(Items::SchemaRef(sch)) => list.push(*sch),
+                Some(Items::SchemaRefs(items)) => match (items.get(i), &s.additional_items) {
+                    (Some(sch), _) | (None, Some(Additional::SchemaRef(sch))) => list.push(*sch),
+                    _ => {}
+                },
+                None => list.extend(s.prefix_items.get(i).copied().or(s.items2020)),
+            }
+        }
+        Guide::new(self.schemas, list)
+    }
+
+    fn coerce(&self, s: String) -> Value {
+        if self.list.is_empty() || self.has_type(Type::String) {
+            return Value::String(s);
+        }
+        if self.has_type(Type::Integer) || self.has_type(Type::Number) {
+            if let Ok(n) = s.parse::<i64>() {
+                return Value::from(n);
+            }
+            if let Ok(n) = s.parse::<u64>() {
+                return Value::from(n);
+            }
+        }
+        if self.has_type(Type::Number) {
+            if let Some(n) = s.parse::<f64>().ok().and_then(Number::from_f64) {
+                return Value::Number(n);
+            }
+        }
+        if self.has_type(Type::Boolean) {
+            match s.as_str() {
+                "true" => return Value::Bool(true),
+                "false" => return Value::Bool(false),
+                _ => {}
+            }
+        }
+        if self.has_type(Type::Null) && s.is_empty() {
+            return Value::Null;
+        }
+        Value::String(s)
+    }
+}
@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     error::Error,
     net::{Ipv4Addr, Ipv6Addr},
+    sync::Arc,
 };
 
 use once_cell::sync::Lazy;
@@ -21,6 +22,41 @@ pub struct Format {
     pub func: fn(v: &Value) -> Result<(), Box<dyn Error>>,
 }
 
+/**
+How `format` keyword is treated by a compiled schema.
+
+see [`Schemas::format_mode`](crate::Schemas::format_mode)
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FormatMode {
+    /// `format` is ignored, since no format vocabulary is enabled
+    #[default]
+    Ignore,
+    /// `format` is only an annotation, invalid values are not errors.
+    /// see [`ValidationOptions::set_format_annotation_tracer`](crate::ValidationOptions::set_format_annotation_tracer)
+    Annotation,
+    /// `format` is asserted, invalid values are errors
+    Assertion,
+}
+
+pub(crate) type FormatFn = dyn Fn(&Value) -> Result<(), Box<dyn Error>> + Send + Sync;
+
+// format whose func may capture state.
+#[derive(Clone)]
+pub(crate) struct DynFormat {
+    pub(crate) name: &'static str,
+    pub(crate) func: Arc<FormatFn>,
+}
+
+impl From<Format> for DynFormat {
+    fn from(format: Format) -> Self {
+        Self {
+            name: format.name,
+            func: Arc::new(format.func),
+        }
+    }
+}
+
 pub(crate) static FORMATS: Lazy<HashMap<&'static str, Format>> = Lazy::new(|| {
     let mut m = HashMap::<&'static str, Format>::new();
     let mut register = |name, func| m.insert(name, Format { name, func });
@@ -34,6 +70,7 @@ pub(crate) static FORMATS: Lazy<HashMap<&'static str, Format>> = Lazy::new(|| {
     register("date", validate_date);
     register("time", validate_time);
     register("date-time", validate_date_time);
+    register("iso-date-time", validate_iso_date_time);
     register("duration", validate_duration);
     register("period", validate_period);
     register("json-pointer", validate_json_pointer);
@@ -51,13 +88,22 @@ fn validate_regex(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
-    ecma::convert(s).map(|_| ())
+    check_regex(s)
+}
+
+fn check_regex(s: &str) -> Result<(), Box<dyn Error>> {
+    ecma::to_rust_regex(s)?;
+    Ok(())
 }
 
 fn validate_ipv4(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
+    check_ipv4(s)
+}
+
+fn check_ipv4(s: &str) -> Result<(), Box<dyn Error>> {
     s.parse::<Ipv4Addr>()?;
     Ok(())
 }
@@ -66,6 +112,10 @@ fn validate_ipv6(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
+    check_ipv6(s)
+}
+
+fn check_ipv6(s: &str) -> Result<(), Box<dyn Error>> {
     s.parse::<Ipv6Addr>()?;
     Ok(())
 }
@@ -82,6 +132,7 @@ fn matches_char(s: &str, index: usize, ch: char) -> bool {
 }
 
 // see https://datatracker.ietf.org/doc/html/rfc3339#section-5.6
+#[allow(clippy::collapsible_match)]
 fn check_date(s: &str) -> Result<(), Box<dyn Error>> {
     // yyyy-mm-dd
     if s.len() != 10 {
@@ -130,7 +181,11 @@ fn validate_time(v: &Value) -> Result<(), Box<dyn Error>> {
     check_time(s)
 }
 
-fn check_time(mut str: &str) -> Result<(), Box<dyn Error>> {
+fn check_time(str: &str) -> Result<(), Box<dyn Error>> {
+    check_time_with(str, true)
+}
+
+fn check_time_with(mut str: &str, leap_seconds: bool) -> Result<(), Box<dyn Error>> {
     // min: hh:mm:ssZ
     if str.len() < 9 {
         Err("less than 9 characters long")?
@@ -197,6 +252,9 @@ fn check_time(mut str: &str) -> Result<(), Box<dyn Error>> {
     }
 
     // check leap second
+    if s == 60 && !leap_seconds {
+        Err("leap second not allowed")?
+    }
     if !(s < 60 || (h == 23 && m == 59)) {
         Err("invalid leap second")?
     }
@@ -211,6 +269,10 @@ fn validate_date_time(v: &Value) -> Result<(), Box<dyn Error>> {
 }
 
 fn check_date_time(s: &str) -> Result<(), Box<dyn Error>> {
+    check_date_time_with(s, &DateTimeOptions::default())
+}
+
+fn check_date_time_with(s: &str, opts: &DateTimeOptions) -> Result<(), Box<dyn Error>> {
     // min: yyyy-mm-ddThh:mm:ssZ
     if s.len() < 20 {
         Err("less than 20 characters long")?;
@@ -221,7 +283,158 @@ fn check_date_time(s: &str) -> Result<(), Box<dyn Error>> {
     if let Err(e) = check_date(&s[..10]) {
         Err(format!("invalid date element: {e}"))?;
     }
-    if let Err(e) = check_time(&s[11..]) {
+    let (time, suffix) = match s.find('[') {
+        Some(i) if opts.extended => s.split_at(i),
+        _ => (s, ""),
+    };
+    if let Err(e) = check_time_with(&time[11..], opts.leap_seconds) {
+        Err(format!("invalid time element: {e}"))?;
+    }
+    if let Err(e) = check_suffix(suffix) {
+        Err(format!("invalid suffix: {e}"))?;
+    }
+    Ok(())
+}
+
+// see https://datatracker.ietf.org/doc/html/rfc9557#section-4.1
+fn check_suffix(mut s: &str) -> Result<(), Box<dyn Error>> {
+    fn is_key(s: &str) -> bool {
+        let mut chars = s.chars();
+        matches!(chars.next(), Some('a'..='z' | '_'))
+            && chars.all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_' | '-'))
+    }
+    fn is_value(s: &str) -> bool {
+        s.split('-').all(|part| {
+            (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+    }
+    fn is_time_zone(s: &str) -> bool {
+        if s.starts_with(['+', '-']) {
+            return check_time_with(&format!("00:00:00{s}"), true).is_ok();
+        }
+        s.split('/').all(|part| {
+            !part.is_empty()
+                && part != "."
+                && part != ".."
+                && part.starts_with(|c: char| c.is_ascii_alphabetic() || matches!(c, '.' | '_'))
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '+'))
+        })
+    }
+
+    while !s.is_empty() {
+        let Some(rem) = s.strip_prefix('[') else {
+            Err("must start with [")?
+        };
+        let Some(end) = rem.find(']') else {
+            Err("missing ]")?
+        };
+        let tag = &rem[..end];
+        let tag = tag.strip_prefix('!').unwrap_or(tag);
+        let valid = match tag.split_once('=') {
+            Some((key, value)) => is_key(key) && is_value(value),
+            None => is_time_zone(tag),
+        };
+        if !valid {
+            Err(format!("invalid tag [{tag}]"))?
+        }
+        s = &rem[end + 1..];
+    }
+    Ok(())
+}
+
+/**
+Options for `date-time` format, beyond [RFC 3339].
+
+Use it to replace the built-in `date-time` format.
+
+```
+# use boon::{*, formats::DateTimeOptions};
+# use serde_json::{json, Value};
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let opts = DateTimeOptions {
+    leap_seconds: false,
+    extended: true,
+};
+let mut compiler = Compiler::new();
+compiler.enable_format_assertions();
+compiler.override_format("date-time", move |v: &Value| opts.validate(v));
+compiler.add_resource("http://a.com/s.json", json!({"format": "date-time"}))?;
+let mut schemas = Schemas::new();
+let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+let instance = json!("2022-07-08T00:14:07+01:00[Europe/Paris]");
+assert!(schemas.validate(&instance, sch).is_ok());
+let instance = json!("1990-12-31T23:59:60Z");
+assert!(schemas.validate(&instance, sch).is_err());
+# Ok(())
+# }
+```
+
+[RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTimeOptions {
+    /// Accept leap second, like `23:59:60Z`. Defaults to `true`.
+    pub leap_seconds: bool,
+    /// Accept [RFC 9557](https://datatracker.ietf.org/doc/html/rfc9557)
+    /// suffixes after the offset, like `[Europe/Paris][u-ca=hebrew]`.
+    /// Defaults to `false`.
+    pub extended: bool,
+}
+
+impl Default for DateTimeOptions {
+    fn default() -> Self {
+        Self {
+            leap_seconds: true,
+            extended: false,
+        }
+    }
+}
+
+impl DateTimeOptions {
+    /// Checks whether `s` is valid `date-time` with these options.
+    pub fn check(&self, s: &str) -> Result<(), checks::FormatError> {
+        check_date_time_with(s, self).map_err(|e| checks::FormatError {
+            format: "date-time",
+            reason: e.to_string(),
+        })
+    }
+
+    /// Validates `v` as `date-time` with these options. Values
+    /// other than strings are valid.
+    pub fn validate(&self, v: &Value) -> Result<(), Box<dyn Error>> {
+        let Value::String(s) = v else {
+            return Ok(());
+        };
+        check_date_time_with(s, self)
+    }
+}
+
+fn validate_iso_date_time(v: &Value) -> Result<(), Box<dyn Error>> {
+    let Value::String(s) = v else {
+        return Ok(());
+    };
+    check_iso_date_time(s)
+}
+
+// date-time without offset, i.e. local time
+fn check_iso_date_time(s: &str) -> Result<(), Box<dyn Error>> {
+    // min: yyyy-mm-ddThh:mm:ss
+    if s.len() < 19 {
+        Err("less than 19 characters long")?;
+    }
+    if !s.is_char_boundary(10) || !s[10..].starts_with(['t', 'T']) {
+        Err("11th character must be t or T")?;
+    }
+    if let Err(e) = check_date(&s[..10]) {
+        Err(format!("invalid date element: {e}"))?;
+    }
+    if s[11..].contains(['z', 'Z', '+', '-']) {
+        Err("must not have offset")?;
+    }
+    if let Err(e) = check_time(&format!("{}Z", &s[11..])) {
         Err(format!("invalid time element: {e}"))?;
     }
     Ok(())
@@ -287,12 +500,15 @@ fn check_duration(s: &str) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-// see https://datatracker.ietf.org/doc/html/rfc3339#appendix-A
 fn validate_period(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
+    check_period(s)
+}
 
+// see https://datatracker.ietf.org/doc/html/rfc3339#appendix-A
+fn check_period(s: &str) -> Result<(), Box<dyn Error>> {
     let Some(slash) = s.find('/') else {
         Err("missing slash")?
     };
@@ -327,19 +543,30 @@ fn validate_hostname(v: &Value) -> Result<(), Box<dyn Error>> {
     check_hostname(s)
 }
 
+fn check_hostname(s: &str) -> Result<(), Box<dyn Error>> {
+    check_hostname_with(s, &HostnameOptions::default())
+}
+
 // see https://en.wikipedia.org/wiki/Hostname#Restrictions_on_valid_host_names
-fn check_hostname(mut s: &str) -> Result<(), Box<dyn Error>> {
+fn check_hostname_with(mut s: &str, opts: &HostnameOptions) -> Result<(), Box<dyn Error>> {
     // entire hostname (including the delimiting dots but not a trailing dot) has a maximum of 253 ASCII characters
     s = s.strip_suffix('.').unwrap_or(s);
     if s.len() > 253 {
         Err("more than 253 characters long")?
     }
 
+    if s.split('.').count() < opts.min_labels {
+        Err(format!("must have at least {} labels", opts.min_labels))?;
+    }
+
     // Hostnames are composed of series of labels concatenated with dots, as are all domain names
     for label in s.split('.') {
         // Each label must be from 1 to 63 characters long
-        if !matches!(label.len(), 1..=63) {
-            Err("label must be 1 to 63 characters long")?;
+        if !(1..=opts.max_label_len).contains(&label.len()) {
+            Err(format!(
+                "label must be 1 to {} characters long",
+                opts.max_label_len
+            ))?;
         }
 
         // labels must not start or end with a hyphen
@@ -353,10 +580,10 @@ fn check_hostname(mut s: &str) -> Result<(), Box<dyn Error>> {
 
         // labels may contain only the ASCII letters 'a' through 'z' (in a case-insensitive manner),
         // the digits '0' through '9', and the hyphen ('-')
-        if let Some(ch) = label
-            .chars()
-            .find(|c| !matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '-'))
-        {
+        if let Some(ch) = label.chars().find(|&c| {
+            !(matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '-')
+                || (c == '_' && opts.allow_underscore))
+        }) {
             Err(format!("invalid character {ch:?}"))?;
         }
     }
@@ -364,6 +591,78 @@ fn check_hostname(mut s: &str) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/**
+Options for `hostname` format, relaxing or tightening
+[RFC 1123](https://datatracker.ietf.org/doc/html/rfc1123#section-2).
+
+Use it to replace the built-in `hostname` format.
+
+```
+# use boon::{*, formats::HostnameOptions};
+# use serde_json::{json, Value};
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let opts = HostnameOptions {
+    allow_underscore: true,
+    min_labels: 2,
+    ..Default::default()
+};
+let mut compiler = Compiler::new();
+compiler.enable_format_assertions();
+compiler.override_format("hostname", move |v: &Value| opts.validate(v));
+compiler.add_resource("http://a.com/s.json", json!({"format": "hostname"}))?;
+let mut schemas = Schemas::new();
+let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+let instance = json!("_sip._tcp.example.com");
+assert!(schemas.validate(&instance, sch).is_ok());
+let instance = json!("localhost");
+assert!(schemas.validate(&instance, sch).is_err());
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostnameOptions {
+    /// Accept `_` in labels, as used in service records and some
+    /// internal DNS names. Defaults to `false`.
+    pub allow_underscore: bool,
+    /// Minimum number of labels, for example `2` rejects single-label
+    /// names like `localhost`. Defaults to `1`.
+    pub min_labels: usize,
+    /// Maximum length of each label. Defaults to `63`, for example
+    /// use `63 - 10` to leave room for generated suffixes.
+    pub max_label_len: usize,
+}
+
+impl Default for HostnameOptions {
+    fn default() -> Self {
+        Self {
+            allow_underscore: false,
+            min_labels: 1,
+            max_label_len: 63,
+        }
+    }
+}
+
+impl HostnameOptions {
+    /// Checks whether `s` is valid `hostname` with these options.
+    pub fn check(&self, s: &str) -> Result<(), checks::FormatError> {
+        check_hostname_with(s, self).map_err(|e| checks::FormatError {
+            format: "hostname",
+            reason: e.to_string(),
+        })
+    }
+
+    /// Validates `v` as `hostname` with these options. Values
+    /// other than strings are valid.
+    pub fn validate(&self, v: &Value) -> Result<(), Box<dyn Error>> {
+        let Value::String(s) = v else {
+            return Ok(());
+        };
+        check_hostname_with(s, self)
+    }
+}
+
 fn validate_idn_hostname(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
@@ -660,7 +959,10 @@ fn validate_idn_email(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
+    check_idn_email(s)
+}
 
+fn check_idn_email(s: &str) -> Result<(), Box<dyn Error>> {
     let Some(at) = s.rfind('@') else {
         Err("missing @")?
     };
@@ -704,12 +1006,15 @@ fn check_json_pointer(s: &str) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-// see https://tools.ietf.org/html/draft-handrews-relative-json-pointer-01#section-3
 fn validate_relative_json_pointer(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
+    check_relative_json_pointer(s)
+}
 
+// see https://tools.ietf.org/html/draft-handrews-relative-json-pointer-01#section-3
+fn check_relative_json_pointer(s: &str) -> Result<(), Box<dyn Error>> {
     // start with non-negative-integer
     let num_digits = s.chars().take_while(char::is_ascii_digit).count();
     if num_digits == 0 {
@@ -730,12 +1035,15 @@ fn validate_relative_json_pointer(v: &Value) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-// see https://datatracker.ietf.org/doc/html/rfc4122#page-4
 fn validate_uuid(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
+    check_uuid(s)
+}
 
+// see https://datatracker.ietf.org/doc/html/rfc4122#page-4
+fn check_uuid(s: &str) -> Result<(), Box<dyn Error>> {
     static HEX_GROUPS: [usize; 5] = [8, 4, 4, 4, 12];
     let mut i = 0;
     for group in s.split('-') {
@@ -764,7 +1072,11 @@ fn validate_uri(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
-    if fluent_uri::UriRef::parse(s.as_str())?.scheme().is_none() {
+    check_uri(s)
+}
+
+fn check_uri(s: &str) -> Result<(), Box<dyn Error>> {
+    if fluent_uri::UriRef::parse(s)?.scheme().is_none() {
         Err("relative url")?;
     };
     Ok(())
@@ -774,6 +1086,10 @@ fn validate_iri(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
+    check_iri(s)
+}
+
+fn check_iri(s: &str) -> Result<(), Box<dyn Error>> {
     match Url::parse(s) {
         Ok(_) => Ok(()),
         Err(url::ParseError::RelativeUrlWithoutBase) => Err("relative url")?,
@@ -794,7 +1110,11 @@ fn validate_uri_reference(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
-    fluent_uri::UriRef::parse(s.as_str())?;
+    check_uri_reference(s)
+}
+
+fn check_uri_reference(s: &str) -> Result<(), Box<dyn Error>> {
+    fluent_uri::UriRef::parse(s)?;
     Ok(())
 }
 
@@ -802,6 +1122,10 @@ fn validate_iri_reference(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
+    check_iri_reference(s)
+}
+
+fn check_iri_reference(s: &str) -> Result<(), Box<dyn Error>> {
     parse_uri_reference(s)?;
     Ok(())
 }
@@ -810,7 +1134,10 @@ fn validate_uri_template(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
+    check_uri_template(s)
+}
 
+fn check_uri_template(s: &str) -> Result<(), Box<dyn Error>> {
     let url = parse_uri_reference(s)?;
 
     let path = url.path();
@@ -836,3 +1163,144 @@ fn validate_uri_template(v: &Value) -> Result<(), Box<dyn Error>> {
     }
     Ok(())
 }
+
+/**
+Built-in format checks, usable standalone.
+
+Each function checks whether given string conforms to the
+corresponding format, and returns [`FormatError`](checks::FormatError)
+describing why it does not.
+
+```
+use boon::formats::checks;
+
+assert!(checks::check_email("john@example.com").is_ok());
+let err = checks::check_date("2024-02-30").unwrap_err();
+assert_eq!(err.format, "date");
+```
+*/
+pub mod checks {
+    use std::{error::Error, fmt::Display};
+
+    /// Error returned by format checks.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FormatError {
+        /// name of the format that failed, for example `date-time`.
+        pub format: &'static str,
+        /// describes why the check failed.
+        pub reason: String,
+    }
+
+    impl Display for FormatError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "invalid {}: {}", self.format, self.reason)
+        }
+    }
+
+    impl Error for FormatError {}
+
+    macro_rules! checks {
+        ($($func:ident: $name:literal,)*) => {
+            $(
+                #[doc = concat!("Checks whether `s` is valid `", $name, "`.")]
+                pub fn $func(s: &str) -> Result<(), FormatError> {
+                    super::$func(s).map_err(|e| FormatError {
+                        format: $name,
+                        reason: e.to_string(),
+                    })
+                }
+            )*
+        };
+    }
+
+    checks! {
+        check_regex: "regex",
+        check_ipv4: "ipv4",
+        check_ipv6: "ipv6",
+        check_hostname: "hostname",
+        check_idn_hostname: "idn-hostname",
+        check_email: "email",
+        check_idn_email: "idn-email",
+        check_date: "date",
+        check_time: "time",
+        check_date_time: "date-time",
+        check_iso_date_time: "iso-date-time",
+        check_duration: "duration",
+        check_period: "period",
+        check_json_pointer: "json-pointer",
+        check_relative_json_pointer: "relative-json-pointer",
+        check_uuid: "uuid",
+        check_uri: "uri",
+        check_iri: "iri",
+        check_uri_reference: "uri-reference",
+        check_iri_reference: "iri-reference",
+        check_uri_template: "uri-template",
+    }
+}
+
+/**
+Conversion of temporal formats into [chrono](https://docs.rs/chrono) types,
+with feature `chrono`.
+
+Each function validates `s` exactly like the corresponding format, so
+values accepted by a schema always convert. Leap second is represented
+by chrono as nanoseconds beyond `1_000_000_000`.
+
+```
+use boon::formats::chrono::*;
+use chrono::{Datelike, Timelike};
+
+let dt = parse_date_time("2024-02-29T10:30:00+05:30").unwrap();
+assert_eq!((dt.month(), dt.day(), dt.hour()), (2, 29, 10));
+assert_eq!(dt.offset().local_minus_utc(), 5 * 3600 + 30 * 60);
+assert!(parse_date("2023-02-29").is_err());
+```
+*/
+#[cfg(feature = "chrono")]
+pub mod chrono {
+    use std::error::Error;
+
+    use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, ParseResult};
+
+    use super::checks::FormatError;
+
+    fn convert<T>(
+        format: &'static str,
+        s: &str,
+        check: fn(&str) -> Result<(), Box<dyn Error>>,
+        parse: impl FnOnce() -> ParseResult<T>,
+    ) -> Result<T, FormatError> {
+        let error = |reason: String| FormatError { format, reason };
+        check(s).map_err(|e| error(e.to_string()))?;
+        parse().map_err(|e| error(e.to_string()))
+    }
+
+    /// Converts `date` format, like `2024-02-29`.
+    pub fn parse_date(s: &str) -> Result<NaiveDate, FormatError> {
+        convert("date", s, super::check_date, || {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        })
+    }
+
+    /// Converts `time` format, like `10:30:00+05:30`.
+    pub fn parse_time(s: &str) -> Result<(NaiveTime, FixedOffset), FormatError> {
+        convert("time", s, super::check_time, || {
+            let dt = DateTime::parse_from_rfc3339(&format!("1970-01-01T{s}"))?;
+            Ok((dt.time(), *dt.offset()))
+        })
+    }
+
+    /// Converts `date-time` format, like `2024-02-29T10:30:00+05:30`.
+    pub fn parse_date_time(s: &str) -> Result<DateTime<FixedOffset>, FormatError> {
+        convert("date-time", s, super::check_date_time, || {
+            DateTime::parse_from_rfc3339(s)
+        })
+    }
+
+    /// Converts `iso-date-time` format, like `2024-02-29T10:30:00`.
+    pub fn parse_iso_date_time(s: &str) -> Result<NaiveDateTime, FormatError> {
+        convert("iso-date-time", s, super::check_iso_date_time, || {
+            Ok(DateTime::parse_from_rfc3339(&format!("{s}Z"))?.naive_utc())
+        })
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use crate::{validator, InstanceLocation, SchemaIndex, Schemas};
+
+/**
+Receives calls around evaluation of keywords, available with feature `hooks`.
+
+Use it to build tools like coverage, which report schema keywords
+that are exercised by a set of instances.
+
+For each subschema evaluated, [`KeywordHook::before`] is called for every
+keyword applicable to the instance, then keywords are evaluated, and
+then [`KeywordHook::after`] is called for every such keyword with its result.
+Keywords like `then`, `minContains` and `contentSchema`, are part of `if`,
+`contains` and `contentMediaType` respectively.
+
+While hook is set, subschemas are evaluated fully even if only their
+result is needed, and memoization is disabled. So it should not be set
+in production.
+
+All methods have empty default implementations, so implement only
+the ones you need.
+
+```
+# use boon::*;
+# use serde_json::json;
+# use std::{collections::HashSet, sync::{Arc, Mutex}};
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+#[derive(Default)]
+struct Failed(Mutex<HashSet<String>>);
+
+impl KeywordHook for Failed {
+    fn after(&self, eval: &KeywordEval, valid: bool) {
+        if !valid {
+            let loc = format!("{}/{}", eval.schema_url, eval.keyword);
+            self.0.lock().unwrap().insert(loc);
+        }
+    }
+}
+
+let mut schemas = Schemas::new();
+let mut compiler = Compiler::new();
+compiler.add_resource("http://a.com/s.json", json!({"type": "integer", "minimum": 1}))?;
+let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+let failed = Arc::new(Failed::default());
+let mut options = ValidationOptions::new();
+options.set_keyword_hook(failed.clone());
+assert!(schemas.validate_with(&json!(0), sch, &options).is_err());
+assert_eq!(
+    *failed.0.lock().unwrap(),
+    HashSet::from(["http://a.com/s.json#/minimum".to_owned()])
+);
+# Ok(())
+# }
+```
+*/
+pub trait KeywordHook: Send + Sync {
+    /// Called before `eval.keyword` is evaluated.
+    fn before(&self, _eval: &KeywordEval) {}
+
+    /// Called after `eval.keyword` is evaluated. `valid` tells whether
+    /// the instance is valid against the keyword.
+    fn after(&self, _eval: &KeywordEval, _valid: bool) {}
+}
+
+/// Evaluation of a keyword, reported to [`KeywordHook`].
+#[derive(Debug)]
+pub struct KeywordEval<'a, 's, 'v> {
+    /// The absolute, dereferenced location of schema containing the keyword.
+    pub schema_url: &'s str,
+    /// Name of the keyword, like `minimum`.
+    pub keyword: &'static str,
+    /// The location of the JSON value within the instance being validated
+    pub instance_location: &'a InstanceLocation<'v>,
+}
+
+impl Schemas {
+    /**
+    Returns absolute locations of keywords, like `http://a.com/s.json#/properties/a/minimum`,
+    of schema identified by `sch_index` and of subschemas statically reachable
+    from it, in sorted order. These are the keywords reported to [`KeywordHook`],
+    so that the ones never evaluated can be found.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn keyword_locations(&self, sch_index: SchemaIndex) -> Vec<String> {
+        if !self.contains(sch_index) {
+            panic!("Schemas::keyword_locations: schema index out of bounds");
+        }
+        let mut seen = HashSet::new();
+        let mut stack = vec![sch_index];
+        let mut locs = vec![];
+        while let Some(idx) = stack.pop() {
+            if !seen.insert(idx) {
+                continue;
+            }
+            let s = self.get(idx);
+            for (kw, _) in validator::keywords(s) {
+                locs.push(format!("{}/{kw}", s.loc));
+            }
+            stack.extend(s.applied_subschemas());
+        }
+        locs.sort();
+        locs
+    }
+}
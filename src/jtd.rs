@@ -0,0 +1,235 @@
+use serde_json::{json, Map, Value};
+
+use crate::util::*;
+
+// keywords defined by rfc8927
+const KEYWORDS: [&str; 13] = [
+    "definitions",
+    "nullable",
+    "metadata",
+    "ref",
+    "type",
+    "enum",
+    "elements",
+    "properties",
+    "optionalProperties",
+    "additionalProperties",
+    "values",
+    "discriminator",
+    "mapping",
+];
+
+// types, with range for integer types
+const TYPES: [(&str, Option<(i64, i64)>); 11] = [
+    ("boolean", None),
+    ("string", None),
+    ("timestamp", None),
+    ("float32", None),
+    ("float64", None),
+    ("int8", Some((i8::MIN as i64, i8::MAX as i64))),
+    ("uint8", Some((u8::MIN as i64, u8::MAX as i64))),
+    ("int16", Some((i16::MIN as i64, i16::MAX as i64))),
+    ("uint16", Some((u16::MIN as i64, u16::MAX as i64))),
+    ("int32", Some((i32::MIN as i64, i32::MAX as i64))),
+    ("uint32", Some((u32::MIN as i64, u32::MAX as i64))),
+];
+
+// converts JSON Type Definition `jtd` into equivalent 2020-12 json schema.
+// see Compiler::set_dialect
+//
+// returns json-pointer and reason, if `jtd` is not valid.
+pub(crate) fn to_json_schema(jtd: &Value) -> Result<Value, (String, String)> {
+    let definitions = match jtd.get("definitions") {
+        None => None,
+        Some(Value::Object(defs)) => Some(defs),
+        Some(_) => return Err(("/definitions".to_owned(), "must be an object".to_owned())),
+    };
+    let cv = Converter { definitions };
+    let mut sch = cv.convert(jtd, "", None)?;
+    if let Some(defs) = definitions {
+        let mut converted = Map::new();
+        for (name, def) in defs {
+            let ptr = format!("/definitions/{}", escape(name));
+            converted.insert(name.clone(), cv.convert(def, &ptr, None)?);
+        }
+        sch["$defs"] = Value::Object(converted);
+    }
+    sch["$schema"] = json!("https://json-schema.org/draft/2020-12/schema");
+    Ok(sch)
+}
+
+struct Converter<'a> {
+    definitions: Option<&'a Map<String, Value>>,
+}
+
+impl Converter<'_> {
+    // `tag` is the discriminator, if `v` is a mapping of discriminator
+    fn convert(&self, v: &Value, ptr: &str, tag: Option<&str>) -> Result<Value, (String, String)> {
+        let fail = |kw: &str, reason: String| {
+            let ptr = match kw {
+                "" => ptr.to_owned(),
+                _ => format!("{ptr}/{kw}"),
+            };
+            Err((ptr, reason))
+        };
+        let Value::Object(obj) = v else {
+            return fail("", "schema must be an object".to_owned());
+        };
+        for kw in obj.keys() {
+            if !KEYWORDS.contains(&kw.as_str()) {
+                return fail("", format!("unknown keyword {}", quote(kw)));
+            }
+        }
+        if !ptr.is_empty() && obj.contains_key("definitions") {
+            return fail("definitions", "allowed only in root schema".to_owned());
+        }
+        let nullable = match obj.get("nullable") {
+            None => false,
+            Some(Value::Bool(b)) => *b,
+            Some(_) => return fail("nullable", "must be a boolean".to_owned()),
+        };
+        if !matches!(obj.get("metadata"), None | Some(Value::Object(_))) {
+            return fail("metadata", "must be an object".to_owned());
+        }
+
+        let has = |kw| obj.contains_key(kw);
+        let props_form = has("properties") || has("optionalProperties");
+        let forms = [
+            has("ref"),
+            has("type"),
+            has("enum"),
+            has("elements"),
+            props_form,
+            has("values"),
+            has("discriminator"),
+        ];
+        if forms.iter().filter(|f| **f).count() > 1 {
+            return fail("", "schema must have at most one form".to_owned());
+        }
+        if has("additionalProperties") && !props_form {
+            return fail(
+                "additionalProperties",
+                "requires properties or optionalProperties".to_owned(),
+            );
+        }
+        if has("mapping") && !has("discriminator") {
+            return fail("mapping", "requires discriminator".to_owned());
+        }
+        if tag.is_some() && (!props_form || nullable) {
+            return fail(
+                "",
+                "mapping must be non-nullable properties form".to_owned(),
+            );
+        }
+
+        let mut sch = if let Some(r) = obj.get("ref") {
+            let Value::String(name) = r else {
+                return fail("ref", "must be a string".to_owned());
+            };
+            if !self.definitions.is_some_and(|defs| defs.contains_key(name)) {
+                return fail("ref", format!("definition {} not found", quote(name)));
+            }
+            json!({"$ref": format!("#/$defs/{}", escape(name))})
+        } else if let Some(t) = obj.get("type") {
+            let Some(&(t, range)) = TYPES.iter().find(|(name, _)| Some(*name) == t.as_str()) else {
+                return fail("type", format!("invalid type {t}"));
+            };
+            match (t, range) {
+                ("boolean", _) => json!({"type": "boolean"}),
+                ("string", _) => json!({"type": "string"}),
+                ("timestamp", _) => json!({"type": "string", "format": "date-time"}),
+                (_, Some((min, max))) => json!({"type": "integer", "minimum": min, "maximum": max}),
+                _ => json!({"type": "number"}),
+            }
+        } else if let Some(e) = obj.get("enum") {
+            let Some(arr) = e.as_array().filter(|arr| !arr.is_empty()) else {
+                return fail("enum", "must be a non-empty array".to_owned());
+            };
+            if !arr.iter().all(Value::is_string) {
+                return fail("enum", "must contain only strings".to_owned());
+            }
+            if arr.iter().enumerate().any(|(i, v)| arr[..i].contains(v)) {
+                return fail("enum", "must not contain duplicates".to_owned());
+            }
+            json!({"enum": arr})
+        } else if let Some(elements) = obj.get("elements") {
+            let items = self.convert(elements, &format!("{ptr}/elements"), None)?;
+            json!({"type": "array", "items": items})
+        } else if props_form {
+            let mut props = Map::new();
+            let mut required = vec![];
+            for kw in ["properties", "optionalProperties"] {
+                let members = match obj.get(kw) {
+                    None => continue,
+                    Some(Value::Object(members)) => members,
+                    Some(_) => return fail(kw, "must be an object".to_owned()),
+                };
+                for (pname, psch) in members {
+                    if props.contains_key(pname) {
+                        return fail(
+                            kw,
+                            format!("{} is both required and optional", quote(pname)),
+                        );
+                    }
+                    if tag == Some(pname.as_str()) {
+                        return fail(kw, format!("{} is the discriminator", quote(pname)));
+                    }
+                    let ptr = format!("{ptr}/{kw}/{}", escape(pname));
+                    props.insert(pname.clone(), self.convert(psch, &ptr, None)?);
+                    if kw == "properties" {
+                        required.push(pname.clone());
+                    }
+                }
+            }
+            let additional = match obj.get("additionalProperties") {
+                None => false,
+                Some(Value::Bool(b)) => *b,
+                Some(_) => return fail("additionalProperties", "must be a boolean".to_owned()),
+            };
+            if let Some(tag) = tag {
+                props.insert(tag.to_owned(), Value::Bool(true));
+            }
+            let mut sch = json!({"type": "object", "properties": props});
+            if !required.is_empty() {
+                sch["required"] = json!(required);
+            }
+            if !additional {
+                sch["additionalProperties"] = Value::Bool(false);
+            }
+            sch
+        } else if let Some(values) = obj.get("values") {
+            let values = self.convert(values, &format!("{ptr}/values"), None)?;
+            json!({"type": "object", "additionalProperties": values})
+        } else if let Some(d) = obj.get("discriminator") {
+            let Value::String(d) = d else {
+                return fail("discriminator", "must be a string".to_owned());
+            };
+            let Some(Value::Object(mapping)) = obj.get("mapping") else {
+                return fail("", "discriminator requires mapping object".to_owned());
+            };
+            let mut branches = vec![];
+            for (tag, msch) in mapping {
+                let ptr = format!("{ptr}/mapping/{}", escape(tag));
+                let then = self.convert(msch, &ptr, Some(d))?;
+                branches.push(json!({"if": {"properties": {d: {"const": tag}}}, "then": then}));
+            }
+            let tags: Vec<_> = mapping.keys().collect();
+            json!({
+                "type": "object",
+                "required": [d],
+                "properties": {d: {"type": "string", "enum": tags}},
+                "allOf": branches
+            })
+        } else {
+            json!({})
+        };
+
+        if nullable && !sch.as_object().is_some_and(Map::is_empty) {
+            sch = json!({"if": {"type": "null"}, "else": sch});
+        }
+        if let Some(Value::String(desc)) = obj.get("metadata").and_then(|m| m.get("description")) {
+            sch["description"] = json!(desc);
+        }
+        Ok(sch)
+    }
+}
@@ -0,0 +1,213 @@
+use serde_json::{Map, Value};
+
+use crate::{util::*, Additional, Items, Schema, SchemaIndex, Schemas, Type};
+
+impl Schemas {
+    /**
+    Removes object members from `v`, which are not specified by the
+    structural schema identified by `sch_index`, as Kubernetes API
+    server does for custom resources. Returns json-pointers of the
+    removed members, in the order they are removed.
+
+    A member is kept if it is listed in `properties`, or if the object
+    schema has `additionalProperties`, or `x-kubernetes-preserve-unknown-fields`.
+    Members preserved that way are not pruned further, unless
+    `additionalProperties` is a schema. `apiVersion`, `kind` and `metadata`
+    are kept in the root object and in objects with `x-kubernetes-embedded-resource`.
+    Members with `null` value are removed, unless their schema is `nullable`.
+
+    Only `properties`, `additionalProperties` and `items` are followed,
+    since structural schema has no other way to specify fields.
+    see [`Compiler::enable_kubernetes_extensions`]
+
+    [`Compiler::enable_kubernetes_extensions`]: crate::Compiler::enable_kubernetes_extensions
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn prune_unknown_fields(&self, v: &mut Value, sch_index: SchemaIndex) -> Vec<String> {
+        if !self.contains(sch_index) {
+            panic!("Schemas::prune_unknown_fields: schema index out of bounds");
+        }
+        let mut pruned = vec![];
+        self.prune_fields(v, sch_index, true, &mut String::new(), &mut pruned);
+        pruned
+    }
+
+    fn prune_fields(
+        &self,
+        v: &mut Value,
+        sch: SchemaIndex,
+        root: bool,
+        ptr: &mut String,
+        pruned: &mut Vec<String>,
+    ) {
+        let s = self.get(sch);
+        match v {
+            Value::Object(obj) => {
+                let embedded = root || s.embedded_resource;
+                obj.retain(|pname, pvalue| {
+                    let len = ptr.len();
+                    ptr.push('/');
+                    ptr.push_str(&escape(pname));
+                    let keep = self.prune_member(s, embedded, pname, pvalue, ptr, pruned);
+                    if !keep {
+                        pruned.push(ptr.clone());
+                    }
+                    ptr.truncate(len);
+                    keep
+                });
+            }
+            Value::Array(arr) => {
+                let items = match &s.items {
+                    Some(Items::SchemaRef(sch)) => Some(*sch),
+                    _ => s.items2020,
+                };
+                let Some(items) = items else {
+                    return;
+                };
+                for (i, item) in arr.iter_mut().enumerate() {
+                    let len = ptr.len();
+                    ptr.push('/');
+                    ptr.push_str(&i.to_string());
+                    self.prune_fields(item, items, false, ptr, pruned);
+                    ptr.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // returns false, if member is to be removed
+    fn prune_member(
+        &self,
+        s: &Schema,
+        embedded: bool,
+        pname: &str,
+        pvalue: &mut Value,
+        ptr: &mut String,
+        pruned: &mut Vec<String>,
+    ) -> bool {
+        let sch = match (s.properties.get(pname), &s.additional_properties) {
+            (Some(sch), _) | (None, Some(Additional::SchemaRef(sch))) => *sch,
+            (None, Some(Additional::Bool(true))) => return true,
+            _ if s.preserve_unknown_fields => return true,
+            _ if embedded && ["apiVersion", "kind", "metadata"].contains(&pname) => return true,
+            _ => return false,
+        };
+        let types = self.get(sch).types;
+        if pvalue.is_null() && !types.is_empty() && !types.contains(Type::Null) {
+            return false;
+        }
+        self.prune_fields(pvalue, sch, false, ptr, pruned);
+        true
+    }
+}
+
+// checks that schema `v` at json-pointer `ptr` is structural, as per
+// https://kubernetes.io/docs/tasks/extend-kubernetes/custom-resources/custom-resource-definitions/#specifying-a-structural-schema
+//
+// returns location and reason of the first violation.
+pub(crate) fn check_structural(v: &Value, ptr: &str) -> Result<(), (String, String)> {
+    let fail = |reason: &str| Err((ptr.to_owned(), reason.to_owned()));
+    let Value::Object(obj) = v else {
+        return fail("schema must be an object");
+    };
+    if obj.contains_key("$ref") {
+        return fail("$ref is not allowed");
+    }
+    let int_or_string = is_true(v, "x-kubernetes-int-or-string");
+    match obj.get("type") {
+        Some(_) if int_or_string => {
+            return fail("type must not be specified with x-kubernetes-int-or-string")
+        }
+        Some(Value::String(t)) if !t.is_empty() => {}
+        None if int_or_string || is_true(v, "x-kubernetes-preserve-unknown-fields") => {}
+        _ => return fail("type must be a non-empty string"),
+    }
+    if is_true(v, "x-kubernetes-embedded-resource") && obj["type"] != "object" {
+        return fail("x-kubernetes-embedded-resource requires type object");
+    }
+    if obj.contains_key("properties") && obj.contains_key("additionalProperties") {
+        return fail("properties and additionalProperties are mutually exclusive");
+    }
+
+    if let Some(Value::Object(props)) = obj.get("properties") {
+        for (pname, sub) in props {
+            check_structural(sub, &format!("{ptr}/properties/{}", escape(pname)))?;
+        }
+    }
+    if let Some(sub @ Value::Object(_)) = obj.get("additionalProperties") {
+        check_structural(sub, &format!("{ptr}/additionalProperties"))?;
+    }
+    match obj.get("items") {
+        Some(Value::Array(_)) => return fail("items must be a single schema"),
+        Some(sub) => check_structural(sub, &format!("{ptr}/items"))?,
+        None => {}
+    }
+    check_junctors(obj, v, ptr)
+}
+
+// checks subschemas of logical junctors in `obj`, against structural schema `outer`.
+fn check_junctors(
+    obj: &Map<String, Value>,
+    outer: &Value,
+    ptr: &str,
+) -> Result<(), (String, String)> {
+    for kw in ["allOf", "anyOf", "oneOf"] {
+        if let Some(Value::Array(arr)) = obj.get(kw) {
+            for (i, sub) in arr.iter().enumerate() {
+                check_value_validation(sub, outer, &format!("{ptr}/{kw}/{i}"))?;
+            }
+        }
+    }
+    if let Some(sub) = obj.get("not") {
+        check_value_validation(sub, outer, &format!("{ptr}/not"))?;
+    }
+    Ok(())
+}
+
+// checks that schema `v` in logical junctor does not specify anything
+// other than value validations of fields already in structural schema `outer`.
+fn check_value_validation(v: &Value, outer: &Value, ptr: &str) -> Result<(), (String, String)> {
+    let fail = |reason: String| Err((ptr.to_owned(), reason));
+    let Value::Object(obj) = v else {
+        return fail("schema must be an object".to_owned());
+    };
+    for kw in obj.keys() {
+        let allowed = match kw.as_str() {
+            // `anyOf: [{type: integer}, {type: string}]` is allowed
+            "type" => is_true(outer, "x-kubernetes-int-or-string"),
+            "$ref" | "description" | "default" | "nullable" | "additionalProperties" => false,
+            kw => !kw.starts_with("x-kubernetes-"),
+        };
+        if !allowed {
+            return fail(format!("{} is not allowed in logical junctors", quote(kw)));
+        }
+    }
+
+    if let Some(Value::Object(props)) = obj.get("properties") {
+        for (pname, sub) in props {
+            let Some(outer) = outer.get("properties").and_then(|props| props.get(pname)) else {
+                return fail(format!(
+                    "property {} must be specified outside of logical junctors",
+                    quote(pname)
+                ));
+            };
+            check_value_validation(sub, outer, &format!("{ptr}/properties/{}", escape(pname)))?;
+        }
+    }
+    if let Some(sub) = obj.get("items") {
+        let Some(outer) = outer.get("items") else {
+            return fail("items must be specified outside of logical junctors".to_owned());
+        };
+        check_value_validation(sub, outer, &format!("{ptr}/items"))?;
+    }
+    check_junctors(obj, outer, ptr)
+}
+
+fn is_true(v: &Value, pname: &str) -> bool {
+    matches!(v.get(pname), Some(Value::Bool(true)))
+}
@@ -80,6 +80,7 @@ for example:
 - [flag] `validation_error.flag_output()`
 - [basic] `validation_error.basic_output()`
 - [detailed] `validation_error.detailed_output()`
+- [ajv] `validation_error.ajv_output()`, list of ajv error objects
 
 The output object implements `serde::Serialize`.
 
@@ -99,37 +100,122 @@ println!("{output:#}"); // prints indented json
 [flag]: https://json-schema.org/draft/2020-12/json-schema-core.html#name-flag
 [basic]: https://json-schema.org/draft/2020-12/json-schema-core.html#name-basic
 [detailed]: https://json-schema.org/draft/2020-12/json-schema-core.html#name-detailed
+[ajv]: https://ajv.js.org/api.html#error-objects
 
 */
 
+#![allow(clippy::result_large_err)]
+
+// logs using `log` crate, if feature `log` is enabled
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        log::debug!($($arg)+)
+    };
+}
+
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        log::trace!($($arg)+)
+    };
+}
+
+mod ajv;
+mod alias;
+#[cfg(all(
+    any(feature = "zip", feature = "tar"),
+    feature = "fs",
+    not(target_arch = "wasm32")
+))]
+mod archive;
+pub mod avro;
+mod builder;
 mod compiler;
 mod content;
+mod doc;
 mod draft;
-mod ecma;
-mod formats;
+/// ECMA-262 regular expression support.
+pub mod ecma;
+#[cfg(feature = "include_dir")]
+mod embed;
+mod error;
+mod flatten;
+mod form;
+/// `format` keyword support and built-in format checks.
+pub mod formats;
+#[cfg(feature = "hooks")]
+mod hooks;
+mod jtd;
+mod kubernetes;
 mod loader;
+mod metrics;
 mod output;
+mod patch;
+pub mod pointer;
+mod prune;
+mod raw;
+mod redact;
+mod revalidate;
 mod root;
 mod roots;
+#[cfg(feature = "simd-json")]
+pub mod simd;
+mod strict;
+#[cfg(all(feature = "fs", not(target_arch = "wasm32")))]
+pub mod testsuite;
 mod util;
 mod validator;
-
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "axum")]
+pub mod web;
+
+#[cfg(all(
+    any(feature = "zip", feature = "tar"),
+    feature = "fs",
+    not(target_arch = "wasm32")
+))]
+pub use archive::ArchiveLoader;
+#[cfg(feature = "hooks")]
+pub use hooks::{KeywordEval, KeywordHook};
+#[cfg(all(feature = "fs", not(target_arch = "wasm32")))]
 pub use loader::FileLoader;
+#[cfg(not(target_arch = "wasm32"))]
+pub use loader::RetryLoader;
 pub use {
-    compiler::{CompileError, Compiler, Draft},
+    ajv::AjvError,
+    builder::CompilerBuilder,
+    compiler::{
+        AnchorKind, CompileError, Compiler, Dialect, Draft, OptimizationLevel, SchemaAnchor,
+    },
     content::{Decoder, MediaType},
-    formats::Format,
-    loader::{SchemeUrlLoader, UrlLoader},
+    doc::DocFormat,
+    draft::strip_annotations,
+    error::{Error, InvalidError, Result},
+    flatten::flatten_all_of,
+    formats::{Format, FormatMode},
+    loader::{DataUrlLoader, MemoryLoader, SchemeUrlLoader, UrlLoader, UrlMapper},
+    metrics::Metrics,
     output::{
-        AbsoluteKeywordLocation, FlagOutput, KeywordPath, OutputError, OutputUnit, SchemaToken,
+        AbsoluteKeywordLocation, ErrorGroup, FlagOutput, GroupedOutput, KeywordPath, OutputError,
+        OutputUnit, ProblemDetails, ProblemError, SchemaToken,
     },
-    validator::{InstanceLocation, InstanceToken},
+    patch::{PatchError, PatchViolation},
+    strict::parse_strict,
+    validator::{InstanceLocation, InstanceToken, LocationFormat},
 };
 
-use std::{borrow::Cow, collections::HashMap, error::Error, fmt::Display};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error as StdError,
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use ahash::AHashMap;
+use content::{DynDecoder, DynMediaType};
+use formats::DynFormat;
 use regex::Regex;
 use serde_json::{Number, Value};
 use util::*;
@@ -138,11 +224,20 @@ use util::*;
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SchemaIndex(usize);
 
-/// Collection of compiled schemas.
-#[derive(Default)]
+/**
+Collection of compiled schemas.
+
+Cloning is cheap, since compiled schemas are shared between clones.
+This allows to compile a common set of schemas once, and then
+add more schemas to its clones as needed, for example per tenant.
+*/
+#[derive(Default, Clone)]
 pub struct Schemas {
-    list: Vec<Schema>,
-    map: HashMap<UrlPtr, usize>, // loc => schema-index
+    list: Vec<Option<Arc<Schema>>>,        // None, if removed
+    map: Arc<HashMap<UrlPtr, usize>>,      // loc => schema-index
+    roots: HashSet<SchemaIndex>,           // returned by Compiler::compile
+    aliases: HashMap<String, SchemaIndex>, // see Schemas::alias
+    metrics: Option<Arc<dyn Metrics>>,
 }
 
 impl Schemas {
@@ -150,29 +245,278 @@ impl Schemas {
         Self::default()
     }
 
+    /// Reports validations done with these schemas to `metrics`.
+    /// Clones made after this call report to the same `metrics`.
+    pub fn set_metrics(&mut self, metrics: Arc<dyn Metrics>) {
+        self.metrics = Some(metrics);
+    }
+
     fn insert(&mut self, locs: Vec<UrlPtr>, compiled: Vec<Schema>) {
-        for (up, sch) in locs.into_iter().zip(compiled.into_iter()) {
+        for (up, sch) in locs.into_iter().zip(compiled) {
             let i = self.list.len();
-            self.list.push(sch);
-            self.map.insert(up, i);
+            self.list.push(Some(Arc::new(sch)));
+            Arc::make_mut(&mut self.map).insert(up, i);
         }
     }
 
     fn get(&self, idx: SchemaIndex) -> &Schema {
-        &self.list[idx.0] // todo: return bug
+        self.try_get(idx).expect("schema must exist") // todo: return bug
+    }
+
+    fn try_get(&self, idx: SchemaIndex) -> Option<&Schema> {
+        self.list.get(idx.0)?.as_deref()
+    }
+
+    // schema is copied, if it is shared with clones
+    fn get_mut(&mut self, idx: SchemaIndex) -> &mut Schema {
+        let sch = self.list[idx.0].as_mut().expect("schema must exist");
+        Arc::make_mut(sch)
+    }
+
+    // index to be assigned to next inserted schema
+    fn next_index(&self) -> usize {
+        self.list.len()
     }
 
     fn get_by_loc(&self, up: &UrlPtr) -> Option<&Schema> {
-        self.map.get(up).and_then(|&i| self.list.get(i))
+        self.map.get(up).map(|&i| self.get(SchemaIndex(i)))
     }
 
     /// Returns true if `sch_index` is generated for this instance.
     pub fn contains(&self, sch_index: SchemaIndex) -> bool {
-        self.list.get(sch_index.0).is_some()
+        self.try_get(sch_index).is_some()
     }
 
+    /// Returns the number of compiled schemas, including subschemas.
     pub fn size(&self) -> usize {
-        self.list.len()
+        self.list.iter().filter(|sch| sch.is_some()).count()
+    }
+
+    /**
+    Removes schema identified by `sch_index`, which was returned by
+    [`Compiler::compile`], along with its subschemas and the schemas
+    it refers to, which are not reachable from other compiled schemas.
+
+    Returns `false`, if `sch_index` is not returned by
+    [`Compiler::compile`] or is already removed.
+
+    Indexes of remaining schemas stay the same. Indexes of removed
+    schemas are never reused, and using them panics, just like
+    indexes generated for another instance. Note that if same
+    location is compiled more than once, the same index is returned,
+    so removing it affects all of them. Aliases of removed schemas
+    are removed too. see [`Schemas::alias`]
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/a.json", json!({"$ref": "b.json"}))?;
+    compiler.add_resource("http://a.com/b.json", json!({"type": "string"}))?;
+    let a = compiler.compile("http://a.com/a.json", &mut schemas)?;
+    let b = compiler.compile("http://a.com/b.json", &mut schemas)?;
+
+    assert!(schemas.remove(a));
+    assert!(!schemas.contains(a));
+    assert!(schemas.contains(b)); // still compiled
+    assert_eq!(schemas.size(), 1);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn remove(&mut self, sch_index: SchemaIndex) -> bool {
+        if !self.roots.remove(&sch_index) {
+            return false;
+        }
+        let roots: Vec<SchemaIndex> = self.roots.iter().copied().collect();
+        self.retain_reachable(roots);
+        true
+    }
+
+    // removes schemas, which are not reachable from `roots`
+    pub(crate) fn retain_reachable(&mut self, roots: impl IntoIterator<Item = SchemaIndex>) {
+        let mut reachable = vec![false; self.list.len()];
+        let mut stack: Vec<SchemaIndex> = roots.into_iter().collect();
+        while let Some(idx) = stack.pop() {
+            if std::mem::replace(&mut reachable[idx.0], true) {
+                continue;
+            }
+            if let Some(sch) = self.try_get(idx) {
+                stack.extend(sch.subschemas());
+            }
+        }
+
+        for (i, sch) in self.list.iter_mut().enumerate() {
+            if !reachable[i] {
+                *sch = None;
+            }
+        }
+        Arc::make_mut(&mut self.map).retain(|_, i| reachable[*i]);
+        self.aliases.retain(|_, idx| reachable[idx.0]);
+    }
+
+    /**
+    Returns the [`Draft`] used to compile schema identified by `sch_index`.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    */
+    pub fn draft(&self, sch_index: SchemaIndex) -> Draft {
+        let Some(sch) = self.try_get(sch_index) else {
+            panic!("Schemas::draft: schema index out of bounds");
+        };
+        Draft::from_version(sch.draft_version).unwrap_or_default()
+    }
+
+    /**
+    Returns the vocabularies enabled for schema identified by `sch_index`.
+
+    Vocabularies are named relative to the draft, for example
+    `applicator` or `unevaluated`. Returns empty slice for drafts
+    prior to 2019-09, which have no notion of vocabularies.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({"type": "object"}))?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert_eq!(schemas.draft(sch), Draft::V2020_12);
+    assert!(schemas.vocabularies(sch).contains(&"unevaluated"));
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    */
+    pub fn vocabularies(&self, sch_index: SchemaIndex) -> &[&'static str] {
+        let Some(sch) = self.try_get(sch_index) else {
+            panic!("Schemas::vocabularies: schema index out of bounds");
+        };
+        &sch.vocabs
+    }
+
+    /**
+    Returns how `format` keyword is treated by schema identified
+    by `sch_index`.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({"format": "ipv4"}))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    assert_eq!(schemas.format_mode(sch), FormatMode::Annotation);
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    */
+    pub fn format_mode(&self, sch_index: SchemaIndex) -> FormatMode {
+        let Some(sch) = self.try_get(sch_index) else {
+            panic!("Schemas::format_mode: schema index out of bounds");
+        };
+        sch.format_mode
+    }
+
+    /**
+    Returns the value of `$comment` in schema identified by `sch_index`.
+
+    `$comment` is supported since draft-07.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({"$comment": "todo: stricter"}))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    assert_eq!(schemas.comment(sch), Some("todo: stricter"));
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    */
+    pub fn comment(&self, sch_index: SchemaIndex) -> Option<&str> {
+        let Some(sch) = self.try_get(sch_index) else {
+            panic!("Schemas::comment: schema index out of bounds");
+        };
+        sch.comment.as_deref()
+    }
+
+    /**
+    Returns stable hash of schema identified by `sch_index`, to be used
+    as cache key in downstream systems.
+
+    The hash covers json of the schema and of every schema it depends
+    on, like `$ref` targets, along with which keyword refers to which
+    of them and the draft. It does not depend on
+    the url of the schema, order of object keys, program run or platform.
+    Compiler options, like [`Compiler::enable_format_assertions`], are
+    not covered.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/a.json", json!({"type": "string", "minLength": 1}))?;
+    compiler.add_resource("http://b.com/b.json", json!({"minLength": 1, "type": "string"}))?;
+    let a = compiler.compile("http://a.com/a.json", &mut schemas)?;
+    let b = compiler.compile("http://b.com/b.json", &mut schemas)?;
+    assert_eq!(schemas.fingerprint(a), schemas.fingerprint(b));
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    */
+    pub fn fingerprint(&self, sch_index: SchemaIndex) -> u64 {
+        let Some(sch) = self.try_get(sch_index) else {
+            panic!("Schemas::fingerprint: schema index out of bounds");
+        };
+        // schemas are numbered in the order they are reached, following
+        // subschemas sorted by keyword path, so that the hash tells which
+        // keyword refers to which schema
+        let mut ids = HashMap::from([(sch.idx, 0)]);
+        let mut queue = VecDeque::from([sch]);
+        let mut h = StableHasher::default();
+        h.write_u64(sch.draft_version as u64);
+        while let Some(s) = queue.pop_front() {
+            h.write_u64(s.hash);
+            let mut subschemas = s.keyed_subschemas();
+            subschemas.sort_unstable();
+            for (kw, idx) in subschemas {
+                let n = ids.len();
+                let id = *ids.entry(idx).or_insert_with(|| {
+                    queue.push_back(self.get(idx));
+                    n
+                });
+                h.write_u64(kw.len() as u64);
+                h.write(kw.as_bytes());
+                h.write_u64(id as u64);
+            }
+        }
+        h.finish()
     }
 
     /**
@@ -188,55 +532,879 @@ impl Schemas {
         v: &'v Value,
         sch_index: SchemaIndex,
     ) -> Result<(), ValidationError<'s, 'v>> {
-        let Some(sch) = self.list.get(sch_index.0) else {
+        self.validate_with(v, sch_index, &ValidationOptions::default())
+    }
+
+    /**
+    Same as [`Schemas::validate`], but uses given `options`
+    for this validation.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_with<'s, 'v>(
+        &'s self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        options: &ValidationOptions,
+    ) -> Result<(), ValidationError<'s, 'v>> {
+        self.validate_warn(v, sch_index, &[], options, &mut vec![])
+    }
+
+    /**
+    Same as [`Schemas::validate_with`], but returns errors with
+    [`Severity::Warning`], when `v` is valid. Keywords get this severity
+    by [`ValidationOptions::set_severity`], or by `x-boon` hints.
+    see [`Compiler::enable_schema_hints`]
+
+    Warnings are reported in the order they are found, and are not
+    nested like errors. Warnings of subschemas, whose result is not
+    needed like those of `not`, are not reported.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_with_warnings<'s, 'v>(
+        &'s self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        options: &ValidationOptions,
+    ) -> Result<Vec<ValidationError<'s, 'v>>, ValidationError<'s, 'v>> {
+        let mut warnings = vec![];
+        self.validate_warn(v, sch_index, &[], options, &mut warnings)?;
+        Ok(warnings)
+    }
+
+    /**
+    Same as [`Schemas::validate_with`], but validates as if schema
+    identified by `sch_index` is entered from the schemas in `dynamic_scope`,
+    given outermost first. These schemas are not applied to `v`, they only
+    seed the dynamic scope used to resolve `$dynamicRef` and `$recursiveRef`.
+
+    This is useful to extend a recursive schema without editing it, as
+    done by metaschemas. In the example below, `tree.json` allows any
+    members in nodes, but when entered from `strict-tree.json`, nested
+    nodes are resolved to `strict-tree.json`, which disallows them.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/tree.json", json!({
+        "$dynamicAnchor": "node",
+        "properties": {
+            "children": {"items": {"$dynamicRef": "#node"}}
+        }
+    }))?;
+    compiler.add_resource("http://a.com/strict-tree.json", json!({
+        "$dynamicAnchor": "node",
+        "$ref": "tree.json",
+        "unevaluatedProperties": false
+    }))?;
+    let tree = compiler.compile("http://a.com/tree.json", &mut schemas)?;
+    let strict = compiler.compile("http://a.com/strict-tree.json", &mut schemas)?;
+
+    let v = json!({"children": [{"x": 1}]});
+    let options = ValidationOptions::default();
+    assert!(schemas.validate(&v, tree).is_ok());
+    assert!(schemas.validate_value_with_context(&v, tree, &[strict], &options).is_err());
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` or any of `dynamic_scope` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_value_with_context<'s, 'v>(
+        &'s self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        dynamic_scope: &[SchemaIndex],
+        options: &ValidationOptions,
+    ) -> Result<(), ValidationError<'s, 'v>> {
+        if !dynamic_scope.iter().all(|&idx| self.contains(idx)) {
+            panic!("Schemas::validate_value_with_context: schema index out of bounds");
+        }
+        self.validate_warn(v, sch_index, dynamic_scope, options, &mut vec![])
+    }
+
+    fn validate_warn<'s, 'v>(
+        &'s self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        dynamic_scope: &[SchemaIndex],
+        options: &ValidationOptions,
+        warnings: &mut Vec<ValidationError<'s, 'v>>,
+    ) -> Result<(), ValidationError<'s, 'v>> {
+        let Some(sch) = self.try_get(sch_index) else {
             panic!("Schemas::validate: schema index out of bounds");
         };
-        validator::validate(v, sch, self)
+        let start = self.metrics.as_ref().map(|_| Instant::now());
+        let mut pending = vec![];
+        let mut memo = validator::Memo::new(options);
+        let mut result = match validator::instance_limit(v, options) {
+            Some(kind) => Err(sch.limit_error(kind)),
+            None => validator::validate_in_scope(
+                v,
+                sch,
+                dynamic_scope,
+                self,
+                options,
+                &mut pending,
+                &mut memo,
+                warnings,
+            ),
+        };
+        if let Some(&idx) = pending.first() {
+            result = Err(self.uncompiled(idx));
+        }
+        if options.redact {
+            if let Err(e) = &mut result {
+                e.redact();
+            }
+            warnings.iter_mut().for_each(ValidationError::redact);
+        }
+        if let (Some(metrics), Some(start)) = (&self.metrics, start) {
+            metrics.validated(&sch.loc, result.is_ok(), start.elapsed());
+            if memo.is_enabled() {
+                metrics.memoized(&sch.loc, memo.hits, memo.misses);
+            }
+            if let Err(e) = &result {
+                for leaf in e.leaves() {
+                    metrics.validation_error(&sch.loc, &leaf.kind);
+                }
+            }
+        }
+        result
+    }
+
+    /**
+    Parses json text `s` and validates it with schema
+    identified by `sch_index`.
+
+    Note that `s` is the instance itself, not a json string value.
+    i.e. use `r#"{"a": 1}"#` rather than `json!(r#"{"a": 1}"#)`.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({"type": "object"}))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+    assert!(schemas.validate_str(r#"{"a": 1}"#, sch).is_ok());
+    assert!(matches!(
+        schemas.validate_str("[1]", sch),
+        Err(InstanceError::Invalid(_))
+    ));
+    assert!(matches!(
+        schemas.validate_str("{", sch),
+        Err(InstanceError::Parse(_))
+    ));
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_str(&self, s: &str, sch_index: SchemaIndex) -> Result<(), InstanceError<'_>> {
+        let v = serde_json::from_str(s).map_err(InstanceError::Parse)?;
+        self.validate_parsed(v, sch_index)
+    }
+
+    /**
+    Same as [`Schemas::validate_str`], but takes json text as bytes.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_slice(
+        &self,
+        s: &[u8],
+        sch_index: SchemaIndex,
+    ) -> Result<(), InstanceError<'_>> {
+        let v = serde_json::from_slice(s).map_err(InstanceError::Parse)?;
+        self.validate_parsed(v, sch_index)
+    }
+
+    fn validate_parsed(&self, v: Value, sch_index: SchemaIndex) -> Result<(), InstanceError<'_>> {
+        self.validate(&v, sch_index)
+            .map_err(|e| InstanceError::Invalid(e.clone_static()))
+    }
+
+    /**
+    Validates `v` with schema identified by `sch_index`, and returns
+    the locations of values within `v`, that were evaluated by the
+    schema, in no particular order.
+
+    A value is evaluated, if it is successfully validated by a subschema
+    applied to it, through keywords such as `properties`, `items`,
+    `additionalProperties` or `unevaluatedProperties`. Values which are
+    not in the returned list are not known to the schema. The root value
+    is not included.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "properties": {"a": {"items": {"type": "number"}}}
+    }))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+    let instance = json!({"a": [1], "b": 2});
+    let evaluated = schemas.evaluated_paths(&instance, sch).unwrap();
+    let evaluated: Vec<String> = evaluated.iter().map(|loc| loc.to_string()).collect();
+    assert_eq!(evaluated, ["/a", "/a/0"]);
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn evaluated_paths<'s, 'v>(
+        &'s self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+    ) -> Result<Vec<InstanceLocation<'v>>, ValidationError<'s, 'v>> {
+        let Some(sch) = self.try_get(sch_index) else {
+            panic!("Schemas::evaluated_paths: schema index out of bounds");
+        };
+        let mut pending = vec![];
+        let options = ValidationOptions::default();
+        let mut memo = validator::Memo::new(&options);
+        let result = validator::evaluate(v, sch, self, &options, &mut pending, &mut memo);
+        if let Some(&idx) = pending.first() {
+            return Err(self.uncompiled(idx));
+        }
+        result
+    }
+
+    // error for hitting uncompiled `$ref` in schema `idx`
+    fn uncompiled(&self, idx: SchemaIndex) -> ValidationError<'_, 'static> {
+        let sch = self.get(idx);
+        ValidationError {
+            schema_url: &sch.loc,
+            instance_location: InstanceLocation::default(),
+            kind: ErrorKind::Uncompiled {
+                url: sch.lazy_ref.as_deref().unwrap_or_default(),
+            },
+            causes: vec![],
+        }
+    }
+
+    // returns schemas with uncompiled `$ref`, which are hit
+    // while validating `v`.
+    fn pending_refs(&self, v: &Value, sch_index: SchemaIndex) -> Vec<SchemaIndex> {
+        let Some(sch) = self.try_get(sch_index) else {
+            panic!("Schemas::validate: schema index out of bounds");
+        };
+        let mut pending = vec![];
+        let options = ValidationOptions::default();
+        let mut memo = validator::Memo::new(&options);
+        _ = validator::validate(v, sch, self, &options, &mut pending, &mut memo, &mut vec![]);
+        pending.sort();
+        pending.dedup();
+        pending
+    }
+
+    fn resolve_lazy_ref(&mut self, idx: SchemaIndex, target: SchemaIndex) {
+        let sch = self.get_mut(idx);
+        sch.ref_ = Some(target);
+        sch.lazy_ref = None;
     }
 }
 
+/**
+Options that apply to a single validation.
+
+Use it to override or disable specific formats per validation,
+for example when one tenant requires strict `date-time` and another
+allows lenient one.
+
+```
+# use boon::*;
+# use serde_json::{json, Value};
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let mut schemas = Schemas::new();
+let mut compiler = Compiler::new();
+compiler.enable_format_assertions();
+compiler.add_resource("schema.json", json!({"format": "date-time"}))?;
+let sch = compiler.compile("schema.json", &mut schemas)?;
+
+let instance = json!("2024-01-01");
+assert!(schemas.validate(&instance, sch).is_err());
+
+let mut options = ValidationOptions::new();
+options.override_format("date-time", |v: &Value| match v {
+    Value::String(s) if s.len() < 10 => Err("too short")?,
+    _ => Ok(()),
+});
+assert!(schemas.validate_with(&instance, sch, &options).is_ok());
+
+let mut options = ValidationOptions::new();
+options.disable_format("date-time");
+assert!(schemas.validate_with(&json!("tomorrow"), sch, &options).is_ok());
+# Ok(())
+# }
+```
+
+Note that formats are checked only if format assertions are enabled
+at compile time. see [`Compiler::enable_format_assertions`]
+*/
 #[derive(Default)]
+pub struct ValidationOptions {
+    formats: HashMap<&'static str, Option<DynFormat>>, // None means disabled
+    tracer: Option<Box<Tracer>>,
+    dynamic_ref_tracer: Option<Box<DynamicRefTracer>>,
+    format_annotation_tracer: Option<Box<FormatAnnotationTracer>>,
+    #[cfg(feature = "hooks")]
+    keyword_hook: Option<Arc<dyn KeywordHook>>,
+    max_content_size: Option<usize>,
+    max_content_depth: Option<usize>,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+    string_length: StringLength,
+    memoize: bool,
+    severities: HashMap<String, Severity>,
+    redact: bool,
+}
+
+type Tracer = dyn Fn(&Trace) + Send + Sync;
+type DynamicRefTracer = dyn Fn(&DynamicRefTrace) + Send + Sync;
+type FormatAnnotationTracer = dyn Fn(&FormatAnnotation) + Send + Sync;
+
+impl ValidationOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses `func` to validate format `name`, instead of the one
+    /// used at compile time.
+    pub fn override_format<F>(&mut self, name: &'static str, func: F)
+    where
+        F: Fn(&Value) -> Result<(), Box<dyn StdError>> + Send + Sync + 'static,
+    {
+        let func = Arc::new(func);
+        self.formats.insert(name, Some(DynFormat { name, func }));
+    }
+
+    /// Skips validation of format `name`.
+    pub fn disable_format(&mut self, name: &'static str) {
+        self.formats.insert(name, None);
+    }
+
+    /**
+    Calls `tracer` each time a subschema is evaluated, after
+    the evaluation completes.
+
+    Use it to profile pathological schemas and find out why a
+    validation is slow. Tracing adds noticeable overhead, so it
+    should not be enabled in production.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # use std::{collections::HashMap, sync::{Arc, Mutex}, time::Duration};
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let mut schemas = Schemas::new();
+    # let mut compiler = Compiler::new();
+    # compiler.add_resource("http://a.com/s.json", json!({"items": {"type": "string"}}))?;
+    # let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let stats = Arc::new(Mutex::new(HashMap::<String, (usize, Duration)>::new()));
+    let mut options = ValidationOptions::new();
+    let sink = stats.clone();
+    options.set_tracer(move |t: &Trace| {
+        let mut stats = sink.lock().unwrap();
+        let entry = stats.entry(t.schema_url.to_owned()).or_default();
+        entry.0 += 1;
+        entry.1 += t.elapsed;
+    });
+    assert!(schemas.validate_with(&json!(["a", "b"]), sch, &options).is_ok());
+    assert_eq!(stats.lock().unwrap()["http://a.com/s.json#/items"].0, 2);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_tracer<F>(&mut self, tracer: F)
+    where
+        F: Fn(&Trace) + Send + Sync + 'static,
+    {
+        self.tracer = Some(Box::new(tracer));
+    }
+
+    /**
+    Calls `tracer` each time a `$dynamicRef` or `$recursiveRef`
+    is resolved, with the details of how it was resolved.
+
+    Use it to debug meta-schemas, where it is not obvious which
+    schema a dynamic reference ends up at.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # use std::sync::{Arc, Mutex};
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/tree.json", json!({
+        "$dynamicAnchor": "node",
+        "properties": {"children": {"items": {"$dynamicRef": "#node"}}}
+    }))?;
+    compiler.add_resource("http://a.com/strict.json", json!({
+        "$dynamicAnchor": "node",
+        "$ref": "tree.json",
+        "unevaluatedProperties": false
+    }))?;
+    let sch = compiler.compile("http://a.com/strict.json", &mut schemas)?;
+
+    let resolved = Arc::new(Mutex::new(vec![]));
+    let mut options = ValidationOptions::new();
+    let sink = resolved.clone();
+    options.set_dynamic_ref_tracer(move |t: &DynamicRefTrace| {
+        sink.lock().unwrap().push((t.initial.to_owned(), t.resolved.to_owned()));
+    });
+    let instance = json!({"children": [{}]});
+    assert!(schemas.validate_with(&instance, sch, &options).is_ok());
+    assert_eq!(
+        resolved.lock().unwrap()[0],
+        ("http://a.com/tree.json#".to_owned(), "http://a.com/strict.json#".to_owned())
+    );
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_dynamic_ref_tracer<F>(&mut self, tracer: F)
+    where
+        F: Fn(&DynamicRefTrace) + Send + Sync + 'static,
+    {
+        self.dynamic_ref_tracer = Some(Box::new(tracer));
+    }
+
+    /**
+    Calls `tracer` each time a schema in [`FormatMode::Annotation`]
+    evaluates `format` of a value, with whether the value is valid
+    for the format.
+
+    In draft/2019-09 and later, `format` is only an annotation by
+    default. Use this to collect format results, without failing
+    validation. Formats with no implementation are not reported.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # use std::sync::{Arc, Mutex};
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({"items": {"format": "ipv4"}}))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+    let invalid = Arc::new(Mutex::new(vec![]));
+    let mut options = ValidationOptions::new();
+    let sink = invalid.clone();
+    options.set_format_annotation_tracer(move |a: &FormatAnnotation| {
+        if !a.valid {
+            sink.lock().unwrap().push(a.instance_location.to_string());
+        }
+    });
+    let instance = json!(["1.2.3.4", "1.2.3"]);
+    assert!(schemas.validate_with(&instance, sch, &options).is_ok());
+    assert_eq!(*invalid.lock().unwrap(), ["/1"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_format_annotation_tracer<F>(&mut self, tracer: F)
+    where
+        F: Fn(&FormatAnnotation) + Send + Sync + 'static,
+    {
+        self.format_annotation_tracer = Some(Box::new(tracer));
+    }
+
+    /**
+    Calls `hook` around evaluation of each keyword.
+    see [`KeywordHook`]
+    */
+    #[cfg(feature = "hooks")]
+    pub fn set_keyword_hook(&mut self, hook: Arc<dyn KeywordHook>) {
+        self.keyword_hook = Some(hook);
+    }
+
+    /**
+    Limits the size in bytes of decoded content, checked by
+    `contentEncoding` and `contentMediaType`.
+
+    Content exceeding the limit is reported as [`ErrorKind::ContentLimit`],
+    without deserializing it.
+
+    By default, there is no limit.
+    */
+    pub fn set_max_content_size(&mut self, max: usize) {
+        self.max_content_size = Some(max);
+    }
+
+    /**
+    Limits the nesting depth of JSON documents deserialized by
+    `contentMediaType`.
+
+    Content exceeding the limit is reported as [`ErrorKind::ContentLimit`],
+    without validating it against `contentSchema`. `application/json`
+    content is measured before it is deserialized. Content of other
+    media types is measured after deserializing, so their
+    [`MediaType::func`] must guard against deep nesting itself.
+
+    By default, there is no limit.
+    */
+    pub fn set_max_content_depth(&mut self, max: usize) {
+        self.max_content_depth = Some(max);
+    }
+
+    /**
+    Limits the nesting depth of the instance. Scalar instance has depth 1,
+    and each enclosing array or object adds 1.
+
+    Instance exceeding the limit is reported as [`ErrorKind::InstanceLimit`],
+    without validating it against the schema. The depth is measured without
+    recursion, so deeply nested instances cannot exhaust the stack.
+    Alternatively enable feature `stacker`, which grows the stack on heap
+    as needed during validation.
+
+    Note that the limit is checked on the instance, after it is parsed.
+    [`Schemas::validate_str`] and the like parse using `serde_json`,
+    which itself rejects json text nested deeper than 128.
+
+    By default, there is no limit.
+    */
+    pub fn set_max_depth(&mut self, max: usize) {
+        self.max_depth = Some(max);
+    }
+
+    /**
+    Limits the number of values in the instance, counting the instance
+    itself, and every array item and object property value within it.
+
+    Instance exceeding the limit is reported as [`ErrorKind::InstanceLimit`],
+    without validating it against the schema.
+
+    By default, there is no limit.
+    */
+    pub fn set_max_nodes(&mut self, max: usize) {
+        self.max_nodes = Some(max);
+    }
+
+    /**
+    Sets the unit in which `minLength` and `maxLength` measure strings.
+
+    By default, unicode scalar values are counted as required
+    by json-schema specification.
+    */
+    pub fn set_string_length(&mut self, unit: StringLength) {
+        self.string_length = unit;
+    }
+
+    /**
+    Caches the result of validating an array or object with a subschema,
+    so that repeated identical values, like the items of a large array
+    of similar records, are validated only once per validation.
+
+    Only subschemas from which no `$dynamicRef` or `$recursiveRef` is
+    reachable are cached, since their result depends on dynamic scope.
+    Nothing is cached while tracing, reporting warnings, or collecting
+    evaluated locations for `unevaluatedProperties` and `unevaluatedItems`. The cost of hashing
+    values outweighs the gain, unless instance has many repeated values.
+
+    Hits and misses are reported with [`Metrics::memoized`].
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "items": {"properties": {"tags": {"maxItems": 2}}}
+    }))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let mut options = ValidationOptions::new();
+    options.enable_memoization();
+    let item = json!({"tags": ["a", "b"]});
+    let instance = json!([item, item, item]);
+    assert!(schemas.validate_with(&instance, sch, &options).is_ok());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn enable_memoization(&mut self) {
+        self.memoize = true;
+    }
+
+    /**
+    Reports errors of `keyword` with given `severity`, overriding
+    `x-boon` hints of schemas. see [`Compiler::enable_schema_hints`]
+
+    Errors with [`Severity::Warning`] do not fail validation, and are
+    reported by [`Schemas::validate_with_warnings`]. Only errors reported
+    by the keyword itself are affected, not those of its subschemas.
+
+    Setting severity of following keywords, also enables checking them:
+    - `deprecated`: value validated by schema with `"deprecated": true`
+      is reported as [`ErrorKind::Deprecated`].
+    - `format`: formats, that are only annotations are checked too.
+      see [`FormatMode::Annotation`]
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "properties": {
+            "ip": {"format": "ipv4"},
+            "host": {"type": "string", "deprecated": true}
+        },
+        "maxProperties": 1
+    }))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let mut options = ValidationOptions::new();
+    options.set_severity("maxProperties", Severity::Warning);
+    options.set_severity("deprecated", Severity::Warning);
+    options.set_severity("format", Severity::Warning);
+    let instance = json!({"ip": "1.2.3", "host": "localhost"});
+    let warnings = schemas.validate_with_warnings(&instance, sch, &options).unwrap();
+    assert_eq!(warnings.len(), 3);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_severity(&mut self, keyword: &str, severity: Severity) {
+        self.severities.insert(keyword.to_owned(), severity);
+    }
+
+    /**
+    Removes instance values from errors and warnings, so that they can
+    be logged when validating payloads with sensitive data.
+
+    Errors whose kind holds an instance value, like [`ErrorKind::Format`],
+    [`ErrorKind::Pattern`] and [`ErrorKind::Minimum`], are reported as
+    [`ErrorKind::Redacted`] wrapping the original kind with the value
+    cleared, and messages show only type and size of the value. Error
+    of format is replaced with `<redacted>`, as it may quote the value. Property
+    names, as in instance locations and [`ErrorKind::AdditionalProperties`],
+    are kept.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "properties": {"ssn": {"pattern": "^[0-9]{3}-[0-9]{2}-[0-9]{4}$"}}
+    }))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let mut options = ValidationOptions::new();
+    options.enable_redaction();
+    let instance = json!({"ssn": "123 45 6789"});
+    let err = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    assert_eq!(
+        err.causes[0].kind.to_string(),
+        "<string, 11 chars> does not match pattern '^[0-9]{3}-[0-9]{2}-[0-9]{4}$'"
+    );
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn enable_redaction(&mut self) {
+        self.redact = true;
+    }
+
+    fn has_keyword_hook(&self) -> bool {
+        #[cfg(feature = "hooks")]
+        return self.keyword_hook.is_some();
+        #[cfg(not(feature = "hooks"))]
+        false
+    }
+
+    fn has_warnings(&self) -> bool {
+        self.severities.values().any(|s| *s == Severity::Warning)
+    }
+
+    // returns format to use in place of `format`
+    fn format<'a>(&'a self, format: &'a DynFormat) -> Option<&'a DynFormat> {
+        match self.formats.get(format.name) {
+            Some(f) => f.as_ref(),
+            None => Some(format),
+        }
+    }
+}
+
+/// Severity of errors of a keyword.
+///
+/// see [`ValidationOptions::set_severity`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Fails validation.
+    Error,
+    /// Does not fail validation, but is reported as warning.
+    Warning,
+}
+
+/// Unit in which `minLength` and `maxLength` measure strings.
+///
+/// see [`ValidationOptions::set_string_length`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StringLength {
+    /// Unicode scalar values, as required by json-schema specification.
+    #[default]
+    Chars,
+    /// UTF-16 code units, same as javascript `String.length`.
+    Utf16,
+    /// UTF-8 bytes.
+    Bytes,
+}
+
+impl StringLength {
+    fn of(self, s: &str) -> usize {
+        match self {
+            Self::Chars => s.chars().count(),
+            Self::Utf16 => s.encode_utf16().count(),
+            Self::Bytes => s.len(),
+        }
+    }
+}
+
+/// Evaluation of a subschema, reported to the tracer.
+///
+/// see [`ValidationOptions::set_tracer`]
+#[derive(Debug)]
+pub struct Trace<'a, 's, 'v> {
+    /// The absolute, dereferenced schema location.
+    pub schema_url: &'s str,
+    /// The location of the JSON value within the instance being validated
+    pub instance_location: &'a InstanceLocation<'v>,
+    /// Number of keywords in the subschema, applicable to the instance
+    pub keywords: usize,
+    /// Time taken, including the nested subschemas
+    pub elapsed: Duration,
+    /// Whether the instance is valid against the subschema
+    pub valid: bool,
+}
+
+/// Resolution of `$dynamicRef` or `$recursiveRef`, reported to the tracer.
+///
+/// see [`ValidationOptions::set_dynamic_ref_tracer`]
+#[derive(Debug)]
+pub struct DynamicRefTrace<'a, 's, 'v> {
+    /// Either `$dynamicRef` or `$recursiveRef`
+    pub keyword: &'static str,
+    /// The absolute, dereferenced location of schema containing the keyword.
+    pub schema_url: &'s str,
+    /// The location of the JSON value within the instance being validated
+    pub instance_location: &'a InstanceLocation<'v>,
+    /// Schema the keyword refers to statically, like `$ref`.
+    pub initial: &'s str,
+    /// Schema resources in dynamic scope, outermost first.
+    pub scope: Vec<&'s str>,
+    /// Schema actually used for validation.
+    pub resolved: &'s str,
+}
+
+/// Result of `format` in [`FormatMode::Annotation`], reported to the tracer.
+///
+/// see [`ValidationOptions::set_format_annotation_tracer`]
+#[derive(Debug)]
+pub struct FormatAnnotation<'a, 's, 'v> {
+    /// The absolute, dereferenced location of schema containing `format`.
+    pub schema_url: &'s str,
+    /// The location of the JSON value within the instance being validated
+    pub instance_location: &'a InstanceLocation<'v>,
+    /// Name of the format
+    pub format: &'static str,
+    /// Whether the value is valid for the format
+    pub valid: bool,
+}
+
+#[derive(Default, Clone)]
 struct Schema {
     draft_version: usize,
+    vocabs: Vec<&'static str>,
     idx: SchemaIndex,
     loc: String,
     resource: SchemaIndex,
     dynamic_anchors: HashMap<String, SchemaIndex>,
+    comment: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    examples: Vec<Value>,
+    hash: u64, // stable hash of schema json
     all_props_evaluated: bool,
     all_items_evaluated: bool,
     num_items_evaluated: usize,
+    name_only: bool, // keywords can be checked on property name, see compiler::NAME_KEYWORDS
 
     // type agnostic --
     boolean: Option<bool>, // boolean schema
     ref_: Option<SchemaIndex>,
+    lazy_ref: Option<String>, // location of `$ref` target, yet to be compiled
     recursive_ref: Option<SchemaIndex>,
     recursive_anchor: bool,
     dynamic_ref: Option<DynamicRef>,
     dynamic_anchor: Option<String>,
     types: Types,
+    strict_integer: bool, // `1.0` is not integer
     enum_: Option<Enum>,
     constant: Option<Value>,
     not: Option<SchemaIndex>,
     all_of: Vec<SchemaIndex>,
     any_of: Vec<SchemaIndex>,
     one_of: Vec<SchemaIndex>,
+    discriminator: Option<Discriminator>,
+    error_message: Option<ErrorMessage>,
+    warn_keywords: Vec<String>, // see Compiler::enable_schema_hints
+    deprecated: bool,
     if_: Option<SchemaIndex>,
     then: Option<SchemaIndex>,
     else_: Option<SchemaIndex>,
-    format: Option<Format>,
+    format: Option<DynFormat>,
+    format_mode: FormatMode,
+    format_annotation: Option<DynFormat>, // checked only if traced
 
     // object --
     min_properties: Option<usize>,
     max_properties: Option<usize>,
     required: Vec<String>,
     properties: AHashMap<String, SchemaIndex>,
-    pattern_properties: Vec<(Regex, SchemaIndex)>,
+    pattern_properties: Vec<(Arc<Regex>, SchemaIndex)>,
     property_names: Option<SchemaIndex>,
     additional_properties: Option<Additional>,
     dependent_required: Vec<(String, Vec<String>)>,
     dependent_schemas: Vec<(String, SchemaIndex)>,
     dependencies: Vec<(String, Dependency)>,
     unevaluated_properties: Option<SchemaIndex>,
+    preserve_unknown_fields: bool, // see Compiler::enable_kubernetes_extensions
+    embedded_resource: bool,
 
     // array --
     min_items: Option<usize>,
@@ -254,9 +1422,9 @@ struct Schema {
     // string --
     min_length: Option<usize>,
     max_length: Option<usize>,
-    pattern: Option<Regex>,
-    content_encoding: Option<Decoder>,
-    content_media_type: Option<MediaType>,
+    pattern: Option<Arc<Regex>>,
+    content_encoding: Option<DynDecoder>,
+    content_media_type: Option<DynMediaType>,
     content_schema: Option<SchemaIndex>,
 
     // number --
@@ -267,38 +1435,224 @@ struct Schema {
     multiple_of: Option<Number>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Enum {
     /// types that occur in enum
     types: Types,
     /// values in enum
     values: Vec<Value>,
+    /// indexes of values by hash, for large enums of primitive values
+    index: Option<EnumIndex>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+struct EnumIndex {
+    state: ahash::RandomState,
+    buckets: AHashMap<u64, Vec<usize>>,
+}
+
+impl Enum {
+    // enums smaller than this are scanned linearly
+    const MIN_INDEX_LEN: usize = 16;
+
+    fn new(values: Vec<Value>) -> Self {
+        let types = values.iter().map(Type::of).collect();
+        let primitive = values
+            .iter()
+            .all(|v| !matches!(v, Value::Array(_) | Value::Object(_)));
+        let index = (primitive && values.len() >= Self::MIN_INDEX_LEN).then(|| {
+            let state = ahash::RandomState::new();
+            let mut buckets = AHashMap::<u64, Vec<usize>>::new();
+            for (i, v) in values.iter().enumerate() {
+                buckets
+                    .entry(state.hash_one(HashedValue(v)))
+                    .or_default()
+                    .push(i);
+            }
+            EnumIndex { state, buckets }
+        });
+        Self {
+            types,
+            values,
+            index,
+        }
+    }
+
+    fn contains(&self, v: &Value) -> bool {
+        if !self.types.contains(Type::of(v)) {
+            return false;
+        }
+        match &self.index {
+            Some(EnumIndex { state, buckets }) => buckets
+                .get(&state.hash_one(HashedValue(v)))
+                .is_some_and(|list| list.iter().any(|i| equals(&self.values[*i], v))),
+            None => self.values.iter().any(|e| equals(e, v)),
+        }
+    }
+
+    // same as `contains(&Value::String(s))`, without allocation
+    fn contains_str(&self, s: &str) -> bool {
+        if !self.types.contains(Type::String) {
+            return false;
+        }
+        match &self.index {
+            // HashedValue hashes string value same as str
+            Some(EnumIndex { state, buckets }) => buckets
+                .get(&state.hash_one(s))
+                .is_some_and(|list| list.iter().any(|i| self.values[*i].as_str() == Some(s))),
+            None => self.values.iter().any(|e| e.as_str() == Some(s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Discriminator {
+    /// property holding the tag
+    prop: String,
+    /// explicit mapping from tag to schema
+    mapping: HashMap<String, SchemaIndex>,
+    /// whether `anyOf` is dispatched too
+    any_of: bool,
+}
+
+// `errorMessage` keyword
+#[derive(Debug, Clone)]
+enum ErrorMessage {
+    // replaces all errors of the schema
+    All(String),
+    // keyword => message
+    Keywords(HashMap<String, String>),
+}
+
+#[derive(Debug, Clone)]
 enum Items {
     SchemaRef(SchemaIndex),
     SchemaRefs(Vec<SchemaIndex>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Additional {
     Bool(bool),
     SchemaRef(SchemaIndex),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Dependency {
     Props(Vec<String>),
     SchemaRef(SchemaIndex),
 }
 
+#[derive(Clone)]
 struct DynamicRef {
     sch: SchemaIndex,
     anchor: Option<String>,
 }
 
 impl Schema {
+    // indexes of schemas this schema refers to
+    // error for instance exceeding limits in ValidationOptions
+    fn limit_error<'s, 'v>(&'s self, kind: ErrorKind<'s, 'v>) -> ValidationError<'s, 'v> {
+        ValidationError {
+            schema_url: &self.loc,
+            instance_location: InstanceLocation::default(),
+            kind: ErrorKind::Schema { url: &self.loc },
+            causes: vec![ValidationError {
+                schema_url: &self.loc,
+                instance_location: InstanceLocation::default(),
+                kind,
+                causes: vec![],
+            }],
+        }
+    }
+
+    fn subschemas(&self) -> Vec<SchemaIndex> {
+        let subschemas = self.keyed_subschemas().into_iter();
+        subschemas.map(|(_, sch)| sch).collect()
+    }
+
+    // subschemas, excluding resource and dynamic anchors, which are
+    // not applied to instance
+    fn applied_subschemas(&self) -> Vec<SchemaIndex> {
+        let subschemas = self.keyed_subschemas().into_iter();
+        subschemas
+            .filter(|(kw, _)| kw != "$resource" && !kw.starts_with("$dynamicAnchor/"))
+            .map(|(_, sch)| sch)
+            .collect()
+    }
+
+    // subschemas, each keyed by its keyword path relative to this
+    // schema, which does not depend on url or HashMap order.
+    fn keyed_subschemas(&self) -> Vec<(String, SchemaIndex)> {
+        use pointer::escape;
+        let kw = |kw: &str, sch| (kw.to_owned(), sch);
+        let key = |kw: &str, k: &str, sch| (format!("{kw}/{}", escape(k)), sch);
+        let index = |kw: &str, list: &[SchemaIndex]| {
+            let list = list.iter().enumerate();
+            list.map(|(i, sch)| (format!("{kw}/{i}"), *sch))
+                .collect::<Vec<_>>()
+        };
+        let mut v = vec![kw("$resource", self.resource)];
+        v.extend(
+            self.dynamic_anchors
+                .iter()
+                .map(|(name, sch)| key("$dynamicAnchor", name, *sch)),
+        );
+        v.extend(self.ref_.map(|sch| kw("$ref", sch)));
+        v.extend(self.recursive_ref.map(|sch| kw("$recursiveRef", sch)));
+        v.extend(
+            self.dynamic_ref
+                .as_ref()
+                .map(|dref| kw("$dynamicRef", dref.sch)),
+        );
+        v.extend(self.not.map(|sch| kw("not", sch)));
+        v.extend(index("allOf", &self.all_of));
+        v.extend(index("anyOf", &self.any_of));
+        v.extend(index("oneOf", &self.one_of));
+        if let Some(d) = &self.discriminator {
+            let mapping = d.mapping.iter();
+            v.extend(mapping.map(|(k, sch)| key("discriminator/mapping", k, *sch)));
+        }
+        v.extend(self.if_.map(|sch| kw("if", sch)));
+        v.extend(self.then.map(|sch| kw("then", sch)));
+        v.extend(self.else_.map(|sch| kw("else", sch)));
+        let props = self.properties.iter();
+        v.extend(props.map(|(k, sch)| key("properties", k, *sch)));
+        let patterns = self.pattern_properties.iter();
+        v.extend(patterns.map(|(re, sch)| key("patternProperties", re.as_str(), *sch)));
+        v.extend(self.property_names.map(|sch| kw("propertyNames", sch)));
+        if let Some(Additional::SchemaRef(sch)) = &self.additional_properties {
+            v.push(kw("additionalProperties", *sch));
+        }
+        let deps = self.dependent_schemas.iter();
+        v.extend(deps.map(|(k, sch)| key("dependentSchemas", k, *sch)));
+        for (k, dep) in &self.dependencies {
+            if let Dependency::SchemaRef(sch) = dep {
+                v.push(key("dependencies", k, *sch));
+            }
+        }
+        v.extend(
+            self.unevaluated_properties
+                .map(|sch| kw("unevaluatedProperties", sch)),
+        );
+        v.extend(self.contains.map(|sch| kw("contains", sch)));
+        match &self.items {
+            Some(Items::SchemaRef(sch)) => v.push(kw("items", *sch)),
+            Some(Items::SchemaRefs(list)) => v.extend(index("items", list)),
+            None => {}
+        }
+        if let Some(Additional::SchemaRef(sch)) = &self.additional_items {
+            v.push(kw("additionalItems", *sch));
+        }
+        v.extend(index("prefixItems", &self.prefix_items));
+        v.extend(self.items2020.map(|sch| kw("items", sch)));
+        v.extend(
+            self.unevaluated_items
+                .map(|sch| kw("unevaluatedItems", sch)),
+        );
+        v.extend(self.content_schema.map(|sch| kw("contentSchema", sch)));
+        v
+    }
+
     fn new(loc: String) -> Self {
         Self {
             loc,
@@ -364,7 +1718,7 @@ impl Display for Type {
 }
 
 /// Set of [`Type`]s
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Types(u8);
 
 impl Types {
@@ -407,7 +1761,7 @@ impl FromIterator<Type> for Types {
 }
 
 /// Error type for validation failures.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ValidationError<'s, 'v> {
     /// The absolute, dereferenced schema location.
     pub schema_url: &'s str,
@@ -419,10 +1773,52 @@ pub struct ValidationError<'s, 'v> {
     pub causes: Vec<ValidationError<'s, 'v>>,
 }
 
-impl Error for ValidationError<'_, '_> {}
+impl StdError for ValidationError<'_, '_> {}
 
-/// A list specifying general categories of validation errors.
+/// Error type for [`Schemas::validate_str`] and [`Schemas::validate_slice`].
 #[derive(Debug)]
+pub enum InstanceError<'s> {
+    /// The instance is not valid json.
+    Parse(serde_json::Error),
+    /// An object in the instance has duplicate `key`, at given position.
+    /// Reported only by strict parsing. see [`parse_strict`].
+    DuplicateKey {
+        key: String,
+        line: usize,
+        column: usize,
+    },
+    /// The instance is not valid against the schema.
+    Invalid(ValidationError<'s, 'static>),
+}
+
+impl Display for InstanceError<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "error parsing instance: {e}"),
+            Self::DuplicateKey { key, line, column } => write!(
+                f,
+                "duplicate key {} at line {line} column {column}",
+                quote(key)
+            ),
+            Self::Invalid(e) => e.fmt(f),
+        }
+    }
+}
+
+impl StdError for InstanceError<'_> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            Self::DuplicateKey { .. } | Self::Invalid(_) => None,
+        }
+    }
+}
+
+/// A list specifying general categories of validation errors.
+///
+/// New variants may be added in minor releases.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum ErrorKind<'s, 'v> {
     Group,
     Schema {
@@ -441,7 +1837,15 @@ pub enum ErrorKind<'s, 'v> {
         kw_loc1: String,
         kw_loc2: String,
     },
+    /// `$ref` target `url` is not compiled yet.
+    /// see [`Compiler::enable_lazy_compilation`]
+    Uncompiled {
+        url: &'s str,
+    },
     FalseSchema,
+    /// value is validated by schema marked `deprecated`.
+    /// see [`ValidationOptions::set_severity`]
+    Deprecated,
     Type {
         got: Type,
         want: Types,
@@ -455,7 +1859,7 @@ pub enum ErrorKind<'s, 'v> {
     Format {
         got: Cow<'v, Value>,
         want: &'static str,
-        err: Box<dyn Error>,
+        err: Arc<dyn StdError>,
     },
     MinProperties {
         got: usize,
@@ -520,12 +1924,31 @@ pub enum ErrorKind<'s, 'v> {
     },
     ContentEncoding {
         want: &'static str,
-        err: Box<dyn Error>,
+        err: Arc<dyn StdError>,
     },
     ContentMediaType {
         got: Vec<u8>,
         want: &'static str,
-        err: Box<dyn Error>,
+        err: Arc<dyn StdError>,
+    },
+    /// decoded content exceeds `limit`, which is either `size` or `depth`.
+    ///
+    /// see [`ValidationOptions::set_max_content_size`] and
+    /// [`ValidationOptions::set_max_content_depth`]
+    ContentLimit {
+        keyword: &'static str,
+        limit: &'static str,
+        got: usize,
+        want: usize,
+    },
+    /// instance exceeds `limit`, which is either `depth` or `nodes`.
+    ///
+    /// see [`ValidationOptions::set_max_depth`] and
+    /// [`ValidationOptions::set_max_nodes`]
+    InstanceLimit {
+        limit: &'static str,
+        got: usize,
+        want: usize,
     },
     Minimum {
         got: Cow<'v, Number>,
@@ -547,7 +1970,13 @@ pub enum ErrorKind<'s, 'v> {
         got: Cow<'v, Number>,
         want: &'s Number,
     },
-    Not,
+    /// value is valid against `not` subschema at `url`.
+    Not {
+        url: &'s str,
+        /// constraints of the subschema, which the value satisfied.
+        /// for example `type string`.
+        matched: Vec<String>,
+    },
     /// none of the subschemas matched
     AllOf,
     /// none of the subschemas matched.
@@ -555,15 +1984,360 @@ pub enum ErrorKind<'s, 'v> {
     /// - `None`: none of the schemas matched.
     /// - Some(i, j): subschemas at i, j matched
     OneOf(Option<(usize, usize)>),
+    /// `kind` of error, reported with `message` from `errorMessage`
+    /// keyword of the schema. `kind` is [`ErrorKind::Group`], if
+    /// `message` replaces all errors of the schema.
+    ///
+    /// see [`Compiler::enable_custom_error_messages`]
+    Custom {
+        message: &'s str,
+        kind: Box<ErrorKind<'s, 'v>>,
+    },
+    /// `kind` of error, whose instance value is removed. `got` describes
+    /// the removed value with its type and size, like `<string, 12 chars>`.
+    ///
+    /// see [`ValidationOptions::enable_redaction`]
+    Redacted {
+        got: String,
+        kind: Box<ErrorKind<'s, 'v>>,
+    },
+}
+
+/// Compares error kinds by value. Inner errors of [`ErrorKind::Format`],
+/// [`ErrorKind::ContentEncoding`] and [`ErrorKind::ContentMediaType`]
+/// are compared by their display strings.
+impl PartialEq for ErrorKind<'_, '_> {
+    fn eq(&self, other: &Self) -> bool {
+        use ErrorKind::*;
+        match (self, other) {
+            (Group, Group) => true,
+            (Schema { url: a_url }, Schema { url: b_url }) => a_url == b_url,
+            (ContentSchema, ContentSchema) => true,
+            (PropertyName { prop: a_prop }, PropertyName { prop: b_prop }) => a_prop == b_prop,
+            (
+                Reference {
+                    kw: a_kw,
+                    url: a_url,
+                },
+                Reference {
+                    kw: b_kw,
+                    url: b_url,
+                },
+            ) => a_kw == b_kw && a_url == b_url,
+            (
+                RefCycle {
+                    url: a_url,
+                    kw_loc1: a_kw_loc1,
+                    kw_loc2: a_kw_loc2,
+                },
+                RefCycle {
+                    url: b_url,
+                    kw_loc1: b_kw_loc1,
+                    kw_loc2: b_kw_loc2,
+                },
+            ) => a_url == b_url && a_kw_loc1 == b_kw_loc1 && a_kw_loc2 == b_kw_loc2,
+            (Uncompiled { url: a_url }, Uncompiled { url: b_url }) => a_url == b_url,
+            (FalseSchema, FalseSchema) => true,
+            (Deprecated, Deprecated) => true,
+            (
+                Type {
+                    got: a_got,
+                    want: a_want,
+                },
+                Type {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (Enum { want: a_want }, Enum { want: b_want }) => a_want == b_want,
+            (Const { want: a_want }, Const { want: b_want }) => a_want == b_want,
+            (
+                Format {
+                    got: a_got,
+                    want: a_want,
+                    err: a_err,
+                },
+                Format {
+                    got: b_got,
+                    want: b_want,
+                    err: b_err,
+                },
+            ) => a_got == b_got && a_want == b_want && a_err.to_string() == b_err.to_string(),
+            (
+                MinProperties {
+                    got: a_got,
+                    want: a_want,
+                },
+                MinProperties {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                MaxProperties {
+                    got: a_got,
+                    want: a_want,
+                },
+                MaxProperties {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (AdditionalProperties { got: a_got }, AdditionalProperties { got: b_got }) => {
+                a_got == b_got
+            }
+            (Required { want: a_want }, Required { want: b_want }) => a_want == b_want,
+            (
+                Dependency {
+                    prop: a_prop,
+                    missing: a_missing,
+                },
+                Dependency {
+                    prop: b_prop,
+                    missing: b_missing,
+                },
+            ) => a_prop == b_prop && a_missing == b_missing,
+            (
+                DependentRequired {
+                    prop: a_prop,
+                    missing: a_missing,
+                },
+                DependentRequired {
+                    prop: b_prop,
+                    missing: b_missing,
+                },
+            ) => a_prop == b_prop && a_missing == b_missing,
+            (
+                MinItems {
+                    got: a_got,
+                    want: a_want,
+                },
+                MinItems {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                MaxItems {
+                    got: a_got,
+                    want: a_want,
+                },
+                MaxItems {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (Contains, Contains) => true,
+            (
+                MinContains {
+                    got: a_got,
+                    want: a_want,
+                },
+                MinContains {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                MaxContains {
+                    got: a_got,
+                    want: a_want,
+                },
+                MaxContains {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (UniqueItems { got: a_got }, UniqueItems { got: b_got }) => a_got == b_got,
+            (AdditionalItems { got: a_got }, AdditionalItems { got: b_got }) => a_got == b_got,
+            (
+                MinLength {
+                    got: a_got,
+                    want: a_want,
+                },
+                MinLength {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                MaxLength {
+                    got: a_got,
+                    want: a_want,
+                },
+                MaxLength {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                Pattern {
+                    got: a_got,
+                    want: a_want,
+                },
+                Pattern {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                ContentEncoding {
+                    want: a_want,
+                    err: a_err,
+                },
+                ContentEncoding {
+                    want: b_want,
+                    err: b_err,
+                },
+            ) => a_want == b_want && a_err.to_string() == b_err.to_string(),
+            (
+                ContentMediaType {
+                    got: a_got,
+                    want: a_want,
+                    err: a_err,
+                },
+                ContentMediaType {
+                    got: b_got,
+                    want: b_want,
+                    err: b_err,
+                },
+            ) => a_got == b_got && a_want == b_want && a_err.to_string() == b_err.to_string(),
+            (
+                ContentLimit {
+                    keyword: a_keyword,
+                    limit: a_limit,
+                    got: a_got,
+                    want: a_want,
+                },
+                ContentLimit {
+                    keyword: b_keyword,
+                    limit: b_limit,
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_keyword == b_keyword && a_limit == b_limit && a_got == b_got && a_want == b_want,
+            (
+                InstanceLimit {
+                    limit: a_limit,
+                    got: a_got,
+                    want: a_want,
+                },
+                InstanceLimit {
+                    limit: b_limit,
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_limit == b_limit && a_got == b_got && a_want == b_want,
+            (
+                Minimum {
+                    got: a_got,
+                    want: a_want,
+                },
+                Minimum {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                Maximum {
+                    got: a_got,
+                    want: a_want,
+                },
+                Maximum {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                ExclusiveMinimum {
+                    got: a_got,
+                    want: a_want,
+                },
+                ExclusiveMinimum {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                ExclusiveMaximum {
+                    got: a_got,
+                    want: a_want,
+                },
+                ExclusiveMaximum {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                MultipleOf {
+                    got: a_got,
+                    want: a_want,
+                },
+                MultipleOf {
+                    got: b_got,
+                    want: b_want,
+                },
+            ) => a_got == b_got && a_want == b_want,
+            (
+                Not {
+                    url: a_url,
+                    matched: a_matched,
+                },
+                Not {
+                    url: b_url,
+                    matched: b_matched,
+                },
+            ) => a_url == b_url && a_matched == b_matched,
+            (AllOf, AllOf) => true,
+            (AnyOf, AnyOf) => true,
+            (OneOf(a), OneOf(b)) => a == b,
+            (
+                Custom {
+                    message: a_message,
+                    kind: a_kind,
+                },
+                Custom {
+                    message: b_message,
+                    kind: b_kind,
+                },
+            ) => a_message == b_message && a_kind == b_kind,
+            (
+                Redacted {
+                    got: a_got,
+                    kind: a_kind,
+                },
+                Redacted {
+                    got: b_got,
+                    kind: b_kind,
+                },
+            ) => a_got == b_got && a_kind == b_kind,
+            _ => false,
+        }
+    }
+}
+
+impl ErrorKind<'_, '_> {
+    /// Returns true if `self` and `other` are same variant,
+    /// ignoring their fields.
+    pub fn matches_kind(&self, other: &ErrorKind) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
 }
 
+/// Strings in messages, including enum values and format errors,
+/// are truncated to 100 chars, with an ellipsis.
+/// Use precision to change it, for example `{:.20}`. The full values
+/// are available in fields of the variants.
 impl Display for ErrorKind<'_, '_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let max = f.precision().unwrap_or(MAX_VALUE_LENGTH);
         match self {
             Self::Group => write!(f, "validation failed"),
             Self::Schema { url } => write!(f, "validation failed with {url}"),
             Self::ContentSchema => write!(f, "contentSchema failed"),
-            Self::PropertyName { prop } => write!(f, "invalid property {}", quote(prop)),
+            Self::PropertyName { prop } => {
+                write!(f, "invalid property {}", quote(&truncate(prop, max)))
+            }
             Self::Reference { .. } => {
                 write!(f, "validation failed")
             }
@@ -577,7 +2351,9 @@ impl Display for ErrorKind<'_, '_> {
                 quote(&kw_loc1.to_string()),
                 quote(&kw_loc2.to_string())
             ),
+            Self::Uncompiled { url } => write!(f, "reference to {url} is not compiled yet"),
             Self::FalseSchema => write!(f, "false schema"),
+            Self::Deprecated => write!(f, "value is deprecated"),
             Self::Type { got, want } => {
                 // todo: why join not working for Type struct ??
                 let want = join_iter(want.iter(), " or ");
@@ -587,9 +2363,9 @@ impl Display for ErrorKind<'_, '_> {
                 if want.iter().all(Type::primitive) {
                     if want.len() == 1 {
                         write!(f, "value must be ")?;
-                        display(f, &want[0])
+                        display(f, &want[0], max)
                     } else {
-                        let want = join_iter(want.iter().map(string), ", ");
+                        let want = join_iter(want.iter().map(|v| string(v, max)), ", ");
                         write!(f, "value must be one of {want}")
                     }
                 } else {
@@ -599,14 +2375,18 @@ impl Display for ErrorKind<'_, '_> {
             Self::Const { want } => {
                 if Type::primitive(want) {
                     write!(f, "value must be ")?;
-                    display(f, want)
+                    display(f, want, max)
                 } else {
                     write!(f, "const failed")
                 }
             }
             Self::Format { got, want, err } => {
-                display(f, got)?;
-                write!(f, " is not valid {want}: {err}")
+                display(f, got, max)?;
+                write!(
+                    f,
+                    " is not valid {want}: {}",
+                    truncate(&err.to_string(), max)
+                )
             }
             Self::MinProperties { got, want } => write!(
                 f,
@@ -620,7 +2400,7 @@ impl Display for ErrorKind<'_, '_> {
                 write!(
                     f,
                     "additionalProperties {} not allowed",
-                    join_iter(got.iter().map(quote), ", ")
+                    join_iter(got.iter().map(|p| quote(&truncate(p, max))), ", ")
                 )
             }
             Self::Required { want } => write!(
@@ -677,7 +2457,8 @@ impl Display for ErrorKind<'_, '_> {
             Self::MinLength { got, want } => write!(f, "length must be >={want}, but got {got}"),
             Self::MaxLength { got, want } => write!(f, "length must be <={want}, but got {got}"),
             Self::Pattern { got, want } => {
-                write!(f, "{} does not match pattern {}", quote(got), quote(want))
+                let got = quote(&truncate(got, max));
+                write!(f, "{got} does not match pattern {}", quote(want))
             }
             Self::ContentEncoding { want, err } => {
                 write!(f, "value is not {} encoded: {err}", quote(want))
@@ -685,31 +2466,70 @@ impl Display for ErrorKind<'_, '_> {
             Self::ContentMediaType { want, err, .. } => {
                 write!(f, "value is not of mediatype {}: {err}", quote(want))
             }
+            Self::ContentLimit {
+                limit, got, want, ..
+            } => write!(f, "decoded content {limit} must be <={want}, but got {got}"),
+            Self::InstanceLimit { limit, got, want } => {
+                write!(f, "instance {limit} must be <={want}, but got {got}")
+            }
             Self::Minimum { got, want } => write!(f, "must be >={want}, but got {got}"),
             Self::Maximum { got, want } => write!(f, "must be <={want}, but got {got}"),
             Self::ExclusiveMinimum { got, want } => write!(f, "must be > {want} but got {got}"),
             Self::ExclusiveMaximum { got, want } => write!(f, "must be < {want} but got {got}"),
             Self::MultipleOf { got, want } => write!(f, "{got} is not multipleOf {want}"),
-            Self::Not => write!(f, "not failed"),
+            Self::Not { url, matched } => {
+                write!(f, "value matched disallowed schema {url}")?;
+                if !matched.is_empty() {
+                    write!(f, " ({})", matched.join(", "))?;
+                }
+                Ok(())
+            }
             Self::AllOf => write!(f, "allOf failed",),
             Self::AnyOf => write!(f, "anyOf failed"),
             Self::OneOf(None) => write!(f, "oneOf failed, none matched"),
             Self::OneOf(Some((i, j))) => write!(f, "oneOf failed, subschemas {i}, {j} matched"),
+            Self::Custom { message, .. } => write!(f, "{message}"),
+            Self::Redacted { got, kind } => match kind.as_ref() {
+                Self::Format { want, err, .. } => {
+                    let err = truncate(&err.to_string(), max).into_owned();
+                    write!(f, "{got} is not valid {want}: {err}")
+                }
+                Self::Pattern { want, .. } => {
+                    write!(f, "{got} does not match pattern {}", quote(want))
+                }
+                Self::Minimum { want, .. } => write!(f, "must be >={want}, but got {got}"),
+                Self::Maximum { want, .. } => write!(f, "must be <={want}, but got {got}"),
+                Self::ExclusiveMinimum { want, .. } => write!(f, "must be > {want} but got {got}"),
+                Self::ExclusiveMaximum { want, .. } => write!(f, "must be < {want} but got {got}"),
+                Self::MultipleOf { want, .. } => write!(f, "{got} is not multipleOf {want}"),
+                kind => kind.fmt(f),
+            },
         }
     }
 }
 
-fn display(f: &mut std::fmt::Formatter, v: &Value) -> std::fmt::Result {
+// default maximum chars of strings in error messages
+const MAX_VALUE_LENGTH: usize = 100;
+
+// truncates `s` to `max` chars, with an ellipsis
+fn truncate(s: &str, max: usize) -> Cow<'_, str> {
+    match s.char_indices().nth(max) {
+        Some((i, _)) => Cow::Owned(format!("{}…", &s[..i])),
+        None => Cow::Borrowed(s),
+    }
+}
+
+fn display(f: &mut std::fmt::Formatter, v: &Value, max: usize) -> std::fmt::Result {
     match v {
-        Value::String(s) => write!(f, "{}", quote(s)),
+        Value::String(s) => write!(f, "{}", quote(&truncate(s, max))),
         Value::Array(_) | Value::Object(_) => write!(f, "value"),
         _ => write!(f, "{v}"),
     }
 }
 
-fn string(primitive: &Value) -> String {
+fn string(primitive: &Value, max: usize) -> String {
     if let Value::String(s) = primitive {
-        quote(s)
+        quote(&truncate(s, max))
     } else {
         format!("{primitive}")
     }
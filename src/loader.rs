@@ -1,22 +1,30 @@
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     collections::{HashMap, HashSet},
     error::Error,
+    rc::Rc,
+    sync::Arc,
 };
 
 #[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+#[cfg(all(feature = "fs", not(target_arch = "wasm32")))]
 use std::fs::File;
 
 use appendlist::AppendList;
+use base64::Engine;
 use once_cell::sync::Lazy;
+use percent_encoding::percent_decode_str;
 use serde_json::Value;
 use url::Url;
 
 use crate::{
-    compiler::CompileError,
+    compiler::{CompileError, Dialect},
     draft::{latest, Draft},
-    util::split,
-    UrlPtr,
+    jtd::to_json_schema,
+    util::{split, Fragment},
+    InstanceError, SchemaIndex, Schemas, UrlPtr,
 };
 
 /// A trait for loading json from given `url`
@@ -27,24 +35,93 @@ pub trait UrlLoader {
 
 // --
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(feature = "fs", not(target_arch = "wasm32")))]
 pub struct FileLoader;
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(feature = "fs", not(target_arch = "wasm32")))]
 impl UrlLoader for FileLoader {
     fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
-        let url = Url::parse(url)?;
-        let path = url.to_file_path().map_err(|_| "invalid file path")?;
-        let file = File::open(path)?;
-        Ok(serde_json::from_reader(file)?)
+        load_file(url, false)
+    }
+}
+
+#[cfg(all(feature = "fs", not(target_arch = "wasm32")))]
+fn load_file(url: &str, strict: bool) -> Result<Value, Box<dyn Error>> {
+    let url = Url::parse(url)?;
+    let path = url.to_file_path().map_err(|_| "invalid file path")?;
+    if strict {
+        return parse_json(&std::fs::read(path)?, true);
+    }
+    let file = File::open(path)?;
+    Ok(serde_json::from_reader(file)?)
+}
+
+fn parse_json(bytes: &[u8], strict: bool) -> Result<Value, Box<dyn Error>> {
+    if strict {
+        Ok(crate::parse_strict(bytes)?)
+    } else {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+fn is_duplicate_key(e: &(dyn Error + 'static)) -> bool {
+    matches!(
+        e.downcast_ref::<InstanceError>(),
+        Some(InstanceError::DuplicateKey { .. })
+    )
+}
+
+// loads url, parsing strictly if flag is true
+type LoadFn = fn(&str, bool) -> Result<Value, Box<dyn Error>>;
+
+// loader registered by DefaultUrlLoader, which parses strictly
+// once Compiler::deny_duplicate_keys is called.
+struct BuiltinLoader {
+    load: LoadFn,
+    strict: Rc<Cell<bool>>,
+}
+
+impl UrlLoader for BuiltinLoader {
+    fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+        (self.load)(url, self.strict.get())
     }
 }
 
 // --
 
+/**
+Loads urls using [`UrlLoader`]s registered by url scheme.
+
+Multiple loaders can be registered for a scheme, which are tried in
+order of registration until one succeeds. Urls with no loader
+registered for their scheme are loaded by default loaders, if any.
+
+```
+# use boon::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+// try local mirror first, then network
+let mut mirror = MemoryLoader::new();
+mirror.add("https://a.com/s.json", json!({"type": "string"}))?;
+let mut loader = SchemeUrlLoader::new();
+loader.register("https", Box::new(mirror));
+# struct HttpLoader;
+# impl UrlLoader for HttpLoader {
+#     fn load(&self, url: &str) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+#         Err("offline".into())
+#     }
+# }
+loader.register_fallback("https", Box::new(HttpLoader));
+assert_eq!(loader.load("https://a.com/s.json")?, json!({"type": "string"}));
+assert!(loader.load("https://a.com/t.json").is_err());
+# Ok(())
+# }
+```
+*/
 #[derive(Default)]
 pub struct SchemeUrlLoader {
-    loaders: HashMap<&'static str, Box<dyn UrlLoader>>,
+    loaders: HashMap<&'static str, Vec<Box<dyn UrlLoader>>>,
+    defaults: Vec<Box<dyn UrlLoader>>,
 }
 
 impl SchemeUrlLoader {
@@ -52,22 +129,322 @@ impl SchemeUrlLoader {
         Self::default()
     }
 
-    /// Registers [`UrlLoader`] for given url `scheme`
+    /// Registers [`UrlLoader`] for given url `scheme`, replacing
+    /// any loaders registered earlier for it.
     pub fn register(&mut self, scheme: &'static str, url_loader: Box<dyn UrlLoader>) {
-        self.loaders.insert(scheme, url_loader);
+        self.loaders.insert(scheme, vec![url_loader]);
+    }
+
+    /// Registers [`UrlLoader`] for given url `scheme`, to be tried
+    /// if loaders registered earlier for it fail.
+    pub fn register_fallback(&mut self, scheme: &'static str, url_loader: Box<dyn UrlLoader>) {
+        self.loaders.entry(scheme).or_default().push(url_loader);
+    }
+
+    /// Registers [`UrlLoader`] for urls, whose scheme has no loader
+    /// registered. Default loaders are tried in order of registration.
+    pub fn register_default(&mut self, url_loader: Box<dyn UrlLoader>) {
+        self.defaults.push(url_loader);
     }
 }
 
 impl UrlLoader for SchemeUrlLoader {
+    /// Returns error from first loader tried, if all of them fail.
     fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
         let url = Url::parse(url)?;
-        let Some(loader) = self.loaders.get(url.scheme()) else {
-            return Err(CompileError::UnsupportedUrlScheme {
+        let loaders = match self.loaders.get(url.scheme()) {
+            Some(loaders) => loaders,
+            None => &self.defaults,
+        };
+        let mut first_err = None;
+        for loader in loaders {
+            match loader.load(url.as_str()) {
+                Ok(doc) => return Ok(doc),
+                Err(e) => _ = first_err.get_or_insert(e),
+            }
+        }
+        Err(first_err.unwrap_or_else(|| {
+            CompileError::UnsupportedUrlScheme {
                 url: url.as_str().to_owned(),
             }
-            .into());
-        };
-        loader.load(url.as_str())
+            .into()
+        }))
+    }
+}
+
+// --
+
+/**
+Retries loading urls with wrapped [`UrlLoader`], on transient errors.
+
+Waits between attempts with exponential backoff, starting with
+`100ms` and doubling upto `5s`. By default loading is retried
+`3` times, only for [`std::io::Error`] of kind `Interrupted`,
+`TimedOut`, `WouldBlock`, `ConnectionReset`, `ConnectionAborted`,
+`ConnectionRefused`, `NotConnected` or `BrokenPipe`, found in the
+error or its sources. Use [`RetryLoader::set_retry_if`] to classify
+other errors of your loader, such as http status codes.
+
+```
+# use boon::*;
+# use serde_json::Value;
+# use std::{cell::Cell, error::Error, time::Duration};
+struct Flaky(Cell<usize>);
+
+impl UrlLoader for Flaky {
+    fn load(&self, _url: &str) -> Result<Value, Box<dyn Error>> {
+        self.0.set(self.0.get() + 1);
+        if self.0.get() < 3 {
+            return Err(std::io::Error::from(std::io::ErrorKind::ConnectionReset).into());
+        }
+        Ok(Value::Bool(true))
+    }
+}
+
+let mut loader = RetryLoader::new(Flaky(Cell::new(0)));
+loader.set_backoff(Duration::from_millis(1), Duration::from_millis(10));
+assert!(loader.load("http://a.com/schema.json").is_ok());
+```
+*/
+#[cfg(not(target_arch = "wasm32"))]
+pub struct RetryLoader<T: UrlLoader> {
+    loader: T,
+    max_retries: usize,
+    backoff: Duration,
+    max_backoff: Duration,
+    retry_if: Box<RetryIfFn>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type RetryIfFn = dyn Fn(&(dyn Error + 'static)) -> bool;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: UrlLoader> RetryLoader<T> {
+    pub fn new(loader: T) -> Self {
+        Self {
+            loader,
+            max_retries: 3,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            retry_if: Box::new(is_transient),
+        }
+    }
+
+    /// Sets the number of retries after first attempt fails.
+    pub fn set_max_retries(&mut self, max_retries: usize) {
+        self.max_retries = max_retries;
+    }
+
+    /// Sets the wait before first retry, which doubles with
+    /// each retry, but never exceeds `max`.
+    pub fn set_backoff(&mut self, initial: Duration, max: Duration) {
+        self.backoff = initial;
+        self.max_backoff = max;
+    }
+
+    /// Retry only if `f` returns true for the error.
+    pub fn set_retry_if(&mut self, f: impl Fn(&(dyn Error + 'static)) -> bool + 'static) {
+        self.retry_if = Box::new(f);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: UrlLoader> UrlLoader for RetryLoader<T> {
+    fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+        let mut backoff = self.backoff;
+        let mut retries = 0;
+        loop {
+            match self.loader.load(url) {
+                Ok(v) => return Ok(v),
+                Err(e) if retries < self.max_retries && (self.retry_if)(e.as_ref()) => {
+                    debug!("loading {url} failed, retrying in {backoff:?}: {e}");
+                    std::thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2).min(self.max_backoff);
+                    retries += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn is_transient(e: &(dyn Error + 'static)) -> bool {
+    use std::io::ErrorKind::*;
+    std::iter::successors(Some(e), |&e| e.source())
+        .filter_map(|e| e.downcast_ref::<std::io::Error>())
+        .any(|e| {
+            matches!(
+                e.kind(),
+                Interrupted
+                    | TimedOut
+                    | WouldBlock
+                    | ConnectionReset
+                    | ConnectionAborted
+                    | ConnectionRefused
+                    | NotConnected
+                    | BrokenPipe
+            )
+        })
+}
+
+// --
+
+/**
+Loads json embedded in `data:` urls.
+
+Both base64 and percent-encoded payloads are supported.
+Media type, if specified, must be json.
+
+```
+# use boon::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let json = DataUrlLoader.load("data:application/json;base64,eyJ0eXBlIjoic3RyaW5nIn0=")?;
+assert_eq!(json, json!({"type": "string"}));
+# Ok(())
+# }
+```
+
+This loader is registered for `data` scheme by default.
+*/
+pub struct DataUrlLoader;
+
+impl UrlLoader for DataUrlLoader {
+    fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+        load_data_url(url, false)
+    }
+}
+
+fn load_data_url(url: &str, strict: bool) -> Result<Value, Box<dyn Error>> {
+    let Some(rest) = url.strip_prefix("data:") else {
+        return Err(format!("{url} is not data url").into());
+    };
+    let Some((meta, data)) = rest.split_once(',') else {
+        return Err("data url must contain ','".into());
+    };
+    let (media_type, base64) = match meta.strip_suffix(";base64") {
+        Some(media_type) => (media_type, true),
+        None => (meta, false),
+    };
+    let media_type = media_type.split(';').next().unwrap_or_default().trim();
+    if !media_type.is_empty() && media_type != "application/json" && !media_type.ends_with("+json")
+    {
+        return Err(format!("unsupported media type {media_type:?} in data url").into());
+    }
+    let bytes: Vec<u8> = percent_decode_str(data).collect();
+    let bytes = if base64 {
+        base64::engine::general_purpose::STANDARD.decode(bytes)?
+    } else {
+        bytes
+    };
+    parse_json(&bytes, strict)
+}
+
+// --
+
+/**
+Loads json from documents held in memory.
+
+Unlike [`Compiler::add_resource`](crate::Compiler::add_resource), documents
+are fetched only when referenced. Typically registered for a synthetic
+scheme such as `mem`, so that schemas can refer each other by urls
+like `mem://schemas/address.json`.
+
+```
+# use boon::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let mut mem = MemoryLoader::new();
+mem.add("mem://schemas/person.json", json!({"$ref": "name.json"}))?;
+mem.add("mem://schemas/name.json", json!({"type": "string"}))?;
+
+let mut loader = SchemeUrlLoader::new();
+loader.register("mem", Box::new(mem));
+let mut compiler = Compiler::new();
+compiler.use_loader(Box::new(loader));
+
+let mut schemas = Schemas::new();
+let sch = compiler.compile("mem://schemas/person.json", &mut schemas)?;
+assert!(schemas.validate(&json!("john"), sch).is_ok());
+# Ok(())
+# }
+```
+*/
+#[derive(Default)]
+pub struct MemoryLoader {
+    docs: HashMap<Url, Value>,
+}
+
+impl MemoryLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `json` document to be served for given absolute `url`.
+    pub fn add(&mut self, url: &str, json: Value) -> Result<(), CompileError> {
+        let url = Url::parse(url).map_err(|e| CompileError::ParseUrlError {
+            url: url.to_owned(),
+            src: e.into(),
+        })?;
+        self.docs.insert(url, json);
+        Ok(())
+    }
+}
+
+impl UrlLoader for MemoryLoader {
+    fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+        let url = Url::parse(url)?;
+        match self.docs.get(&url) {
+            Some(doc) => Ok(doc.clone()),
+            None => Err(format!("{url} not found in memory").into()),
+        }
+    }
+}
+
+// --
+
+/**
+Rewrites url prefixes before loading.
+
+This is useful to resolve remote references from local mirror,
+without writing custom [`UrlLoader`].
+
+```
+# use boon::*;
+let mut mapper = UrlMapper::new();
+mapper.add("https://schemas.company.com/", "file:///opt/schemas/");
+let mut compiler = Compiler::new();
+compiler.use_url_mapper(mapper);
+```
+
+Note that the schema retains its original url. Only the location
+from where it is loaded changes.
+*/
+#[derive(Default)]
+pub struct UrlMapper {
+    mappings: Vec<(String, String)>,
+}
+
+impl UrlMapper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps urls starting with `from` to start with `to`.
+    /// If multiple prefixes match, longest one wins.
+    pub fn add(&mut self, from: &str, to: &str) {
+        self.mappings.retain(|(f, _)| f != from);
+        self.mappings.push((from.to_owned(), to.to_owned()));
+    }
+
+    /// Returns the mapped url, if any prefix matches `url`.
+    pub fn map(&self, url: &str) -> Option<String> {
+        self.mappings
+            .iter()
+            .filter(|(from, _)| url.starts_with(from.as_str()))
+            .max_by_key(|(from, _)| from.len())
+            .map(|(from, to)| format!("{to}{}", &url[from.len()..]))
     }
 }
 
@@ -77,21 +454,56 @@ pub(crate) struct DefaultUrlLoader {
     doc_map: RefCell<HashMap<Url, usize>>,
     doc_list: AppendList<Value>,
     loader: Box<dyn UrlLoader>,
+    extensions: Vec<&'static str>,
+    mapper: UrlMapper,
+    metaschemas: HashMap<Url, MetaSchema>,
+    pub(crate) dialect: Dialect,
+    preloaded_only: bool,   // see Compiler::require_preloaded_resources
+    strict: Rc<Cell<bool>>, // see Compiler::deny_duplicate_keys
+}
+
+// custom metaschema registered by user.
+pub(crate) struct MetaSchema {
+    pub(crate) draft: &'static Draft,
+    pub(crate) vocabs: Option<Vec<String>>,
+    // None while metaschema itself is being compiled
+    pub(crate) validator: Option<(Arc<Schemas>, SchemaIndex)>,
 }
 
 impl DefaultUrlLoader {
-    #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
     pub fn new() -> Self {
+        let strict = Rc::new(Cell::new(false));
+        let builtin = |load| {
+            Box::new(BuiltinLoader {
+                load,
+                strict: Rc::clone(&strict),
+            })
+        };
         let mut loader = SchemeUrlLoader::new();
-        #[cfg(not(target_arch = "wasm32"))]
-        loader.register("file", Box::new(FileLoader));
+        #[cfg(all(feature = "fs", not(target_arch = "wasm32")))]
+        loader.register("file", builtin(load_file));
+        loader.register("data", builtin(load_data_url));
+        #[cfg(all(feature = "zip", feature = "fs", not(target_arch = "wasm32")))]
+        loader.register("zip", Box::new(crate::ArchiveLoader::new()));
+        #[cfg(all(feature = "tar", feature = "fs", not(target_arch = "wasm32")))]
+        loader.register("tar", Box::new(crate::ArchiveLoader::new()));
         Self {
             doc_map: Default::default(),
             doc_list: AppendList::new(),
             loader: Box::new(loader),
+            extensions: vec![],
+            mapper: UrlMapper::default(),
+            metaschemas: HashMap::new(),
+            dialect: Dialect::default(),
+            preloaded_only: false,
+            strict,
         }
     }
 
+    pub(crate) fn doc_urls(&self) -> Vec<Url> {
+        self.doc_map.borrow().keys().cloned().collect()
+    }
+
     pub fn get_doc(&self, url: &Url) -> Option<&Value> {
         self.doc_map
             .borrow()
@@ -113,6 +525,67 @@ impl DefaultUrlLoader {
         self.loader = loader;
     }
 
+    pub fn set_extensions(&mut self, extensions: &[&'static str]) {
+        self.extensions = extensions.to_vec();
+    }
+
+    pub fn use_mapper(&mut self, mapper: UrlMapper) {
+        self.mapper = mapper;
+    }
+
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
+    pub fn require_preloaded(&mut self) {
+        self.preloaded_only = true;
+    }
+
+    pub fn deny_duplicate_keys(&mut self) {
+        self.strict.set(true);
+    }
+
+    // converts `doc` at `url` from dialect into json schema
+    pub(crate) fn convert(&self, url: &Url, doc: Value) -> Result<Value, CompileError> {
+        match self.dialect {
+            Dialect::JsonSchema => Ok(doc),
+            Dialect::Jtd => {
+                to_json_schema(&doc).map_err(|(ptr, reason)| CompileError::InvalidJtdSchema {
+                    loc: format!("{url}#{}", Fragment::encode(&ptr)),
+                    reason,
+                })
+            }
+        }
+    }
+
+    // loads `url` using loader, trying each of the extensions in order.
+    fn load_candidates(&self, url: &Url) -> Result<Value, Box<dyn Error>> {
+        let mapped;
+        let url = match self.mapper.map(url.as_str()) {
+            Some(s) => {
+                mapped = Url::parse(&s)?;
+                trace!("{url} mapped to {mapped}");
+                &mapped
+            }
+            None => url,
+        };
+        if self.extensions.is_empty() || url.cannot_be_a_base() || url.path().ends_with('/') {
+            return self.loader.load(url.as_str());
+        }
+        let mut first_err = None;
+        for ext in &self.extensions {
+            let mut candidate = url.clone();
+            candidate.set_path(&format!("{}{ext}", url.path()));
+            match self.loader.load(candidate.as_str()) {
+                Ok(doc) => return Ok(doc),
+                // candidate exists, but is invalid
+                Err(e) if is_duplicate_key(e.as_ref()) => return Err(e),
+                Err(e) => _ = first_err.get_or_insert(e),
+            }
+        }
+        Err(first_err.unwrap_or_else(|| "no extension matched".into()))
+    }
+
     pub(crate) fn load(&self, url: &Url) -> Result<&Value, CompileError> {
         if let Some(doc) = self.get_doc(url) {
             return Ok(doc);
@@ -124,14 +597,31 @@ impl DefaultUrlLoader {
                 url: url.to_string(),
                 src: e.into(),
             })?
+        } else if self.preloaded_only {
+            return Err(CompileError::NotPreloaded {
+                url: url.as_str().to_owned(),
+            });
         } else {
-            self.loader
-                .load(url.as_str())
-                .map_err(|src| CompileError::LoadUrlError {
-                    url: url.as_str().to_owned(),
-                    src,
-                })?
+            let doc = self.load_candidates(url).map_err(|src| {
+                let url = url.as_str().to_owned();
+                match src.downcast::<InstanceError>() {
+                    Ok(e) => match *e {
+                        InstanceError::DuplicateKey { key, line, column } => {
+                            CompileError::DuplicateKey {
+                                url,
+                                key,
+                                line,
+                                column,
+                            }
+                        }
+                        e => CompileError::LoadUrlError { url, src: e.into() },
+                    },
+                    Err(src) => CompileError::LoadUrlError { url, src },
+                }
+            })?;
+            self.convert(url, doc)?
         };
+        debug!("loaded {url}");
         self.add_doc(url.clone(), doc);
         self.get_doc(url)
             .ok_or(CompileError::Bug("doc must exist".into()))
@@ -144,13 +634,12 @@ impl DefaultUrlLoader {
         default_draft: &'static Draft,
         mut cycle: HashSet<Url>,
     ) -> Result<&'static Draft, CompileError> {
-        let Value::Object(obj) = &doc else {
-            return Ok(default_draft);
-        };
-        let Some(Value::String(sch)) = obj.get("$schema") else {
+        let Some(Value::String(sch)) = doc.get("$schema") else {
+            trace!("{up}: no $schema, using draft {}", default_draft.version);
             return Ok(default_draft);
         };
         if let Some(draft) = Draft::from_url(sch) {
+            trace!("{up}: $schema {sch} is draft {}", draft.version);
             return Ok(draft);
         }
         let (sch, _) = split(sch);
@@ -158,6 +647,13 @@ impl DefaultUrlLoader {
             url: up.to_string(),
             src: e.into(),
         })?;
+        if let Some(meta) = self.metaschemas.get(&sch) {
+            trace!(
+                "{up}: custom metaschema {sch} uses draft {}",
+                meta.draft.version
+            );
+            return Ok(meta.draft);
+        }
         if up.ptr.is_empty() && sch == up.url {
             return Err(CompileError::UnsupportedDraft { url: sch.into() });
         }
@@ -192,13 +688,51 @@ impl DefaultUrlLoader {
             url: sch.to_string(),
             src: e.into(),
         })?;
+        if let Some(meta) = self.metaschemas.get(&sch) {
+            return Ok(meta.vocabs.clone());
+        }
         let doc = self.load(&sch)?;
-        draft.get_vocabs(&sch, doc)
+        draft.get_vocabs(&sch, doc, &[])
+    }
+
+    pub(crate) fn add_metaschema(&mut self, url: Url, meta: MetaSchema) {
+        self.metaschemas.insert(url, meta);
+    }
+
+    // validates `doc` against its `$schema`, if it is registered metaschema.
+    pub(crate) fn validate_meta(&self, up: &UrlPtr, doc: &Value) -> Result<(), CompileError> {
+        let Value::Object(obj) = &doc else {
+            return Ok(());
+        };
+        let Some(Value::String(sch)) = obj.get("$schema") else {
+            return Ok(());
+        };
+        let Ok(sch) = Url::parse(split(sch).0) else {
+            return Ok(());
+        };
+        let Some(MetaSchema {
+            validator: Some((schemas, idx)),
+            ..
+        }) = self.metaschemas.get(&sch)
+        else {
+            return Ok(());
+        };
+        // error borrows metaschema, which is dropped along with compiler
+        schemas
+            .validate(doc, *idx)
+            .map_err(|src| CompileError::MetaSchemaValidationError {
+                url: up.to_string(),
+                metaschema: sch.to_string(),
+                src: src.to_string().into(),
+            })
     }
 }
 
 pub(crate) static STD_METAFILES: Lazy<HashMap<String, &str>> = Lazy::new(|| {
+    // unused, if no draft feature is enabled
+    #[allow(unused_mut)]
     let mut files = HashMap::new();
+    #[allow(unused_macros)]
     macro_rules! add {
         ($path:expr) => {
             files.insert(
@@ -207,25 +741,34 @@ pub(crate) static STD_METAFILES: Lazy<HashMap<String, &str>> = Lazy::new(|| {
             );
         };
     }
+    #[cfg(feature = "draft4")]
     add!("metaschemas/draft-04/schema");
+    #[cfg(feature = "draft6")]
     add!("metaschemas/draft-06/schema");
+    #[cfg(feature = "draft7")]
     add!("metaschemas/draft-07/schema");
-    add!("metaschemas/draft/2019-09/schema");
-    add!("metaschemas/draft/2019-09/meta/core");
-    add!("metaschemas/draft/2019-09/meta/applicator");
-    add!("metaschemas/draft/2019-09/meta/validation");
-    add!("metaschemas/draft/2019-09/meta/meta-data");
-    add!("metaschemas/draft/2019-09/meta/format");
-    add!("metaschemas/draft/2019-09/meta/content");
-    add!("metaschemas/draft/2020-12/schema");
-    add!("metaschemas/draft/2020-12/meta/core");
-    add!("metaschemas/draft/2020-12/meta/applicator");
-    add!("metaschemas/draft/2020-12/meta/unevaluated");
-    add!("metaschemas/draft/2020-12/meta/validation");
-    add!("metaschemas/draft/2020-12/meta/meta-data");
-    add!("metaschemas/draft/2020-12/meta/content");
-    add!("metaschemas/draft/2020-12/meta/format-annotation");
-    add!("metaschemas/draft/2020-12/meta/format-assertion");
+    #[cfg(feature = "draft2019")]
+    {
+        add!("metaschemas/draft/2019-09/schema");
+        add!("metaschemas/draft/2019-09/meta/core");
+        add!("metaschemas/draft/2019-09/meta/applicator");
+        add!("metaschemas/draft/2019-09/meta/validation");
+        add!("metaschemas/draft/2019-09/meta/meta-data");
+        add!("metaschemas/draft/2019-09/meta/format");
+        add!("metaschemas/draft/2019-09/meta/content");
+    }
+    #[cfg(feature = "draft2020")]
+    {
+        add!("metaschemas/draft/2020-12/schema");
+        add!("metaschemas/draft/2020-12/meta/core");
+        add!("metaschemas/draft/2020-12/meta/applicator");
+        add!("metaschemas/draft/2020-12/meta/unevaluated");
+        add!("metaschemas/draft/2020-12/meta/validation");
+        add!("metaschemas/draft/2020-12/meta/meta-data");
+        add!("metaschemas/draft/2020-12/meta/content");
+        add!("metaschemas/draft/2020-12/meta/format-annotation");
+        add!("metaschemas/draft/2020-12/meta/format-assertion");
+    }
     files
 });
 
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use crate::{CompileError, ErrorKind};
+
+/**
+Receives counts and durations of compilations and validations, so that
+they can be reported to a metrics system.
+
+All methods have empty default implementations, so implement only
+the ones you need. Methods are called synchronously, so they should
+be cheap, like incrementing a counter.
+
+```
+# use boon::*;
+# use serde_json::json;
+# use std::{sync::{Arc, atomic::{AtomicUsize, Ordering}}, time::Duration};
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+#[derive(Default)]
+struct Counters {
+    validations: AtomicUsize,
+    type_errors: AtomicUsize,
+}
+
+impl Metrics for Counters {
+    fn validated(&self, _schema_url: &str, _valid: bool, _elapsed: Duration) {
+        self.validations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn validation_error(&self, _schema_url: &str, kind: &ErrorKind) {
+        if matches!(kind, ErrorKind::Type { .. }) {
+            self.type_errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+let counters = Arc::new(Counters::default());
+let mut schemas = Schemas::new();
+schemas.set_metrics(counters.clone());
+let mut compiler = Compiler::new();
+compiler.add_resource("http://a.com/s.json", json!({"type": "string"}))?;
+let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+assert!(schemas.validate(&json!(1), sch).is_err());
+assert_eq!(counters.validations.load(Ordering::Relaxed), 1);
+assert_eq!(counters.type_errors.load(Ordering::Relaxed), 1);
+# Ok(())
+# }
+```
+*/
+pub trait Metrics: Send + Sync {
+    /**
+    Called after [`Compiler::compile`] of `loc`.
+
+    `cached` is true, if `loc` was already compiled into the target
+    [`Schemas`], in which case nothing is compiled.
+
+    [`Compiler::compile`]: crate::Compiler::compile
+    [`Schemas`]: crate::Schemas
+    */
+    fn compiled(
+        &self,
+        loc: &str,
+        cached: bool,
+        result: Result<(), &CompileError>,
+        elapsed: Duration,
+    ) {
+        _ = (loc, cached, result, elapsed);
+    }
+
+    /// Called after each validation of instance, with schema
+    /// identified by `schema_url`.
+    fn validated(&self, schema_url: &str, valid: bool, elapsed: Duration) {
+        _ = (schema_url, valid, elapsed);
+    }
+
+    /// Called for each leaf error of failed validation, with schema
+    /// identified by `schema_url`, after [`Metrics::validated`].
+    fn validation_error(&self, schema_url: &str, kind: &ErrorKind) {
+        _ = (schema_url, kind);
+    }
+
+    /// Called after [`Metrics::validated`], if memoization is enabled,
+    /// with number of subschema results reused from cache (`hits`) and
+    /// computed (`misses`). see [`ValidationOptions::enable_memoization`]
+    ///
+    /// [`ValidationOptions::enable_memoization`]: crate::ValidationOptions::enable_memoization
+    fn memoized(&self, schema_url: &str, hits: usize, misses: usize) {
+        _ = (schema_url, hits, misses);
+    }
+}
@@ -8,7 +8,9 @@ use serde::{
     Serialize,
 };
 
-use crate::{util::*, ErrorKind, InstanceLocation, ValidationError};
+use crate::{
+    util::*, ErrorKind, InstanceLocation, LocationFormat, ValidationError, MAX_VALUE_LENGTH,
+};
 
 impl<'s> ValidationError<'s, '_> {
     fn absolute_keyword_location(&self) -> AbsoluteKeywordLocation<'s> {
@@ -29,13 +31,56 @@ impl<'s> ValidationError<'s, '_> {
         self.causes.len() == 1 && matches!(self.kind, ErrorKind::Reference { .. })
     }
 
+    /// For `oneOf` and `anyOf` errors where none of the subschemas matched,
+    /// returns the number of leaf errors reported by each subschema, in
+    /// subschema order.
+    pub fn branch_error_counts(&self) -> Option<Vec<usize>> {
+        match self.kind {
+            ErrorKind::OneOf(None) | ErrorKind::AnyOf => {
+                Some(self.causes.iter().map(|e| e.leaves().count()).collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// For `oneOf` and `anyOf` errors where none of the subschemas matched,
+    /// returns the index of the subschema the instance most likely intended
+    /// to match.
+    ///
+    /// The subschema with fewest leaf errors is chosen. Ties are broken by
+    /// preferring the subschema whose errors are deepest in the instance,
+    /// i.e. the one that got furthest before failing.
+    pub fn closest_branch(&self) -> Option<usize> {
+        let counts = self.branch_error_counts()?;
+        let depth = |e: &ValidationError| {
+            e.leaves()
+                .map(|e| e.instance_location.tokens.len())
+                .max()
+                .unwrap_or_default()
+        };
+        counts
+            .iter()
+            .zip(&self.causes)
+            .enumerate()
+            .min_by_key(|(i, (n, e))| (**n, std::cmp::Reverse(depth(e)), *i))
+            .map(|(i, _)| i)
+    }
+
+    pub(crate) fn leaves(&self) -> Box<dyn Iterator<Item = &Self> + '_> {
+        if self.causes.is_empty() {
+            Box::new(std::iter::once(self))
+        } else {
+            Box::new(self.causes.iter().flat_map(|e| e.leaves()))
+        }
+    }
+
     /// The `Flag` output format, merely the boolean result.
     pub fn flag_output(&self) -> FlagOutput {
         FlagOutput { valid: false }
     }
 
     /// The `Basic` structure, a flat list of output units.
-    pub fn basic_output(&self) -> OutputUnit {
+    pub fn basic_output(&self) -> OutputUnit<'_, 's, '_> {
         let mut outputs = vec![];
 
         let mut in_ref = InRef::default();
@@ -58,6 +103,8 @@ impl<'s> ValidationError<'s, '_> {
                         keyword_location: kw_loc.get(e),
                         absolute_keyword_location,
                         instance_location: &e.instance_location,
+                        location_format: LocationFormat::default(),
+                        params: false,
                         error: OutputError::Leaf(&e.kind),
                     });
                 }
@@ -81,12 +128,14 @@ impl<'s> ValidationError<'s, '_> {
             keyword_location: String::new(),
             absolute_keyword_location: None,
             instance_location: &self.instance_location,
+            location_format: LocationFormat::default(),
+            params: false,
             error,
         }
     }
 
     /// The `Detailed` structure, based on the schema.
-    pub fn detailed_output(&self) -> OutputUnit {
+    pub fn detailed_output(&self) -> OutputUnit<'_, 's, '_> {
         let mut root = None;
         let mut stack: Vec<OutputUnit> = vec![];
 
@@ -110,6 +159,8 @@ impl<'s> ValidationError<'s, '_> {
                         keyword_location: kw_loc.get(e),
                         absolute_keyword_location,
                         instance_location: &e.instance_location,
+                        location_format: LocationFormat::default(),
+                        params: false,
                         error: OutputError::Leaf(&e.kind),
                     });
                 }
@@ -135,13 +186,128 @@ impl<'s> ValidationError<'s, '_> {
         }
         root.unwrap()
     }
+
+    /**
+    Leaf errors grouped by instance location, in the order they are
+    reported. Duplicate errors at same location are reported once.
+
+    This is useful to show errors next to the fields of a form.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # let mut schemas = Schemas::new();
+    # let mut compiler = Compiler::new();
+    # compiler.add_resource("http://example.com/schema.json", json!({
+    #     "properties": {"name": {"type": "string", "minLength": 2, "pattern": "^[a-z]+$"}}
+    # })).unwrap();
+    # let sch = compiler.compile("http://example.com/schema.json", &mut schemas).unwrap();
+    let instance = json!({"name": "A"});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let output = err.grouped_output();
+    assert_eq!(output.groups.len(), 1);
+    assert_eq!(output.groups[0].errors.len(), 2);
+    assert_eq!(
+        output.to_string(),
+        r#"{"/name":["length must be >=2, but got 1","'A' does not match pattern '^[a-z]+$'"]}"#
+    );
+    ```
+    */
+    pub fn grouped_output(&self) -> GroupedOutput<'_, 's, '_> {
+        let mut groups: Vec<ErrorGroup> = vec![];
+        for e in self.leaves() {
+            if matches!(e.kind, ErrorKind::Group | ErrorKind::Schema { .. }) {
+                continue;
+            }
+            let loc = &e.instance_location;
+            match groups.iter_mut().find(|g| g.instance_location == loc) {
+                Some(g) => {
+                    if !g.errors.contains(&&e.kind) {
+                        g.errors.push(&e.kind);
+                    }
+                }
+                None => groups.push(ErrorGroup {
+                    instance_location: loc,
+                    errors: vec![&e.kind],
+                }),
+            }
+        }
+        GroupedOutput {
+            groups,
+            location_format: LocationFormat::default(),
+        }
+    }
+
+    /**
+    Problem details as per [rfc9457], to be sent as `application/problem+json`
+    response body, when request is not valid.
+
+    `type_uri` identifies the problem type, and `status` is the http status
+    code. Leaf errors are reported in `errors` extension member, each with
+    `pointer` to the value in instance, the failed `keyword` and `message`.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # let mut schemas = Schemas::new();
+    # let mut compiler = Compiler::new();
+    # compiler.add_resource("http://example.com/schema.json", json!({
+    #     "properties": {"name": {"type": "string", "minLength": 2}}
+    # })).unwrap();
+    # let sch = compiler.compile("http://example.com/schema.json", &mut schemas).unwrap();
+    let instance = json!({"name": "A"});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let problem = err
+        .to_problem_details("https://example.com/probs/invalid-body", 422)
+        .with_instance("/users/1");
+    assert_eq!(
+        serde_json::to_value(&problem).unwrap(),
+        json!({
+            "type": "https://example.com/probs/invalid-body",
+            "title": "validation failed",
+            "status": 422,
+            "detail": "validation failed with http://example.com/schema.json#",
+            "instance": "/users/1",
+            "errors": [{
+                "pointer": "/name",
+                "keyword": "minLength",
+                "message": "length must be >=2, but got 1"
+            }]
+        })
+    );
+    ```
+
+    [rfc9457]: https://www.rfc-editor.org/rfc/rfc9457
+    */
+    pub fn to_problem_details(&self, type_uri: &str, status: u16) -> ProblemDetails {
+        let errors = self
+            .leaves()
+            .filter(|e| !matches!(e.kind, ErrorKind::Group | ErrorKind::Schema { .. }))
+            .map(|e| ProblemError {
+                pointer: e.instance_location.to_string(),
+                keyword: e.kind.keyword_path().map(|p| p.keyword.to_owned()),
+                message: e.kind.to_string(),
+            })
+            .collect();
+        ProblemDetails {
+            type_uri: type_uri.to_owned(),
+            title: "validation failed".to_owned(),
+            status,
+            detail: self.kind.to_string(),
+            instance: None,
+            errors,
+        }
+    }
 }
 
 // DfsIterator --
 
 impl Display for ValidationError<'_, '_> {
     /// Formats error hierarchy. Use `#` to show the schema location.
+    /// Use precision, like `{:.20}`, to change the length to which strings
+    /// in messages are truncated, which is 100 chars by default.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let max = f.precision().unwrap_or(MAX_VALUE_LENGTH);
         let mut indent = Indent::default();
         let mut sloc = SchemaLocation::default();
         // let mut kw_loc = KeywordLocation::default();
@@ -165,7 +331,12 @@ impl Display for ValidationError<'_, '_> {
                             // write!(f, " [{}]", kw_loc.get(e))?;
                             // write!(f, " [{}]", e.absolute_keyword_location())?;
                         }
-                        write!(f, ": {}", e.kind)?;
+                        write!(f, ": {:.max$}", e.kind)?;
+                        if let ErrorKind::OneOf(None) = e.kind {
+                            if let Some(i) = e.closest_branch() {
+                                write!(f, ", closest match is subschema {i}")?;
+                            }
+                        }
                     }
                 }
                 DfsItem::Post(e) => {
@@ -420,27 +591,114 @@ pub struct OutputUnit<'e, 's, 'v> {
     pub absolute_keyword_location: Option<AbsoluteKeywordLocation<'s>>,
     /// The location of the JSON value within the instance being validated
     pub instance_location: &'e InstanceLocation<'v>,
+    /// How `instance_location` is rendered
+    pub location_format: LocationFormat,
+    /// Whether parameters of error are serialized.
+    /// see [`OutputUnit::with_params`]
+    pub params: bool,
     pub error: OutputError<'e, 's, 'v>,
 }
 
+impl OutputUnit<'_, '_, '_> {
+    /**
+    Sets how instance locations of this unit and its nested units
+    are rendered.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # let mut schemas = Schemas::new();
+    # let mut compiler = Compiler::new();
+    # compiler.add_resource("http://example.com/schema.json", json!({"items": {"type": "string"}})).unwrap();
+    # let sch = compiler.compile("http://example.com/schema.json", &mut schemas).unwrap();
+    let instance = json!(["a", 1]);
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let output = err.basic_output().with_location_format(LocationFormat::JsonPath);
+    assert!(output.to_string().contains(r#""instanceLocation":"$[1]""#));
+    ```
+    */
+    pub fn with_location_format(mut self, format: LocationFormat) -> Self {
+        self.set_location_format(format);
+        self
+    }
+
+    fn set_location_format(&mut self, format: LocationFormat) {
+        self.location_format = format;
+        if let OutputError::Branch(units) = &mut self.error {
+            for unit in units {
+                unit.set_location_format(format);
+            }
+        }
+    }
+
+    /**
+    Serializes units having single error with `params` object, holding
+    keyword specific values like expected types, limits, pattern, or
+    missing properties, so that consumers need not parse the messages.
+
+    `params` is not part of json-schema output format, so it is
+    serialized only when asked. Parameters are named as in
+    [`ValidationError::ajv_output`], except that `type` is an array,
+    and `required`, `dependentRequired` and `additionalProperties`
+    errors list all their properties.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # let mut schemas = Schemas::new();
+    # let mut compiler = Compiler::new();
+    # compiler.add_resource("http://example.com/schema.json", json!({"items": {"maxLength": 2}})).unwrap();
+    # let sch = compiler.compile("http://example.com/schema.json", &mut schemas).unwrap();
+    let instance = json!(["abc"]);
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let output = err.basic_output().with_params();
+    assert!(output.to_string().contains(r#""params":{"limit":2}"#));
+    ```
+    */
+    pub fn with_params(mut self) -> Self {
+        self.enable_params();
+        self
+    }
+
+    fn enable_params(&mut self) {
+        self.params = true;
+        if let OutputError::Branch(units) = &mut self.error {
+            for unit in units {
+                unit.enable_params();
+            }
+        }
+    }
+}
+
 impl Serialize for OutputUnit<'_, '_, '_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let n = 4 + self.absolute_keyword_location.as_ref().map_or(0, |_| 1);
+        let params = match self.error {
+            OutputError::Leaf(kind) if self.params => Some(kind.params()),
+            _ => None,
+        }
+        .filter(|params| !params.is_empty());
+        let n = 4
+            + self.absolute_keyword_location.as_ref().map_or(0, |_| 1)
+            + params.as_ref().map_or(0, |_| 1);
         let mut map = serializer.serialize_map(Some(n))?;
         map.serialize_entry("valid", &self.valid)?;
         map.serialize_entry("keywordLocation", &self.keyword_location.to_string())?;
         if let Some(s) = &self.absolute_keyword_location {
             map.serialize_entry("absoluteKeywordLocation", &s.to_string())?;
         }
-        map.serialize_entry("instanceLocation", &self.instance_location.to_string())?;
+        let instance_location = self.instance_location.format(self.location_format);
+        map.serialize_entry("instanceLocation", &instance_location)?;
         let pname = match self.error {
             OutputError::Leaf(_) => "error",
             OutputError::Branch(_) => "errors",
         };
         map.serialize_entry(pname, &self.error)?;
+        if let Some(params) = &params {
+            map.serialize_entry("params", params)?;
+        }
         map.end()
     }
 }
@@ -451,6 +709,136 @@ impl Display for OutputUnit<'_, '_, '_> {
     }
 }
 
+/// Output format with errors grouped by instance location.
+///
+/// Serializes to json object, mapping instance location to list of
+/// error messages. see [`ValidationError::grouped_output`]
+pub struct GroupedOutput<'e, 's, 'v> {
+    pub groups: Vec<ErrorGroup<'e, 's, 'v>>,
+    /// How instance locations are rendered
+    pub location_format: LocationFormat,
+}
+
+/// Errors at an instance location. see [`GroupedOutput`]
+pub struct ErrorGroup<'e, 's, 'v> {
+    /// The location of the JSON value within the instance being validated
+    pub instance_location: &'e InstanceLocation<'v>,
+    pub errors: Vec<&'e ErrorKind<'s, 'v>>,
+}
+
+impl GroupedOutput<'_, '_, '_> {
+    /// Sets how instance locations are rendered.
+    pub fn with_location_format(mut self, format: LocationFormat) -> Self {
+        self.location_format = format;
+        self
+    }
+}
+
+impl Serialize for GroupedOutput<'_, '_, '_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.groups.len()))?;
+        for g in &self.groups {
+            let errors: Vec<_> = g.errors.iter().map(|kind| kind.to_string()).collect();
+            map.serialize_entry(&g.instance_location.format(self.location_format), &errors)?;
+        }
+        map.end()
+    }
+}
+
+impl Display for GroupedOutput<'_, '_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_json_to_fmt(f, self)
+    }
+}
+
+/// Problem details as per [rfc9457]. see [`ValidationError::to_problem_details`]
+///
+/// [rfc9457]: https://www.rfc-editor.org/rfc/rfc9457
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProblemDetails {
+    /// Uri identifying the problem type
+    pub type_uri: String,
+    /// Short summary of the problem type
+    pub title: String,
+    /// Http status code
+    pub status: u16,
+    /// Explanation specific to this occurrence of the problem
+    pub detail: String,
+    /// Uri identifying this occurrence of the problem
+    pub instance: Option<String>,
+    pub errors: Vec<ProblemError>,
+}
+
+/// Leaf error in [`ProblemDetails`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProblemError {
+    /// Json-pointer to the value in instance
+    pub pointer: String,
+    /// The keyword that failed
+    pub keyword: Option<String>,
+    pub message: String,
+}
+
+impl ProblemDetails {
+    /// Sets the title.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets the detail.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+
+    /// Sets the instance uri.
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+}
+
+impl Serialize for ProblemDetails {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", &self.type_uri)?;
+        map.serialize_entry("title", &self.title)?;
+        map.serialize_entry("status", &self.status)?;
+        map.serialize_entry("detail", &self.detail)?;
+        if let Some(instance) = &self.instance {
+            map.serialize_entry("instance", instance)?;
+        }
+        map.serialize_entry("errors", &self.errors)?;
+        map.end()
+    }
+}
+
+impl Serialize for ProblemError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("pointer", &self.pointer)?;
+        map.serialize_entry("keyword", &self.keyword)?;
+        map.serialize_entry("message", &self.message)?;
+        map.end()
+    }
+}
+
+impl Display for ProblemDetails {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_json_to_fmt(f, self)
+    }
+}
+
 /// Error of [`OutputUnit`].
 pub enum OutputError<'e, 's, 'v> {
     /// Single.
@@ -505,14 +893,16 @@ impl<'s> ErrorKind<'s, '_> {
             PropertyName { .. } => kw("propertyNames"),
             Reference { kw: kword, .. } => kw(kword),
             RefCycle { .. } => None,
+            Uncompiled { .. } => kw("$ref"),
             FalseSchema => None,
+            Deprecated => kw("deprecated"),
             Type { .. } => kw("type"),
             Enum { .. } => kw("enum"),
             Const { .. } => kw("const"),
             Format { .. } => kw("format"),
             MinProperties { .. } => kw("minProperties"),
             MaxProperties { .. } => kw("maxProperties"),
-            AdditionalProperties { .. } => kw("additionalProperty"),
+            AdditionalProperties { .. } => kw("additionalProperties"),
             Required { .. } => kw("required"),
             Dependency { prop, .. } => kw_prop("dependencies", prop),
             DependentRequired { prop, .. } => kw_prop("dependentRequired", prop),
@@ -528,15 +918,18 @@ impl<'s> ErrorKind<'s, '_> {
             Pattern { .. } => kw("pattern"),
             ContentEncoding { .. } => kw("contentEncoding"),
             ContentMediaType { .. } => kw("contentMediaType"),
+            ContentLimit { keyword, .. } => kw(keyword),
+            InstanceLimit { .. } => None,
             Minimum { .. } => kw("minimum"),
             Maximum { .. } => kw("maximum"),
             ExclusiveMinimum { .. } => kw("exclusiveMinimum"),
             ExclusiveMaximum { .. } => kw("exclusiveMaximum"),
             MultipleOf { .. } => kw("multipleOf"),
-            Not => kw("not"),
+            Not { .. } => kw("not"),
             AllOf => kw("allOf"),
             AnyOf => kw("anyOf"),
             OneOf(_) => kw("oneOf"),
+            Custom { kind, .. } | Redacted { kind, .. } => kind.keyword_path(),
         }
     }
 }
@@ -0,0 +1,388 @@
+use std::{error::Error, fmt::Display};
+
+use serde_json::{Map, Value};
+
+use crate::{util::*, SchemaIndex, Schemas, ValidationError};
+
+/// Error returned by [`Schemas::validate_patch`] and
+/// [`Schemas::validate_merge_patch`].
+#[derive(Debug)]
+pub enum PatchError<'s> {
+    /// Operation at index `op` in JSON Patch could not be applied.
+    Apply { op: usize, reason: String },
+    /// Patched instance is not valid. Holds the leaf validation errors.
+    Invalid(Vec<PatchViolation<'s>>),
+}
+
+/// Validation error of patched instance, mapped back to the patch.
+#[derive(Debug)]
+pub struct PatchViolation<'s> {
+    /// json-pointer into the patch document, to the value responsible
+    /// for the error. For JSON Patch, it is the operation, like `/2`,
+    /// or the offending part of its value, like `/2/value/name`.
+    /// None if no operation touches the location of error. For Merge
+    /// Patch, it is the deepest member of patch, within which the error is.
+    pub patch_location: Option<String>,
+    pub error: ValidationError<'s, 'static>,
+}
+
+impl Schemas {
+    /**
+    Applies JSON Patch `patch`, as per [rfc6902], to `base` and validates
+    the result with schema identified by `sch_index`. Returns the patched
+    instance if it is valid.
+
+    Errors of the patched instance are mapped back to the patch operations
+    causing them, for reporting in responses of http `PATCH` endpoints.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "properties": {"tags": {"items": {"type": "string"}}}
+    }))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let base = json!({"tags": ["a"]});
+    let patch = json!([
+        {"op": "add", "path": "/tags/-", "value": "b"},
+        {"op": "add", "path": "/tags/-", "value": 1}
+    ]);
+    let Err(PatchError::Invalid(errors)) = schemas.validate_patch(&base, &patch, sch) else {
+        panic!("patch must fail");
+    };
+    assert_eq!(errors[0].patch_location.as_deref(), Some("/1/value"));
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`PatchError::Apply`] if `patch` is not valid JSON Patch or cannot
+    be applied to `base`, and [`PatchError::Invalid`] if result is not valid.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+
+    [rfc6902]: https://www.rfc-editor.org/rfc/rfc6902
+    */
+    pub fn validate_patch(
+        &self,
+        base: &Value,
+        patch: &Value,
+        sch_index: SchemaIndex,
+    ) -> Result<Value, PatchError<'_>> {
+        let ops = parse_ops(patch)?;
+        let mut v = base.clone();
+        let mut paths = Vec::with_capacity(ops.len());
+        for (i, op) in ops.iter().enumerate() {
+            let path = op
+                .apply(&mut v)
+                .map_err(|reason| PatchError::Apply { op: i, reason })?;
+            paths.push(path);
+        }
+        self.validate_patched(v, sch_index, |loc| {
+            let ops = ops.iter().zip(&paths).enumerate().rev();
+            let ops = ops.filter(|(_, (op, _))| op.op != "test");
+            // last operation modifying value containing loc
+            let within = ops.clone().find_map(|(i, (op, path))| {
+                let rest = strip_ptr(loc, path)?;
+                match op.value.is_some_and(|v| v.pointer(rest).is_some()) {
+                    true => Some(format!("/{i}/value{rest}")),
+                    false => Some(format!("/{i}")),
+                }
+            });
+            // else operation closest to loc, within its value
+            within.or_else(|| {
+                ops.filter_map(|(i, (_, path))| {
+                    let depth = strip_ptr(path, loc)?.matches('/').count();
+                    Some((depth, i))
+                })
+                .min_by_key(|(depth, _)| *depth)
+                .map(|(_, i)| format!("/{i}"))
+            })
+        })
+    }
+
+    /**
+    Applies JSON Merge Patch `patch`, as per [rfc7386], to `base` and
+    validates the result with schema identified by `sch_index`. Returns
+    the patched instance if it is valid.
+
+    Errors of the patched instance are mapped back to the members of patch
+    causing them. see [`Schemas::validate_patch`]
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "properties": {"age": {"minimum": 0}},
+        "required": ["name"]
+    }))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let base = json!({"name": "john", "age": 20});
+    let patched = schemas.validate_merge_patch(&base, &json!({"age": 21}), sch).unwrap();
+    assert_eq!(patched, json!({"name": "john", "age": 21}));
+
+    let patch = json!({"age": -1});
+    let Err(PatchError::Invalid(errors)) = schemas.validate_merge_patch(&base, &patch, sch) else {
+        panic!("patch must fail");
+    };
+    assert_eq!(errors[0].patch_location.as_deref(), Some("/age"));
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`PatchError::Invalid`] if result is not valid.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+
+    [rfc7386]: https://www.rfc-editor.org/rfc/rfc7386
+    */
+    pub fn validate_merge_patch(
+        &self,
+        base: &Value,
+        patch: &Value,
+        sch_index: SchemaIndex,
+    ) -> Result<Value, PatchError<'_>> {
+        let mut v = base.clone();
+        merge_patch(&mut v, patch);
+        self.validate_patched(v, sch_index, |loc| {
+            // deepest member of patch, containing loc
+            let mut ptr = String::new();
+            let mut patch = patch;
+            for tok in loc.split('/').skip(1) {
+                let Value::Object(obj) = patch else {
+                    break;
+                };
+                let Some(member) = JsonPointer::unescape(tok)
+                    .ok()
+                    .and_then(|t| obj.get(t.as_ref()))
+                else {
+                    break;
+                };
+                ptr.push('/');
+                ptr.push_str(tok);
+                patch = member;
+            }
+            Some(ptr)
+        })
+    }
+
+    fn validate_patched(
+        &self,
+        v: Value,
+        sch_index: SchemaIndex,
+        patch_location: impl Fn(&str) -> Option<String>,
+    ) -> Result<Value, PatchError<'_>> {
+        let violations: Vec<_> = match self.validate(&v, sch_index) {
+            Ok(_) => return Ok(v),
+            Err(e) => e
+                .leaves()
+                .map(|leaf| PatchViolation {
+                    patch_location: patch_location(&leaf.instance_location.to_string()),
+                    error: leaf.clone().clone_static(),
+                })
+                .collect(),
+        };
+        Err(PatchError::Invalid(violations))
+    }
+}
+
+impl Display for PatchError<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Apply { op, reason } => write!(f, "cannot apply patch operation {op}: {reason}"),
+            Self::Invalid(violations) => {
+                write!(f, "patched instance is invalid")?;
+                for v in violations {
+                    let loc = v.patch_location.as_deref().unwrap_or("-");
+                    write!(
+                        f,
+                        "\n  {} (patch {loc}): {}",
+                        v.error.instance_location, v.error.kind
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for PatchError<'_> {}
+
+// returns part of `loc` after json-pointer `ptr`, if `ptr` is prefix of `loc`
+fn strip_ptr<'a>(loc: &'a str, ptr: &str) -> Option<&'a str> {
+    loc.strip_prefix(ptr)
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+// json patch --
+
+struct Op<'p> {
+    op: &'p str,
+    path: &'p str,
+    from: Option<&'p str>,
+    value: Option<&'p Value>,
+}
+
+fn parse_ops(patch: &Value) -> Result<Vec<Op<'_>>, PatchError<'static>> {
+    let Value::Array(arr) = patch else {
+        return Err(PatchError::Apply {
+            op: 0,
+            reason: "patch must be an array".to_owned(),
+        });
+    };
+    let mut ops = Vec::with_capacity(arr.len());
+    for (i, op) in arr.iter().enumerate() {
+        let err = |reason: &str| PatchError::Apply {
+            op: i,
+            reason: reason.to_owned(),
+        };
+        let str = |name| match op.get(name) {
+            Some(Value::String(s)) => Ok(Some(s.as_str())),
+            Some(_) => Err(err(&format!("{name} must be string"))),
+            None => Ok(None),
+        };
+        let op = Op {
+            op: str("op")?.ok_or_else(|| err("op is missing"))?,
+            path: str("path")?.ok_or_else(|| err("path is missing"))?,
+            from: str("from")?,
+            value: op.get("value"),
+        };
+        let valid = match op.op {
+            "add" | "replace" | "test" => op.value.is_some(),
+            "move" | "copy" => op.from.is_some(),
+            "remove" => true,
+            _ => return Err(err(&format!("unknown op {}", quote(op.op)))),
+        };
+        if !valid {
+            return Err(err("value or from is missing"));
+        }
+        ops.push(op);
+    }
+    Ok(ops)
+}
+
+impl Op<'_> {
+    // returns path of the value modified, with `-` replaced by array index
+    fn apply(&self, doc: &mut Value) -> Result<String, String> {
+        match (self.op, self.from, self.value) {
+            ("add", _, Some(value)) => add(doc, self.path, value.clone()),
+            ("remove", _, _) => remove(doc, self.path).map(|_| self.path.to_owned()),
+            ("replace", _, Some(value)) => {
+                *target(doc, self.path)? = value.clone();
+                Ok(self.path.to_owned())
+            }
+            ("move", Some(from), _) => {
+                if strip_ptr(self.path, from).is_some_and(|rest| !rest.is_empty()) {
+                    return Err(format!("cannot move {} into its child", quote(from)));
+                }
+                let value = remove(doc, from)?;
+                add(doc, self.path, value)
+            }
+            ("copy", Some(from), _) => {
+                let value = target(doc, from)?.clone();
+                add(doc, self.path, value)
+            }
+            ("test", _, Some(value)) => match equals(target(doc, self.path)?, value) {
+                true => Ok(self.path.to_owned()),
+                false => Err(format!("test failed at {}", quote(self.path))),
+            },
+            _ => unreachable!("ops are validated in parse_ops"),
+        }
+    }
+}
+
+fn target<'a>(doc: &'a mut Value, path: &str) -> Result<&'a mut Value, String> {
+    doc.pointer_mut(path)
+        .ok_or_else(|| format!("{} not found", quote(path)))
+}
+
+// returns parent of `path` and unescaped last token
+fn parent<'a>(doc: &'a mut Value, path: &str) -> Result<(&'a mut Value, String), String> {
+    let Some((parent, tok)) = path.rsplit_once('/') else {
+        return Err(format!("invalid path {}", quote(path)));
+    };
+    let tok = JsonPointer::unescape(tok).map_err(|_| format!("invalid path {}", quote(path)))?;
+    Ok((target(doc, parent)?, tok.into_owned()))
+}
+
+fn add(doc: &mut Value, path: &str, value: Value) -> Result<String, String> {
+    if path.is_empty() {
+        *doc = value;
+        return Ok(String::new());
+    }
+    match parent(doc, path)? {
+        (Value::Object(obj), tok) => {
+            obj.insert(tok, value);
+        }
+        (Value::Array(arr), tok) => {
+            if tok == "-" {
+                arr.push(value);
+                let parent = &path[..path.len() - 1];
+                return Ok(format!("{parent}{}", arr.len() - 1));
+            }
+            let i = index(&tok, arr.len() + 1, path)?;
+            arr.insert(i, value);
+        }
+        _ => return Err(format!("parent of {} is not container", quote(path))),
+    }
+    Ok(path.to_owned())
+}
+
+fn remove(doc: &mut Value, path: &str) -> Result<Value, String> {
+    let removed = match parent(doc, path)? {
+        (Value::Object(obj), tok) => obj.remove(&tok),
+        (Value::Array(arr), tok) => {
+            let i = index(&tok, arr.len(), path)?;
+            Some(arr.remove(i))
+        }
+        _ => None,
+    };
+    removed.ok_or_else(|| format!("{} not found", quote(path)))
+}
+
+// parses array index, which must be less than `len`
+fn index(tok: &str, len: usize, path: &str) -> Result<usize, String> {
+    let valid = !tok.is_empty()
+        && tok.bytes().all(|b| b.is_ascii_digit())
+        && (tok == "0" || !tok.starts_with('0'));
+    match tok.parse() {
+        Ok(i) if valid && i < len => Ok(i),
+        _ => Err(format!("invalid array index in {}", quote(path))),
+    }
+}
+
+// merge patch --
+
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(obj) = target {
+        for (name, value) in patch {
+            if value.is_null() {
+                obj.remove(name);
+            } else {
+                merge_patch(obj.entry(name.as_str()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
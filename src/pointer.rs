@@ -0,0 +1,300 @@
+/*!
+JSON Pointer, as per [RFC 6901](https://www.rfc-editor.org/rfc/rfc6901).
+
+Useful when handling instance locations reported in errors,
+or building `$ref` values.
+
+```
+# use boon::pointer::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let ptr = JsonPointer::root().append("paths").append("/users");
+assert_eq!(ptr.as_str(), "/paths/~1users");
+assert_eq!(format!("#/$defs/{}", escape("a/b")), "#/$defs/a~1b");
+
+let doc = json!({"paths": {"/users": {"get": {}}}});
+assert_eq!(ptr.lookup(&doc), Some(&json!({"get": {}})));
+
+let ptr = JsonPointer::parse("/paths/~1users/get")?;
+assert_eq!(ptr.tokens().collect::<Vec<_>>(), ["paths", "/users", "get"]);
+# Ok(())
+# }
+```
+*/
+
+use std::{borrow::Cow, fmt::Display, str::FromStr};
+
+use serde_json::Value;
+
+use crate::util::quote;
+
+/// Error returned by [`JsonPointer::parse`] and [`unescape`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerError {
+    /// The invalid pointer or reference token.
+    pub input: String,
+}
+
+impl Display for PointerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid json-pointer {}", quote(&self.input))
+    }
+}
+
+impl std::error::Error for PointerError {}
+
+/// Escapes reference `token`, replacing `~` with `~0` and `/` with `~1`.
+pub fn escape(token: &str) -> Cow<'_, str> {
+    const SPECIAL: [char; 2] = ['~', '/'];
+    if token.contains(SPECIAL) {
+        token.replace('~', "~0").replace('/', "~1").into()
+    } else {
+        token.into()
+    }
+}
+
+/**
+Unescapes reference `token`, replacing `~1` with `/` and `~0` with `~`.
+
+# Errors
+
+returns [`PointerError`], if `~` is not followed by `0` or `1`.
+*/
+pub fn unescape(token: &str) -> Result<Cow<'_, str>, PointerError> {
+    let Some(mut tilde) = token.find('~') else {
+        return Ok(Cow::Borrowed(token));
+    };
+    let mut tok = token;
+    let mut s = String::with_capacity(tok.len());
+    loop {
+        s.push_str(&tok[..tilde]);
+        tok = &tok[tilde + 1..];
+        match tok.chars().next() {
+            Some('1') => s.push('/'),
+            Some('0') => s.push('~'),
+            _ => {
+                return Err(PointerError {
+                    input: token.to_owned(),
+                })
+            }
+        }
+        tok = &tok[1..];
+        let Some(i) = tok.find('~') else {
+            s.push_str(tok);
+            break;
+        };
+        tilde = i;
+    }
+    Ok(Cow::Owned(s))
+}
+
+/// JSON Pointer, which is either empty or a sequence of `/` prefixed,
+/// escaped reference tokens.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JsonPointer(String);
+
+impl JsonPointer {
+    /// Returns the empty pointer, referring to whole document.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /**
+    Parses `s` as json-pointer.
+
+    # Errors
+
+    returns [`PointerError`], if `s` is neither empty nor starts with `/`,
+    or has invalid escape sequence.
+    */
+    pub fn parse(s: &str) -> Result<Self, PointerError> {
+        let err = || PointerError {
+            input: s.to_owned(),
+        };
+        if !s.is_empty() && !s.starts_with('/') {
+            return Err(err());
+        }
+        if s.split('/').any(|tok| unescape(tok).is_err()) {
+            return Err(err());
+        }
+        Ok(Self(s.to_owned()))
+    }
+
+    /// Creates pointer from unescaped reference `tokens`.
+    pub fn from_tokens<I, T>(tokens: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        tokens
+            .into_iter()
+            .fold(Self::root(), |ptr, tok| ptr.append(tok.as_ref()))
+    }
+
+    /// Returns new pointer with unescaped reference `token` appended.
+    pub fn append(&self, token: &str) -> Self {
+        Self(format!("{}/{}", self.0, escape(token)))
+    }
+
+    /// Appends unescaped reference `token`.
+    pub fn push(&mut self, token: &str) {
+        self.0.push('/');
+        self.0.push_str(&escape(token));
+    }
+
+    /// Returns unescaped reference tokens.
+    pub fn tokens(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        // always valid, as it is checked on construction
+        self.0
+            .split('/')
+            .skip(1)
+            .map(|tok| unescape(tok).unwrap_or(Cow::Borrowed(tok)))
+    }
+
+    /// Returns parent pointer and last unescaped reference token,
+    /// or `None` if this is root.
+    pub fn split_last(&self) -> Option<(Self, Cow<'_, str>)> {
+        let i = self.0.rfind('/')?;
+        let tok = &self.0[i + 1..];
+        let tok = unescape(tok).unwrap_or(Cow::Borrowed(tok));
+        Some((Self(self.0[..i].to_owned()), tok))
+    }
+
+    /// Tells whether it is the empty pointer.
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the escaped form, like `/a~1b/0`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /**
+    Returns the value in `doc`, referred by this pointer.
+
+    Array indexes must be decimal without leading zeros.
+    `-` refers to nonexistent element after last, so it
+    never finds a value.
+    */
+    pub fn lookup<'v>(&self, doc: &'v Value) -> Option<&'v Value> {
+        self.tokens().try_fold(doc, |v, tok| match v {
+            Value::Object(obj) => obj.get(tok.as_ref()),
+            Value::Array(arr) => arr.get(array_index(&tok)?),
+            _ => None,
+        })
+    }
+
+    /// Mutable version of [`JsonPointer::lookup`].
+    pub fn lookup_mut<'v>(&self, doc: &'v mut Value) -> Option<&'v mut Value> {
+        self.tokens().try_fold(doc, |v, tok| match v {
+            Value::Object(obj) => obj.get_mut(tok.as_ref()),
+            Value::Array(arr) => arr.get_mut(array_index(&tok)?),
+            _ => None,
+        })
+    }
+}
+
+// as per rfc, index is `0` or digits without leading zero
+fn array_index(tok: &str) -> Option<usize> {
+    if tok.len() > 1 && tok.starts_with('0') || !tok.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    usize::from_str(tok).ok()
+}
+
+impl Display for JsonPointer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for JsonPointer {
+    type Err = PointerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl AsRef<str> for JsonPointer {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<JsonPointer> for String {
+    fn from(ptr: JsonPointer) -> Self {
+        ptr.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_unescape() {
+        let tests = [
+            ("a", Some("a")),
+            ("a~1b~0c", Some("a/b~c")),
+            ("~01", Some("~1")),
+            ("a~", None),
+            ("a~2", None),
+        ];
+        for (tok, want) in tests {
+            let got = unescape(tok).ok();
+            assert_eq!(got.as_deref(), want, "unescape({tok:?})");
+            if let Some(want) = want {
+                assert_eq!(escape(want), tok, "escape({want:?})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for s in ["", "/", "/a/0", "/a~0b~1c"] {
+            assert_eq!(JsonPointer::parse(s).unwrap().as_str(), s);
+        }
+        for s in ["a", "/a~", "/~2"] {
+            assert!(JsonPointer::parse(s).is_err(), "{s:?}");
+        }
+        let err = JsonPointer::parse("/a\"b~").unwrap_err();
+        assert_eq!(err.to_string(), r#"invalid json-pointer '/a"b~'"#);
+    }
+
+    #[test]
+    fn test_tokens() {
+        let ptr = JsonPointer::from_tokens(["a/b", "", "c~d"]);
+        assert_eq!(ptr.as_str(), "/a~1b//c~0d");
+        assert_eq!(ptr.tokens().collect::<Vec<_>>(), ["a/b", "", "c~d"]);
+        let (parent, last) = ptr.split_last().unwrap();
+        assert_eq!((parent.as_str(), last.as_ref()), ("/a~1b/", "c~d"));
+        assert!(JsonPointer::root().split_last().is_none());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut doc = json!({"a": [{"b": 1}, 2], "": {"~": 3}});
+        let tests = [
+            ("", Some(doc.clone())),
+            ("/a/0/b", Some(json!(1))),
+            ("/a/1", Some(json!(2))),
+            ("//~0", Some(json!(3))),
+            ("/a/01", None),
+            ("/a/-", None),
+            ("/a/2", None),
+            ("/a/0/b/c", None),
+        ];
+        for (ptr, want) in tests {
+            let ptr = JsonPointer::parse(ptr).unwrap();
+            assert_eq!(ptr.lookup(&doc), want.as_ref(), "{ptr}");
+        }
+
+        let ptr = JsonPointer::parse("/a/0/b").unwrap();
+        *ptr.lookup_mut(&mut doc).unwrap() = json!(5);
+        assert_eq!(doc["a"][0]["b"], json!(5));
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::{ErrorKind, InstanceLocation, InstanceToken, SchemaIndex, Schemas, ValidationError};
+
+impl Schemas {
+    /**
+    Removes object members from `v`, which are rejected by the schema
+    identified by `sch_index`.
+
+    Members rejected by `additionalProperties` or `unevaluatedProperties`
+    are removed until `v` becomes valid. Other members are kept, even if
+    the schema does not know them. For example members of an object whose
+    schema is `{"type": "object"}` or has `"additionalProperties": true`
+    are kept.
+
+    Array items are never removed, since that would shift the
+    remaining items.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({
+        "properties": {
+            "name": {"type": "string"},
+            "address": {"properties": {"city": true}, "additionalProperties": false},
+            "meta": {"type": "object"}
+        },
+        "additionalProperties": false
+    }))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+    let mut instance = json!({
+        "name": "a",
+        "admin": true,
+        "address": {"city": "x", "zip": 1},
+        "meta": {"k": 1}
+    });
+    assert!(schemas.prune(&mut instance, sch).is_ok());
+    assert_eq!(
+        instance,
+        json!({"name": "a", "address": {"city": "x"}, "meta": {"k": 1}})
+    );
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`ValidationError`], if `v` is invalid for reasons other
+    than its unknown members. In that case `v` may be partially pruned.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn prune(
+        &self,
+        v: &mut Value,
+        sch_index: SchemaIndex,
+    ) -> Result<(), ValidationError<'_, 'static>> {
+        loop {
+            let rejected = match self.validate(v, sch_index) {
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    // locations of unevaluatedProperties subschemas
+                    let uneval_props: HashSet<&str> = (self.list.iter().flatten())
+                        .filter_map(|s| s.unevaluated_properties)
+                        .map(|sch| self.get(sch).loc.as_str())
+                        .collect();
+                    let mut rejected = vec![];
+                    let mut invalid = vec![];
+                    collect_rejected(&e, &uneval_props, &mut rejected, &mut invalid);
+                    // members which failed other keywords are known, but invalid
+                    rejected
+                        .retain(|loc| !invalid.iter().any(|l| l.tokens.starts_with(&loc.tokens)));
+                    if rejected.is_empty() {
+                        return Err(e.clone_static());
+                    }
+                    rejected
+                }
+            };
+            let mut removed = false;
+            for loc in &rejected {
+                removed |= remove(v, &loc.tokens);
+            }
+            if !removed {
+                // should not happen. validate once more to report error
+                return match self.validate(v, sch_index) {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(e.clone_static()),
+                };
+            }
+        }
+    }
+}
+
+// collects locations of members rejected by `additionalProperties`
+// or `unevaluatedProperties` in `e`, and locations of other errors.
+fn collect_rejected<'v>(
+    e: &ValidationError<'_, 'v>,
+    uneval_props: &HashSet<&str>,
+    rejected: &mut Vec<InstanceLocation<'static>>,
+    invalid: &mut Vec<InstanceLocation<'v>>,
+) {
+    match &e.kind {
+        ErrorKind::AdditionalProperties { got } => {
+            for pname in got {
+                let mut loc = e.instance_location.clone().clone_static();
+                loc.tokens.push(InstanceToken::from(pname.to_string()));
+                rejected.push(loc);
+            }
+        }
+        ErrorKind::FalseSchema if uneval_props.contains(e.schema_url) => {
+            if let Some(InstanceToken::Prop(_)) = e.instance_location.tokens.last() {
+                rejected.push(e.instance_location.clone().clone_static());
+            }
+        }
+        ErrorKind::OneOf(None) | ErrorKind::AnyOf => {
+            // follow the subschema, instance is most likely intended for
+            if let Some(i) = e.closest_branch() {
+                collect_rejected(&e.causes[i], uneval_props, rejected, invalid);
+            }
+            return;
+        }
+        ErrorKind::OneOf(Some(_)) | ErrorKind::Not { .. } | ErrorKind::ContentSchema => {
+            invalid.push(e.instance_location.clone());
+            return;
+        }
+        _ if e.causes.is_empty() => invalid.push(e.instance_location.clone()),
+        _ => {}
+    }
+    for cause in &e.causes {
+        collect_rejected(cause, uneval_props, rejected, invalid);
+    }
+}
+
+// removes value at `tokens`. returns false if not found.
+fn remove(v: &mut Value, tokens: &[InstanceToken]) -> bool {
+    let Some((last, parent)) = tokens.split_last() else {
+        return false;
+    };
+    let mut v = v;
+    for tok in parent {
+        let child = match (v, tok) {
+            (Value::Object(obj), InstanceToken::Prop(pname)) => obj.get_mut(pname.as_ref()),
+            (Value::Array(arr), InstanceToken::Item(i)) => arr.get_mut(*i),
+            _ => None,
+        };
+        let Some(child) = child else {
+            return false;
+        };
+        v = child;
+    }
+    match (v, last) {
+        (Value::Object(obj), InstanceToken::Prop(pname)) => obj.remove(pname.as_ref()).is_some(),
+        _ => false,
+    }
+}
@@ -0,0 +1,192 @@
+use std::{collections::HashSet, fmt};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use serde_json::{value::RawValue, Map, Value};
+
+use crate::{Additional, Dependency, InstanceError, Items, SchemaIndex, Schemas};
+
+impl Schemas {
+    /**
+    Same as [`Schemas::validate_str`], but materializes only those parts
+    of `s`, which the schema identified by `sch_index` may look at.
+
+    Values of object members and array items, to which no subschema
+    applies, are checked to be well-formed json, but not parsed into
+    memory. This is useful for large documents, where the schema
+    constrains only a few of the members.
+
+    A value is fully materialized, if any schema applied to it uses
+    `enum`, `const`, `format`, `uniqueItems`, `discriminator`,
+    `$recursiveRef` or `$dynamicRef`, or a `$ref` which is not yet
+    compiled, since these may look at the whole value.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_raw(&self, s: &str, sch_index: SchemaIndex) -> Result<(), InstanceError<'_>> {
+        if !self.contains(sch_index) {
+            panic!("Schemas::validate_raw: schema index out of bounds");
+        }
+        let raw: &RawValue = serde_json::from_str(s).map_err(InstanceError::Parse)?;
+        let v = self
+            .materialize(raw, vec![sch_index])
+            .map_err(InstanceError::Parse)?;
+        self.validate(&v, sch_index)
+            .map_err(|e| InstanceError::Invalid(e.clone_static()))
+    }
+
+    // parses `raw`, to which schemas `schs` apply.
+    fn materialize(&self, raw: &RawValue, schs: Vec<SchemaIndex>) -> serde_json::Result<Value> {
+        let schs = self.in_place(schs);
+        let json = raw.get();
+        let whole = schs.iter().any(|sch| self.needs_whole(*sch));
+        if whole || !json.starts_with(['{', '[']) {
+            return serde_json::from_str(json);
+        }
+
+        if json.starts_with('[') {
+            let items: Vec<&RawValue> = serde_json::from_str(json)?;
+            let mut arr = Vec::with_capacity(items.len());
+            for (i, item) in items.into_iter().enumerate() {
+                let item_schs = schs.iter().flat_map(|sch| self.item_schemas(*sch, i));
+                arr.push(self.child(item, item_schs.collect())?);
+            }
+            return Ok(Value::Array(arr));
+        }
+
+        let Members(members) = serde_json::from_str(json)?;
+        let mut obj = Map::new();
+        for (pname, pvalue) in members {
+            let prop_schs = schs.iter().flat_map(|sch| self.prop_schemas(*sch, &pname));
+            let pvalue = self.child(pvalue, prop_schs.collect())?;
+            obj.insert(pname, pvalue);
+        }
+        Ok(Value::Object(obj))
+    }
+
+    fn child(&self, raw: &RawValue, schs: Vec<SchemaIndex>) -> serde_json::Result<Value> {
+        if schs.is_empty() {
+            // no keyword looks at it
+            Ok(Value::Null)
+        } else {
+            self.materialize(raw, schs)
+        }
+    }
+
+    // `schs` along with the schemas they apply in place, such as `allOf`.
+    fn in_place(&self, mut schs: Vec<SchemaIndex>) -> Vec<SchemaIndex> {
+        let mut seen = HashSet::new();
+        let mut i = 0;
+        while i < schs.len() {
+            let sch = schs[i];
+            i += 1;
+            if !seen.insert(sch) {
+                continue;
+            }
+            let s = self.get(sch);
+            schs.extend(s.ref_);
+            schs.extend(&s.all_of);
+            schs.extend(&s.any_of);
+            schs.extend(&s.one_of);
+            schs.extend(s.not);
+            schs.extend(s.if_);
+            schs.extend(s.then);
+            schs.extend(s.else_);
+            schs.extend(s.dependent_schemas.iter().map(|(_, sch)| *sch));
+            schs.extend(s.dependencies.iter().filter_map(|(_, dep)| match dep {
+                Dependency::SchemaRef(sch) => Some(*sch),
+                Dependency::Props(_) => None,
+            }));
+        }
+        schs.retain(|sch| seen.remove(sch));
+        schs
+    }
+
+    fn needs_whole(&self, sch: SchemaIndex) -> bool {
+        let s = self.get(sch);
+        s.enum_.is_some()
+            || s.constant.is_some()
+            || s.format.is_some()
+            || s.unique_items
+            || s.discriminator.is_some()
+            || s.recursive_ref.is_some()
+            || s.dynamic_ref.is_some()
+            || s.lazy_ref.is_some()
+    }
+
+    // schemas applied to member `pname` by schema `sch`.
+    fn prop_schemas(&self, sch: SchemaIndex, pname: &str) -> Vec<SchemaIndex> {
+        let s = self.get(sch);
+        let mut schs = vec![];
+        schs.extend(s.properties.get(pname));
+        for (regex, sch) in &s.pattern_properties {
+            if regex.is_match(pname) {
+                schs.push(*sch);
+            }
+        }
+        if schs.is_empty() {
+            if let Some(Additional::SchemaRef(sch)) = &s.additional_properties {
+                schs.push(*sch);
+            }
+        }
+        schs.extend(s.unevaluated_properties);
+        schs
+    }
+
+    // schemas applied to item at index `i` by schema `sch`.
+    fn item_schemas(&self, sch: SchemaIndex, i: usize) -> Vec<SchemaIndex> {
+        let s = self.get(sch);
+        let mut schs = vec![];
+        match &s.items {
+            Some(Items::SchemaRef(sch)) => schs.push(*sch),
+            Some(Items::SchemaRefs(list)) => match list.get(i) {
+                Some(sch) => schs.push(*sch),
+                None => {
+                    if let Some(Additional::SchemaRef(sch)) = &s.additional_items {
+                        schs.push(*sch);
+                    }
+                }
+            },
+            None => {}
+        }
+        match s.prefix_items.get(i) {
+            Some(sch) => schs.push(*sch),
+            None => schs.extend(s.items2020),
+        }
+        schs.extend(s.contains);
+        schs.extend(s.unevaluated_items);
+        schs
+    }
+}
+
+// object members in document order, with values left unparsed.
+struct Members<'a>(Vec<(String, &'a RawValue)>);
+
+impl<'de> Deserialize<'de> for Members<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MembersVisitor;
+
+        impl<'de> Visitor<'de> for MembersVisitor {
+            type Value = Members<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("json object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut members = vec![];
+                while let Some(member) = map.next_entry()? {
+                    members.push(member);
+                }
+                Ok(Members(members))
+            }
+        }
+
+        deserializer.deserialize_map(MembersVisitor)
+    }
+}
@@ -0,0 +1,60 @@
+use std::{borrow::Cow, error::Error, mem, sync::Arc};
+
+use serde_json::{Number, Value};
+
+use crate::{ErrorKind, ValidationError};
+
+// see ValidationOptions::enable_redaction
+impl ValidationError<'_, '_> {
+    pub(crate) fn redact(&mut self) {
+        self.kind.redact();
+        self.causes.iter_mut().for_each(Self::redact);
+    }
+}
+
+impl ErrorKind<'_, '_> {
+    fn redact(&mut self) {
+        use ErrorKind::*;
+        let got = match self {
+            Format { got, err, .. } => {
+                let desc = describe(got);
+                *got = Cow::Owned(Value::Null);
+                // custom format errors may quote the value
+                *err = Arc::from(Box::<dyn Error>::from("<redacted>"));
+                desc
+            }
+            Pattern { got, .. } => describe_str(&mem::take(got)),
+            ContentMediaType { got, .. } => format!("<{} bytes>", mem::take(got).len()),
+            Minimum { got, .. }
+            | Maximum { got, .. }
+            | ExclusiveMinimum { got, .. }
+            | ExclusiveMaximum { got, .. }
+            | MultipleOf { got, .. } => {
+                *got = Cow::Owned(Number::from(0));
+                "<number>".to_owned()
+            }
+            Custom { kind, .. } => return kind.redact(),
+            _ => return,
+        };
+        let kind = mem::replace(self, Group);
+        *self = Redacted {
+            got,
+            kind: Box::new(kind),
+        };
+    }
+}
+
+fn describe(v: &Value) -> String {
+    match v {
+        Value::Null => "<null>".to_owned(),
+        Value::Bool(_) => "<boolean>".to_owned(),
+        Value::Number(_) => "<number>".to_owned(),
+        Value::String(s) => describe_str(s),
+        Value::Array(arr) => format!("<array, {} items>", arr.len()),
+        Value::Object(obj) => format!("<object, {} properties>", obj.len()),
+    }
+}
+
+fn describe_str(s: &str) -> String {
+    format!("<string, {} chars>", s.chars().count())
+}
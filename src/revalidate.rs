@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+    pointer::JsonPointer,
+    validator::{self, parse_index, Memo},
+    InstanceToken, SchemaIndex, Schemas, ValidationError, ValidationOptions,
+};
+
+impl Schemas {
+    /**
+    Validates `v`, which is the result of applying json `patch` to an
+    instance that was validated earlier with schema identified by
+    `sch_index`, and failed with `previous` error if any.
+
+    `patch` is a JSON Patch as per [rfc6902]. Only subtrees touched by
+    `patch` or by `previous` error are validated again, along with their
+    ancestors. Other subtrees are known to be valid from last validation,
+    and are skipped, unless `$dynamicRef` or `$recursiveRef` is reachable
+    from their subschema, or `unevaluatedProperties` or `unevaluatedItems`
+    needs them to be evaluated. Subtrees evaluated by `not`, `if`, `anyOf`,
+    `oneOf` or `contains` are always validated again, because their failures
+    are not reported in `previous`. If `patch` is not valid, `v` is validated
+    fully.
+
+    This is useful for editors, validating large documents as the user
+    types. The result is same as [`Schemas::validate`], provided that
+    `previous` and `patch` are accurate.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", json!({"items": {"maximum": 10}}))?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+    let mut instance = json!([1, 2, 3]);
+    assert!(schemas.validate(&instance, sch).is_ok());
+    instance[1] = json!(20);
+    let patch = json!([{"op": "replace", "path": "/1", "value": 20}]);
+    let err = schemas.revalidate(&instance, sch, None, &patch).unwrap_err();
+    assert_eq!(err.causes[0].instance_location.to_string(), "/1");
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+
+    [rfc6902]: https://www.rfc-editor.org/rfc/rfc6902
+    */
+    pub fn revalidate<'s, 'v>(
+        &'s self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        previous: Option<&ValidationError>,
+        patch: &Value,
+    ) -> Result<(), ValidationError<'s, 'v>> {
+        let options = ValidationOptions::default();
+        self.revalidate_with(v, sch_index, previous, patch, &options)
+    }
+
+    /**
+    Same as [`Schemas::revalidate`], but uses given `options`
+    for this validation. `previous` error must be reported
+    with same `options`.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn revalidate_with<'s, 'v>(
+        &'s self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        previous: Option<&ValidationError>,
+        patch: &Value,
+        options: &ValidationOptions,
+    ) -> Result<(), ValidationError<'s, 'v>> {
+        let Some(sch) = self.try_get(sch_index) else {
+            panic!("Schemas::revalidate: schema index out of bounds");
+        };
+        if let Some(kind) = validator::instance_limit(v, options) {
+            return Err(sch.limit_error(kind));
+        }
+        let mut memo = Memo::new(options);
+        memo.unchanged = Some(Unchanged::new(v, previous, patch));
+        let mut pending = vec![];
+        let mut result =
+            validator::validate(v, sch, self, options, &mut pending, &mut memo, &mut vec![]);
+        if let Some(&idx) = pending.first() {
+            result = Err(self.uncompiled(idx));
+        }
+        if options.redact {
+            if let Err(e) = &mut result {
+                e.redact();
+            }
+        }
+        result
+    }
+}
+
+// locations in instance, which may have changed since last validation,
+// as trie of their tokens. everything else is known to be valid.
+#[derive(Default)]
+pub(crate) struct Unchanged {
+    dirty: bool, // this location may have changed
+    props: HashMap<String, Unchanged>,
+    items: HashMap<usize, Unchanged>,
+}
+
+impl Unchanged {
+    fn new(v: &Value, previous: Option<&ValidationError>, patch: &Value) -> Self {
+        let mut root = Self::default();
+        if let Some(err) = previous {
+            let mut errors = vec![err];
+            while let Some(err) = errors.pop() {
+                let mut node = &mut root;
+                for tok in &err.instance_location.tokens {
+                    node = match tok {
+                        InstanceToken::Prop(p) => node.child(p),
+                        InstanceToken::Item(i) => node.items.entry(*i).or_default(),
+                    };
+                }
+                node.dirty = true;
+                errors.extend(&err.causes);
+            }
+        }
+        let paths = patch_paths(v, patch).unwrap_or_else(|| vec![String::new()]);
+        for path in paths {
+            let mut node = &mut root;
+            // invalid path marks everything dirty
+            if let Ok(ptr) = JsonPointer::parse(&path) {
+                for tok in ptr.tokens() {
+                    node = node.child(&tok);
+                }
+            }
+            node.dirty = true;
+        }
+        root
+    }
+
+    fn child(&mut self, tok: &str) -> &mut Self {
+        match parse_index(tok) {
+            Some(i) => self.items.entry(i).or_default(),
+            None => self.props.entry(tok.to_owned()).or_default(),
+        }
+    }
+
+    // tells whether value at location `tokens` is unchanged, that is
+    // neither it nor any of its ancestors or descendants is dirty.
+    pub(crate) fn contains(&self, tokens: &[InstanceToken]) -> bool {
+        let mut node = self;
+        for tok in tokens {
+            if node.dirty {
+                return false;
+            }
+            let child = match tok {
+                InstanceToken::Prop(p) => match parse_index(p) {
+                    Some(i) => node.items.get(&i),
+                    None => node.props.get(p.as_ref()),
+                },
+                InstanceToken::Item(i) => node.items.get(i),
+            };
+            let Some(child) = child else {
+                return true;
+            };
+            node = child;
+        }
+        // node exists only if something at or below it is dirty, except root
+        !node.dirty && node.props.is_empty() && node.items.is_empty()
+    }
+}
+
+// returns locations modified by `patch`. None if `patch` is invalid.
+fn patch_paths(v: &Value, patch: &Value) -> Option<Vec<String>> {
+    let mut paths = vec![];
+    // adding or removing array item, shifts items after it
+    let mut push = |path: &str, shifts: bool| {
+        let (parent, _) = path.rsplit_once('/')?;
+        let parent_is_array = v.pointer(parent).is_none_or(Value::is_array);
+        match shifts && parent_is_array {
+            true => paths.push(parent.to_owned()),
+            false => paths.push(path.to_owned()),
+        }
+        Some(())
+    };
+    for op in patch.as_array()? {
+        let path = op.get("path")?.as_str()?;
+        match op.get("op")?.as_str()? {
+            "add" | "remove" | "copy" => push(path, true)?,
+            "replace" => push(path, false)?,
+            "move" => {
+                push(op.get("from")?.as_str()?, true)?;
+                push(path, true)?;
+            }
+            "test" => {}
+            _ => return None,
+        }
+    }
+    Some(paths)
+}
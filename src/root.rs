@@ -91,11 +91,18 @@ impl Root {
         &mut self,
         doc: &Value,
         ptr: &JsonPointer,
+        max_depth: Option<usize>,
     ) -> Result<(), CompileError> {
         let v = ptr.lookup(doc, &self.url)?;
         let base_url = self.base_url(ptr).clone();
-        self.draft
-            .collect_resources(v, &base_url, ptr.clone(), &self.url, &mut self.resources)?;
+        self.draft.collect_resources(
+            v,
+            &base_url,
+            ptr.clone(),
+            &self.url,
+            &mut self.resources,
+            max_depth,
+        )?;
 
         // collect anchors
         if !self.resources.contains_key(ptr) {
@@ -104,7 +111,9 @@ impl Root {
                 self.draft.collect_anchors(v, ptr, res, &self.url)?;
             }
         }
-        Ok(())
+        self.resources
+            .values()
+            .try_for_each(|res| res.check_duplicates(&self.url))
     }
 }
 
@@ -114,6 +123,8 @@ pub(crate) struct Resource {
     pub(crate) id: Url,
     pub(crate) anchors: HashMap<Anchor, JsonPointer>, // anchor => ptr
     pub(crate) dynamic_anchors: HashSet<Anchor>,
+    // anchors found again at another ptr, see Resource::check_duplicates
+    pub(crate) duplicate_anchors: Vec<(Anchor, JsonPointer)>,
 }
 
 impl Resource {
@@ -123,6 +134,20 @@ impl Resource {
             id,
             anchors: HashMap::new(),
             dynamic_anchors: HashSet::new(),
+            duplicate_anchors: vec![],
         }
     }
+
+    // fails with first of duplicate anchors, collected by Draft::collect_anchors
+    pub(crate) fn check_duplicates(&self, url: &Url) -> Result<(), CompileError> {
+        let Some((anchor, ptr2)) = self.duplicate_anchors.first() else {
+            return Ok(());
+        };
+        Err(CompileError::DuplicateAnchor {
+            url: url.as_str().to_owned(),
+            anchor: anchor.to_string(),
+            ptr1: self.anchors[anchor].to_string(),
+            ptr2: ptr2.to_string(),
+        })
+    }
 }
@@ -9,6 +9,9 @@ use url::Url;
 
 pub(crate) struct Roots {
     pub(crate) default_draft: &'static Draft,
+    pub(crate) drafts: HashMap<Url, &'static Draft>,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) aliases: HashMap<Url, Url>, // url => url with same content
     map: HashMap<Url, Root>,
     pub(crate) loader: DefaultUrlLoader,
 }
@@ -17,6 +20,9 @@ impl Roots {
     fn new() -> Self {
         Self {
             default_draft: latest(),
+            drafts: Default::default(),
+            max_depth: None,
+            aliases: Default::default(),
             map: Default::default(),
             loader: DefaultUrlLoader::new(),
         }
@@ -34,7 +40,19 @@ impl Roots {
         self.map.get(url)
     }
 
+    // url of document with same content, that is added first
+    pub(crate) fn canonical(&self, url: Url) -> Url {
+        match self.aliases.get(&url) {
+            Some(same) => same.clone(),
+            None => url,
+        }
+    }
+
     pub(crate) fn resolve_fragment(&mut self, uf: UrlFrag) -> Result<UrlPtr, CompileError> {
+        let uf = UrlFrag {
+            url: self.canonical(uf.url),
+            frag: uf.frag,
+        };
         self.or_load(uf.url.clone())?;
         let Some(root) = self.map.get(&uf.url) else {
             return Err(CompileError::Bug("or_load didn't add".into()));
@@ -51,7 +69,7 @@ impl Roots {
             let doc = self.loader.load(&root.url)?;
             let v = up.ptr.lookup(doc, &up.url)?;
             root.draft.validate(up, v)?;
-            root.add_subschema(doc, &up.ptr)?;
+            root.add_subschema(doc, &up.ptr, self.max_depth)?;
         }
         Ok(())
     }
@@ -67,30 +85,49 @@ impl Roots {
         Ok(())
     }
 
-    pub(crate) fn create_root(&self, url: Url, doc: &Value) -> Result<Root, CompileError> {
+    pub(crate) fn get_draft(&self, url: &Url, doc: &Value) -> Result<&'static Draft, CompileError> {
         let draft = {
             let up = UrlPtr {
                 url: url.clone(),
                 ptr: "".into(),
             };
+            let default_draft = self.drafts.get(url).unwrap_or(&self.default_draft);
             self.loader
-                .get_draft(&up, doc, self.default_draft, HashSet::new())?
+                .get_draft(&up, doc, default_draft, HashSet::new())?
         };
+        if !draft.enabled() {
+            return Err(CompileError::DraftNotEnabled {
+                url: url.to_string(),
+                draft: crate::Draft::from_version(draft.version)
+                    .ok_or(CompileError::Bug("unknown draft".into()))?,
+            });
+        }
+        Ok(draft)
+    }
+
+    pub(crate) fn create_root(&self, url: Url, doc: &Value) -> Result<Root, CompileError> {
+        let draft = self.get_draft(&url, doc)?;
         let vocabs = self.loader.get_meta_vocabs(doc, draft)?;
+        debug!(
+            "{url}: using draft {}, vocabularies {vocabs:?}",
+            draft.version
+        );
         let resources = {
             let mut m = HashMap::default();
-            draft.collect_resources(doc, &url, "".into(), &url, &mut m)?;
+            draft.collect_resources(doc, &url, "".into(), &url, &mut m, self.max_depth)?;
             m
         };
+        resources
+            .values()
+            .try_for_each(|res| res.check_duplicates(&url))?;
 
         if !matches!(url.host_str(), Some("json-schema.org")) {
-            draft.validate(
-                &UrlPtr {
-                    url: url.clone(),
-                    ptr: "".into(),
-                },
-                doc,
-            )?;
+            let up = UrlPtr {
+                url: url.clone(),
+                ptr: "".into(),
+            };
+            draft.validate(&up, doc)?;
+            self.loader.validate_meta(&up, doc)?;
         }
 
         Ok(Root {
@@ -0,0 +1,103 @@
+/*!
+Validation of [simd-json](https://docs.rs/simd-json) values.
+
+Enabled with cargo feature `simd-json`. The validator works on
+`serde_json::Value`, so simd-json values are copied into it before
+validation. This saves writing the conversion, but not its cost.
+
+```
+# use boon::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let mut schemas = Schemas::new();
+let mut compiler = Compiler::new();
+compiler.add_resource("http://a.com/s.json", json!({"items": {"type": "number"}}))?;
+let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+let mut json = br#"[1, 2, 3]"#.to_vec();
+let instance = simd_json::to_borrowed_value(&mut json)?;
+assert!(schemas.validate_simd(&instance, sch).is_ok());
+# Ok(())
+# }
+```
+*/
+
+use serde_json::{Map, Number, Value};
+use simd_json::{prelude::*, BorrowedValue, OwnedValue, StaticNode};
+
+use crate::{SchemaIndex, Schemas, ValidationError};
+
+/// A simd-json value, which can be validated by [`Schemas::validate_simd`].
+///
+/// Implemented for [`OwnedValue`] and [`BorrowedValue`].
+pub trait SimdValue {
+    /// Converts this value into `serde_json::Value`.
+    fn to_serde(&self) -> Value;
+}
+
+impl SimdValue for OwnedValue {
+    fn to_serde(&self) -> Value {
+        match self {
+            Self::Static(n) => static_node(n),
+            Self::String(s) => Value::String(s.clone()),
+            Self::Array(arr) => Value::Array(arr.iter().map(Self::to_serde).collect()),
+            Self::Object(obj) => Value::Object(
+                obj.iter()
+                    .map(|(k, v)| (k.clone(), v.to_serde()))
+                    .collect::<Map<_, _>>(),
+            ),
+        }
+    }
+}
+
+impl SimdValue for BorrowedValue<'_> {
+    fn to_serde(&self) -> Value {
+        match self {
+            Self::Static(n) => static_node(n),
+            Self::String(s) => Value::String(s.to_string()),
+            Self::Array(arr) => Value::Array(arr.iter().map(Self::to_serde).collect()),
+            Self::Object(obj) => Value::Object(
+                obj.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_serde()))
+                    .collect::<Map<_, _>>(),
+            ),
+        }
+    }
+}
+
+fn static_node(n: &StaticNode) -> Value {
+    if let Some(b) = n.as_bool() {
+        Value::Bool(b)
+    } else if let Some(i) = n.as_u64() {
+        Value::Number(i.into())
+    } else if let Some(i) = n.as_i64() {
+        Value::Number(i.into())
+    } else if let Some(f) = n.as_f64() {
+        // NaN and infinity are not valid json
+        Number::from_f64(f).map_or(Value::Null, Value::Number)
+    } else {
+        Value::Null
+    }
+}
+
+impl Schemas {
+    /**
+    Validates simd-json value `v` with schema identified by `sch_index`.
+
+    `v` is converted into `serde_json::Value` before validation,
+    so returned error does not borrow from it.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_simd<V: SimdValue>(
+        &self,
+        v: &V,
+        sch_index: SchemaIndex,
+    ) -> Result<(), ValidationError<'_, 'static>> {
+        let v = v.to_serde();
+        self.validate(&v, sch_index).map_err(|e| e.clone_static())
+    }
+}
@@ -0,0 +1,132 @@
+use std::{cell::RefCell, fmt};
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+
+use crate::{util::quote, InstanceError, SchemaIndex, Schemas};
+
+/**
+Parses json text `s`, failing with [`InstanceError::DuplicateKey`] if
+any object in it has duplicate keys.
+
+`serde_json` silently keeps the last of duplicate keys. Use this in your
+[`UrlLoader`] to reject such schema documents; the error is reported as
+[`CompileError::DuplicateKey`]. see [`Compiler::deny_duplicate_keys`]
+for builtin loaders.
+
+```
+# use boon::*;
+let err = parse_strict(br#"{"a": 1, "b": {"c": 2, "c": 3}}"#).unwrap_err();
+assert!(matches!(err, InstanceError::DuplicateKey { ref key, line: 1, .. } if key == "c"));
+```
+
+[`UrlLoader`]: crate::UrlLoader
+[`CompileError::DuplicateKey`]: crate::CompileError::DuplicateKey
+[`Compiler::deny_duplicate_keys`]: crate::Compiler::deny_duplicate_keys
+*/
+pub fn parse_strict(s: &[u8]) -> Result<Value, InstanceError<'static>> {
+    let duplicate = RefCell::new(None);
+    let mut de = serde_json::Deserializer::from_slice(s);
+    let result = StrictValue(&duplicate)
+        .deserialize(&mut de)
+        .and_then(|v| de.end().map(|_| v));
+    result.map_err(|e| match duplicate.into_inner() {
+        Some(key) => InstanceError::DuplicateKey {
+            key,
+            line: e.line(),
+            column: e.column(),
+        },
+        None => InstanceError::Parse(e),
+    })
+}
+
+impl Schemas {
+    /**
+    Same as [`Schemas::validate_str`], but fails with
+    [`InstanceError::DuplicateKey`] if any object in `s` has
+    duplicate keys. see [`parse_strict`].
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_str_strict(
+        &self,
+        s: &str,
+        sch_index: SchemaIndex,
+    ) -> Result<(), InstanceError<'_>> {
+        let v = parse_strict(s.as_bytes())?;
+        self.validate(&v, sch_index)
+            .map_err(|e| InstanceError::Invalid(e.clone_static()))
+    }
+}
+
+// deserializes Value, recording first duplicate key found
+struct StrictValue<'a>(&'a RefCell<Option<String>>);
+
+impl<'de> DeserializeSeed<'de> for StrictValue<'_> {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for StrictValue<'_> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut arr = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(item) = seq.next_element_seed(StrictValue(self.0))? {
+            arr.push(item);
+        }
+        Ok(Value::Array(arr))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut obj = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if obj.contains_key(&key) {
+                let msg = format!("duplicate key {}", quote(&key));
+                *self.0.borrow_mut() = Some(key);
+                return Err(de::Error::custom(msg));
+            }
+            let value = map.next_value_seed(StrictValue(self.0))?;
+            obj.insert(key, value);
+        }
+        Ok(Value::Object(obj))
+    }
+}
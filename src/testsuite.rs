@@ -0,0 +1,278 @@
+/*!
+Runs [JSON-Schema-Test-Suite](https://github.com/json-schema-org/JSON-Schema-Test-Suite)
+against a [`Compiler`] configuration.
+
+Use it to verify that custom formats, loaders or other compiler
+settings do not break compliance with the specification.
+
+```no_run
+# use boon::{*, testsuite::*};
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+let mut options = Options::new();
+options.skip_file("float-overflow.json");
+options.set_configure(|c: &mut Compiler| {
+    c.register_format_fn("country", |_| Ok(()));
+});
+let report = run("JSON-Schema-Test-Suite", Draft::V2020_12, &options)?;
+for failure in &report.failures {
+    println!("{failure}");
+}
+assert!(report.failures.is_empty());
+# Ok(())
+# }
+```
+*/
+
+use std::{
+    error::Error,
+    fmt::Display,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::{Compiler, Draft, Schemas, UrlLoader};
+
+/// Options to [`run`] the test suite.
+#[derive(Default)]
+pub struct Options {
+    skip: Vec<String>,
+    skip_optional: bool,
+    configure: Option<Box<Configure>>,
+}
+
+type Configure = dyn Fn(&mut Compiler);
+
+impl Options {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips test files named `name`, like `"zeroTerminatedFloats.json"`.
+    pub fn skip_file(&mut self, name: &str) {
+        self.skip.push(name.to_owned());
+    }
+
+    /// Skips tests under `optional` directories.
+    pub fn skip_optional(&mut self) {
+        self.skip_optional = true;
+    }
+
+    /**
+    Calls `configure` on each [`Compiler`] created for a test group,
+    before the group schema is compiled.
+
+    Default draft, loader for suite remotes, and for optional tests,
+    format and content assertions are already set, when `configure`
+    is called.
+    */
+    pub fn set_configure<F>(&mut self, configure: F)
+    where
+        F: Fn(&mut Compiler) + 'static,
+    {
+        self.configure = Some(Box::new(configure));
+    }
+}
+
+/// Outcome of running the test suite.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Number of tests passed
+    pub passed: usize,
+    /// Tests which failed
+    pub failures: Vec<Failure>,
+}
+
+/// A test, which failed.
+#[derive(Debug)]
+pub struct Failure {
+    /// Test file, relative to `tests` directory of the suite
+    pub file: PathBuf,
+    /// Description of test group
+    pub group: String,
+    /// Description of test. Empty, if the group schema failed to compile.
+    pub test: String,
+    /// Whether the test data is expected to be valid
+    pub valid: bool,
+    /// Compile or validation error, if any
+    pub error: Option<String>,
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.file.display(), self.group)?;
+        if !self.test.is_empty() {
+            write!(f, ": {}", self.test)?;
+        }
+        match &self.error {
+            Some(e) if self.test.is_empty() => write!(f, ": compilation failed: {e}"),
+            Some(e) => write!(f, ": want valid, but got: {e}"),
+            None => write!(f, ": want invalid, but got valid"),
+        }
+    }
+}
+
+/**
+Runs the tests for `draft`, from test suite in directory `suite`.
+
+Test files are read from `tests/<draft>` within `suite`, where
+`<draft>` is, for example `draft2020-12` or `draft7`. Remote schemas
+referenced by tests are loaded from `remotes`.
+
+# Errors
+
+returns error, if test files cannot be read or parsed. Tests which
+fail, including those whose schema fails to compile, are reported
+in [`Report::failures`].
+*/
+pub fn run(
+    suite: impl AsRef<Path>,
+    draft: Draft,
+    options: &Options,
+) -> Result<Report, Box<dyn Error>> {
+    let suite = suite.as_ref();
+    if !suite.is_dir() {
+        Err(format!("test suite {} does not exist", suite.display()))?;
+    }
+    let dir = match draft {
+        Draft::V4 => "draft4",
+        Draft::V6 => "draft6",
+        Draft::V7 => "draft7",
+        Draft::V2019_09 => "draft2019-09",
+        Draft::V2020_12 => "draft2020-12",
+    };
+    let runner = Runner {
+        suite,
+        draft,
+        options,
+    };
+    let mut report = Report::default();
+    let prefix = suite.join("tests");
+    if prefix.join(dir).is_dir() {
+        runner.run_dir(&prefix, Path::new(dir), &mut report)?;
+    }
+    Ok(report)
+}
+
+struct Runner<'a> {
+    suite: &'a Path,
+    draft: Draft,
+    options: &'a Options,
+}
+
+impl Runner<'_> {
+    fn run_dir(
+        &self,
+        prefix: &Path,
+        dir: &Path,
+        report: &mut Report,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut entries = prefix
+            .join(dir)
+            .read_dir()?
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let path = dir.join(entry.file_name());
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                if !(self.options.skip_optional && entry.file_name() == "optional") {
+                    self.run_dir(prefix, &path, report)?;
+                }
+            } else if file_type.is_file() {
+                let name = entry.file_name();
+                if !self.options.skip.iter().any(|n| name == n.as_str()) {
+                    self.run_file(prefix, &path, report)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn run_file(
+        &self,
+        prefix: &Path,
+        path: &Path,
+        report: &mut Report,
+    ) -> Result<(), Box<dyn Error>> {
+        let optional = path.components().any(|comp| comp.as_os_str() == "optional");
+        let groups: Value = serde_json::from_reader(File::open(prefix.join(path))?)?;
+        let Value::Array(groups) = groups else {
+            Err(format!("{}: want array of test groups", path.display()))?
+        };
+
+        let url = "http://testsuite.com/schema.json";
+        for group in groups {
+            let (Some(description), Some(schema), Some(Value::Array(tests))) = (
+                group.get("description").and_then(Value::as_str),
+                group.get("schema"),
+                group.get("tests"),
+            ) else {
+                Err(format!("{}: invalid test group", path.display()))?
+            };
+            let failure = |test: &str, valid, error| Failure {
+                file: path.to_owned(),
+                group: description.to_owned(),
+                test: test.to_owned(),
+                valid,
+                error,
+            };
+
+            let mut schemas = Schemas::new();
+            let mut compiler = Compiler::new();
+            compiler.set_default_draft(self.draft);
+            if optional {
+                compiler.enable_format_assertions();
+                compiler.enable_content_assertions();
+            }
+            compiler.use_loader(Box::new(RemotesLoader(self.suite.join("remotes"))));
+            if let Some(configure) = &self.options.configure {
+                configure(&mut compiler);
+            }
+            let compiled = compiler
+                .add_resource(url, schema.clone())
+                .and_then(|_| compiler.compile(url, &mut schemas));
+            let sch = match compiled {
+                Ok(sch) => sch,
+                Err(e) => {
+                    report.failures.push(failure("", true, Some(e.to_string())));
+                    continue;
+                }
+            };
+
+            for test in tests {
+                let (Some(description), Some(data), Some(valid)) = (
+                    test.get("description").and_then(Value::as_str),
+                    test.get("data"),
+                    test.get("valid").and_then(Value::as_bool),
+                ) else {
+                    Err(format!("{}: invalid test", path.display()))?
+                };
+                match schemas.validate(data, sch) {
+                    Ok(()) if !valid => report.failures.push(failure(description, valid, None)),
+                    Err(e) if valid => {
+                        let e = Some(e.to_string());
+                        report.failures.push(failure(description, valid, e));
+                    }
+                    _ => report.passed += 1,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// serves suite's remotes directory at http://localhost:1234/
+struct RemotesLoader(PathBuf);
+
+impl UrlLoader for RemotesLoader {
+    fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+        if let Some(path) = url.strip_prefix("http://localhost:1234/") {
+            let file = File::open(self.0.join(path))?;
+            let json: Value = serde_json::from_reader(file)?;
+            return Ok(json);
+        }
+        Err("no internet")?
+    }
+}
@@ -10,44 +10,25 @@ use percent_encoding::{percent_decode_str, AsciiSet, CONTROLS};
 use serde_json::Value;
 use url::Url;
 
-use crate::CompileError;
+use crate::{pointer, CompileError};
 
 // --
 
+// raw json-pointer of schema locations, unlike pointer::JsonPointer which
+// is validated on construction. fragments from $ref/$id are stored unchecked,
+// and are reported as CompileError with document url only on lookup.
+// lookup also accepts array indexes with leading zeros, which older
+// releases resolved.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(crate) struct JsonPointer(pub(crate) String);
 
 impl JsonPointer {
-    pub(crate) fn escape(token: &str) -> Cow<str> {
-        const SPECIAL: [char; 2] = ['~', '/'];
-        if token.contains(SPECIAL) {
-            token.replace('~', "~0").replace('/', "~1").into()
-        } else {
-            token.into()
-        }
+    pub(crate) fn escape(token: &str) -> Cow<'_, str> {
+        pointer::escape(token)
     }
 
-    pub(crate) fn unescape(mut tok: &str) -> Result<Cow<str>, ()> {
-        let Some(mut tilde) = tok.find('~') else {
-            return Ok(Cow::Borrowed(tok));
-        };
-        let mut s = String::with_capacity(tok.len());
-        loop {
-            s.push_str(&tok[..tilde]);
-            tok = &tok[tilde + 1..];
-            match tok.chars().next() {
-                Some('1') => s.push('/'),
-                Some('0') => s.push('~'),
-                _ => return Err(()),
-            }
-            tok = &tok[1..];
-            let Some(i) = tok.find('~') else {
-                s.push_str(tok);
-                break;
-            };
-            tilde = i;
-        }
-        Ok(Cow::Owned(s))
+    pub(crate) fn unescape(tok: &str) -> Result<Cow<'_, str>, ()> {
+        pointer::unescape(tok).map_err(|_| ())
     }
 
     pub(crate) fn lookup<'a>(
@@ -297,10 +278,11 @@ impl Display for UrlPtr {
 
 // --
 
-pub(crate) fn is_integer(v: &Value) -> bool {
+// if strict, floats with zero fractional part are not integers
+pub(crate) fn is_integer(v: &Value, strict: bool) -> bool {
     match v {
         Value::Number(n) => {
-            n.is_i64() || n.is_u64() || n.as_f64().filter(|n| n.fract() == 0.0).is_some()
+            n.is_i64() || n.is_u64() || !strict && n.as_f64().filter(|n| n.fract() == 0.0).is_some()
         }
         _ => false,
     }
@@ -332,7 +314,7 @@ where
         .join(sep)
 }
 
-pub(crate) fn escape(token: &str) -> Cow<str> {
+pub(crate) fn escape(token: &str) -> Cow<'_, str> {
     JsonPointer::escape(token)
 }
 
@@ -387,6 +369,39 @@ pub(crate) fn equals(v1: &Value, v2: &Value) -> bool {
     }
 }
 
+// levenshtein distance between `a` and `b`
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+// returns upto 3 candidates which are similar to `target`, closest first
+pub(crate) fn similar<I>(target: &str, candidates: I) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let max = (target.chars().count() / 3).max(2);
+    let mut v: Vec<(usize, String)> = candidates
+        .into_iter()
+        .filter(|c| c != target)
+        .map(|c| (edit_distance(target, &c), c))
+        .filter(|(d, _)| *d <= max)
+        .collect();
+    v.sort();
+    v.dedup();
+    v.into_iter().take(3).map(|(_, c)| c).collect()
+}
+
 pub(crate) fn duplicates(arr: &Vec<Value>) -> Option<(usize, usize)> {
     match arr.as_slice() {
         [e0, e1] => {
@@ -447,6 +462,8 @@ impl Hash for HashedValue<'_> {
             Value::Bool(ref b) => b.hash(state),
             Value::Number(ref num) => {
                 if let Some(num) = num.as_f64() {
+                    // 0.0 and -0.0 are equal, but have different bits
+                    let num = if num == 0.0 { 0.0 } else { num };
                     num.to_bits().hash(state);
                 } else if let Some(num) = num.as_u64() {
                     num.hash(state);
@@ -476,6 +493,82 @@ impl Hash for HashedValue<'_> {
     }
 }
 
+// stable hash --
+
+// FNV-1a, whose result does not change across runs, versions and platforms.
+pub(crate) struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl StableHasher {
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn write_u64(&mut self, n: u64) {
+        self.write(&n.to_le_bytes());
+    }
+
+    pub(crate) fn write_value(&mut self, v: &Value) {
+        match v {
+            Value::Null => self.write(b"n"),
+            Value::Bool(b) => self.write(if *b { b"t" } else { b"f" }),
+            Value::Number(num) => {
+                // 1 and 1.0 are equal
+                self.write(b"d");
+                match num.as_f64() {
+                    Some(f) if f == f.trunc() && f.abs() < 9e15 => {
+                        self.write(&(f as i64).to_le_bytes())
+                    }
+                    Some(f) => self.write(&f.to_le_bytes()),
+                    None => self.write(num.to_string().as_bytes()),
+                }
+            }
+            Value::String(s) => {
+                self.write(b"s");
+                self.write_u64(s.len() as u64);
+                self.write(s.as_bytes());
+            }
+            Value::Array(arr) => {
+                self.write(b"a");
+                self.write_u64(arr.len() as u64);
+                for item in arr {
+                    self.write_value(item);
+                }
+            }
+            Value::Object(obj) => {
+                // order of keys may vary with serde_json features
+                let mut keys: Vec<_> = obj.keys().collect();
+                keys.sort();
+                self.write(b"o");
+                self.write_u64(keys.len() as u64);
+                for k in keys {
+                    self.write_u64(k.len() as u64);
+                    self.write(k.as_bytes());
+                    self.write_value(&obj[k]);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+pub(crate) fn stable_hash(v: &Value) -> u64 {
+    let mut h = StableHasher::default();
+    h.write_value(v);
+    h.finish()
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -484,6 +577,17 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("/$defs/nmae", "/$defs/name"), 2);
+        assert_eq!(
+            similar("/$defs/nmae", ["/$defs/name".into(), "/$defs/age".into()]),
+            vec!["/$defs/name", "/$defs/age"]
+        );
+    }
+
     #[test]
     fn test_quote() {
         assert_eq!(quote(r#"abc"def'ghi"#), r#"'abc"def\'ghi'"#);
@@ -542,4 +646,21 @@ mod tests {
         assert!(seen.insert(HashedValue(&v1), 1).is_none());
         assert!(seen.insert(HashedValue(&v2), 1).is_some());
     }
+
+    #[test]
+    fn test_stable_hash() {
+        let v1 = json!({"a": [1, "x", null], "b": {"c": true}});
+        let v2 = json!({"b": {"c": true}, "a": [1.0, "x", null]});
+        assert_eq!(stable_hash(&v1), stable_hash(&v2));
+        assert_ne!(
+            stable_hash(&json!(["ab", "c"])),
+            stable_hash(&json!(["a", "bc"]))
+        );
+        assert_ne!(stable_hash(&json!("1")), stable_hash(&json!(1)));
+        // must not change across releases
+        assert_eq!(
+            stable_hash(&json!({"type": "string"})),
+            18184305498245654631
+        );
+    }
 }
@@ -1,8 +1,14 @@
-use std::{borrow::Cow, cmp::min, collections::HashSet, fmt::Write};
+use std::{
+    borrow::Cow,
+    cmp::min,
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    time::Instant,
+};
 
 use serde_json::{Map, Value};
 
-use crate::{util::*, *};
+use crate::{content::json_depth, revalidate::Unchanged, util::*, *};
 
 macro_rules! prop {
     ($prop:expr) => {
@@ -20,25 +26,97 @@ pub(crate) fn validate<'s, 'v>(
     v: &'v Value,
     schema: &'s Schema,
     schemas: &'s Schemas,
+    options: &ValidationOptions,
+    pending: &mut Vec<SchemaIndex>,
+    memo: &mut Memo<'v>,
+    warnings: &mut Vec<ValidationError<'s, 'v>>,
 ) -> Result<(), ValidationError<'s, 'v>> {
-    let scope = Scope {
-        sch: schema.idx,
-        ref_kw: None,
-        vid: 0,
-        parent: None,
-    };
-    let mut vloc = Vec::with_capacity(8);
-    let result = Validator {
+    validate_in_scope(v, schema, &[], schemas, options, pending, memo, warnings)
+}
+
+// same as validate, but dynamic scope is seeded with `outer` schemas,
+// outermost first. see Schemas::validate_value_with_context
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn validate_in_scope<'s, 'v>(
+    v: &'v Value,
+    schema: &'s Schema,
+    outer: &[SchemaIndex],
+    schemas: &'s Schemas,
+    options: &ValidationOptions,
+    pending: &mut Vec<SchemaIndex>,
+    memo: &mut Memo<'v>,
+    warnings: &mut Vec<ValidationError<'s, 'v>>,
+) -> Result<(), ValidationError<'s, 'v>> {
+    validate_root(
+        v, schema, outer, schemas, options, pending, memo, warnings, false,
+    )
+    .map(|_| ())
+}
+
+// validates `v` and returns locations of values evaluated.
+pub(crate) fn evaluate<'s, 'v>(
+    v: &'v Value,
+    schema: &'s Schema,
+    schemas: &'s Schemas,
+    options: &ValidationOptions,
+    pending: &mut Vec<SchemaIndex>,
+    memo: &mut Memo<'v>,
+) -> Result<Vec<InstanceLocation<'v>>, ValidationError<'s, 'v>> {
+    let mut warnings = vec![];
+    let uneval = validate_root(
         v,
-        vloc: &mut vloc,
         schema,
+        &[],
         schemas,
-        scope,
-        uneval: Uneval::from(v, schema, false),
-        errors: vec![],
-        bool_result: false,
-    }
-    .validate();
+        options,
+        pending,
+        memo,
+        &mut warnings,
+        true,
+    )?;
+    let mut evaluated = uneval.evaluated.unwrap_or_default();
+    let mut seen = HashSet::new();
+    evaluated.retain(|loc| seen.insert(loc.clone()));
+    Ok(evaluated)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn validate_root<'s, 'v>(
+    v: &'v Value,
+    schema: &'s Schema,
+    outer: &[SchemaIndex],
+    schemas: &'s Schemas,
+    options: &ValidationOptions,
+    pending: &mut Vec<SchemaIndex>,
+    memo: &mut Memo<'v>,
+    warnings: &mut Vec<ValidationError<'s, 'v>>,
+    track: bool,
+) -> Result<Uneval<'v>, ValidationError<'s, 'v>> {
+    let mut vloc = Vec::with_capacity(8);
+    let result = with_outer_scope(outer, None, |parent| {
+        let scope = Scope {
+            sch: schema.idx,
+            ref_kw: None,
+            vid: 0,
+            parent,
+        };
+        Validator {
+            v,
+            vloc: &mut vloc,
+            options,
+            pending,
+            memo,
+            warnings,
+            schema,
+            schemas,
+            scope,
+            uneval: Uneval::from(v, schema, false, track),
+            errors: vec![],
+            bool_result: false,
+            reported: true,
+        }
+        .validate()
+    });
     match result {
         Err(err) => {
             let mut e = ValidationError {
@@ -54,7 +132,27 @@ pub(crate) fn validate<'s, 'v>(
             }
             Err(e)
         }
-        Ok(_) => Ok(()),
+        Ok(uneval) => Ok(uneval),
+    }
+}
+
+// calls `f` with scope chain of `outer` schemas, outermost first.
+fn with_outer_scope<R>(
+    outer: &[SchemaIndex],
+    parent: Option<&Scope>,
+    f: impl FnOnce(Option<&Scope>) -> R,
+) -> R {
+    match outer.split_first() {
+        None => f(parent),
+        Some((&sch, rest)) => {
+            let scope = Scope {
+                sch,
+                ref_kw: None,
+                vid: OUTER_VID,
+                parent,
+            };
+            with_outer_scope(rest, Some(&scope), f)
+        }
     }
 }
 
@@ -83,19 +181,110 @@ macro_rules! kind {
 struct Validator<'v, 's, 'd, 'e> {
     v: &'v Value,
     vloc: &'e mut Vec<InstanceToken<'v>>,
+    options: &'e ValidationOptions,
+    pending: &'e mut Vec<SchemaIndex>, // schemas hit with uncompiled `$ref`
+    memo: &'e mut Memo<'v>,
+    warnings: &'e mut Vec<ValidationError<'s, 'v>>, // see Compiler::enable_schema_hints
     schema: &'s Schema,
     schemas: &'s Schemas,
     scope: Scope<'d>,
     uneval: Uneval<'v>,
     errors: Vec<ValidationError<'s, 'v>>,
     bool_result: bool, // is interested to know valid or not (but not actuall error)
+    reported: bool,    // failures end up in returned error, see Schemas::revalidate
 }
 
+// with feature `stacker`, validation runs on heap allocated stack
+// segments once remaining stack is below RED_ZONE, so that deeply
+// nested instances or schemas cannot overflow the stack.
+#[cfg(feature = "stacker")]
+const RED_ZONE: usize = 128 * 1024;
+#[cfg(feature = "stacker")]
+const STACK_SIZE: usize = 2 * 1024 * 1024;
+
+// errors deeper than this in instance, are collapsed into single error
+// at this depth. each error holds copy of its location, so nesting them
+// in deep instances would take quadratic memory.
+const MAX_ERROR_DEPTH: usize = 256;
+
 impl<'v, 's> Validator<'v, 's, '_, '_> {
-    fn validate(mut self) -> Result<Uneval<'v>, ValidationError<'s, 'v>> {
+    fn validate(self) -> Result<Uneval<'v>, ValidationError<'s, 'v>> {
+        #[cfg(feature = "stacker")]
+        return stacker::maybe_grow(RED_ZONE, STACK_SIZE, || self.validate_traced());
+        #[cfg(not(feature = "stacker"))]
+        self.validate_traced()
+    }
+
+    fn validate_traced(self) -> Result<Uneval<'v>, ValidationError<'s, 'v>> {
+        let Some(tracer) = &self.options.tracer else {
+            return self.validate_hooked();
+        };
+        let start = Instant::now();
+        let schema_url = self.schema.loc.as_str();
+        let instance_location = self.instance_location();
+        let keywords = keyword_count(self.schema, self.v);
+        let result = self.validate_hooked();
+        tracer(&Trace {
+            schema_url,
+            instance_location: &instance_location,
+            keywords,
+            elapsed: start.elapsed(),
+            valid: result.is_ok(),
+        });
+        result
+    }
+
+    #[cfg(not(feature = "hooks"))]
+    fn validate_hooked(self) -> Result<Uneval<'v>, ValidationError<'s, 'v>> {
+        self.validate_keywords()
+    }
+
+    // see KeywordHook
+    #[cfg(feature = "hooks")]
+    fn validate_hooked(mut self) -> Result<Uneval<'v>, ValidationError<'s, 'v>> {
+        let Some(hook) = self.options.keyword_hook.clone() else {
+            return self.validate_keywords();
+        };
+        let s = self.schema;
+        let keywords = applicable_keywords(s, self.v);
+        if keywords.is_empty() {
+            return self.validate_keywords();
+        }
+        let instance_location = self.instance_location();
+        let eval = |keyword| crate::KeywordEval {
+            schema_url: &s.loc,
+            keyword,
+            instance_location: &instance_location,
+        };
+        for &kw in &keywords {
+            hook.before(&eval(kw));
+        }
+        // hook must see result of every keyword
+        self.bool_result = false;
+        let result = self.validate_keywords();
+        let failed = result.as_ref().err().map(|e| failed_keywords(s, e));
+        for &kw in &keywords {
+            let valid = match &failed {
+                None => true,
+                Some(None) => false,
+                Some(Some(failed)) => !failed.contains(&kw),
+            };
+            hook.after(&eval(kw), valid);
+        }
+        result
+    }
+
+    fn validate_keywords(mut self) -> Result<Uneval<'v>, ValidationError<'s, 'v>> {
         let s = self.schema;
         let v = self.v;
 
+        // errors are needed to tell warnings apart
+        let bool_result = self.bool_result;
+        let may_warn = !s.warn_keywords.is_empty() || self.options.has_warnings();
+        if may_warn {
+            self.bool_result = false;
+        }
+
         // boolean --
         if let Some(b) = s.boolean {
             return match b {
@@ -117,34 +306,61 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
         // type --
         if !s.types.is_empty() {
             let v_type = Type::of(v);
-            let matched =
-                s.types.contains(v_type) || (s.types.contains(Type::Integer) && is_integer(v));
+            let matched = s.types.contains(v_type)
+                || (s.types.contains(Type::Integer) && is_integer(v, s.strict_integer));
             if !matched {
-                return Err(self.error(kind!(Type, v_type, s.types)));
+                self.fail(kind!(Type, v_type, s.types))?;
             }
         }
 
         // constant --
         if let Some(c) = &s.constant {
             if !equals(v, c) {
-                return Err(self.error(kind!(Const, want: c)));
+                self.fail(kind!(Const, want: c))?;
             }
         }
 
         // enum --
-        if let Some(Enum { types, values }) = &s.enum_ {
-            if !types.contains(Type::of(v)) || !values.iter().any(|e| equals(e, v)) {
-                return Err(self.error(kind!(Enum, want: values)));
+        if let Some(e) = &s.enum_ {
+            if !e.contains(v) {
+                self.fail(kind!(Enum, want: &e.values))?;
             }
         }
 
         // format --
-        if let Some(format) = &s.format {
+        if let Some(format) = s.format.as_ref().and_then(|f| self.options.format(f)) {
             if let Err(e) = (format.func)(v) {
-                self.add_error(kind!(Format, Cow::Borrowed(v), format.name, e));
+                self.add_error(kind!(Format, Cow::Borrowed(v), format.name, e.into()));
+            }
+        }
+        if let Some(format) = s
+            .format_annotation
+            .as_ref()
+            .and_then(|f| self.options.format(f))
+        {
+            let checked = self.options.severities.contains_key("format");
+            let tracer = &self.options.format_annotation_tracer;
+            if checked || tracer.is_some() {
+                let result = (format.func)(v);
+                if let Some(tracer) = tracer {
+                    tracer(&FormatAnnotation {
+                        schema_url: &s.loc,
+                        instance_location: &self.instance_location(),
+                        format: format.name,
+                        valid: result.is_ok(),
+                    });
+                }
+                if let (true, Err(e)) = (checked, result) {
+                    self.add_error(kind!(Format, Cow::Borrowed(v), format.name, e.into()));
+                }
             }
         }
 
+        // deprecated --
+        if s.deprecated && self.options.severities.contains_key("deprecated") {
+            self.add_error(kind!(Deprecated));
+        }
+
         // $ref --
         if let Some(ref_) = s.ref_ {
             let result = self.validate_ref(ref_, "$ref");
@@ -152,6 +368,11 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
                 return result.map(|_| self.uneval);
             }
             self.errors.extend(result.err());
+        } else if s.lazy_ref.is_some() {
+            self.pending.push(s.idx);
+            if s.draft_version < 2019 {
+                return Ok(self.uneval);
+            }
         }
 
         // type specific validations --
@@ -173,6 +394,29 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
             }
         }
 
+        if may_warn {
+            let (warnings, errors) = std::mem::take(&mut self.errors)
+                .into_iter()
+                .partition(|e| e.schema_url == s.loc && self.is_warning(&e.kind));
+            self.errors = errors;
+            self.warnings.extend::<Vec<_>>(warnings);
+            self.bool_result = bool_result;
+        }
+
+        if let Some(ErrorMessage::All(message)) = &s.error_message {
+            if !self.errors.is_empty() && !self.bool_result {
+                return Err(ValidationError {
+                    schema_url: &s.loc,
+                    instance_location: self.instance_location(),
+                    kind: ErrorKind::Custom {
+                        message,
+                        kind: Box::new(kind!(Group)),
+                    },
+                    causes: vec![],
+                });
+            }
+        }
+
         match self.errors.len() {
             0 => Ok(self.uneval),
             1 => Err(self.errors.remove(0)),
@@ -290,14 +534,28 @@ impl<'v> Validator<'v, '_, '_, '_> {
 
         // propertyNames --
         if let Some(sch) = &s.property_names {
+            let names = self.schemas.get(*sch);
             for pname in obj.keys() {
-                let v = Value::String(pname.to_owned());
-                if let Err(mut e) = self.schemas.validate(&v, *sch) {
-                    e.schema_url = &s.loc;
-                    e.kind = ErrorKind::PropertyName {
-                        prop: pname.to_owned(),
-                    };
-                    self.errors.push(e.clone_static());
+                let causes = if names.name_only {
+                    // errors borrow pname, no need to clone them
+                    name_errors(names, pname)
+                } else {
+                    let v = Value::String(pname.to_owned());
+                    match self.schemas.validate(&v, *sch) {
+                        Ok(_) => vec![],
+                        Err(_) if self.bool_result => vec![self.error(kind!(Group))],
+                        Err(e) => e.clone_static().causes,
+                    }
+                };
+                if !causes.is_empty() {
+                    self.errors.push(ValidationError {
+                        schema_url: &s.loc,
+                        instance_location: InstanceLocation::new(),
+                        kind: ErrorKind::PropertyName {
+                            prop: pname.to_owned(),
+                        },
+                        causes,
+                    });
                 }
             }
         }
@@ -414,6 +672,8 @@ impl<'v> Validator<'v, '_, '_, '_> {
             let mut matched = vec![];
             let mut errors = vec![];
 
+            // errors are dropped, if enough items match
+            let reported = std::mem::replace(&mut self.reported, false);
             for (i, item) in arr.iter().enumerate() {
                 if let Err(e) = self.validate_val(*sch, item, item!(i)) {
                     errors.push(e);
@@ -424,6 +684,7 @@ impl<'v> Validator<'v, '_, '_, '_> {
                     }
                 }
             }
+            self.reported = reported;
 
             // minContains --
             if let Some(min) = s.min_contains {
@@ -453,7 +714,7 @@ impl<'v> Validator<'v, '_, '_, '_> {
 
         // minLength --
         if let Some(min) = s.min_length {
-            let len = len.get_or_insert_with(|| str.chars().count());
+            let len = len.get_or_insert_with(|| self.options.string_length.of(str));
             if *len < min {
                 self.add_error(kind!(MinLength, *len, min));
             }
@@ -461,7 +722,7 @@ impl<'v> Validator<'v, '_, '_, '_> {
 
         // maxLength --
         if let Some(max) = s.max_length {
-            let len = len.get_or_insert_with(|| str.chars().count());
+            let len = len.get_or_insert_with(|| self.options.string_length.of(str));
             if *len > max {
                 self.add_error(kind!(MaxLength, *len, max));
             }
@@ -487,29 +748,72 @@ impl<'v> Validator<'v, '_, '_, '_> {
                     decoded = None;
                     self.add_error(ErrorKind::ContentEncoding {
                         want: decoder.name,
-                        err,
+                        err: err.into(),
                     })
                 }
             }
         }
 
+        // content size --
+        let kw = match (&s.content_encoding, &s.content_media_type) {
+            (Some(_), _) => Some("contentEncoding"),
+            (None, Some(_)) => Some("contentMediaType"),
+            _ => None,
+        };
+        if let (Some(kw), Some(max), Some(bytes)) = (kw, self.options.max_content_size, &decoded) {
+            if bytes.len() > max {
+                self.add_error(content_limit(kw, "size", bytes.len(), max));
+                decoded = None;
+            }
+        }
+
+        // json content depth, before deserializing --
+        if let (Some(max), Some(mt), Some(bytes)) = (
+            self.options.max_content_depth,
+            &s.content_media_type,
+            &decoded,
+        ) {
+            if mt.name == "application/json" && s.content_schema.is_some() {
+                let depth = json_depth(bytes);
+                if depth > max {
+                    self.add_error(content_limit("contentMediaType", "depth", depth, max));
+                    decoded = None;
+                }
+            }
+        }
+
         // contentMediaType --
         let mut deserialized = None;
         if let (Some(mt), Some(decoded)) = (&s.content_media_type, decoded) {
             match (mt.func)(decoded.as_ref(), s.content_schema.is_some()) {
                 Ok(des) => deserialized = des,
                 Err(e) => {
-                    self.add_error(kind!(ContentMediaType, decoded.into(), mt.name, e));
+                    self.add_error(kind!(ContentMediaType, decoded.into(), mt.name, e.into()));
                 }
             }
         }
 
+        // content depth of other media types, after deserializing --
+        let json = s.content_media_type.as_ref().map(|mt| mt.name) == Some("application/json");
+        if let (Some(max), Some(v), false) = (self.options.max_content_depth, &deserialized, json) {
+            let (depth, _) = measure(v);
+            if depth > max {
+                self.add_error(content_limit("contentMediaType", "depth", depth, max));
+                deserialized = None;
+            }
+        }
+
         // contentSchema --
         if let (Some(sch), Some(v)) = (s.content_schema, deserialized) {
-            if let Err(mut e) = self.schemas.validate(&v, sch) {
-                e.schema_url = &s.loc;
-                e.kind = kind!(ContentSchema);
-                self.errors.push(e.clone_static());
+            if let Err(e) = self.schemas.validate_with(&v, sch, self.options) {
+                // errors borrow deserialized value. clone them,
+                // only if they are reported.
+                let mut err = self.error(kind!(ContentSchema));
+                if !self.bool_result {
+                    err.instance_location = InstanceLocation::new();
+                    err.causes = e.clone_static().causes;
+                }
+                self.errors.push(err);
             }
         }
     }
@@ -577,10 +881,12 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
         }
 
         // $recursiveRef --
-        if let Some(mut sch) = s.recursive_ref {
+        if let Some(initial) = s.recursive_ref {
+            let mut sch = initial;
             if self.schemas.get(sch).recursive_anchor {
                 sch = self.resolve_recursive_anchor(sch);
             }
+            self.trace_dynamic_ref("$recursiveRef", initial, sch);
             add_err!(self.validate_ref(sch, "$recursiveRef"));
         }
 
@@ -594,6 +900,7 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
                     sch = self.resolve_dynamic_anchor(anchor, sch);
                 }
             }
+            self.trace_dynamic_ref("$dynamicRef", dref.sch, sch);
             add_err!(self.validate_ref(sch, "$dynamicRef"));
         }
     }
@@ -616,6 +923,35 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
         Ok(())
     }
 
+    fn trace_dynamic_ref(
+        &self,
+        keyword: &'static str,
+        initial: SchemaIndex,
+        resolved: SchemaIndex,
+    ) {
+        let Some(tracer) = &self.options.dynamic_ref_tracer else {
+            return;
+        };
+        let mut scope = vec![];
+        let mut scp = Some(&self.scope);
+        while let Some(s) = scp {
+            let res = &self.schemas.get(self.schemas.get(s.sch).resource).loc;
+            if scope.last() != Some(&res.as_str()) {
+                scope.push(res.as_str());
+            }
+            scp = s.parent;
+        }
+        scope.reverse();
+        tracer(&DynamicRefTrace {
+            keyword,
+            schema_url: &self.schema.loc,
+            instance_location: &self.instance_location(),
+            initial: &self.schemas.get(initial).loc,
+            scope,
+            resolved: &self.schemas.get(resolved).loc,
+        });
+    }
+
     fn resolve_recursive_anchor(&self, fallback: SchemaIndex) -> SchemaIndex {
         let mut sch = fallback;
         let mut scope = &self.scope;
@@ -667,7 +1003,16 @@ impl Validator<'_, '_, '_, '_> {
         // not --
         if let Some(not) = s.not {
             if self._validate_self(not, None, true).is_ok() {
-                self.add_error(kind!(Not));
+                let not = self.schemas.get(not);
+                let matched = if self.bool_result {
+                    vec![]
+                } else {
+                    constraints(not)
+                };
+                self.add_error(ErrorKind::Not {
+                    url: &not.loc,
+                    matched,
+                });
             }
         }
 
@@ -688,30 +1033,42 @@ impl Validator<'_, '_, '_, '_> {
         }
 
         // anyOf --
-        if !s.any_of.is_empty() {
+        // for uneval, all schemas must be checked
+        let tagged = (s.discriminator.as_ref()).filter(|d| d.any_of && self.uneval.is_empty());
+        if let Some(i) = tagged.and_then(|d| self.discriminated(d, &s.any_of)) {
+            add_err!(self.validate_self(s.any_of[i]));
+        } else if !s.any_of.is_empty() {
             let mut matched = false;
             let mut errors = vec![];
+            // errors are dropped, if any subschema matches
+            let reported = std::mem::replace(&mut self.reported, false);
             for sch in &s.any_of {
                 match self.validate_self(*sch) {
                     Ok(_) => {
                         matched = true;
                         // for uneval, all schemas must be checked
-                        if self.uneval.is_empty() {
+                        if self.uneval.is_empty() && self.uneval.evaluated.is_none() {
                             break;
                         }
                     }
                     Err(e) => errors.push(e),
                 }
             }
+            self.reported = reported;
             if !matched {
                 self.add_errors(errors, kind!(AnyOf));
             }
         }
 
         // oneOf --
-        if !s.one_of.is_empty() {
+        let tagged = s.discriminator.as_ref();
+        if let Some(i) = tagged.and_then(|d| self.discriminated(d, &s.one_of)) {
+            add_err!(self.validate_self(s.one_of[i]));
+        } else if !s.one_of.is_empty() {
             let mut matched = None;
             let mut errors = vec![];
+            // errors are dropped, if any subschema matches
+            let reported = std::mem::replace(&mut self.reported, false);
             for (i, sch) in s.one_of.iter().enumerate() {
                 if let Err(e) = self._validate_self(*sch, None, matched.is_some()) {
                     if matched.is_none() {
@@ -727,6 +1084,7 @@ impl Validator<'_, '_, '_, '_> {
                     }
                 }
             }
+            self.reported = reported;
             if matched.is_none() {
                 self.add_errors(errors, ErrorKind::OneOf(None));
             }
@@ -760,24 +1118,22 @@ impl Validator<'_, '_, '_, '_> {
 
         // unevaluatedProperties --
         if let (Some(sch), Value::Object(obj)) = (s.unevaluated_properties, v) {
-            let uneval = std::mem::take(&mut self.uneval);
-            for pname in &uneval.props {
+            let props = std::mem::take(&mut self.uneval.props);
+            for pname in &props {
                 if let Some(pvalue) = obj.get(*pname) {
                     add_err!(self.validate_val(sch, pvalue, prop!(pname)));
                 }
             }
-            self.uneval.props.clear();
         }
 
         // unevaluatedItems --
         if let (Some(sch), Value::Array(arr)) = (s.unevaluated_items, v) {
-            let uneval = std::mem::take(&mut self.uneval);
-            for i in &uneval.items {
+            let items = std::mem::take(&mut self.uneval.items);
+            for i in &items {
                 if let Some(pvalue) = arr.get(*i) {
                     add_err!(self.validate_val(sch, pvalue, item!(*i)));
                 }
             }
-            self.uneval.items.clear();
         }
     }
 }
@@ -795,20 +1151,92 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
         } else {
             self.vloc[self.scope.vid] = token;
         }
+        let track = self.uneval.evaluated.is_some();
+        if !track && self.is_unchanged(sch) {
+            return Ok(());
+        }
         let scope = self.scope.child(sch, None, self.scope.vid + 1);
         let schema = &self.schemas.get(sch);
-        Validator {
+        let memoize = !track && self.memo.applies(sch, v, self.schemas);
+        if memoize {
+            match self.memo.results.get(&(sch, HashedValue(v))) {
+                Some(true) => {
+                    self.memo.hits += 1;
+                    return Ok(());
+                }
+                // actual error is needed, unless bool_result
+                Some(false) if self.bool_result => {
+                    self.memo.hits += 1;
+                    return Err(ValidationError {
+                        schema_url: &schema.loc,
+                        instance_location: InstanceLocation::new(),
+                        kind: ErrorKind::Group,
+                        causes: vec![],
+                    });
+                }
+                _ => self.memo.misses += 1,
+            }
+        }
+        let num_warnings = self.warnings.len();
+        let collapse = !self.bool_result && scope.vid == MAX_ERROR_DEPTH;
+        let mut result = Validator {
             v,
             vloc: self.vloc,
+            options: self.options,
+            pending: self.pending,
+            memo: self.memo,
+            warnings: self.warnings,
             schema,
             schemas: self.schemas,
             scope,
-            uneval: Uneval::from(v, schema, false),
+            uneval: Uneval::from(v, schema, false, track),
             errors: vec![],
-            bool_result: self.bool_result,
+            bool_result: self.bool_result || collapse,
+            reported: self.reported,
         }
-        .validate()
-        .map(|_| ())
+        .validate();
+        if self.bool_result || collapse {
+            // warnings of probes are not reported
+            self.warnings.truncate(num_warnings);
+        }
+        if let (true, Err(e)) = (collapse, &mut result) {
+            let tokens = self.vloc[..=self.scope.vid].to_vec();
+            *e = ValidationError {
+                schema_url: &schema.loc,
+                instance_location: InstanceLocation { tokens },
+                kind: ErrorKind::Schema { url: &schema.loc },
+                causes: vec![],
+            };
+        }
+        if memoize {
+            self.memo
+                .results
+                .insert((sch, HashedValue(v)), result.is_ok());
+        }
+        let reply = result?;
+        if let Some(evaluated) = &mut self.uneval.evaluated {
+            let tokens = self.vloc[..=self.scope.vid].to_vec();
+            evaluated.push(InstanceLocation { tokens });
+            evaluated.extend(reply.evaluated.into_iter().flatten());
+        }
+        Ok(())
+    }
+
+    // tells whether `sch` is known to be valid at current location.
+    // only failures that are reported, would have been in previous
+    // error. see Schemas::revalidate
+    fn is_unchanged(&mut self, sch: SchemaIndex) -> bool {
+        if !self.reported || self.bool_result || self.memo.unchanged.is_none() {
+            return false;
+        }
+        if !self.memo.is_pure(sch, self.schemas) {
+            return false;
+        }
+        let tokens = &self.vloc[..=self.scope.vid];
+        self.memo
+            .unchanged
+            .as_ref()
+            .is_some_and(|u| u.contains(tokens))
     }
 
     fn _validate_self(
@@ -819,21 +1247,34 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
     ) -> Result<(), ValidationError<'s, 'v>> {
         let scope = self.scope.child(sch, ref_kw, self.scope.vid);
         let schema = &self.schemas.get(sch);
+        let num_warnings = self.warnings.len();
         let result = Validator {
             v: self.v,
             vloc: self.vloc,
+            options: self.options,
+            pending: self.pending,
+            memo: self.memo,
+            warnings: self.warnings,
             schema,
             schemas: self.schemas,
             scope,
-            uneval: Uneval::from(self.v, schema, !self.uneval.is_empty()),
+            uneval: Uneval::from(
+                self.v,
+                schema,
+                !self.uneval.is_empty(),
+                self.uneval.evaluated.is_some(),
+            ),
             errors: vec![],
             bool_result: self.bool_result || bool_result,
+            reported: self.reported && !bool_result,
         }
         .validate();
-        if let Ok(reply) = &result {
-            self.uneval.merge(reply);
+        if self.bool_result || bool_result {
+            // warnings of probes are not reported
+            self.warnings.truncate(num_warnings);
         }
-        result.map(|_| ())
+        self.uneval.merge(result?);
+        Ok(())
     }
 
     #[inline(always)]
@@ -842,6 +1283,316 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
     }
 }
 
+// discriminator helpers
+impl<'v, 's> Validator<'v, 's, '_, '_> {
+    // returns index of subschema in `branches`, which `d` dispatches to.
+    fn discriminated(&self, d: &Discriminator, branches: &[SchemaIndex]) -> Option<usize> {
+        let Value::Object(obj) = self.v else {
+            return None;
+        };
+        let tag = obj.get(&d.prop)?;
+        if let Some(target) = tag.as_str().and_then(|tag| d.mapping.get(tag)) {
+            return branches
+                .iter()
+                .position(|sch| self.refers_to(*sch, *target, 0));
+        }
+        branches
+            .iter()
+            .position(|sch| self.has_tag(*sch, &d.prop, tag, 0))
+    }
+
+    // tells whether `sch` is `target`, or refers to it
+    fn refers_to(&self, sch: SchemaIndex, target: SchemaIndex, depth: usize) -> bool {
+        if sch == target {
+            return true;
+        }
+        match self.schemas.get(sch).ref_ {
+            Some(ref_) if depth < MAX_TAG_DEPTH => self.refers_to(ref_, target, depth + 1),
+            _ => false,
+        }
+    }
+
+    // tells whether `sch` constrains property `prop` to `tag`
+    fn has_tag(&self, sch: SchemaIndex, prop: &str, tag: &Value, depth: usize) -> bool {
+        if depth > MAX_TAG_DEPTH {
+            return false;
+        }
+        let s = self.schemas.get(sch);
+        if let Some(psch) = s.properties.get(prop) {
+            let ps = self.schemas.get(*psch);
+            if let Some(c) = &ps.constant {
+                if equals(c, tag) {
+                    return true;
+                }
+            }
+            if let Some(e) = &ps.enum_ {
+                if e.values.iter().any(|v| equals(v, tag)) {
+                    return true;
+                }
+            }
+        }
+        if let (Some(ref_), Value::String(tag)) = (s.ref_, tag) {
+            let loc = &self.schemas.get(ref_).loc;
+            if loc
+                .strip_suffix(tag.as_str())
+                .is_some_and(|s| s.ends_with('/'))
+            {
+                return true;
+            }
+        }
+        s.ref_
+            .iter()
+            .chain(&s.all_of)
+            .any(|sch| self.has_tag(*sch, prop, tag, depth + 1))
+    }
+}
+
+// guards against `$ref` cycles, while looking for discriminator tags.
+const MAX_TAG_DEPTH: usize = 16;
+
+fn content_limit<'s, 'v>(
+    keyword: &'static str,
+    limit: &'static str,
+    got: usize,
+    want: usize,
+) -> ErrorKind<'s, 'v> {
+    ErrorKind::ContentLimit {
+        keyword,
+        limit,
+        got,
+        want,
+    }
+}
+
+// checks `v` against instance limits in `options`.
+pub(crate) fn instance_limit<'s, 'v>(
+    v: &Value,
+    options: &ValidationOptions,
+) -> Option<ErrorKind<'s, 'v>> {
+    if options.max_depth.is_none() && options.max_nodes.is_none() {
+        return None;
+    }
+    let (depth, nodes) = measure(v);
+    let exceeded = [
+        ("depth", depth, options.max_depth),
+        ("nodes", nodes, options.max_nodes),
+    ];
+    exceeded.into_iter().find_map(|(limit, got, max)| {
+        let want = max.filter(|max| got > *max)?;
+        Some(ErrorKind::InstanceLimit { limit, got, want })
+    })
+}
+
+// nesting depth and number of values in `v`. scalars have depth 1.
+fn measure(v: &Value) -> (usize, usize) {
+    let mut max = 0;
+    let mut nodes = 0;
+    let mut stack = vec![(v, 1)];
+    while let Some((v, d)) = stack.pop() {
+        max = max.max(d);
+        nodes += 1;
+        match v {
+            Value::Array(arr) => stack.extend(arr.iter().map(|v| (v, d + 1))),
+            Value::Object(obj) => stack.extend(obj.values().map(|v| (v, d + 1))),
+            _ => {}
+        }
+    }
+    (max, nodes)
+}
+
+// number of keywords in `s`, which apply to `v`.
+fn keyword_count(s: &Schema, v: &Value) -> usize {
+    if s.boolean.is_some() {
+        return 1;
+    }
+    applicable_keywords(s, v).len()
+}
+
+// keywords of `s`, applicable to `v`
+fn applicable_keywords(s: &Schema, v: &Value) -> Vec<&'static str> {
+    let t = match v {
+        Value::Object(_) => Some(Type::Object),
+        Value::Array(_) => Some(Type::Array),
+        Value::String(_) => Some(Type::String),
+        Value::Number(_) => Some(Type::Number),
+        _ => None,
+    };
+    keywords(s)
+        .into_iter()
+        .filter(|(_, kw_type)| kw_type.is_none() || *kw_type == t)
+        .map(|(kw, _)| kw)
+        .collect()
+}
+
+// keywords of `s`, along with type of values they apply to.
+// None means they apply to any value.
+pub(crate) fn keywords(s: &Schema) -> Vec<(&'static str, Option<Type>)> {
+    if s.boolean.is_some() {
+        return vec![];
+    }
+    let (obj, arr, str, num) = (
+        Some(Type::Object),
+        Some(Type::Array),
+        Some(Type::String),
+        Some(Type::Number),
+    );
+    let all = [
+        ("$ref", None, s.ref_.is_some() || s.lazy_ref.is_some()),
+        ("$recursiveRef", None, s.recursive_ref.is_some()),
+        ("$dynamicRef", None, s.dynamic_ref.is_some()),
+        ("type", None, !s.types.is_empty()),
+        ("enum", None, s.enum_.is_some()),
+        ("const", None, s.constant.is_some()),
+        ("format", None, s.format.is_some()),
+        ("not", None, s.not.is_some()),
+        ("allOf", None, !s.all_of.is_empty()),
+        ("anyOf", None, !s.any_of.is_empty()),
+        ("oneOf", None, !s.one_of.is_empty()),
+        ("if", None, s.if_.is_some()),
+        ("minProperties", obj, s.min_properties.is_some()),
+        ("maxProperties", obj, s.max_properties.is_some()),
+        ("required", obj, !s.required.is_empty()),
+        ("properties", obj, !s.properties.is_empty()),
+        ("patternProperties", obj, !s.pattern_properties.is_empty()),
+        ("propertyNames", obj, s.property_names.is_some()),
+        (
+            "additionalProperties",
+            obj,
+            s.additional_properties.is_some(),
+        ),
+        ("dependentRequired", obj, !s.dependent_required.is_empty()),
+        ("dependentSchemas", obj, !s.dependent_schemas.is_empty()),
+        ("dependencies", obj, !s.dependencies.is_empty()),
+        (
+            "unevaluatedProperties",
+            obj,
+            s.unevaluated_properties.is_some(),
+        ),
+        ("minItems", arr, s.min_items.is_some()),
+        ("maxItems", arr, s.max_items.is_some()),
+        ("uniqueItems", arr, s.unique_items),
+        ("contains", arr, s.contains.is_some()),
+        ("items", arr, s.items.is_some() || s.items2020.is_some()),
+        ("additionalItems", arr, s.additional_items.is_some()),
+        ("prefixItems", arr, !s.prefix_items.is_empty()),
+        ("unevaluatedItems", arr, s.unevaluated_items.is_some()),
+        ("minLength", str, s.min_length.is_some()),
+        ("maxLength", str, s.max_length.is_some()),
+        ("pattern", str, s.pattern.is_some()),
+        ("contentEncoding", str, s.content_encoding.is_some()),
+        ("contentMediaType", str, s.content_media_type.is_some()),
+        ("minimum", num, s.minimum.is_some()),
+        ("maximum", num, s.maximum.is_some()),
+        ("exclusiveMinimum", num, s.exclusive_minimum.is_some()),
+        ("exclusiveMaximum", num, s.exclusive_maximum.is_some()),
+        ("multipleOf", num, s.multiple_of.is_some()),
+    ];
+    all.into_iter()
+        .filter(|(_, _, present)| *present)
+        .map(|(kw, kw_type, _)| (kw, kw_type))
+        .collect()
+}
+
+// keywords which report errors of their subschemas as is
+#[cfg(feature = "hooks")]
+const SUBSCHEMA_KEYWORDS: [&str; 12] = [
+    "properties",
+    "patternProperties",
+    "additionalProperties",
+    "dependentSchemas",
+    "dependencies",
+    "unevaluatedProperties",
+    "items",
+    "additionalItems",
+    "prefixItems",
+    "unevaluatedItems",
+    "then",
+    "else",
+];
+
+// keywords of `s` that caused error `e`, returned by `s`.
+// returns None, if it cannot be told, like with `errorMessage`.
+#[cfg(feature = "hooks")]
+fn failed_keywords(s: &Schema, e: &ValidationError) -> Option<Vec<&'static str>> {
+    let errors = match e.kind {
+        ErrorKind::Group => e.causes.iter().collect(),
+        _ => vec![e],
+    };
+    let mut failed = vec![];
+    for e in errors {
+        let kw = if e.schema_url == s.loc {
+            e.kind.keyword_path()?.keyword
+        } else {
+            // error from subschema, like `properties/a`
+            let rest = e.schema_url.strip_prefix(s.loc.as_str())?;
+            let token = rest.strip_prefix('/')?.split('/').next()?;
+            SUBSCHEMA_KEYWORDS.into_iter().find(|kw| *kw == token)?
+        };
+        failed.push(match kw {
+            "then" | "else" => "if",
+            "minContains" | "maxContains" => "contains",
+            "contentSchema" => "contentMediaType",
+            kw => kw,
+        });
+    }
+    Some(failed)
+}
+
+// describes constraints of `s`, which a valid value satisfies.
+// used to explain why value failed against `not`.
+pub(crate) fn constraints(s: &Schema) -> Vec<String> {
+    let mut v = vec![];
+    if !s.types.is_empty() {
+        v.push(format!("type {}", join_iter(s.types.iter(), " or ")));
+    }
+    if let Some(e) = &s.enum_ {
+        v.push(format!("enum {}", Value::Array(e.values.clone())));
+    }
+    if let Some(c) = &s.constant {
+        v.push(format!("const {c}"));
+    }
+    if let Some(f) = &s.format {
+        v.push(format!("format {}", quote(f.name)));
+    }
+    if !s.required.is_empty() {
+        v.push(format!(
+            "required {}",
+            join_iter(s.required.iter().map(quote), ", ")
+        ));
+    }
+    if !s.properties.is_empty() {
+        let mut props: Vec<_> = s.properties.keys().map(quote).collect();
+        props.sort();
+        v.push(format!("properties {}", props.join(", ")));
+    }
+    let mut bound = |kw: &str, n: Option<String>| {
+        if let Some(n) = n {
+            v.push(format!("{kw} {n}"));
+        }
+    };
+    bound("minProperties", s.min_properties.map(|n| n.to_string()));
+    bound("maxProperties", s.max_properties.map(|n| n.to_string()));
+    bound("minItems", s.min_items.map(|n| n.to_string()));
+    bound("maxItems", s.max_items.map(|n| n.to_string()));
+    bound("minLength", s.min_length.map(|n| n.to_string()));
+    bound("maxLength", s.max_length.map(|n| n.to_string()));
+    bound("pattern", s.pattern.as_ref().map(|re| quote(re.as_str())));
+    bound("minimum", s.minimum.as_ref().map(Number::to_string));
+    bound("maximum", s.maximum.as_ref().map(Number::to_string));
+    let exclusive_minimum = s.exclusive_minimum.as_ref().map(Number::to_string);
+    bound("exclusiveMinimum", exclusive_minimum);
+    let exclusive_maximum = s.exclusive_maximum.as_ref().map(Number::to_string);
+    bound("exclusiveMaximum", exclusive_maximum);
+    bound("multipleOf", s.multiple_of.as_ref().map(Number::to_string));
+    if s.unique_items {
+        v.push("uniqueItems".to_owned());
+    }
+    if s.contains.is_some() {
+        v.push("contains".to_owned());
+    }
+    v
+}
+
 // error helpers
 impl<'v, 's> Validator<'v, 's, '_, '_> {
     #[inline(always)]
@@ -854,6 +1605,20 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
                 causes: vec![],
             };
         }
+        let message = match &self.schema.error_message {
+            Some(ErrorMessage::All(message)) => Some(message),
+            Some(ErrorMessage::Keywords(messages)) => kind
+                .keyword_path()
+                .and_then(|path| messages.get(path.keyword)),
+            None => None,
+        };
+        let kind = match message {
+            Some(message) => ErrorKind::Custom {
+                message,
+                kind: Box::new(kind),
+            },
+            None => kind,
+        };
         ValidationError {
             schema_url: &self.schema.loc,
             instance_location: self.instance_location(),
@@ -862,6 +1627,30 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
         }
     }
 
+    fn is_warning(&self, kind: &ErrorKind) -> bool {
+        let Some(path) = kind.keyword_path() else {
+            return false;
+        };
+        match self.options.severities.get(path.keyword) {
+            Some(severity) => *severity == Severity::Warning,
+            None => self
+                .schema
+                .warn_keywords
+                .iter()
+                .any(|kw| kw == path.keyword),
+        }
+    }
+
+    // returns error for `kind`, unless its keyword is hinted as warning.
+    fn fail(&mut self, kind: ErrorKind<'s, 'v>) -> Result<(), ValidationError<'s, 'v>> {
+        if !self.is_warning(&kind) {
+            return Err(self.error(kind));
+        }
+        let warning = self.error(kind);
+        self.warnings.push(warning);
+        Ok(())
+    }
+
     #[inline(always)]
     fn add_error(&mut self, kind: ErrorKind<'s, 'v>) {
         self.errors.push(self.error(kind));
@@ -880,7 +1669,7 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
 
     fn kw_loc(&self, mut scope: &Scope) -> String {
         let mut loc = String::new();
-        while let Some(parent) = scope.parent {
+        while let Some(parent) = scope.parent.filter(|p| p.vid != OUTER_VID) {
             if let Some(kw) = scope.ref_kw {
                 loc.insert_str(0, kw);
                 loc.insert(0, '/');
@@ -925,12 +1714,139 @@ impl<'v, 's> Validator<'v, 's, '_, '_> {
     }
 }
 
+// validates property name with `propertyNames` subschema `s`, having
+// only keywords applicable to strings, without allocating `Value::String`.
+// returns errors same as `Schemas::validate`. see Schema::name_only
+fn name_errors<'s, 'v>(s: &'s Schema, name: &'v String) -> Vec<ValidationError<'s, 'v>> {
+    let error = |kind| ValidationError {
+        schema_url: &s.loc,
+        instance_location: InstanceLocation::new(),
+        kind,
+        causes: vec![],
+    };
+    if let Some(b) = s.boolean {
+        return match b {
+            true => vec![],
+            false => vec![error(kind!(FalseSchema))],
+        };
+    }
+    if !s.types.is_empty() && !s.types.contains(Type::String) {
+        return vec![error(kind!(Type, Type::String, s.types))];
+    }
+    if let Some(c) = s.constant.as_ref().filter(|c| c.as_str() != Some(name)) {
+        return vec![error(kind!(Const, want: c))];
+    }
+    if let Some(e) = s.enum_.as_ref().filter(|e| !e.contains_str(name)) {
+        return vec![error(kind!(Enum, want: &e.values))];
+    }
+    let mut errors = vec![];
+    if s.min_length.is_some() || s.max_length.is_some() {
+        // Schemas::validate uses default options
+        let len = StringLength::default().of(name);
+        if let Some(min) = s.min_length.filter(|min| len < *min) {
+            errors.push(error(kind!(MinLength, len, min)));
+        }
+        if let Some(max) = s.max_length.filter(|max| len > *max) {
+            errors.push(error(kind!(MaxLength, len, max)));
+        }
+    }
+    if let Some(regex) = s.pattern.as_ref().filter(|re| !re.is_match(name)) {
+        errors.push(error(kind!(Pattern, name.into(), regex.as_str())));
+    }
+    errors
+}
+
+// Memo --
+
+// caches results of validating array and object values with pure
+// subschemas, i.e. whose result depends only on the value.
+pub(crate) struct Memo<'v> {
+    enabled: bool,
+    results: HashMap<(SchemaIndex, HashedValue<'v>), bool>,
+    pure: HashMap<SchemaIndex, bool>,
+    pub(crate) hits: usize,
+    pub(crate) misses: usize,
+    pub(crate) unchanged: Option<Unchanged>, // see Schemas::revalidate
+}
+
+impl Memo<'_> {
+    pub(crate) fn new(options: &ValidationOptions) -> Self {
+        Self {
+            // tracers and hooks must see every evaluation, and warnings are not cached
+            enabled: options.memoize
+                && options.tracer.is_none()
+                && options.format_annotation_tracer.is_none()
+                && !options.has_keyword_hook()
+                && !options.has_warnings(),
+            results: HashMap::new(),
+            pure: HashMap::new(),
+            hits: 0,
+            misses: 0,
+            unchanged: None,
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // tells whether result of validating `v` with `sch` can be memoized.
+    // scalars are cheaper to validate than to lookup.
+    fn applies(&mut self, sch: SchemaIndex, v: &Value, schemas: &Schemas) -> bool {
+        self.enabled
+            && matches!(v, Value::Array(_) | Value::Object(_))
+            && self.is_pure(sch, schemas)
+    }
+
+    // tells whether no `$dynamicRef`, `$recursiveRef` or uncompiled `$ref`
+    // is reachable from `sch`, whose resolution depends on dynamic scope.
+    fn is_pure(&mut self, sch: SchemaIndex, schemas: &Schemas) -> bool {
+        if let Some(&pure) = self.pure.get(&sch) {
+            return pure;
+        }
+        let mut seen = HashSet::from([sch]);
+        let mut queue = vec![sch];
+        let mut pure = true;
+        while let Some(idx) = queue.pop() {
+            match self.pure.get(&idx) {
+                Some(true) => continue,
+                Some(false) => pure = false,
+                None => {
+                    let s = schemas.get(idx);
+                    pure = s.dynamic_ref.is_none()
+                        && s.recursive_ref.is_none()
+                        && s.lazy_ref.is_none()
+                        && s.warn_keywords.is_empty();
+                }
+            }
+            if !pure {
+                break;
+            }
+            let s = schemas.get(idx);
+            for sub in s.applied_subschemas() {
+                if seen.insert(sub) {
+                    queue.push(sub);
+                }
+            }
+        }
+        if pure {
+            // everything reachable from pure schema is pure
+            self.pure.extend(seen.into_iter().map(|idx| (idx, true)));
+        } else {
+            self.pure.insert(sch, false);
+        }
+        pure
+    }
+}
+
 // Uneval --
 
 #[derive(Default)]
 struct Uneval<'v> {
     props: HashSet<&'v String>,
     items: HashSet<usize>,
+    // locations of values evaluated. collected only if requested.
+    evaluated: Option<Vec<InstanceLocation<'v>>>,
 }
 
 impl<'v> Uneval<'v> {
@@ -938,8 +1854,12 @@ impl<'v> Uneval<'v> {
         self.props.is_empty() && self.items.is_empty()
     }
 
-    fn from(v: &'v Value, sch: &Schema, caller_needs: bool) -> Self {
+    #[allow(clippy::collapsible_match)]
+    fn from(v: &'v Value, sch: &Schema, caller_needs: bool, track: bool) -> Self {
         let mut uneval = Self::default();
+        if track {
+            uneval.evaluated = Some(vec![]);
+        }
         match v {
             Value::Object(obj) => {
                 if !sch.all_props_evaluated
@@ -961,9 +1881,12 @@ impl<'v> Uneval<'v> {
         uneval
     }
 
-    fn merge(&mut self, other: &Uneval) {
+    fn merge(&mut self, other: Uneval<'v>) {
         self.props.retain(|p| other.props.contains(p));
         self.items.retain(|i| other.items.contains(i));
+        if let (Some(evaluated), Some(other)) = (&mut self.evaluated, other.evaluated) {
+            evaluated.extend(other);
+        }
     }
 }
 
@@ -981,6 +1904,9 @@ struct Scope<'a> {
     parent: Option<&'a Scope<'a>>,
 }
 
+// vid of scopes seeded before root. see validate_in_scope
+const OUTER_VID: usize = usize::MAX;
+
 impl Scope<'_> {
     fn child<'x>(
         &'x self,
@@ -996,7 +1922,7 @@ impl Scope<'_> {
         }
     }
 
-    fn check_cycle(&self) -> Option<&Scope> {
+    fn check_cycle(&self) -> Option<&Scope<'_>> {
         let mut scope = self.parent;
         while let Some(scp) = scope {
             if scp.vid != self.vid {
@@ -1012,7 +1938,7 @@ impl Scope<'_> {
 }
 
 /// Token in InstanceLocation json-pointer.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum InstanceToken<'v> {
     /// Token for property.
     Prop(Cow<'v, str>),
@@ -1039,17 +1965,76 @@ impl From<usize> for InstanceToken<'_> {
 }
 
 /// The location of the JSON value within the instance being validated
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct InstanceLocation<'v> {
     pub tokens: Vec<InstanceToken<'v>>,
 }
 
-impl InstanceLocation<'_> {
+impl<'v> InstanceLocation<'v> {
     fn new() -> Self {
         Self::default()
     }
 
-    fn clone_static(self) -> InstanceLocation<'static> {
+    /**
+    Parses json-pointer `ptr`, like `"/a/0/b"`.
+
+    Tokens which are array indexes, such as `0` or `12`, are
+    parsed as [`InstanceToken::Item`]. Returns `None` if `ptr` is
+    not valid json-pointer.
+    */
+    pub fn parse(ptr: &'v str) -> Option<Self> {
+        let mut tokens = vec![];
+        if !ptr.is_empty() {
+            let ptr = ptr.strip_prefix('/')?;
+            for tok in ptr.split('/') {
+                let tok = JsonPointer::unescape(tok).ok()?;
+                tokens.push(match parse_index(&tok) {
+                    Some(i) => InstanceToken::Item(i),
+                    None => InstanceToken::Prop(tok),
+                });
+            }
+        }
+        Some(Self { tokens })
+    }
+
+    /// Appends `token` to this location.
+    pub fn push(&mut self, token: impl Into<InstanceToken<'v>>) {
+        self.tokens.push(token.into());
+    }
+
+    /// Removes the last token and returns it, or `None` if this is root location.
+    pub fn pop(&mut self) -> Option<InstanceToken<'v>> {
+        self.tokens.pop()
+    }
+
+    /// Returns iterator over tokens, starting from root.
+    pub fn iter(&self) -> std::slice::Iter<'_, InstanceToken<'v>> {
+        self.tokens.iter()
+    }
+
+    /**
+    Returns the value at this location within `instance`, or `None` if
+    there is no such value.
+
+    Since parsed tokens may not know whether they refer to
+    property or item, [`InstanceToken::Item`] on object is looked up
+    as property, and [`InstanceToken::Prop`] on array as index.
+    */
+    pub fn resolve<'a>(&self, instance: &'a Value) -> Option<&'a Value> {
+        let mut v = instance;
+        for tok in &self.tokens {
+            v = match (v, tok) {
+                (Value::Object(obj), InstanceToken::Prop(p)) => obj.get(p.as_ref())?,
+                (Value::Object(obj), InstanceToken::Item(i)) => obj.get(&i.to_string())?,
+                (Value::Array(arr), InstanceToken::Item(i)) => arr.get(*i)?,
+                (Value::Array(arr), InstanceToken::Prop(p)) => arr.get(parse_index(p)?)?,
+                _ => return None,
+            };
+        }
+        Some(v)
+    }
+
+    pub(crate) fn clone_static(self) -> InstanceLocation<'static> {
         let mut tokens = Vec::with_capacity(self.tokens.len());
         for tok in self.tokens {
             let tok = match tok {
@@ -1062,6 +2047,97 @@ impl InstanceLocation<'_> {
     }
 }
 
+impl InstanceLocation<'_> {
+    /// Renders this location in given `format`.
+    pub fn format(&self, format: LocationFormat) -> String {
+        match format {
+            LocationFormat::JsonPointer => self.to_string(),
+            LocationFormat::UriFragment => self.to_uri_fragment(),
+            LocationFormat::JsonPath => self.to_json_path(),
+        }
+    }
+
+    /// Renders this location as json-pointer in uri fragment form,
+    /// like `#/a%20b/0`, as per rfc6901 section 6.
+    pub fn to_uri_fragment(&self) -> String {
+        format!("#{}", Fragment::encode(&self.to_string()))
+    }
+
+    /// Renders this location as normalized JSONPath, like `$.items[3].name`.
+    /// Property names that are not identifiers are written in bracket
+    /// notation, like `$['a b']`.
+    pub fn to_json_path(&self) -> String {
+        let mut s = String::from("$");
+        for tok in &self.tokens {
+            match tok {
+                InstanceToken::Prop(p) if is_identifier(p) => {
+                    s.push('.');
+                    s.push_str(p);
+                }
+                InstanceToken::Prop(p) => {
+                    s.push_str("['");
+                    for ch in p.chars() {
+                        match ch {
+                            '\\' | '\'' => {
+                                s.push('\\');
+                                s.push(ch);
+                            }
+                            '\n' => s.push_str("\\n"),
+                            '\r' => s.push_str("\\r"),
+                            '\t' => s.push_str("\\t"),
+                            ch if ch.is_control() => {
+                                _ = write!(s, "\\u{:04x}", ch as u32);
+                            }
+                            ch => s.push(ch),
+                        }
+                    }
+                    s.push_str("']");
+                }
+                InstanceToken::Item(i) => _ = write!(s, "[{i}]"),
+            }
+        }
+        s
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Rendering of [`InstanceLocation`] in output formats.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LocationFormat {
+    /// json-pointer, like `/items/3/name`.
+    #[default]
+    JsonPointer,
+    /// json-pointer in uri fragment, like `#/items/3/name`.
+    UriFragment,
+    /// JSONPath, like `$.items[3].name`.
+    JsonPath,
+}
+
+impl<'a, 'v> IntoIterator for &'a InstanceLocation<'v> {
+    type Item = &'a InstanceToken<'v>;
+    type IntoIter = std::slice::Iter<'a, InstanceToken<'v>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// array index as per rfc6901: "0" or digits without leading zero
+pub(crate) fn parse_index(tok: &str) -> Option<usize> {
+    if tok.is_empty() || !tok.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if tok.len() > 1 && tok.starts_with('0') {
+        return None;
+    }
+    tok.parse().ok()
+}
+
 impl Display for InstanceLocation<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for tok in &self.tokens {
@@ -1133,6 +2209,7 @@ impl<'s> ErrorKind<'s, '_> {
             ContentSchema => ContentSchema,
             PropertyName { prop } => PropertyName { prop },
             Reference { kw, url } => Reference { kw, url },
+            Uncompiled { url } => Uncompiled { url },
             RefCycle {
                 url,
                 kw_loc1,
@@ -1143,6 +2220,7 @@ impl<'s> ErrorKind<'s, '_> {
                 kw_loc2,
             },
             FalseSchema => FalseSchema,
+            Deprecated => Deprecated,
             Type { got, want } => Type { got, want },
             Enum { want } => Enum { want },
             Const { want } => Const { want },
@@ -1162,10 +2240,30 @@ impl<'s> ErrorKind<'s, '_> {
             MaxLength { got, want } => MaxLength { got, want },
             ContentEncoding { want, err } => ContentEncoding { want, err },
             ContentMediaType { got, want, err } => ContentMediaType { got, want, err },
-            Not => Not,
+            InstanceLimit { limit, got, want } => InstanceLimit { limit, got, want },
+            ContentLimit {
+                keyword,
+                limit,
+                got,
+                want,
+            } => ContentLimit {
+                keyword,
+                limit,
+                got,
+                want,
+            },
+            Not { url, matched } => Not { url, matched },
             AllOf => AllOf,
             AnyOf => AnyOf,
             OneOf(opt) => OneOf(opt),
+            Custom { message, kind } => Custom {
+                message,
+                kind: Box::new(kind.clone_static()),
+            },
+            Redacted { got, kind } => Redacted {
+                got,
+                kind: Box::new(kind.clone_static()),
+            },
         }
     }
 }
@@ -0,0 +1,174 @@
+/*!
+Integration with [axum] web framework, available with feature `axum`.
+
+[`Validated<T>`] extractor deserializes json request body into `T`, after
+validating it with the schema registered for the route using [`RouteSchema`].
+Invalid body is rejected with `422 Unprocessable Content` response, having
+[rfc9457] problem details in body.
+
+```
+# use std::sync::Arc;
+# use axum::{routing::post, Extension, Router};
+# use boon::{web::{RouteSchema, Validated}, *};
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+#[derive(serde::Deserialize)]
+struct User {
+    name: String,
+}
+
+async fn create_user(Validated(user): Validated<User>) -> String {
+    user.name
+}
+
+let mut schemas = Schemas::new();
+let mut compiler = Compiler::new();
+compiler.add_resource("http://a.com/user.json", json!({
+    "properties": {"name": {"type": "string", "minLength": 1}},
+    "required": ["name"]
+}))?;
+let sch = compiler.compile("http://a.com/user.json", &mut schemas)?;
+let schemas = Arc::new(schemas);
+
+let app: Router = Router::new().route(
+    "/users",
+    post(create_user).layer(Extension(RouteSchema::new(schemas.clone(), sch))),
+);
+# Ok(())
+# }
+```
+
+[axum]: https://docs.rs/axum
+[rfc9457]: https://www.rfc-editor.org/rfc/rfc9457
+*/
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{rejection::BytesRejection, FromRequest, Request},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::{ProblemDetails, SchemaIndex, Schemas};
+
+/**
+Schema to validate request body of a route, with [`Validated`] extractor.
+
+Register it for the route as [`axum::Extension`] layer.
+*/
+#[derive(Clone)]
+pub struct RouteSchema {
+    schemas: Arc<Schemas>,
+    sch_index: SchemaIndex,
+}
+
+impl RouteSchema {
+    /// Uses schema identified by `sch_index` in `schemas`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sch_index` is not generated for `schemas`.
+    pub fn new(schemas: Arc<Schemas>, sch_index: SchemaIndex) -> Self {
+        if !schemas.contains(sch_index) {
+            panic!("RouteSchema::new: schema index out of bounds");
+        }
+        Self { schemas, sch_index }
+    }
+}
+
+/**
+Extractor, that deserializes json request body into `T`, after
+validating it with [`RouteSchema`] registered for the route.
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Validated<T>(pub T);
+
+/// Rejection used for [`Validated`].
+#[derive(Debug)]
+pub enum ValidatedRejection {
+    /// No [`RouteSchema`] registered for the route.
+    MissingSchema,
+    /// Failed to read request body.
+    Body(BytesRejection),
+    /// Request body is not valid json.
+    Parse(serde_json::Error),
+    /// Request body is not valid against the schema.
+    Invalid(ProblemDetails),
+    /// Request body is valid, but cannot be deserialized into target type.
+    Deserialize(serde_json::Error),
+}
+
+impl<T, S> FromRequest<S> for Validated<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ValidatedRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Some(route) = req.extensions().get::<RouteSchema>().cloned() else {
+            return Err(ValidatedRejection::MissingSchema);
+        };
+        let body = Bytes::from_request(req, state)
+            .await
+            .map_err(ValidatedRejection::Body)?;
+        let v: Value = serde_json::from_slice(&body).map_err(ValidatedRejection::Parse)?;
+        if let Err(e) = route.schemas.validate(&v, route.sch_index) {
+            let problem = e
+                .to_problem_details("about:blank", 422)
+                .with_title(status_title(StatusCode::UNPROCESSABLE_ENTITY))
+                .with_detail("request body is not valid");
+            return Err(ValidatedRejection::Invalid(problem));
+        }
+        match serde_json::from_value(v) {
+            Ok(v) => Ok(Validated(v)),
+            Err(e) => Err(ValidatedRejection::Deserialize(e)),
+        }
+    }
+}
+
+impl IntoResponse for ValidatedRejection {
+    fn into_response(self) -> Response {
+        let (status, body) = match self {
+            Self::MissingSchema => {
+                let detail = "no schema registered for the route";
+                let status = StatusCode::INTERNAL_SERVER_ERROR;
+                (status, problem(status, detail))
+            }
+            Self::Body(rejection) => return rejection.into_response(),
+            Self::Parse(e) => {
+                let status = StatusCode::BAD_REQUEST;
+                (status, problem(status, &format!("invalid json: {e}")))
+            }
+            Self::Invalid(problem) => (StatusCode::UNPROCESSABLE_ENTITY, json!(problem)),
+            Self::Deserialize(e) => {
+                let status = StatusCode::UNPROCESSABLE_ENTITY;
+                (status, problem(status, &e.to_string()))
+            }
+        };
+        (
+            status,
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            body.to_string(),
+        )
+            .into_response()
+    }
+}
+
+fn problem(status: StatusCode, detail: &str) -> Value {
+    json!({
+        "type": "about:blank",
+        "title": status_title(status),
+        "status": status.as_u16(),
+        "detail": detail,
+    })
+}
+
+// with type `about:blank`, title should be the http status phrase
+fn status_title(status: StatusCode) -> &'static str {
+    status.canonical_reason().unwrap_or_default()
+}
@@ -1,7 +1,12 @@
-use std::error::Error;
+use std::{cell::RefCell, error::Error, rc::Rc, time::Duration};
 
-use boon::{Compiler, Schemas};
-use serde_json::json;
+#[cfg(all(feature = "zip", feature = "tar", feature = "fs"))]
+use boon::ArchiveLoader;
+use boon::{
+    AnchorKind, CompileError, Compiler, Dialect, Draft, FormatMode, MemoryLoader, RetryLoader,
+    Schemas, SchemeUrlLoader, UrlLoader, UrlMapper,
+};
+use serde_json::{json, Value};
 
 #[test]
 fn test_metaschema_resource() -> Result<(), Box<dyn Error>> {
@@ -85,3 +90,1385 @@ fn test_compile_nonstd() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_load_extensions() -> Result<(), Box<dyn Error>> {
+    struct Loader;
+    impl UrlLoader for Loader {
+        fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+            match url {
+                "http://tmp.com/schema.json" => Ok(json!({"$ref": "meta/core"})),
+                "http://tmp.com/meta/core.json" => Ok(json!({"type": "number"})),
+                _ => Err("not found")?,
+            }
+        }
+    }
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(Loader));
+    assert!(compiler
+        .compile("http://tmp.com/schema.json", &mut schemas)
+        .is_err());
+
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(Loader));
+    compiler.set_load_extensions(&["", ".json"]);
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!(1), sch).is_ok());
+    assert!(schemas.validate(&json!("1"), sch).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_url_mapper() -> Result<(), Box<dyn Error>> {
+    struct Loader;
+    impl UrlLoader for Loader {
+        fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+            match url {
+                "file:///opt/schemas/main.json" => Ok(json!({"$ref": "../types/num.json"})),
+                "file:///opt/types/num.json" => Ok(json!({"type": "number"})),
+                _ => Err("not found")?,
+            }
+        }
+    }
+
+    let mut mapper = UrlMapper::new();
+    mapper.add("https://schemas.com/", "file:///opt/");
+    mapper.add("https://schemas.com/v1/", "file:///opt/schemas/");
+    assert_eq!(
+        mapper.map("https://schemas.com/v1/main.json").as_deref(),
+        Some("file:///opt/schemas/main.json")
+    );
+    assert_eq!(mapper.map("https://other.com/main.json"), None);
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(Loader));
+    compiler.use_url_mapper(mapper);
+    let sch = compiler.compile("https://schemas.com/v1/main.json", &mut schemas)?;
+    assert!(schemas.validate(&json!(1), sch).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_metaschema() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_metaschema(
+        "http://example.com/dialect",
+        json!({
+            "$schema": "http://example.com/dialect",
+            "$vocabulary": {
+                "https://json-schema.org/draft/2020-12/vocab/core": true,
+                "https://json-schema.org/draft/2020-12/vocab/applicator": true,
+                "https://json-schema.org/draft/2020-12/vocab/validation": true,
+                "http://example.com/vocab/ui": true
+            },
+            "allOf": [{ "$ref": "https://json-schema.org/draft/2020-12/schema" }],
+            "properties": { "x-ui": { "type": "string" } }
+        }),
+        boon::Draft::V2020_12,
+        &["http://example.com/vocab/ui"],
+    )?;
+
+    let mut schemas = Schemas::new();
+    compiler.add_resource(
+        "http://example.com/valid.json",
+        json!({"$schema": "http://example.com/dialect", "type": "integer", "x-ui": "slider"}),
+    )?;
+    let sch = compiler.compile("http://example.com/valid.json", &mut schemas)?;
+    assert!(schemas.validate(&json!(1), sch).is_ok());
+    assert!(schemas.validate(&json!("one"), sch).is_err());
+
+    compiler.add_resource(
+        "http://example.com/invalid.json",
+        json!({"$schema": "http://example.com/dialect", "x-ui": 1}),
+    )?;
+    let result = compiler.compile("http://example.com/invalid.json", &mut schemas);
+    let Err(err @ boon::CompileError::MetaSchemaValidationError { .. }) = result else {
+        panic!("want MetaSchemaValidationError, got {result:?}");
+    };
+    drop(compiler);
+    assert!(format!("{err:#}").contains("/x-ui"), "{err:#}");
+
+    Ok(())
+}
+
+#[test]
+fn test_data_url() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/schema.json",
+        json!({
+            "properties": {
+                "a": { "$ref": "data:application/json;base64,eyJ0eXBlIjoic3RyaW5nIn0=" },
+                "b": { "$ref": "data:,%7B%22type%22%3A%22integer%22%7D" }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!({"a": "x", "b": 1}), sch).is_ok());
+    assert!(schemas.validate(&json!({"a": 1}), sch).is_err());
+    assert!(schemas.validate(&json!({"b": "x"}), sch).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_lazy_compilation() -> Result<(), Box<dyn Error>> {
+    struct Remotes(Rc<RefCell<Vec<String>>>);
+    impl UrlLoader for Remotes {
+        fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+            self.0.borrow_mut().push(url.to_owned());
+            match url {
+                "http://remotes/a.json" => Ok(json!({"type": "integer"})),
+                "http://remotes/b.json" => Ok(json!({"type": "string"})),
+                _ => Err("remote not found")?,
+            }
+        }
+    }
+
+    let loaded = Rc::new(RefCell::new(vec![]));
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_lazy_compilation();
+    compiler.use_loader(Box::new(Remotes(loaded.clone())));
+    compiler.add_resource(
+        "http://example.com/schema.json",
+        json!({
+            "properties": {
+                "a": { "$ref": "http://remotes/a.json" },
+                "b": { "$ref": "http://remotes/b.json" }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    assert!(loaded.borrow().is_empty());
+
+    let instance = json!({"a": 1});
+    let result = schemas.validate(&instance, sch);
+    assert!(matches!(
+        result.unwrap_err().kind,
+        boon::ErrorKind::Uncompiled { .. }
+    ));
+
+    assert!(compiler
+        .validate_lazily(&json!({"a": 1}), sch, &mut schemas)?
+        .is_ok());
+    assert_eq!(*loaded.borrow(), vec!["http://remotes/a.json"]);
+    assert!(compiler
+        .validate_lazily(&json!({"a": "x"}), sch, &mut schemas)?
+        .is_err());
+    assert!(schemas.validate(&json!({"a": 2}), sch).is_ok());
+
+    assert!(compiler
+        .validate_lazily(&json!({"b": 1}), sch, &mut schemas)?
+        .is_err());
+    assert_eq!(loaded.borrow().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_draft_vocabularies() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/meta.json",
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$vocabulary": {
+                "https://json-schema.org/draft/2020-12/vocab/core": true,
+                "https://json-schema.org/draft/2020-12/vocab/applicator": true
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://tmp.com/a.json",
+        json!({"$schema": "http://tmp.com/meta.json"}),
+    )?;
+    compiler.add_resource(
+        "http://tmp.com/b.json",
+        json!({"$schema": "http://json-schema.org/draft-07/schema"}),
+    )?;
+
+    let a = compiler.compile("http://tmp.com/a.json", &mut schemas)?;
+    assert_eq!(schemas.draft(a), boon::Draft::V2020_12);
+    assert_eq!(schemas.vocabularies(a), ["core", "applicator"]);
+
+    let b = compiler.compile("http://tmp.com/b.json", &mut schemas)?;
+    assert_eq!(schemas.draft(b), boon::Draft::V7);
+    assert!(schemas.vocabularies(b).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_deny_unknown_keywords() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.deny_unknown_keywords();
+    compiler.add_resource(
+        "http://tmp.com/valid.json",
+        json!({"$comment": "ok", "properties": {"x": {"exclusiveMinimum": 1}}}),
+    )?;
+    compiler.compile("http://tmp.com/valid.json", &mut schemas)?;
+
+    compiler.add_resource(
+        "http://tmp.com/typo.json",
+        json!({"properties": {"x": {"exclusiveMinmum": 1}}}),
+    )?;
+    let err = compiler
+        .compile("http://tmp.com/typo.json", &mut schemas)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown keyword 'exclusiveMinmum' at http://tmp.com/typo.json#/properties/x/exclusiveMinmum, did you mean exclusiveMinimum or exclusiveMaximum?"
+    );
+
+    // keywords of newer drafts are unknown to older drafts
+    compiler.add_resource(
+        "http://tmp.com/draft7.json",
+        json!({"$schema": "http://json-schema.org/draft-07/schema", "$defs": {}}),
+    )?;
+    let result = compiler.compile("http://tmp.com/draft7.json", &mut schemas);
+    assert!(matches!(
+        result,
+        Err(boon::CompileError::UnknownKeyword { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_deny_unknown_formats() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.deny_unknown_formats();
+    compiler.register_format_fn("country", |_| Ok(()));
+    compiler.add_resource(
+        "http://tmp.com/valid.json",
+        json!({"properties": {"x": {"format": "date-time"}, "y": {"format": "country"}}}),
+    )?;
+    compiler.compile("http://tmp.com/valid.json", &mut schemas)?;
+
+    compiler.add_resource(
+        "http://tmp.com/typo.json",
+        json!({"properties": {"x": {"format": "date-tiem"}}}),
+    )?;
+    let err = compiler
+        .compile("http://tmp.com/typo.json", &mut schemas)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown format 'date-tiem' at http://tmp.com/typo.json#/properties/x/format, did you mean date-time?"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dangling_dynamic_refs() -> Result<(), Box<dyn Error>> {
+    let tree = json!({
+        "$dynamicAnchor": "nodes",
+        "$anchor": "node",
+        "items": {"$dynamicRef": "#node"}
+    });
+    let tree2019 = json!({
+        "$schema": "https://json-schema.org/draft/2019-09/schema",
+        "items": {"$recursiveRef": "#"}
+    });
+    let valid = json!({
+        "$dynamicAnchor": "node",
+        "items": {"$dynamicRef": "#node"}
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.compile_value("http://tmp.com/valid.json", valid.clone(), &mut schemas)?;
+    assert!(compiler.diagnostics().is_empty());
+    compiler.compile_value("http://tmp.com/tree.json", tree.clone(), &mut schemas)?;
+    compiler.compile_value(
+        "http://tmp.com/tree2019.json",
+        tree2019.clone(),
+        &mut schemas,
+    )?;
+    let diagnostics: Vec<_> = compiler
+        .diagnostics()
+        .iter()
+        .map(|e| e.to_string())
+        .collect();
+    assert_eq!(
+        diagnostics,
+        [
+            "http://tmp.com/tree.json#/items/$dynamicRef never resolves dynamically, since http://tmp.com/tree.json# has no matching dynamic anchor, did you mean nodes?",
+            "http://tmp.com/tree2019.json#/items/$recursiveRef never resolves dynamically, since http://tmp.com/tree2019.json# has no matching dynamic anchor",
+        ]
+    );
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.deny_dangling_dynamic_refs();
+    compiler.compile_value("http://tmp.com/valid.json", valid, &mut schemas)?;
+    for (url, v) in [
+        ("http://tmp.com/tree.json", tree),
+        ("http://tmp.com/tree2019.json", tree2019),
+    ] {
+        let err = compiler.compile_value(url, v, &mut schemas).unwrap_err();
+        assert!(
+            matches!(err, CompileError::DanglingDynamicRef { .. }),
+            "{err}"
+        );
+    }
+    assert_eq!(schemas.size(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_override_format() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.override_format("regex", |v: &Value| match v {
+        Value::String(s) if s.contains("(?<") => Err("named groups not allowed")?,
+        _ => Ok(()),
+    });
+    compiler.unregister_format("date");
+    assert!(!compiler.formats().contains(&"date"));
+    assert!(compiler.formats().contains(&"regex"));
+
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({"properties": {"re": {"format": "regex"}, "date": {"format": "date"}}}),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!({"re": "(?<x>a)"}), sch).is_err());
+    assert!(schemas
+        .validate(&json!({"re": "a", "date": "tomorrow"}), sch)
+        .is_ok());
+
+    compiler.deny_unknown_formats();
+    compiler.add_resource("http://tmp.com/date.json", json!({"format": "date"}))?;
+    let result = compiler.compile("http://tmp.com/date.json", &mut schemas);
+    assert!(matches!(result, Err(CompileError::UnknownFormat { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_remove_schema() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/common.json",
+        json!({"$defs": {"name": {"type": "string"}}}),
+    )?;
+    compiler.add_resource(
+        "http://tmp.com/t1.json",
+        json!({"properties": {"name": {"$ref": "common.json#/$defs/name"}}}),
+    )?;
+    compiler.add_resource(
+        "http://tmp.com/t2.json",
+        json!({"items": {"$ref": "common.json#/$defs/name"}}),
+    )?;
+    let t1 = compiler.compile("http://tmp.com/t1.json", &mut schemas)?;
+    let t2 = compiler.compile("http://tmp.com/t2.json", &mut schemas)?;
+    let size = schemas.size();
+
+    assert!(schemas.remove(t1));
+    assert!(!schemas.remove(t1));
+    assert!(!schemas.contains(t1));
+    assert_eq!(schemas.size(), size - 2); // t1 and its properties/name
+    assert!(schemas.validate(&json!([1]), t2).is_err());
+
+    assert!(schemas.remove(t2));
+    assert_eq!(schemas.size(), 0);
+
+    // compiling again gives new index
+    let t1_again = compiler.compile("http://tmp.com/t1.json", &mut schemas)?;
+    assert_ne!(t1, t1_again);
+    assert!(schemas.validate(&json!({"name": 1}), t1_again).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_schema_aliases() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/shop.json",
+        json!({
+            "$defs": {
+                "customer": {"required": ["id"]},
+                "order": {"required": ["items"]}
+            }
+        }),
+    )?;
+    let mut schemas = Schemas::new();
+    let order = compiler.compile("http://tmp.com/shop.json#/$defs/order", &mut schemas)?;
+    let customer = compiler.compile("http://tmp.com/shop.json#/$defs/customer", &mut schemas)?;
+    assert_eq!(schemas.alias(customer, "Customer"), None);
+    assert_eq!(schemas.alias(order, "Order"), None);
+    assert_eq!(schemas.alias(order, "Customer"), Some(customer));
+    assert_eq!(schemas.alias(customer, "Customer"), Some(order));
+    assert_eq!(schemas.by_alias("Order"), Some(order));
+    assert_eq!(schemas.by_alias("Invoice"), None);
+
+    let map = schemas.alias_map();
+    assert_eq!(
+        serde_json::to_value(&map)?,
+        json!({
+            "Customer": "http://tmp.com/shop.json#/$defs/customer",
+            "Order": "http://tmp.com/shop.json#/$defs/order"
+        })
+    );
+
+    // compiled in different order, but same names
+    let mut other = Schemas::new();
+    compiler.compile("http://tmp.com/shop.json#/$defs/customer", &mut other)?;
+    compiler.compile_aliases(&map, &mut other)?;
+    let customer = other.by_alias("Customer").unwrap();
+    assert_ne!(Some(order), other.by_alias("Order"));
+    assert!(other.validate(&json!({"id": 1}), customer).is_ok());
+    assert!(other.validate(&json!({"items": []}), customer).is_err());
+
+    assert!(schemas.remove(order));
+    assert_eq!(schemas.by_alias("Order"), None);
+    assert!(schemas.by_alias("Customer").is_some());
+    Ok(())
+}
+
+#[test]
+fn test_optional_vocabularies() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/meta.json",
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$vocabulary": {
+                "https://json-schema.org/draft/2020-12/vocab/core": true,
+                "https://json-schema.org/draft/2020-12/vocab/applicator": true,
+                "https://json-schema.org/draft/2020-12/vocab/validation": false,
+                "http://example.com/vocab/ui": false
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://tmp.com/a.json",
+        json!({"$schema": "http://tmp.com/meta.json", "type": "string", "widget": "textarea"}),
+    )?;
+
+    // unsupported optional vocabulary is ignored
+    let a = compiler.compile("http://tmp.com/a.json", &mut schemas)?;
+    // supported optional vocabulary is enabled
+    assert_eq!(
+        schemas.vocabularies(a),
+        ["core", "applicator", "validation"]
+    );
+    assert!(schemas.validate(&json!(1), a).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_list_capabilities() {
+    let mut compiler = Compiler::new();
+    compiler.register_format_fn("country", |_| Ok(()));
+    let formats = compiler.formats();
+    assert!(formats.contains(&"date-time"));
+    assert!(formats.contains(&"country"));
+    assert!(formats.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(compiler.content_encodings(), ["base64"]);
+    assert_eq!(compiler.content_media_types(), ["application/json"]);
+    assert_eq!(
+        boon::Draft::all().map(|d| d.version()),
+        [4, 6, 7, 2019, 2020]
+    );
+}
+
+#[test]
+fn test_add_resource_with_draft() -> Result<(), Box<dyn Error>> {
+    let legacy = json!({"type": "number", "maximum": 10, "exclusiveMaximum": true});
+    let schema = json!({"$ref": "http://example.com/legacy.json"});
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource_with_draft(
+        "http://example.com/legacy.json",
+        legacy.clone(),
+        Draft::V4,
+    )?;
+    compiler.add_resource("http://example.com/schema.json", schema)?;
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!(9), sch).is_ok());
+    assert!(schemas.validate(&json!(10), sch).is_err());
+
+    // without override, legacy resource is invalid 2020-12 schema
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/legacy.json", legacy)?;
+    assert!(compiler
+        .compile("http://example.com/legacy.json", &mut Schemas::new())
+        .is_err());
+    Ok(())
+}
+
+#[cfg(feature = "log")]
+#[test]
+fn test_log() -> Result<(), Box<dyn Error>> {
+    use std::sync::Mutex;
+
+    static LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    struct Logger;
+    impl log::Log for Logger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+        fn log(&self, record: &log::Record) {
+            LOGS.lock().unwrap().push(record.args().to_string());
+        }
+        fn flush(&self) {}
+    }
+    log::set_logger(&Logger).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://logtest.com/schema.json",
+        json!({"$ref": "other.json#/$defs/a"}),
+    )?;
+    compiler.add_resource(
+        "http://logtest.com/other.json",
+        json!({"$schema": "http://json-schema.org/draft-07/schema#", "$defs": {"a": {}}}),
+    )?;
+    compiler.compile("http://logtest.com/schema.json", &mut Schemas::new())?;
+
+    let logs = LOGS.lock().unwrap();
+    let has = |msg: &str| logs.iter().any(|m| m == msg);
+    assert!(has(
+        "http://logtest.com/schema.json#: no $schema, using draft 2020"
+    ));
+    assert!(has(
+        "http://logtest.com/other.json#: $schema http://json-schema.org/draft-07/schema# is draft 7"
+    ));
+    assert!(has("http://logtest.com/schema.json#: $ref \"other.json#/$defs/a\" with base http://logtest.com/schema.json is http://logtest.com/other.json#/$defs/a"));
+    assert!(has(
+        "remote ref resolved to http://logtest.com/other.json#/$defs/a"
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_metrics() -> Result<(), Box<dyn Error>> {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    #[derive(Default)]
+    struct Events(Mutex<Vec<String>>);
+    impl boon::Metrics for Events {
+        fn compiled(
+            &self,
+            loc: &str,
+            cached: bool,
+            result: Result<(), &boon::CompileError>,
+            _: Duration,
+        ) {
+            let event = format!("compiled {loc} cached={cached} ok={}", result.is_ok());
+            self.0.lock().unwrap().push(event);
+        }
+        fn validated(&self, schema_url: &str, valid: bool, _: Duration) {
+            let event = format!("validated {schema_url} valid={valid}");
+            self.0.lock().unwrap().push(event);
+        }
+        fn validation_error(&self, _: &str, kind: &boon::ErrorKind) {
+            let event = format!("error {}", kind.keyword_path().unwrap());
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    let events = Arc::new(Events::default());
+    let mut schemas = Schemas::new();
+    schemas.set_metrics(events.clone());
+    let mut compiler = Compiler::new();
+    compiler.set_metrics(events.clone());
+    compiler.add_resource(
+        "http://a.com/schema.json",
+        json!({"properties": {"a": {"type": "string"}, "b": {"minimum": 1}}}),
+    )?;
+    let sch = compiler.compile("http://a.com/schema.json", &mut schemas)?;
+    compiler.compile("http://a.com/schema.json", &mut schemas)?;
+    assert!(compiler
+        .compile("http://a.com/missing.json", &mut schemas)
+        .is_err());
+    assert!(schemas.validate(&json!({"a": 1, "b": 0}), sch).is_err());
+    assert!(schemas.validate(&json!({}), sch).is_ok());
+
+    assert_eq!(
+        *events.0.lock().unwrap(),
+        [
+            "compiled http://a.com/schema.json cached=false ok=true",
+            "compiled http://a.com/schema.json cached=true ok=true",
+            "compiled http://a.com/missing.json cached=false ok=false",
+            "validated http://a.com/schema.json# valid=false",
+            "error type",
+            "error minimum",
+            "validated http://a.com/schema.json# valid=true",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_max_schema_depth() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.set_max_schema_depth(3);
+    let url = "http://a.com/schema.json";
+    compiler.add_resource(url, json!({"properties": {"a": {"items": {}}}}))?;
+    compiler.compile(url, &mut schemas)?;
+
+    let url = "http://a.com/deep.json";
+    compiler.add_resource(url, json!({"properties": {"a": {"items": {"not": {}}}}}))?;
+    let Err(CompileError::MaxDepthExceeded { loc, max_depth }) =
+        compiler.compile(url, &mut schemas)
+    else {
+        panic!("want MaxDepthExceeded");
+    };
+    assert_eq!(loc, "http://a.com/deep.json#/properties/a/items/not");
+    assert_eq!(max_depth, 3);
+
+    // deep schema is rejected before it is validated against metaschema,
+    // which recurses
+    let deep_schema = |depth| {
+        let mut schema = json!({});
+        for _ in 0..depth {
+            schema = Value::Object([("not".to_owned(), schema)].into_iter().collect());
+        }
+        schema
+    };
+    let url = "http://a.com/deeper.json";
+    compiler.add_resource(url, deep_schema(1000))?;
+    let result = compiler.compile(url, &mut schemas);
+    assert!(
+        matches!(
+            result,
+            Err(CompileError::MaxDepthExceeded { max_depth: 3, .. })
+        ),
+        "{result:?}"
+    );
+
+    // no limit by default
+    let mut compiler = Compiler::new();
+    compiler.add_resource(url, deep_schema(10))?;
+    let sch = compiler.compile(url, &mut schemas)?;
+    assert!(schemas.validate(&json!(1), sch).is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_comment() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let url = "http://a.com/schema.json";
+    compiler.add_resource(url, json!({"$comment": "root", "$defs": {"a": {}}}))?;
+    let sch = compiler.compile(url, &mut schemas)?;
+    assert_eq!(schemas.comment(sch), Some("root"));
+    let sch = compiler.compile("http://a.com/schema.json#/$defs/a", &mut schemas)?;
+    assert_eq!(schemas.comment(sch), None);
+
+    // not supported before draft-07
+    let url = "http://a.com/draft6.json";
+    compiler.add_resource_with_draft(url, json!({"$comment": "root"}), Draft::V6)?;
+    let sch = compiler.compile(url, &mut schemas)?;
+    assert_eq!(schemas.comment(sch), None);
+    Ok(())
+}
+
+#[test]
+fn test_strip_annotations() {
+    let mut sch = json!({
+        "$schema": "http://json-schema.org/draft-07/schema",
+        "description": "root",
+        "definitions": {"a": {"$comment": "a", "examples": [1]}},
+        "items": [{"description": "0"}, {"description": "1"}],
+        "not": {"description": "not"},
+        "enum": [{"description": "value"}],
+    });
+    boon::strip_annotations(&mut sch);
+    assert_eq!(
+        sch,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema",
+            "definitions": {"a": {}},
+            "items": [{}, {}],
+            "not": {},
+            "enum": [{"description": "value"}],
+        })
+    );
+}
+
+#[test]
+fn test_flatten_all_of() -> Result<(), Box<dyn Error>> {
+    let orig = json!({
+        "type": ["object", "null"],
+        "properties": {"a": {"type": "number", "minimum": 1}},
+        "allOf": [
+            {
+                "type": "object",
+                "properties": {"a": {"type": "integer", "minimum": 0, "maximum": 10}},
+                "required": ["a"]
+            },
+            {"properties": {"b": {"allOf": [{"minLength": 1}, {"maxLength": 5}]}}},
+            {"$ref": "#/$defs/c"},
+            {"additionalProperties": false}
+        ],
+        "$defs": {"c": {"required": ["b"]}}
+    });
+    let mut sch = orig.clone();
+    boon::flatten_all_of(&mut sch);
+    assert_eq!(
+        sch,
+        json!({
+            "type": "object",
+            "properties": {
+                "a": {"type": "integer", "minimum": 1, "maximum": 10},
+                "b": {"minLength": 1, "maxLength": 5}
+            },
+            "required": ["a"],
+            "allOf": [{"$ref": "#/$defs/c"}, {"additionalProperties": false}],
+            "$defs": {"c": {"required": ["b"]}}
+        })
+    );
+
+    // validates same instances
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/orig.json", orig)?;
+    compiler.add_resource("http://a.com/flat.json", sch)?;
+    let orig = compiler.compile("http://a.com/orig.json", &mut schemas)?;
+    let flat = compiler.compile("http://a.com/flat.json", &mut schemas)?;
+    for v in [
+        json!(null),
+        json!({"a": 1, "b": "x"}),
+        json!({"a": 0, "b": "x"}),
+        json!({"a": 1.5, "b": "x"}),
+        json!({"a": 1, "b": ""}),
+        json!({"a": 1}),
+    ] {
+        assert_eq!(
+            schemas.validate(&v, orig).is_ok(),
+            schemas.validate(&v, flat).is_ok()
+        );
+    }
+
+    // siblings of $ref are ignored before draft2019
+    let mut sch = json!({
+        "$schema": "http://json-schema.org/draft-07/schema",
+        "$ref": "#/definitions/a",
+        "allOf": [{"type": "string"}],
+        "definitions": {"a": {"allOf": [{"type": "string"}]}}
+    });
+    boon::flatten_all_of(&mut sch);
+    assert_eq!(sch["allOf"], json!([{"type": "string"}]));
+    assert_eq!(sch["definitions"]["a"], json!({"type": "string"}));
+    Ok(())
+}
+
+#[test]
+fn test_format_mode() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/meta.json",
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$vocabulary": {
+                "https://json-schema.org/draft/2020-12/vocab/core": true,
+                "https://json-schema.org/draft/2020-12/vocab/validation": true
+            }
+        }),
+    )?;
+    let tests = [
+        (
+            json!({"$schema": "http://json-schema.org/draft-07/schema"}),
+            FormatMode::Assertion,
+        ),
+        (json!({}), FormatMode::Annotation),
+        (
+            json!({"$schema": "http://tmp.com/meta.json"}),
+            FormatMode::Ignore,
+        ),
+    ];
+    for (i, (schema, want)) in tests.into_iter().enumerate() {
+        let url = format!("http://tmp.com/schema{i}.json");
+        compiler.add_resource(&url, schema)?;
+        let sch = compiler.compile(&url, &mut schemas)?;
+        assert_eq!(schemas.format_mode(sch), want, "schema{i}");
+    }
+
+    compiler.enable_format_assertions();
+    compiler.add_resource("http://tmp.com/assert.json", json!({"format": "ipv4"}))?;
+    let sch = compiler.compile("http://tmp.com/assert.json", &mut schemas)?;
+    assert_eq!(schemas.format_mode(sch), FormatMode::Assertion);
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "draft4"))]
+fn test_draft_not_enabled() -> Result<(), Box<dyn Error>> {
+    assert!(!Draft::V4.is_enabled());
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/schema.json",
+        json!({"$schema": "http://json-schema.org/draft-04/schema"}),
+    )?;
+    let result = compiler.compile("http://a.com/schema.json", &mut schemas);
+    assert!(
+        matches!(
+            result,
+            Err(CompileError::DraftNotEnabled {
+                draft: Draft::V4,
+                ..
+            })
+        ),
+        "{result:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_retry_loader() {
+    struct Failing(Rc<RefCell<usize>>, fn() -> Box<dyn Error>);
+    impl UrlLoader for Failing {
+        fn load(&self, _url: &str) -> Result<Value, Box<dyn Error>> {
+            *self.0.borrow_mut() += 1;
+            Err((self.1)())
+        }
+    }
+
+    let attempts = Rc::new(RefCell::new(0));
+    let mut loader = RetryLoader::new(Failing(attempts.clone(), || {
+        std::io::Error::from(std::io::ErrorKind::TimedOut).into()
+    }));
+    loader.set_backoff(Duration::from_millis(1), Duration::from_millis(2));
+    loader.set_max_retries(2);
+    assert!(loader.load("http://a.com/s.json").is_err());
+    assert_eq!(*attempts.borrow(), 3);
+
+    // not transient
+    let errors: [fn() -> Box<dyn Error>; 3] = [
+        || serde_json::from_str::<Value>("{").unwrap_err().into(),
+        || std::io::Error::from(std::io::ErrorKind::NotFound).into(),
+        || "unknown".into(),
+    ];
+    for err in errors {
+        let attempts = Rc::new(RefCell::new(0));
+        let loader = RetryLoader::new(Failing(attempts.clone(), err));
+        assert!(loader.load("http://a.com/s.json").is_err());
+        assert_eq!(*attempts.borrow(), 1);
+    }
+
+    // custom classification
+    let attempts = Rc::new(RefCell::new(0));
+    let mut loader = RetryLoader::new(Failing(attempts.clone(), || "503".into()));
+    loader.set_backoff(Duration::from_millis(1), Duration::from_millis(2));
+    loader.set_retry_if(|e| e.to_string() == "503");
+    assert!(loader.load("http://a.com/s.json").is_err());
+    assert_eq!(*attempts.borrow(), 4);
+}
+
+#[test]
+fn test_scheme_loader_fallback() -> Result<(), Box<dyn Error>> {
+    let mut mirror = MemoryLoader::new();
+    mirror.add("https://a.com/a.json", json!({"$ref": "b.json"}))?;
+    let mut remote = MemoryLoader::new();
+    remote.add("https://a.com/b.json", json!({"$ref": "x://c.json"}))?;
+    let mut other = MemoryLoader::new();
+    other.add("x://c.json", json!({"type": "string"}))?;
+
+    let mut loader = SchemeUrlLoader::new();
+    loader.register("https", Box::new(mirror));
+    loader.register_fallback("https", Box::new(remote));
+    loader.register_default(Box::new(other));
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(loader));
+    let mut schemas = Schemas::new();
+    let sch = compiler.compile("https://a.com/a.json", &mut schemas)?;
+    assert!(schemas.validate(&json!("x"), sch).is_ok());
+    assert!(schemas.validate(&json!(1), sch).is_err());
+
+    // error from first loader is reported
+    let result = compiler.compile("https://a.com/d.json", &mut schemas);
+    let Err(CompileError::LoadUrlError { src, .. }) = result else {
+        panic!("{result:?}");
+    };
+    assert_eq!(src.to_string(), "https://a.com/d.json not found in memory");
+
+    // no default loader
+    let loader = SchemeUrlLoader::new();
+    let err = loader.load("https://a.com/a.json").unwrap_err();
+    assert!(err.is::<CompileError>(), "{err}");
+    Ok(())
+}
+
+#[test]
+fn test_require_preloaded_resources() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.require_preloaded_resources();
+    compiler.add_resource(
+        "http://a.com/person.json",
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object"
+        }),
+    )?;
+    compiler.add_resource(
+        "http://a.com/team.json",
+        json!({"items": {"$ref": "person.json"}}),
+    )?;
+    compiler.add_resource(
+        "http://a.com/typo.json",
+        json!({"items": {"$ref": "persn.json"}}),
+    )?;
+    let mut schemas = Schemas::new();
+
+    // std metaschemas are available
+    let sch = compiler.compile("http://a.com/team.json", &mut schemas)?;
+    assert!(schemas.validate(&json!([1]), sch).is_err());
+
+    let result = compiler.compile("http://a.com/typo.json", &mut schemas);
+    let Err(e @ CompileError::UnresolvedReference { .. }) = result else {
+        panic!("{result:?}");
+    };
+    let msg = format!("{e:#}");
+    assert!(
+        msg.starts_with(
+            "unresolved reference 'persn.json' at http://a.com/typo.json#/items/$ref \
+            (resolved to http://a.com/persn.json# against http://a.com/typo.json): \
+            http://a.com/persn.json is not preloaded, did you mean http://a.com/person.json"
+        ),
+        "{msg}"
+    );
+
+    // file is not loaded
+    let path = std::path::absolute("tests/examples/schema.json")?;
+    let url = url::Url::from_file_path(path).unwrap();
+    let result = compiler.compile(url.as_str(), &mut schemas);
+    assert!(
+        matches!(result, Err(CompileError::NotPreloaded { .. })),
+        "{result:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_deny_duplicate_keys() -> Result<(), Box<dyn Error>> {
+    let url = r#"data:application/json,{"type": "string", "type": "number"}"#;
+
+    // last one wins, by default
+    let mut schemas = Schemas::new();
+    let sch = Compiler::new().compile(url, &mut schemas)?;
+    assert!(schemas.validate(&json!(1), sch).is_ok());
+
+    let mut compiler = Compiler::new();
+    compiler.deny_duplicate_keys();
+    let result = compiler.compile(url, &mut schemas);
+    let Err(e @ CompileError::DuplicateKey { .. }) = result else {
+        panic!("{result:?}");
+    };
+    assert!(
+        e.to_string().starts_with("duplicate key 'type' in data:"),
+        "{e}"
+    );
+
+    // custom loader using parse_strict
+    struct StrictLoader;
+    impl UrlLoader for StrictLoader {
+        fn load(&self, _url: &str) -> Result<Value, Box<dyn Error>> {
+            Ok(boon::parse_strict(
+                br#"{"items": {"minimum": 1, "minimum": 2}}"#,
+            )?)
+        }
+    }
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(StrictLoader));
+    let result = compiler.compile("http://a.com/s.json", &mut schemas);
+    assert!(
+        matches!(
+            result,
+            Err(CompileError::DuplicateKey { ref url, ref key, line: 1, .. })
+                if url == "http://a.com/s.json" && key == "minimum"
+        ),
+        "{result:?}"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "zip", feature = "tar", feature = "fs"))]
+fn test_archive() -> Result<(), Box<dyn Error>> {
+    for archive in [
+        "tests/archives/schemas.zip",
+        "tests/archives/schemas.tar.gz",
+    ] {
+        let mut compiler = Compiler::new();
+        compiler.add_archive(archive)?;
+        let base = url::Url::from_file_path(std::path::absolute(archive)?).unwrap();
+        let scheme = if archive.ends_with(".zip") {
+            "zip"
+        } else {
+            "tar"
+        };
+        let url = format!("{scheme}{}!/schemas/root.json", &base.as_str()[4..]);
+        let mut schemas = Schemas::new();
+        let sch = compiler.compile(&url, &mut schemas)?;
+        assert!(schemas
+            .validate(&json!({"name": "a", "tags": ["b"]}), sch)
+            .is_ok());
+        assert!(schemas.validate(&json!({"tags": [1]}), sch).is_err());
+    }
+
+    // loaded lazily, relative to current directory
+    let mut compiler = Compiler::new();
+    let mut schemas = Schemas::new();
+    let sch = compiler.compile(
+        "zip://tests/archives/schemas.zip!/schemas/root.json",
+        &mut schemas,
+    )?;
+    assert!(schemas.validate(&json!({"name": 1}), sch).is_err());
+    let result = compiler.compile(
+        "tar://tests/archives/schemas.tar.gz!/schemas/x.json",
+        &mut schemas,
+    );
+    assert!(
+        matches!(result, Err(CompileError::LoadUrlError { .. })),
+        "{result:?}"
+    );
+
+    // archive is read only once
+    let path = std::env::temp_dir().join(format!("boon-{}.zip", std::process::id()));
+    std::fs::copy("tests/archives/schemas.zip", &path)?;
+    let loader = ArchiveLoader::new();
+    let base = url::Url::from_file_path(&path).unwrap();
+    let base = format!("zip{}", &base.as_str()[4..]);
+    loader.load(&format!("{base}!/schemas/root.json"))?;
+    std::fs::remove_file(&path)?;
+    let names = loader.load(&format!("{base}!/schemas/name.json"));
+    assert!(names.is_ok(), "{names:?}");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "include_dir")]
+fn test_embedded() -> Result<(), Box<dyn Error>> {
+    static SCHEMAS: include_dir::Dir =
+        include_dir::include_dir!("$CARGO_MANIFEST_DIR/tests/embedded");
+
+    let mut compiler = Compiler::new();
+    compiler.add_embedded(&SCHEMAS, "https://a.com/schemas")?;
+    let mut schemas = Schemas::new();
+    let sch = compiler.compile("https://a.com/schemas/root.json", &mut schemas)?;
+    assert!(schemas
+        .validate(&json!({"name": "a", "tags": ["b"]}), sch)
+        .is_ok());
+    assert!(schemas.validate(&json!({"tags": [1]}), sch).is_err());
+
+    // subdirectory
+    let mut compiler = Compiler::new();
+    let dir = SCHEMAS.get_dir("common").unwrap();
+    compiler.add_embedded(dir, "https://a.com/common/")?;
+    let sch = compiler.compile("https://a.com/common/tags.json", &mut schemas)?;
+    assert!(schemas.validate(&json!(["a"]), sch).is_ok());
+
+    let result = compiler.add_embedded(&SCHEMAS, "urn:a");
+    assert!(
+        matches!(result, Err(CompileError::ParseUrlError { .. })),
+        "{result:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_resource_dedup() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_resource_dedup();
+    let name = json!({"type": "string", "minLength": 1});
+    compiler.add_resource("http://a.com/v1/name.json", name.clone())?;
+    compiler.add_resource("http://a.com/v2/name.json", name.clone())?;
+    // relative $ref, depends on url
+    let person = json!({"properties": {"name": {"$ref": "name.json"}}});
+    compiler.add_resource("http://a.com/v1/person.json", person.clone())?;
+    compiler.add_resource("http://a.com/v2/person.json", person.clone())?;
+    // absolute $id
+    let id = json!({"$id": "http://a.com/id.json", "$ref": "v2/name.json"});
+    compiler.add_resource("http://a.com/x.json", id.clone())?;
+    compiler.add_resource("http://a.com/y.json", id.clone())?;
+
+    let v1 = compiler.compile("http://a.com/v1/name.json", &mut schemas)?;
+    let v2 = compiler.compile("http://a.com/v2/name.json#", &mut schemas)?;
+    assert_eq!(v1, v2);
+    let p1 = compiler.compile("http://a.com/v1/person.json", &mut schemas)?;
+    let p2 = compiler.compile("http://a.com/v2/person.json", &mut schemas)?;
+    assert_ne!(p1, p2);
+    let (x, y) = (
+        compiler.compile("http://a.com/x.json", &mut schemas)?,
+        compiler.compile("http://a.com/y.json", &mut schemas)?,
+    );
+    assert_eq!(x, y);
+
+    // fingerprint covers referenced schemas, not urls
+    assert_eq!(schemas.fingerprint(p1), schemas.fingerprint(p2));
+    assert_ne!(schemas.fingerprint(p1), schemas.fingerprint(v1));
+    let mut other = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://b.com/person.json", person)?;
+    compiler.add_resource(
+        "http://b.com/name.json",
+        json!({"minLength": 2, "type": "string"}),
+    )?;
+    let p3 = compiler.compile("http://b.com/person.json", &mut other)?;
+    assert_ne!(schemas.fingerprint(p1), other.fingerprint(p3));
+    Ok(())
+}
+
+#[test]
+fn test_fingerprint_ref_targets() -> Result<(), Box<dyn Error>> {
+    let (string, number) = (json!({"type": "string"}), json!({"type": "number"}));
+    let schema = json!({
+        "properties": {
+            "a": {"$ref": "a.json"},
+            "b": {"$ref": "b.json"}
+        }
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    for (base, a, b) in [
+        ("http://a.com", &string, &number),
+        ("http://b.com", &number, &string),
+        ("http://c.com", &string, &number),
+    ] {
+        compiler.add_resource(&format!("{base}/schema.json"), schema.clone())?;
+        compiler.add_resource(&format!("{base}/a.json"), a.clone())?;
+        compiler.add_resource(&format!("{base}/b.json"), b.clone())?;
+    }
+    let a = compiler.compile("http://a.com/schema.json", &mut schemas)?;
+    let b = compiler.compile("http://b.com/schema.json", &mut schemas)?;
+    let c = compiler.compile("http://c.com/schema.json", &mut schemas)?;
+
+    // same set of schemas, but with swapped ref targets
+    assert_ne!(schemas.fingerprint(a), schemas.fingerprint(b));
+    assert_eq!(schemas.fingerprint(a), schemas.fingerprint(c));
+    Ok(())
+}
+
+#[test]
+fn test_compile_value() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/name.json", json!({"type": "string"}))?;
+    let sch = compiler.compile_value(
+        "http://a.com/person.json#/ignored",
+        json!({"properties": {"name": {"$ref": "name.json"}}}),
+        &mut schemas,
+    )?;
+    assert_eq!(
+        compiler.compile("http://a.com/person.json", &mut schemas)?,
+        sch
+    );
+    assert!(schemas.validate(&json!({"name": "x"}), sch).is_ok());
+    assert!(schemas.validate(&json!({"name": 1}), sch).is_err());
+
+    let schema = json!({"$defs": {"pos": {"minimum": 0}}, "$ref": "#/$defs/pos"});
+    let sch1 = compiler.compile_anonymous(schema.clone(), &mut schemas)?;
+    let sch2 = compiler.compile_anonymous(schema, &mut schemas)?;
+    assert_ne!(sch1, sch2);
+    assert!(schemas.validate(&json!(1), sch1).is_ok());
+    assert!(schemas.validate(&json!(-1), sch2).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_anchors() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/s.json",
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "definitions": {
+                "a": {"$id": "#a"},
+                "b": {"$id": "b.json", "definitions": {"c": {"$id": "#c"}}}
+            }
+        }),
+    )?;
+    let anchors: Vec<_> = compiler
+        .anchors("http://a.com/s.json#/ignored")?
+        .into_iter()
+        .map(|a| (a.kind, a.url))
+        .collect();
+    assert_eq!(
+        anchors,
+        [
+            (AnchorKind::Resource, "http://a.com/s.json".to_owned()),
+            (AnchorKind::Anchor, "http://a.com/s.json#a".to_owned()),
+            (AnchorKind::Resource, "http://a.com/b.json".to_owned()),
+            (AnchorKind::Anchor, "http://a.com/b.json#c".to_owned()),
+        ]
+    );
+
+    compiler.add_resource(
+        "http://a.com/dup.json",
+        json!({"$defs": {"a": {"$anchor": "x"}, "b": {"$anchor": "x"}}}),
+    )?;
+    let anchors: Vec<_> = compiler
+        .anchors("http://a.com/dup.json")?
+        .into_iter()
+        .map(|a| (a.url, a.ptr, a.duplicate))
+        .collect();
+    assert_eq!(
+        anchors,
+        [
+            ("http://a.com/dup.json".to_owned(), "".to_owned(), false),
+            (
+                "http://a.com/dup.json#x".to_owned(),
+                "/$defs/a".to_owned(),
+                true
+            ),
+            (
+                "http://a.com/dup.json#x".to_owned(),
+                "/$defs/b".to_owned(),
+                true
+            ),
+        ]
+    );
+    let mut schemas = Schemas::new();
+    let result = compiler.compile("http://a.com/dup.json", &mut schemas);
+    assert!(matches!(result, Err(CompileError::DuplicateAnchor { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_kubernetes_structural() {
+    let cases = [
+        (
+            json!({"properties": {"a": {"type": "string"}}}),
+            "#",
+            "type",
+        ),
+        (
+            json!({"type": "object", "properties": {"a": {"description": "x"}}}),
+            "#/properties/a",
+            "type",
+        ),
+        (
+            json!({"type": "object", "properties": {"a": {"type": "string", "x-kubernetes-int-or-string": true}}}),
+            "#/properties/a",
+            "x-kubernetes-int-or-string",
+        ),
+        (
+            json!({"type": "object", "properties": {"a": {"type": "string"}}, "additionalProperties": false}),
+            "#",
+            "mutually exclusive",
+        ),
+        (
+            json!({"type": "object", "properties": {"a": {"type": "string"}}, "anyOf": [{"properties": {"b": {}}}]}),
+            "#/anyOf/0",
+            "'b' must be specified outside",
+        ),
+        (
+            json!({"type": "object", "allOf": [{"type": "object"}]}),
+            "#/allOf/0",
+            "'type' is not allowed",
+        ),
+        (
+            json!({"type": "array", "items": {"$ref": "#/definitions/x"}}),
+            "#/items",
+            "$ref",
+        ),
+    ];
+    for (schema, loc, reason) in cases {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        compiler.enable_kubernetes_extensions();
+        let result = compiler.compile_value("http://a.com/crd.json", schema.clone(), &mut schemas);
+        let Err(CompileError::NonStructural {
+            loc: got_loc,
+            reason: got,
+        }) = result
+        else {
+            panic!("{schema} must not be structural: {result:?}");
+        };
+        assert_eq!(got_loc, format!("http://a.com/crd.json{loc}"));
+        assert!(got.contains(reason), "{got:?} must contain {reason:?}");
+        assert_eq!(schemas.size(), 0);
+    }
+
+    // int-or-string may use anyOf with types
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_kubernetes_extensions();
+    let schema = json!({
+        "x-kubernetes-int-or-string": true,
+        "anyOf": [{"type": "integer"}, {"type": "string", "pattern": "^[0-9]+%$"}]
+    });
+    let sch = compiler.compile_value("http://a.com/crd.json", schema, &mut schemas);
+    assert!(sch.is_ok(), "{sch:?}");
+}
+
+#[test]
+fn test_invalid_jtd() {
+    let cases = [
+        (json!([]), "#", "must be an object"),
+        (json!({"type": "int64"}), "#/type", "invalid type"),
+        (json!({"type": "string", "enum": ["a"]}), "#", "one form"),
+        (json!({"enum": ["a", "a"]}), "#/enum", "duplicates"),
+        (
+            json!({"elements": {"ref": "x"}}),
+            "#/elements/ref",
+            "not found",
+        ),
+        (
+            json!({"properties": {"a": {}}, "optionalProperties": {"a": {}}}),
+            "#/optionalProperties",
+            "both required and optional",
+        ),
+        (
+            json!({"values": {}, "additionalProperties": true}),
+            "#/additionalProperties",
+            "requires",
+        ),
+        (
+            json!({"properties": {"a": {"definitions": {}}}}),
+            "#/properties/a/definitions",
+            "root",
+        ),
+        (
+            json!({"discriminator": "k", "mapping": {"a": {"type": "string"}}}),
+            "#/mapping/a",
+            "properties form",
+        ),
+        (
+            json!({"discriminator": "k", "mapping": {"a": {"properties": {"k": {}}}}}),
+            "#/mapping/a/properties",
+            "discriminator",
+        ),
+        (json!({"$ref": "#"}), "#", "unknown keyword"),
+    ];
+    for (schema, loc, reason) in cases {
+        let mut compiler = Compiler::new();
+        compiler.set_dialect(Dialect::Jtd);
+        let result = compiler.add_resource("http://a.com/s.jtd.json", schema.clone());
+        let Err(CompileError::InvalidJtdSchema {
+            loc: got_loc,
+            reason: got,
+        }) = result
+        else {
+            panic!("{schema} must be invalid: {result:?}");
+        };
+        assert_eq!(got_loc, format!("http://a.com/s.jtd.json{loc}"));
+        assert!(got.contains(reason), "{got:?} must contain {reason:?}");
+    }
+}
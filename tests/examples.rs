@@ -1,3 +1,5 @@
+#![cfg(feature = "fs")]
+
 use std::{error::Error, fs::File};
 
 use boon::{Compiler, Decoder, FileLoader, Format, MediaType, Schemas, SchemeUrlLoader, UrlLoader};
@@ -154,6 +156,35 @@ fn example_custom_format() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn example_custom_format_fn() -> Result<(), Box<dyn Error>> {
+    let schema_url = "http://tmp/schema.json";
+    let schema: Value = json!({"type": "string", "format": "vat"});
+
+    // closure captures the lookup table
+    let prefixes = ["DE", "FR", "NL"];
+    let is_vat = move |v: &Value| -> Result<(), Box<dyn Error>> {
+        let Value::String(s) = v else {
+            return Ok(()); // applicable only on strings
+        };
+        if !prefixes.iter().any(|p| s.starts_with(p)) {
+            Err("unknown country prefix")?;
+        }
+        Ok(())
+    };
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions(); // in draft2020-12 format assertions are not enabled by default
+    compiler.register_format_fn("vat", is_vat);
+    compiler.add_resource(schema_url, schema)?;
+    let sch_index = compiler.compile(schema_url, &mut schemas)?;
+    assert!(schemas.validate(&json!("DE123456789"), sch_index).is_ok());
+    assert!(schemas.validate(&json!("XX123456789"), sch_index).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn example_custom_content_encoding() -> Result<(), Box<dyn Error>> {
     let schema_url = "http://tmp/schema.json";
@@ -168,6 +199,7 @@ fn example_custom_content_encoding() -> Result<(), Box<dyn Error>> {
             _ => Err("decode_hex: non-hex char")?,
         }
     }
+    #[allow(clippy::manual_is_multiple_of)]
     fn decode_hex(s: &str) -> Result<Vec<u8>, Box<dyn Error>> {
         if s.len() % 2 != 0 {
             Err("decode_hex: odd length")?;
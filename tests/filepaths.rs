@@ -1,3 +1,5 @@
+#![allow(clippy::result_large_err)]
+
 use std::fs;
 
 use boon::{CompileError, Compiler, Schemas};
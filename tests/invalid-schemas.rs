@@ -1,3 +1,5 @@
+#![allow(clippy::result_large_err)]
+
 use std::{collections::HashMap, error::Error, fs::File};
 
 use boon::{CompileError, Compiler, Schemas, UrlLoader};
@@ -1,8 +1,8 @@
 use std::{env, error::Error, fs::File, path::Path};
 
-use boon::{Compiler, Draft, Schemas};
+use boon::{Compiler, DocFormat, Draft, LocationFormat, Schemas};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 
 #[test]
 fn test_suites() -> Result<(), Box<dyn Error>> {
@@ -120,3 +120,284 @@ struct Output {
     basic: Option<Value>,
     detailed: Option<Value>,
 }
+
+#[test]
+fn test_ajv_output() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "$defs": {"name": {"type": "string", "minLength": 2}},
+        "required": ["id", "name"],
+        "properties": {
+            "name": {"$ref": "#/$defs/name"},
+            "age": {"anyOf": [{"type": "integer"}, {"type": "null"}]}
+        },
+        "additionalProperties": false
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!({"name": "a", "age": "x", "x": 1});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let got = serde_json::to_value(err.ajv_output())?;
+    let want = json!([
+        {
+            "instancePath": "",
+            "schemaPath": "#/required",
+            "keyword": "required",
+            "params": {"missingProperty": "id"},
+            "message": "must have required property 'id'"
+        },
+        {
+            "instancePath": "/age",
+            "schemaPath": "#/properties/age/anyOf/0/type",
+            "keyword": "type",
+            "params": {"type": "integer"},
+            "message": "must be integer"
+        },
+        {
+            "instancePath": "/age",
+            "schemaPath": "#/properties/age/anyOf/1/type",
+            "keyword": "type",
+            "params": {"type": "null"},
+            "message": "must be null"
+        },
+        {
+            "instancePath": "/age",
+            "schemaPath": "#/properties/age/anyOf",
+            "keyword": "anyOf",
+            "params": {},
+            "message": "must match a schema in anyOf"
+        },
+        {
+            "instancePath": "/name",
+            "schemaPath": "#/$defs/name/minLength",
+            "keyword": "minLength",
+            "params": {"limit": 2},
+            "message": "must NOT have fewer than 2 characters"
+        },
+        {
+            "instancePath": "",
+            "schemaPath": "#/additionalProperties",
+            "keyword": "additionalProperties",
+            "params": {"additionalProperty": "x"},
+            "message": "must NOT have additional properties"
+        }
+    ]);
+    assert_eq!(got, want, "{got:#}");
+    Ok(())
+}
+
+#[test]
+fn test_additional_properties_location() -> Result<(), Box<dyn Error>> {
+    let schema = json!({"properties": {"a": {}}, "additionalProperties": false});
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!({"x": 1});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let cause = &err.causes[0];
+    assert_eq!(
+        cause.kind.keyword_path().unwrap().to_string(),
+        "additionalProperties"
+    );
+    let basic = serde_json::to_value(err.basic_output())?;
+    let unit = basic["errors"].as_array().unwrap().last().unwrap();
+    assert_eq!(unit["keywordLocation"], "/additionalProperties");
+    let ajv = err.ajv_output();
+    assert_eq!(ajv[0].keyword, "additionalProperties");
+    Ok(())
+}
+
+#[test]
+fn test_grouped_output() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "required": ["id"],
+        "properties": {
+            "name": {"type": "string", "minLength": 2, "allOf": [{"minLength": 2}]},
+            "tags": {"items": {"anyOf": [{"type": "integer"}, {"type": "null"}]}}
+        }
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!({"name": "a", "tags": [1, "x"]});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let got = serde_json::to_value(
+        err.grouped_output()
+            .with_location_format(LocationFormat::JsonPath),
+    )?;
+    let want = json!({
+        "$": ["missing properties 'id'"],
+        "$.name": ["length must be >=2, but got 1"],
+        "$.tags[1]": ["want integer, but got string", "want null, but got string"]
+    });
+    assert_eq!(got, want, "{got:#}");
+    Ok(())
+}
+
+#[test]
+fn test_output_params() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "required": ["id", "name"],
+        "properties": {
+            "age": {"minimum": 0},
+            "kind": {"type": ["string", "null"]},
+            "code": {"pattern": "^[A-Z]+$"}
+        },
+        "additionalProperties": false
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!({"age": -1.5, "kind": 1, "code": "x", "x": 1});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+
+    // not serialized by default
+    let got = serde_json::to_value(err.basic_output())?;
+    assert!(!got.to_string().contains("params"), "{got:#}");
+
+    let got = serde_json::to_value(err.basic_output().with_params())?;
+    let mut params: Vec<_> = got["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|unit| (unit["keywordLocation"].clone(), unit["params"].clone()))
+        .collect();
+    params.sort_by_key(|(loc, _)| loc.to_string());
+    let want = [
+        (
+            json!("/additionalProperties"),
+            json!({"additionalProperties": ["x"]}),
+        ),
+        (
+            json!("/properties/age/minimum"),
+            json!({"comparison": ">=", "limit": 0}),
+        ),
+        (
+            json!("/properties/code/pattern"),
+            json!({"pattern": "^[A-Z]+$"}),
+        ),
+        (
+            json!("/properties/kind/type"),
+            json!({"type": ["null", "string"]}),
+        ),
+        (
+            json!("/required"),
+            json!({"missingProperties": ["id", "name"]}),
+        ),
+    ];
+    assert_eq!(params, want);
+
+    // nested units of detailed output too
+    let got = err.detailed_output().with_params().to_string();
+    assert!(got.contains(r#""params":{"pattern":"^[A-Z]+$"}"#), "{got}");
+    Ok(())
+}
+
+#[test]
+fn test_problem_details() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "required": ["id"],
+        "properties": {
+            "tags": {"items": {"anyOf": [{"type": "integer"}, {"$ref": "#/$defs/null"}]}}
+        },
+        "$defs": {"null": {"type": "null"}}
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!({"tags": [1, "x"]});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let problem = err
+        .to_problem_details("about:blank", 400)
+        .with_title("Bad Request");
+    assert_eq!(problem.instance, None);
+    let want = json!({
+        "type": "about:blank",
+        "title": "Bad Request",
+        "status": 400,
+        "detail": "validation failed with http://tmp.com/schema.json#",
+        "errors": [
+            {"pointer": "", "keyword": "required", "message": "missing properties 'id'"},
+            {"pointer": "/tags/1", "keyword": "type", "message": "want integer, but got string"},
+            {"pointer": "/tags/1", "keyword": "type", "message": "want null, but got string"}
+        ]
+    });
+    let got: Value = serde_json::from_str(&problem.to_string())?;
+    assert_eq!(got, want, "{got:#}");
+    Ok(())
+}
+
+#[test]
+fn test_doc() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/tree.json",
+        json!({
+            "title": "Tree",
+            "description": "a <binary> tree",
+            "type": "object",
+            "properties": {
+                "value": {"type": ["integer", "null"], "minimum": 0, "examples": [1]},
+                "left": {"$ref": "#"},
+                "right": {"$ref": "#/$defs/node"},
+                "tag": true
+            },
+            "required": ["value"],
+            "$defs": {"node": {"$ref": "#"}}
+        }),
+    )?;
+    let sch = compiler.compile("http://a.com/tree.json", &mut schemas)?;
+
+    let doc = schemas.doc(sch, DocFormat::Markdown);
+    let want = r#"<a id="s0"></a>
+## Tree
+
+`http://a.com/tree.json#`
+
+a <binary> tree
+
+- **type:** `object`
+- `required 'value'`
+
+### Properties
+
+| Name | Type | Required | Description |
+| --- | --- | --- | --- |
+| [`left`](#s0) | `object` | no |  |
+| [`right`](#s0) | `object` | no |  |
+| `tag` | `any` | no |  |
+| [`value`](#s1) | `null or integer` | yes |  |
+
+<a id="s1"></a>
+## http://a.com/tree.json#/properties/value
+
+`http://a.com/tree.json#/properties/value`
+
+- **type:** `null or integer`
+- `minimum 0`
+
+**examples:**
+
+```json
+1
+```
+"#;
+    assert_eq!(doc, want);
+
+    let doc = schemas.doc(sch, DocFormat::Html);
+    assert!(doc.starts_with("<section id=\"s0\">\n<h2>Tree</h2>\n"));
+    assert!(doc.contains("<p>a &lt;binary&gt; tree</p>"));
+    assert!(doc.contains("<a href=\"#s1\"><code>value</code></a>"));
+    Ok(())
+}
@@ -0,0 +1,27 @@
+#![cfg(all(feature = "fs", not(target_arch = "wasm32")))]
+
+use std::error::Error;
+
+use boon::{
+    testsuite::{run, Options},
+    Compiler, Draft,
+};
+
+#[test]
+fn test_run() -> Result<(), Box<dyn Error>> {
+    let report = run("tests/Extra-Test-Suite", Draft::V2020_12, &Options::new())?;
+    assert!(report.passed > 0);
+    assert!(report.failures.is_empty(), "{:?}", report.failures);
+
+    // a broken configuration is reported as failures
+    let mut options = Options::new();
+    options.set_configure(|c: &mut Compiler| c.override_format("date", |_| Ok(())));
+    let report = run("tests/Extra-Test-Suite", Draft::V2020_12, &options)?;
+    assert!(report
+        .failures
+        .iter()
+        .any(|f| f.file.ends_with("optional/format/date.json") && !f.valid));
+
+    assert!(run("tests/no-such-suite", Draft::V2020_12, &options).is_err());
+    Ok(())
+}
@@ -0,0 +1,2354 @@
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+use boon::{
+    avro,
+    formats::{DateTimeOptions, HostnameOptions},
+    parse_strict, Compiler, Dialect, Draft, DynamicRefTrace, ErrorKind, InstanceError,
+    InstanceLocation, InstanceToken, LocationFormat, Metrics, OptimizationLevel, PatchError,
+    Schemas, Severity, StringLength, Trace, ValidationError, ValidationOptions,
+};
+use serde_json::json;
+
+#[test]
+fn test_clone_error() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.add_resource("http://tmp.com/schema.json", json!({"format": "ipv4"}))?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!("1.2.3");
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let clone = err.clone();
+    assert_eq!(format!("{err:#}"), format!("{clone:#}"));
+    let ErrorKind::Format { err: e1, .. } = &err.causes[0].kind else {
+        panic!("want format error");
+    };
+    let ErrorKind::Format { err: e2, .. } = &clone.causes[0].kind else {
+        panic!("want format error");
+    };
+    assert_eq!(e1.to_string(), e2.to_string());
+
+    Ok(())
+}
+
+#[test]
+fn test_error_kind_eq() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({"type": "object", "required": ["a", "b"], "minProperties": 3}),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!({"a": 1});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let kinds: Vec<_> = err.causes.iter().map(|e| &e.kind).collect();
+    assert!(kinds.contains(&&ErrorKind::Required { want: vec!["b"] }));
+    assert!(kinds.contains(&&ErrorKind::MinProperties { got: 1, want: 3 }));
+    assert!(kinds
+        .iter()
+        .any(|k| k.matches_kind(&ErrorKind::MinProperties { got: 0, want: 0 })));
+    assert_eq!(err, err.clone());
+
+    Ok(())
+}
+
+#[test]
+fn test_not_explanation() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({"not": {"type": "string", "maxLength": 3}}),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!("abc");
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    assert_eq!(
+        err.causes[0].kind,
+        ErrorKind::Not {
+            url: "http://tmp.com/schema.json#/not",
+            matched: vec!["type string".to_owned(), "maxLength 3".to_owned()],
+        }
+    );
+    assert_eq!(
+        err.causes[0].kind.to_string(),
+        "value matched disallowed schema http://tmp.com/schema.json#/not (type string, maxLength 3)"
+    );
+    assert!(schemas.validate(&json!("abcd"), sch).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_one_of_closest_branch() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({
+            "oneOf": [
+                {"type": "string"},
+                {
+                    "type": "object",
+                    "properties": {"kind": {"const": "circle"}, "radius": {"type": "number"}},
+                    "required": ["kind", "radius"]
+                },
+                {
+                    "type": "object",
+                    "properties": {"kind": {"const": "square"}, "side": {"type": "number"}},
+                    "required": ["kind", "side"]
+                }
+            ]
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!({"kind": "circle", "radius": "1"});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let err = &err.causes[0];
+    assert_eq!(err.kind, ErrorKind::OneOf(None));
+    assert_eq!(err.branch_error_counts(), Some(vec![1, 1, 2]));
+    assert_eq!(err.closest_branch(), Some(1));
+    assert!(err
+        .to_string()
+        .contains("oneOf failed, none matched, closest match is subschema 1"));
+
+    Ok(())
+}
+
+#[test]
+fn test_discriminator() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "components": {
+            "schemas": {
+                "Pet": {
+                    "oneOf": [
+                        { "$ref": "#/components/schemas/Cat" },
+                        { "$ref": "#/components/schemas/Dog" },
+                        { "$ref": "#/components/schemas/Lizard" }
+                    ],
+                    "discriminator": {
+                        "propertyName": "petType",
+                        "mapping": { "reptile": "#/components/schemas/Lizard" }
+                    }
+                },
+                "Cat": {
+                    "properties": { "lives": { "type": "integer" } }
+                },
+                "Dog": {
+                    "properties": { "bark": { "type": "boolean" } }
+                },
+                "Lizard": {
+                    "properties": { "scales": { "type": "integer" } },
+                    "required": ["scales"]
+                }
+            }
+        }
+    });
+    let loc = "http://tmp.com/openapi.json#/components/schemas/Pet";
+
+    // by default, discriminator is just an annotation
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/openapi.json", schema.clone())?;
+    let sch = compiler.compile(loc, &mut schemas)?;
+    let instance = json!({"petType": "Cat"});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    assert_eq!(err.causes[0].kind, ErrorKind::OneOf(Some((0, 1))));
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_openapi_discriminator();
+    compiler.add_resource("http://tmp.com/openapi.json", schema)?;
+    let sch = compiler.compile(loc, &mut schemas)?;
+
+    // without discriminator, it would match both Cat and Dog
+    assert!(schemas.validate(&json!({"petType": "Cat"}), sch).is_ok());
+    assert!(schemas
+        .validate(&json!({"petType": "reptile", "scales": 3}), sch)
+        .is_ok());
+
+    let instance = json!({"petType": "Dog", "bark": 1});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    assert!(matches!(err.causes[0].kind, ErrorKind::Reference { .. }));
+    assert_eq!(
+        err.causes[0].causes[0].instance_location.to_string(),
+        "/bark"
+    );
+
+    let instance = json!({"petType": "reptile"});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    assert!(matches!(
+        err.causes[0].causes[0].kind,
+        ErrorKind::Required { .. }
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_one_of_discriminator() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.set_one_of_discriminator("kind");
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({
+            "oneOf": [
+                { "properties": { "kind": { "const": "circle" }, "radius": { "type": "number" } } },
+                {
+                    "allOf": [{ "$ref": "#/$defs/shape" }],
+                    "properties": { "side": { "type": "number" } }
+                }
+            ],
+            "$defs": {
+                "shape": { "properties": { "kind": { "enum": ["square", "rect"] } } }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+    assert!(schemas
+        .validate(&json!({"kind": "rect", "side": 1}), sch)
+        .is_ok());
+
+    let instance = json!({"kind": "square", "side": "1"});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    assert_eq!(err.causes.len(), 1);
+    assert!(matches!(err.causes[0].kind, ErrorKind::Type { .. }));
+
+    // falls back, if no subschema matches the tag
+    let instance = json!({"kind": "triangle", "side": 1});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    assert_eq!(err.causes[0].kind, ErrorKind::OneOf(None));
+
+    // anyOf is not dispatched
+    compiler.add_resource(
+        "http://tmp.com/any.json",
+        json!({
+            "anyOf": [
+                { "properties": { "kind": { "const": "circle" }, "radius": { "type": "number" } } },
+                { "properties": { "kind": { "const": "square" }, "side": { "type": "number" } } }
+            ]
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/any.json", &mut schemas)?;
+    let instance = json!({"kind": "square", "side": "1"});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    assert_eq!(err.causes[0].kind, ErrorKind::AnyOf);
+    assert_eq!(err.causes[0].causes.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_tracer() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({"properties": {"a": {"type": "string", "minLength": 2, "minimum": 1}}}),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let traces = Arc::new(Mutex::new(vec![]));
+    let sink = traces.clone();
+    let mut options = ValidationOptions::new();
+    options.set_tracer(move |t: &Trace| {
+        let entry = (
+            t.schema_url.to_owned(),
+            t.instance_location.to_string(),
+            t.keywords,
+            t.valid,
+        );
+        sink.lock().unwrap().push(entry);
+    });
+    let instance = json!({"a": "x"});
+    assert!(schemas.validate_with(&instance, sch, &options).is_err());
+    assert_eq!(
+        *traces.lock().unwrap(),
+        [
+            (
+                "http://tmp.com/schema.json#/properties/a".to_owned(),
+                "/a".to_owned(),
+                2,
+                false
+            ),
+            (
+                "http://tmp.com/schema.json#".to_owned(),
+                "".to_owned(),
+                1,
+                false
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_content_limits() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_content_assertions();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({
+            "contentEncoding": "base64",
+            "contentMediaType": "application/json",
+            "contentSchema": {"type": "array"}
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!("W1tbMV1dXQ=="); // [[[1]]]
+    assert!(schemas.validate(&instance, sch).is_ok());
+
+    let mut options = ValidationOptions::new();
+    options.set_max_content_size(4);
+    let err = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    assert_eq!(
+        err.causes[0].kind,
+        ErrorKind::ContentLimit {
+            keyword: "contentEncoding",
+            limit: "size",
+            got: 7,
+            want: 4
+        }
+    );
+
+    let mut options = ValidationOptions::new();
+    options.set_max_content_depth(3);
+    let err = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    assert_eq!(
+        err.causes[0].to_string(),
+        "at '': decoded content depth must be <=3, but got 4"
+    );
+
+    // json content is measured before deserializing
+    compiler.add_resource(
+        "http://tmp.com/json.json",
+        json!({"contentMediaType": "application/json", "contentSchema": true}),
+    )?;
+    let sch = compiler.compile("http://tmp.com/json.json", &mut schemas)?;
+    let instance = json!(r#"{"a": ["[[{", "\"]]"], "b": {}}"#);
+    assert!(schemas.validate_with(&instance, sch, &options).is_ok());
+    let instance = json!(format!("{}{}", "[".repeat(10_000), "]".repeat(10_000)));
+    let err = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    assert_eq!(
+        err.causes[0].kind,
+        ErrorKind::ContentLimit {
+            keyword: "contentMediaType",
+            limit: "depth",
+            got: 10_000,
+            want: 3
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_evaluated_paths() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({
+            "properties": { "name": { "type": "string" } },
+            "anyOf": [
+                { "properties": { "age": { "type": "string" } } },
+                { "properties": { "tags": { "items": true } } },
+                { "properties": { "extra": true } }
+            ],
+            "patternProperties": { "^x-": true }
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!({"name": "a", "age": 1, "tags": ["t"], "x-id": 1, "extra": 1, "other": 1});
+    let evaluated = schemas.evaluated_paths(&instance, sch).unwrap();
+    let mut evaluated: Vec<_> = evaluated.iter().map(|loc| loc.to_string()).collect();
+    evaluated.sort();
+    // "age" is validated only by failed subschema
+    assert_eq!(evaluated, ["/extra", "/name", "/tags", "/tags/0", "/x-id"]);
+
+    assert!(schemas.evaluated_paths(&json!({"name": 1}), sch).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_prune() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({
+            "$defs": {
+                "base": { "properties": { "id": { "type": "integer" } } }
+            },
+            "allOf": [{ "$ref": "#/$defs/base" }],
+            "properties": {
+                "shape": {
+                    "oneOf": [
+                        { "properties": { "radius": { "type": "number" } }, "required": ["radius"], "additionalProperties": false },
+                        { "properties": { "side": { "type": "number" } }, "required": ["side"], "additionalProperties": false }
+                    ]
+                }
+            },
+            "unevaluatedProperties": false
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let mut instance = json!({"id": 1, "debug": true, "shape": {"side": 2, "color": "red"}});
+    assert!(schemas.prune(&mut instance, sch).is_ok());
+    assert_eq!(instance, json!({"id": 1, "shape": {"side": 2}}));
+
+    let mut instance = json!({"id": "1", "debug": true});
+    let err = schemas.prune(&mut instance, sch).unwrap_err();
+    assert!(err.to_string().contains("want integer, but got string"));
+    assert_eq!(instance, json!({"id": "1"}));
+
+    // property named unevaluatedProperties is not rejected by that keyword
+    compiler.add_resource(
+        "http://tmp.com/named.json",
+        json!({"properties": {"unevaluatedProperties": false}}),
+    )?;
+    let sch = compiler.compile("http://tmp.com/named.json", &mut schemas)?;
+    let mut instance = json!({"unevaluatedProperties": 1});
+    assert!(schemas.prune(&mut instance, sch).is_err());
+    assert_eq!(instance, json!({"unevaluatedProperties": 1}));
+
+    // members allowed by schema are kept
+    compiler.add_resource(
+        "http://tmp.com/open.json",
+        json!({
+            "properties": {"meta": {"type": "object"}, "any": {}},
+            "additionalProperties": true
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/open.json", &mut schemas)?;
+    let mut instance = json!({"meta": {"k": 1}, "any": {"x": 1}, "extra": {"y": [1]}});
+    let want = instance.clone();
+    assert!(schemas.prune(&mut instance, sch).is_ok());
+    assert_eq!(instance, want);
+
+    Ok(())
+}
+
+#[test]
+fn test_prune_unknown_fields() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "spec": {
+                "type": "object",
+                "properties": {
+                    "port": {"x-kubernetes-int-or-string": true},
+                    "image": {"type": "string", "nullable": true},
+                    "labels": {"type": "object", "additionalProperties": {"type": "string"}},
+                    "config": {"type": "object", "x-kubernetes-preserve-unknown-fields": true},
+                    "template": {
+                        "type": "object",
+                        "x-kubernetes-embedded-resource": true,
+                        "properties": {"spec": {"type": "object"}}
+                    },
+                    "args": {"type": "array", "items": {"type": "object", "properties": {"a": {"type": "string"}}}}
+                }
+            }
+        }
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::builder()
+        .default_draft(Draft::V4)
+        .enable_kubernetes_extensions()
+        .deny_unknown_keywords()
+        .build();
+    compiler.add_resource("http://a.com/crd.json", schema)?;
+    let sch = compiler.compile("http://a.com/crd.json", &mut schemas)?;
+
+    let mut instance = json!({
+        "apiVersion": "v1",
+        "kind": "Foo",
+        "status": {},
+        "spec": {
+            "port": null,
+            "image": null,
+            "labels": {"x": "y"},
+            "config": {"any": {"thing": 1}},
+            "template": {"kind": "Pod", "spec": {"x": 1}, "status": {}},
+            "args": [{"a": "x", "b": 1}]
+        }
+    });
+    let pruned = schemas.prune_unknown_fields(&mut instance, sch);
+    assert_eq!(
+        pruned,
+        [
+            "/spec/args/0/b",
+            "/spec/port",
+            "/spec/template/spec/x",
+            "/spec/template/status",
+            "/status"
+        ]
+    );
+    assert_eq!(
+        instance,
+        json!({
+            "apiVersion": "v1",
+            "kind": "Foo",
+            "spec": {
+                "image": null,
+                "labels": {"x": "y"},
+                "config": {"any": {"thing": 1}},
+                "template": {"kind": "Pod", "spec": {}},
+                "args": [{"a": "x"}]
+            }
+        })
+    );
+    assert!(schemas.validate(&instance, sch).is_ok());
+    assert!(schemas
+        .validate(&json!({"spec": {"port": 80}}), sch)
+        .is_ok());
+    assert!(schemas
+        .validate(&json!({"spec": {"port": 1.5}}), sch)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_large_enum() -> Result<(), Box<dyn Error>> {
+    let mut values: Vec<_> = (0..50).map(|i| json!(format!("v{i}"))).collect();
+    values.extend([json!(0), json!(2.5), json!(null), json!(true)]);
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema.json", json!({"enum": values}))?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    for v in [
+        json!("v0"),
+        json!("v49"),
+        json!(0.0),
+        json!(-0.0),
+        json!(2.5),
+        json!(null),
+        json!(true),
+    ] {
+        assert!(schemas.validate(&v, sch).is_ok(), "{v}");
+    }
+    for v in [json!("v50"), json!(1), json!(false), json!(["v0"])] {
+        assert!(schemas.validate(&v, sch).is_err(), "{v}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_string_length() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({"minLength": 2, "maxLength": 2}),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let instance = json!("😀é"); // 2 chars, 3 utf-16 units, 6 bytes
+    assert!(schemas.validate(&instance, sch).is_ok());
+
+    let mut options = ValidationOptions::new();
+    options.set_string_length(StringLength::Utf16);
+    let err = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    assert_eq!(err.causes[0].kind, ErrorKind::MaxLength { got: 3, want: 2 });
+
+    options.set_string_length(StringLength::Bytes);
+    let err = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    assert_eq!(err.causes[0].kind, ErrorKind::MaxLength { got: 6, want: 2 });
+
+    Ok(())
+}
+
+#[test]
+fn test_strict_integers() -> Result<(), Box<dyn Error>> {
+    let draft4 = json!({"$schema": "http://json-schema.org/draft-04/schema#", "type": "integer"});
+    let draft7 = json!({"$schema": "http://json-schema.org/draft-07/schema#", "type": "integer"});
+    let instance = json!(1.0);
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/draft4.json", draft4.clone())?;
+    compiler.add_resource("http://tmp.com/draft7.json", draft7.clone())?;
+    let sch4 = compiler.compile("http://tmp.com/draft4.json", &mut schemas)?;
+    let sch7 = compiler.compile("http://tmp.com/draft7.json", &mut schemas)?;
+    assert!(schemas.validate(&instance, sch4).is_err());
+    assert!(schemas.validate(&instance, sch7).is_ok());
+    assert!(schemas.validate(&json!(1), sch4).is_ok());
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.set_strict_integers(false);
+    compiler.add_resource("http://tmp.com/draft4.json", draft4)?;
+    let sch4 = compiler.compile("http://tmp.com/draft4.json", &mut schemas)?;
+    assert!(schemas.validate(&instance, sch4).is_ok());
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.set_strict_integers(true);
+    compiler.add_resource("http://tmp.com/draft7.json", draft7)?;
+    let sch7 = compiler.compile("http://tmp.com/draft7.json", &mut schemas)?;
+    assert!(schemas.validate(&instance, sch7).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_uneval_if_then_else() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "if": {"required": ["a"]},
+        "then": {"properties": {"a": true}, "additionalProperties": {"type": "string"}},
+        "else": {"$ref": "#/$defs/strings"},
+        "unevaluatedProperties": false,
+        "$defs": {"strings": {"additionalProperties": {"type": "string"}}}
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    assert!(schemas.validate(&json!({"a": 1, "b": "x"}), sch).is_ok());
+    assert!(schemas.validate(&json!({"b": "x", "c": "y"}), sch).is_ok());
+    assert!(schemas.validate(&json!({"a": 1, "b": 2}), sch).is_err());
+    assert!(schemas.validate(&json!({"b": 2}), sch).is_err());
+
+    // without else, unevaluated properties must still be checked
+    let schema = json!({
+        "if": {"required": ["a"]},
+        "then": {"additionalProperties": true},
+        "unevaluatedProperties": false
+    });
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema2.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/schema2.json", &mut schemas)?;
+    assert!(schemas.validate(&json!({"a": 1, "b": 2}), sch).is_ok());
+    assert!(schemas.validate(&json!({"b": 2}), sch).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_optimization_level() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "not": {
+            "allOf": [
+                {"anyOf": [{"multipleOf": 3}, {"multipleOf": 5}]},
+                {"type": "integer"}
+            ]
+        }
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.set_optimization_level(OptimizationLevel::Reorder);
+    compiler.add_resource("http://tmp.com/schema.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    assert!(schemas.validate(&json!(7), sch).is_ok());
+    assert!(schemas.validate(&json!(7.5), sch).is_ok());
+    assert!(schemas.validate(&json!(9), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_optimization_fold() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "$defs": {"any": {}},
+        "allOf": [true, {}, {"$ref": "#/$defs/any"}],
+        "properties": {
+            "a": true,
+            "b": {"allOf": [{"allOf": [{"minimum": 1}]}]},
+            "c": {"propertyNames": {}, "then": true}
+        },
+        "unevaluatedProperties": false
+    });
+    let mut sizes = vec![];
+    for level in [OptimizationLevel::None, OptimizationLevel::Fold] {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        compiler.set_optimization_level(level);
+        let sch =
+            compiler.compile_value("http://tmp.com/schema.json", schema.clone(), &mut schemas)?;
+        assert!(schemas
+            .validate(&json!({"a": 1, "b": 1, "c": {}}), sch)
+            .is_ok());
+        assert!(schemas.validate(&json!({"d": 1}), sch).is_err());
+        let instance = json!({"b": 0});
+        let err = schemas.validate(&instance, sch).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "jsonschema validation failed with http://tmp.com/schema.json#\n- at '/b': must be >=1, but got 0"
+        );
+        sizes.push(schemas.size());
+
+        // folded subschemas are compiled again, if asked
+        let loc = "http://tmp.com/schema.json#/properties/b/allOf/0";
+        let b = compiler.compile(loc, &mut schemas)?;
+        assert!(schemas.validate(&json!(0), b).is_err());
+    }
+    // folded subschemas are reclaimed
+    assert!(sizes[1] < sizes[0], "{sizes:?}");
+    Ok(())
+}
+
+#[test]
+fn test_ref_inlining() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "$defs": {
+            "pos": {"type": "integer", "minimum": 1},
+            "big": {"type": "integer", "minimum": 1, "maximum": 9, "multipleOf": 2},
+            "node": {"items": {"$ref": "#/$defs/node"}}
+        },
+        "properties": {
+            "a": {"$ref": "#/$defs/pos"},
+            "b": {"$ref": "#/$defs/pos", "minimum": 5},
+            "c": {"$ref": "#/$defs/big"},
+            "d": {"$ref": "#/$defs/node"}
+        }
+    });
+    for inline in [false, true] {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        if inline {
+            compiler.enable_ref_inlining(3);
+        }
+        let sch =
+            compiler.compile_value("http://tmp.com/schema.json", schema.clone(), &mut schemas)?;
+        // conflicting, large and non-leaf targets are not inlined
+        assert_eq!(compiler.inlined_refs(), if inline { 1 } else { 0 });
+        let valid = json!({"a": 1, "b": 5, "c": 2, "d": [[]]});
+        assert!(schemas.validate(&valid, sch).is_ok());
+        for invalid in [json!({"a": 0}), json!({"a": "x"}), json!({"b": 2})] {
+            assert!(schemas.validate(&invalid, sch).is_err());
+        }
+        let instance = json!({"a": 0});
+        let err = schemas.validate(&instance, sch).unwrap_err();
+        let loc = if inline {
+            "/properties/a/minimum"
+        } else {
+            "/properties/a/$ref/minimum"
+        };
+        assert!(err.detailed_output().to_string().contains(loc));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_property_names() -> Result<(), Box<dyn Error>> {
+    let codes: Vec<_> = (0..20).map(|i| format!("c{i}")).collect();
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/s.json",
+        json!({
+            "properties": {
+                "a": {"propertyNames": {"type": "string", "pattern": "^[a-z]+$", "maxLength": 3}},
+                "b": {"propertyNames": {"enum": codes}},
+                "c": {"propertyNames": {"const": "x", "title": "only x"}},
+                "d": {"propertyNames": false}
+            }
+        }),
+    )?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+    let valid = json!({"a": {"ab": 1, "xyz": 2}, "b": {"c0": 1, "c19": 2}, "c": {"x": 1}, "d": {}});
+    assert!(schemas.validate(&valid, sch).is_ok());
+
+    for (instance, prop) in [
+        (json!({"a": {"ab": 1, "abcd": 2}}), "abcd"),
+        (json!({"a": {"Ab": 1}}), "Ab"),
+        (json!({"b": {"c20": 1}}), "c20"),
+        (json!({"c": {"y": 1}}), "y"),
+        (json!({"d": {"z": 1}}), "z"),
+    ] {
+        let err = schemas.validate(&instance, sch).unwrap_err();
+        let kind = &err.causes[0].kind;
+        assert_eq!(*kind, ErrorKind::PropertyName { prop: prop.into() });
+    }
+
+    let instance = json!({"a": {"Abcd": 1}});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let causes = &err.causes[0].causes;
+    assert_eq!(
+        causes[0].schema_url,
+        "http://a.com/s.json#/properties/a/propertyNames"
+    );
+    assert_eq!(causes[0].kind, ErrorKind::MaxLength { got: 4, want: 3 });
+    assert!(matches!(causes[1].kind, ErrorKind::Pattern { ref got, .. } if got == "Abcd"));
+    Ok(())
+}
+
+#[test]
+fn test_memoization() -> Result<(), Box<dyn Error>> {
+    #[derive(Default)]
+    struct Memoized(Mutex<(usize, usize)>);
+    impl Metrics for Memoized {
+        fn memoized(&self, _schema_url: &str, hits: usize, misses: usize) {
+            *self.0.lock().unwrap() = (hits, misses);
+        }
+    }
+
+    let metrics = Arc::new(Memoized::default());
+    let mut schemas = Schemas::new();
+    schemas.set_metrics(metrics.clone());
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/s.json",
+        json!({
+            "items": {
+                "oneOf": [
+                    {"properties": {"tags": {"maxItems": 1}}},
+                    {"properties": {"tags": {"maxItems": 2}}}
+                ]
+            }
+        }),
+    )?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let mut options = ValidationOptions::new();
+    options.enable_memoization();
+
+    let item = json!({"tags": ["a", "b"]});
+    let instance = json!([item, item, item]);
+    assert!(schemas.validate_with(&instance, sch, &options).is_ok());
+    assert_eq!(*metrics.0.lock().unwrap(), (2, 3));
+
+    // failures are recomputed, to report them in full
+    let item = json!({"tags": ["a", "b", "c"]});
+    let instance = json!([item, item]);
+    let err = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    assert_eq!(err.causes.len(), 2);
+    assert_eq!(err.causes[1].instance_location.to_string(), "/1");
+    assert_eq!(err.causes[1].causes.len(), 2);
+    assert_eq!(*metrics.0.lock().unwrap(), (0, 6));
+
+    // failures are reused, where only validity matters
+    compiler.add_resource(
+        "http://a.com/not.json",
+        json!({"items": {"not": {"items": {"items": {"type": "string"}}}}}),
+    )?;
+    let sch = compiler.compile("http://a.com/not.json", &mut schemas)?;
+    let instance = json!([[[1]], [[1], 2]]);
+    assert!(schemas.validate_with(&instance, sch, &options).is_ok());
+    assert_eq!(*metrics.0.lock().unwrap(), (1, 3));
+
+    // dynamic references are never cached
+    compiler.add_resource(
+        "http://a.com/tree.json",
+        json!({
+            "$dynamicAnchor": "node",
+            "items": {"$dynamicRef": "#node"}
+        }),
+    )?;
+    let sch = compiler.compile("http://a.com/tree.json", &mut schemas)?;
+    assert!(schemas
+        .validate_with(&json!([[], []]), sch, &options)
+        .is_ok());
+    assert_eq!(*metrics.0.lock().unwrap(), (0, 0));
+    Ok(())
+}
+
+#[test]
+fn test_unified_error() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile_value(
+        "http://a.com/s.json",
+        json!({"properties": {"a": {"type": "string"}}}),
+        &mut schemas,
+    )?;
+    let instance = json!({"a": 1});
+    let want = format!("{:#}", schemas.validate(&instance, sch).unwrap_err());
+    let Err(boon::Error::Invalid(err)) = schemas.validate_reader(&br#"{"a": 1}"#[..], sch) else {
+        panic!("instance must be invalid");
+    };
+    assert_eq!(err.schema_url, "http://a.com/s.json#");
+    assert_eq!(format!("{err:#}"), want);
+    assert_eq!(err.output["errors"][0]["instanceLocation"], "/a");
+
+    let err: boon::Error = schemas
+        .validate_str_strict(r#"{"a": 1, "a": 2}"#, sch)
+        .unwrap_err()
+        .into();
+    assert!(matches!(err, boon::Error::DuplicateKey { line: 1, .. }));
+    let err: boon::Error = compiler
+        .compile("http://a.com/missing.json", &mut schemas)
+        .unwrap_err()
+        .into();
+    assert!(matches!(err, boon::Error::Compile(_)));
+    Ok(())
+}
+
+#[test]
+fn test_custom_error_messages() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {
+            "name": {
+                "type": "string",
+                "minLength": 2,
+                "errorMessage": {"minLength": "name is too short"}
+            },
+            "age": {
+                "type": "integer",
+                "minimum": 0,
+                "errorMessage": "age must be a non-negative integer"
+            },
+            "tags": {
+                "items": {"type": "string"},
+                "errorMessage": "tags must be strings"
+            }
+        }
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::builder()
+        .enable_custom_error_messages()
+        .deny_unknown_keywords()
+        .build();
+    compiler.add_resource("http://a.com/s.json", schema.clone())?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+    let instance = json!({"name": "a", "age": -1, "tags": ["x", 1, 2]});
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    let output = serde_json::to_value(err.basic_output())?;
+    let leaves: Vec<_> = output["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|unit| unit["error"].as_str())
+        .collect();
+    assert_eq!(
+        leaves,
+        [
+            "age must be a non-negative integer",
+            "name is too short",
+            "tags must be strings"
+        ]
+    );
+    let name_err = err
+        .causes
+        .iter()
+        .find(|e| e.instance_location.to_string() == "/name");
+    let ErrorKind::Custom { kind, .. } = &name_err.unwrap().kind else {
+        panic!("must be custom error");
+    };
+    assert_eq!(**kind, ErrorKind::MinLength { got: 1, want: 2 });
+    let ajv = err.ajv_output();
+    assert_eq!(ajv[1].keyword, "minLength");
+    assert_eq!(ajv[1].message, "name is too short");
+    assert_eq!(ajv[2].keyword, "errorMessage");
+
+    // not enabled
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", schema)?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let err = schemas.validate(&instance, sch).unwrap_err();
+    assert!(!err.to_string().contains("too short"));
+    Ok(())
+}
+
+#[test]
+fn test_schema_hints() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {
+            "name": {"type": "string"},
+            "tags": {
+                "uniqueItems": true,
+                "maxItems": 3,
+                "x-boon": {"skip": ["uniqueItems"]}
+            },
+            "age": {
+                "type": "integer",
+                "minimum": 0,
+                "x-boon": {"warn": ["type", "minimum"]}
+            }
+        },
+        "additionalProperties": false,
+        "x-boon": {"warn": ["additionalProperties"]}
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::builder()
+        .enable_schema_hints()
+        .deny_unknown_keywords()
+        .build();
+    compiler.add_resource("http://a.com/s.json", schema.clone())?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let options = ValidationOptions::default();
+
+    let instance = json!({"name": "john", "tags": [1, 1], "age": -1, "x": 1});
+    let warnings = schemas
+        .validate_with_warnings(&instance, sch, &options)
+        .unwrap();
+    let kinds: Vec<_> = warnings
+        .iter()
+        .map(|w| w.kind.keyword_path().unwrap().keyword)
+        .collect();
+    assert_eq!(kinds, ["minimum", "additionalProperties"]);
+    assert!(schemas.validate(&instance, sch).is_ok());
+
+    // errors of other keywords still fail
+    let instance = json!({"name": 1, "age": "x"});
+    let err = schemas
+        .validate_with_warnings(&instance, sch, &options)
+        .unwrap_err();
+    assert_eq!(err.causes.len(), 1);
+    assert_eq!(err.causes[0].instance_location.to_string(), "/name");
+
+    // subschema with only warnings is valid
+    let mut compiler = Compiler::builder().enable_schema_hints().build();
+    compiler.add_resource(
+        "http://a.com/not.json",
+        json!({"not": {"type": "string", "x-boon": {"warn": ["type"]}}}),
+    )?;
+    let sch = compiler.compile("http://a.com/not.json", &mut schemas)?;
+    let instance = json!(1);
+    assert!(schemas
+        .validate_with_warnings(&instance, sch, &options)
+        .is_err());
+
+    // not enabled
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://a.com/s.json", schema)?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let instance = json!({"tags": [1, 1]});
+    assert!(schemas.validate(&instance, sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_severity() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::builder().enable_schema_hints().build();
+    compiler.add_resource(
+        "http://a.com/s.json",
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "properties": {
+                "ip": {"format": "ipv4"},
+                "old": {"deprecated": true},
+                "n": {"minimum": 0, "x-boon": {"warn": ["minimum"]}}
+            },
+            "required": ["id"]
+        }),
+    )?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let instance = json!({"ip": "1.2.3", "old": 1, "n": -1});
+
+    let mut options = ValidationOptions::new();
+    let valid = json!({"id": 1, "ip": "1.2.3", "old": 1, "n": -1});
+    let warnings = schemas.validate_with_warnings(&valid, sch, &options);
+    assert_eq!(warnings.map(|w| w.len()).ok(), Some(1));
+
+    options.set_severity("required", Severity::Warning);
+    options.set_severity("deprecated", Severity::Warning);
+    options.set_severity("format", Severity::Warning);
+    let warnings = schemas
+        .validate_with_warnings(&instance, sch, &options)
+        .unwrap();
+    let locations: Vec<_> = warnings
+        .iter()
+        .map(|w| format!("{} {}", w.instance_location, w.kind.keyword_path().unwrap()))
+        .collect();
+    assert_eq!(
+        locations,
+        ["/ip format", "/n minimum", "/old deprecated", " required"]
+    );
+
+    // overrides schema hints
+    options.set_severity("minimum", Severity::Error);
+    options.set_severity("deprecated", Severity::Error);
+    let err = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    let kinds: Vec<_> = err.causes.iter().map(|e| e.kind.to_string()).collect();
+    assert_eq!(kinds, ["must be >=0, but got -1", "value is deprecated"]);
+    Ok(())
+}
+
+#[test]
+fn test_revalidate() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/s.json",
+        json!({
+            "properties": {
+                "users": {
+                    "items": {"properties": {"age": {"minimum": 0}}},
+                    "uniqueItems": true
+                }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+    let old = json!({"users": [{"age": -1}, {"age": 2}, {"age": 3}]});
+    let previous = schemas.validate(&old, sch).unwrap_err();
+    fn leaves<'a, 's, 'v>(e: &'a ValidationError<'s, 'v>) -> Vec<&'a ValidationError<'s, 'v>> {
+        match e.causes.is_empty() {
+            true => vec![e],
+            false => e.causes.iter().flat_map(leaves).collect(),
+        }
+    }
+
+    // fixes error reported earlier
+    let instance = json!({"users": [{"age": 1}, {"age": 2}, {"age": 3}]});
+    let patch = json!([{"op": "replace", "path": "/users/0/age", "value": 1}]);
+    assert!(schemas
+        .revalidate(&instance, sch, Some(&previous), &patch)
+        .is_ok());
+
+    // unchanged values are not validated
+    let instance = json!({"users": [{"age": 1}, {"age": -2}, {"age": -3}]});
+    let patch = json!([{"op": "replace", "path": "/users/2/age", "value": -3}]);
+    let err = schemas
+        .revalidate(&instance, sch, None, &patch)
+        .unwrap_err();
+    let errors = leaves(&err);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].instance_location.to_string(), "/users/2/age");
+
+    // items shift
+    let instance = json!({"users": [{"age": 1}, {"age": 1}]});
+    let patch = json!([{"op": "remove", "path": "/users/1"}]);
+    let err = schemas
+        .revalidate(&instance, sch, None, &patch)
+        .unwrap_err();
+    assert_eq!(leaves(&err)[0].kind, ErrorKind::UniqueItems { got: [0, 1] });
+
+    // invalid patch
+    let instance = json!({"users": [{"age": -1}]});
+    let patch = json!({"op": "replace"});
+    assert!(schemas.revalidate(&instance, sch, None, &patch).is_err());
+
+    // with options
+    let instance = json!({"users": [{"age": 1}, {"age": [2]}]});
+    let patch = json!([{"op": "replace", "path": "/users/1/age", "value": [2]}]);
+    let mut options = ValidationOptions::new();
+    options.set_max_depth(3);
+    assert!(schemas.revalidate(&instance, sch, None, &patch).is_ok());
+    let err = schemas
+        .revalidate_with(&instance, sch, None, &patch, &options)
+        .unwrap_err();
+    assert!(matches!(
+        leaves(&err)[0].kind,
+        ErrorKind::InstanceLimit { limit: "depth", .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_revalidate_probes() -> Result<(), Box<dyn Error>> {
+    // failures under these keywords are not in previous error,
+    // so unchanged values under them must be validated again
+    let tests = [
+        (
+            json!({"not": {"properties": {"a": {"type": "integer"}}}}),
+            json!({"a": "s", "b": 1}),
+            "/b",
+        ),
+        (
+            json!({"oneOf": [
+                {"properties": {"a": {"type": "string"}}},
+                {"properties": {"b": {"type": "string"}}}
+            ]}),
+            json!({"a": 1, "b": "x", "c": 1}),
+            "/c",
+        ),
+        (
+            json!({"anyOf": [
+                {"properties": {"a": {"type": "string"}}},
+                {"properties": {"b": {"type": "string"}}}
+            ]}),
+            json!({"a": 1, "b": 1}),
+            "/b",
+        ),
+        (
+            json!({
+                "if": {"properties": {"a": {"const": 1}}},
+                "then": {"required": ["x"]}
+            }),
+            json!({"a": 2, "b": 1}),
+            "/b",
+        ),
+        (
+            json!({"contains": {"properties": {"a": {"const": 1}}}}),
+            json!([{"a": 2}, {"a": 3}]),
+            "/1",
+        ),
+    ];
+    for (i, (schema, instance, path)) in tests.into_iter().enumerate() {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        let url = format!("http://a.com/s{i}.json");
+        compiler.add_resource(&url, schema)?;
+        let sch = compiler.compile(&url, &mut schemas)?;
+        let value = instance.pointer(path).unwrap();
+        let patch = json!([{"op": "replace", "path": path, "value": value}]);
+        assert_eq!(
+            schemas.revalidate(&instance, sch, None, &patch).is_ok(),
+            schemas.validate(&instance, sch).is_ok(),
+            "test {i}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_validate_patch() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/s.json",
+        json!({
+            "properties": {
+                "name": {"type": "string"},
+                "tags": {"items": {"type": "string"}, "maxItems": 3}
+            },
+            "required": ["name"]
+        }),
+    )?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+    let base = json!({"name": "john", "tags": ["a", "b"]});
+
+    let patch = json!([
+        {"op": "test", "path": "/name", "value": "john"},
+        {"op": "copy", "from": "/name", "path": "/nick"},
+        {"op": "move", "from": "/tags/0", "path": "/tags/-"},
+        {"op": "replace", "path": "/name", "value": "jack"}
+    ]);
+    let patched = schemas.validate_patch(&base, &patch, sch).unwrap();
+    assert_eq!(
+        patched,
+        json!({"name": "jack", "nick": "john", "tags": ["b", "a"]})
+    );
+
+    let patch = json!([
+        {"op": "remove", "path": "/name"},
+        {"op": "add", "path": "/tags/0", "value": 1}
+    ]);
+    let Err(PatchError::Invalid(errors)) = schemas.validate_patch(&base, &patch, sch) else {
+        panic!("patch must fail");
+    };
+    let locations: Vec<_> = errors.iter().map(|e| e.patch_location.as_deref()).collect();
+    assert_eq!(locations, [Some("/0"), Some("/1/value")]);
+
+    for (patch, op) in [
+        (json!([{"op": "test", "path": "/name", "value": "x"}]), 0),
+        (json!([{"op": "add", "path": "/tags/0"}]), 0),
+        (
+            json!([{"op": "add", "path": "/x", "value": 1}, {"op": "remove", "path": "/y"}]),
+            1,
+        ),
+        (json!([{"op": "add", "path": "/tags/3", "value": "c"}]), 0),
+        (
+            json!([{"op": "move", "from": "/tags", "path": "/tags/0"}]),
+            0,
+        ),
+    ] {
+        let err = schemas.validate_patch(&base, &patch, sch).unwrap_err();
+        assert!(
+            matches!(err, PatchError::Apply { op: i, .. } if i == op),
+            "{patch}: {err}"
+        );
+    }
+
+    // merge patch
+    let patch = json!({"name": null, "tags": ["a", "b", "c", "d"], "age": 20});
+    let Err(PatchError::Invalid(errors)) = schemas.validate_merge_patch(&base, &patch, sch) else {
+        panic!("patch must fail");
+    };
+    let locations: Vec<_> = errors.iter().map(|e| e.patch_location.as_deref()).collect();
+    assert_eq!(locations, [Some(""), Some("/tags")]);
+    let patched = schemas
+        .validate_merge_patch(&base, &json!({"tags": null}), sch)
+        .unwrap();
+    assert_eq!(patched, json!({"name": "john"}));
+    Ok(())
+}
+
+#[test]
+fn test_decode_form() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/s.json",
+        json!({
+            "$defs": {"point": {"prefixItems": [{"type": "number"}, {"type": ["number", "null"]}]}},
+            "properties": {
+                "ids": {"type": "array", "items": {"type": "integer"}},
+                "point": {"$ref": "#/$defs/point", "type": "array"},
+                "users": {
+                    "type": "array",
+                    "items": {"properties": {"admin": {"type": "boolean"}}}
+                }
+            },
+            "additionalProperties": {"type": "integer"},
+            "required": ["ids"]
+        }),
+    )?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+    let pairs = [
+        ("ids", "1"),
+        ("ids", "2"),
+        ("point[]", "1.5"),
+        ("point[]", ""),
+        ("users[1][admin]", "false"),
+        ("users[0][admin]", "true"),
+        ("users[0][name]", "1"),
+        ("page", "3"),
+        ("q", "a b"),
+    ];
+    let v = schemas.decode_pairs(pairs, sch);
+    assert_eq!(
+        v,
+        json!({
+            "ids": [1, 2],
+            "point": [1.5, null],
+            "users": [{"admin": true, "name": "1"}, {"admin": false}],
+            "page": 3,
+            "q": "a b"
+        })
+    );
+
+    let v = schemas.decode_form("ids=1&x%5By%5D=%C3%A9", sch);
+    assert_eq!(v, json!({"ids": [1], "x": {"y": "é"}}));
+
+    // deep keys are not nested
+    let key = format!("a{}", "[x]".repeat(10000));
+    let v = schemas.decode_form(&format!("{key}=1"), sch);
+    assert_eq!(v, json!({ key: 1 }));
+    let key = format!("a{}", "[x]".repeat(32));
+    let v = schemas.decode_form(&format!("{key}=1"), sch);
+    assert_eq!(
+        v.pointer(&format!("/a{}", "/x".repeat(32))),
+        Some(&json!("1"))
+    );
+
+    let v = schemas.validate_form("ids=1&page=2", sch)?;
+    assert_eq!(v, json!({"ids": [1], "page": 2}));
+    for form in ["page=2", "ids=x", "ids=1&q=x"] {
+        assert!(matches!(
+            schemas.validate_form(form, sch),
+            Err(boon::Error::Invalid(_))
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_validate_str() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({"properties": {"a": {"type": "string"}}}),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    assert!(schemas.validate_str(r#"{"a": "x"}"#, sch).is_ok());
+    assert!(schemas.validate_slice(br#"{"a": "x"}"#, sch).is_ok());
+
+    let Err(InstanceError::Invalid(e)) = schemas.validate_str(r#"{"a": 1}"#, sch) else {
+        panic!("validation must fail");
+    };
+    assert_eq!(e.causes[0].instance_location.to_string(), "/a");
+
+    let result = schemas.validate_slice(br#"{"a": "#, sch);
+    assert!(matches!(result, Err(InstanceError::Parse(_))));
+    Ok(())
+}
+
+#[cfg(feature = "simd-json")]
+#[test]
+fn test_validate_simd() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({"properties": {"a": {"type": "integer"}, "b": {"maxLength": 1}}}),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let mut json = br#"{"a": 1, "b": "x"}"#.to_vec();
+    let owned = simd_json::to_owned_value(&mut json)?;
+    assert!(schemas.validate_simd(&owned, sch).is_ok());
+
+    let mut json = br#"{"a": 1.5, "b": "xy"}"#.to_vec();
+    let borrowed = simd_json::to_borrowed_value(&mut json)?;
+    let err = schemas.validate_simd(&borrowed, sch).unwrap_err();
+    assert_eq!(err.causes.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_validate_raw() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({
+            "required": ["data"],
+            "properties": {
+                "id": {"type": "integer"},
+                "tags": {"items": {"type": "string"}},
+                "kind": {"enum": [{"a": 1}]}
+            }
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let json = r#" {"id": 1, "tags": ["x"], "kind": {"a": 1}, "data": [[1, 2], {"b": null}]} "#;
+    assert!(schemas.validate_raw(json, sch).is_ok());
+
+    let json = r#"{"id": 1, "tags": ["x", 2], "data": [{"b": null}]}"#;
+    let Err(InstanceError::Invalid(e)) = schemas.validate_raw(json, sch) else {
+        panic!("validation must fail");
+    };
+    assert_eq!(e.causes[0].instance_location.to_string(), "/tags/1");
+
+    // enum looks at whole value
+    let json = r#"{"kind": {"a": 1, "b": 2}, "data": 1}"#;
+    assert!(schemas.validate_raw(json, sch).is_err());
+
+    // skipped values must be well-formed
+    let json = r#"{"data": [1, }"#;
+    assert!(matches!(
+        schemas.validate_raw(json, sch),
+        Err(InstanceError::Parse(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_clone_schemas() -> Result<(), Box<dyn Error>> {
+    let mut base = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/name.json", json!({"type": "string"}))?;
+    compiler.add_resource(
+        "http://tmp.com/tenant.json",
+        json!({"properties": {"name": {"$ref": "name.json"}}}),
+    )?;
+    let name = compiler.compile("http://tmp.com/name.json", &mut base)?;
+
+    let mut tenant = base.clone();
+    let sch = compiler.compile("http://tmp.com/tenant.json", &mut tenant)?;
+    assert!(tenant.size() > base.size());
+    assert!(!base.contains(sch));
+    assert!(tenant.validate(&json!({"name": 1}), sch).is_err());
+
+    // shared schemas are still valid in both
+    assert!(base.validate(&json!("x"), name).is_ok());
+    assert!(tenant.validate(&json!(1), name).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_dynamic_ref_tracer() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/tree.json",
+        json!({
+            "$schema": "https://json-schema.org/draft/2019-09/schema",
+            "$recursiveAnchor": true,
+            "properties": {"children": {"items": {"$recursiveRef": "#"}}}
+        }),
+    )?;
+    compiler.add_resource(
+        "http://tmp.com/strict.json",
+        json!({
+            "$schema": "https://json-schema.org/draft/2019-09/schema",
+            "$recursiveAnchor": true,
+            "$ref": "tree.json",
+            "unevaluatedProperties": false
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/strict.json", &mut schemas)?;
+
+    let traces = Arc::new(Mutex::new(vec![]));
+    let mut options = ValidationOptions::new();
+    let sink = traces.clone();
+    options.set_dynamic_ref_tracer(move |t: &DynamicRefTrace| {
+        let scope = t.scope.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        sink.lock().unwrap().push((
+            t.keyword,
+            t.instance_location.to_string(),
+            scope,
+            t.resolved.to_owned(),
+        ));
+    });
+    let instance = json!({"children": [{"x": 1}]});
+    assert!(schemas.validate_with(&instance, sch, &options).is_err());
+
+    let traces = traces.lock().unwrap();
+    assert_eq!(traces.len(), 1);
+    let (keyword, loc, scope, resolved) = &traces[0];
+    assert_eq!(*keyword, "$recursiveRef");
+    assert_eq!(loc, "/children/0");
+    assert_eq!(
+        scope,
+        &["http://tmp.com/strict.json#", "http://tmp.com/tree.json#"]
+    );
+    assert_eq!(resolved, "http://tmp.com/strict.json#");
+    Ok(())
+}
+
+#[test]
+fn test_instance_location() -> Result<(), Box<dyn Error>> {
+    let schema = json!({"properties": {"a/b": {"items": {"type": "string"}}}});
+    let instance = json!({"a/b": ["x", 1], "0": {"01": true}});
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+    let e = schemas.validate(&instance, sch).unwrap_err();
+    let loc = &e.causes[0].instance_location;
+    assert_eq!(loc.resolve(&instance), Some(&json!(1)));
+
+    let parsed = InstanceLocation::parse("/a~1b/1").unwrap();
+    assert_eq!(&parsed, loc);
+    assert_eq!(
+        parsed.iter().collect::<Vec<_>>(),
+        [&InstanceToken::from("a/b"), &InstanceToken::Item(1)]
+    );
+
+    let mut loc = InstanceLocation::parse("/0").unwrap();
+    loc.push("01");
+    assert_eq!(loc.to_string(), "/0/01");
+    assert_eq!(loc.resolve(&instance), Some(&json!(true)));
+    assert_eq!(loc.pop(), Some(InstanceToken::from("01")));
+    assert_eq!(loc.pop(), Some(InstanceToken::Item(0)));
+    assert_eq!(loc.pop(), None);
+    assert_eq!(loc.resolve(&instance), Some(&instance));
+
+    assert!(InstanceLocation::parse("a").is_none());
+    assert!(InstanceLocation::parse("/a~2").is_none());
+    assert!(InstanceLocation::parse("/missing")
+        .unwrap()
+        .resolve(&instance)
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn test_location_format() -> Result<(), Box<dyn Error>> {
+    let loc = InstanceLocation::parse("/items/3/name").unwrap();
+    assert_eq!(loc.to_json_path(), "$.items[3].name");
+    assert_eq!(loc.to_uri_fragment(), "#/items/3/name");
+    assert_eq!(loc.format(LocationFormat::JsonPointer), "/items/3/name");
+
+    let loc = InstanceLocation::parse("/a b/it's/~1").unwrap();
+    assert_eq!(loc.to_json_path(), r"$['a b']['it\'s']['/']");
+    assert_eq!(loc.to_uri_fragment(), "#/a%20b/it's/~1");
+    assert_eq!(InstanceLocation::default().to_json_path(), "$");
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_keys() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema.json", json!({"type": "object"}))?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let json = "{\"a\": [{\"b\": 1}],\n \"c\": {\"d\": 1, \"d\": 2}}";
+    assert!(schemas.validate_str(json, sch).is_ok());
+    let Err(InstanceError::DuplicateKey { key, line, .. }) = schemas.validate_str_strict(json, sch)
+    else {
+        panic!("duplicate key must be reported");
+    };
+    assert_eq!((key.as_str(), line), ("d", 2));
+
+    assert!(schemas
+        .validate_str_strict(r#"{"a": 1, "b": {"a": 2}}"#, sch)
+        .is_ok());
+    assert!(matches!(
+        schemas.validate_str_strict("[]", sch),
+        Err(InstanceError::Invalid(_))
+    ));
+    assert!(matches!(
+        schemas.validate_str_strict("{} x", sch),
+        Err(InstanceError::Parse(_))
+    ));
+    assert_eq!(
+        parse_strict(br#"{"a": [1, 2.5, null, true, "x"]}"#).unwrap(),
+        json!({"a": [1, 2.5, null, true, "x"]})
+    );
+    Ok(())
+}
+
+#[test]
+fn test_instance_limits() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://tmp.com/schema.json", json!({}))?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let mut options = ValidationOptions::new();
+    options.set_max_depth(3);
+    options.set_max_nodes(5);
+    let instance = json!({"a": [1, 2]});
+    assert!(schemas.validate_with(&instance, sch, &options).is_ok());
+
+    let instance = json!({"a": [[1]]});
+    let e = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    let want = ErrorKind::InstanceLimit {
+        limit: "depth",
+        got: 4,
+        want: 3,
+    };
+    assert_eq!(e.causes[0].kind, want);
+
+    let instance = json!([1, 2, 3, 4, 5]);
+    let e = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    assert_eq!(
+        e.causes[0].kind.to_string(),
+        "instance nodes must be <=5, but got 6"
+    );
+
+    // deep nesting must not overflow stack
+    let mut instance = json!(1);
+    for _ in 0..100_000 {
+        instance = serde_json::Value::Array(vec![instance]);
+    }
+    assert!(schemas.validate_with(&instance, sch, &options).is_err());
+    drop_deep(instance);
+    Ok(())
+}
+
+// drops deeply nested `v` without recursion, which overflows stack
+fn drop_deep(v: serde_json::Value) {
+    let mut stack = vec![v];
+    while let Some(v) = stack.pop() {
+        match v {
+            serde_json::Value::Array(arr) => stack.extend(arr),
+            serde_json::Value::Object(obj) => stack.extend(obj.into_iter().map(|(_, v)| v)),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn test_value_truncation() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({"properties": {"a": {"pattern": "^[a-z]+$"}}}),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+    let long = format!("{}!", "é".repeat(150));
+    let instance = json!({ "a": long });
+    let e = schemas.validate(&instance, sch).unwrap_err();
+    let kind = &e.causes[0].kind;
+
+    let want = format!("'{}…' does not match pattern '^[a-z]+$'", "é".repeat(100));
+    assert_eq!(kind.to_string(), want);
+    assert!(e.to_string().ends_with(&want), "{e}");
+    assert!(e.basic_output().to_string().contains(&want));
+    assert_eq!(
+        format!("{kind:.3}"),
+        "'ééé…' does not match pattern '^[a-z]+$'"
+    );
+    assert!(format!("{e:.3}").ends_with("'ééé…' does not match pattern '^[a-z]+$'"));
+
+    // full value is available
+    let ErrorKind::Pattern { got, .. } = kind else {
+        panic!("{kind:?}");
+    };
+    assert_eq!(got.as_ref(), long);
+
+    // enum values and format errors are truncated too
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.register_format_fn("short", |v| match v.as_str() {
+        Some(s) if s.len() > 10 => Err(format!("{s:?} is too long").into()),
+        _ => Ok(()),
+    });
+    compiler.add_resource("http://tmp.com/enum.json", json!({"enum": [long, 1]}))?;
+    compiler.add_resource("http://tmp.com/short.json", json!({"format": "short"}))?;
+    let sch = compiler.compile("http://tmp.com/enum.json", &mut schemas)?;
+    let two = json!(2);
+    let e = schemas.validate(&two, sch).unwrap_err();
+    assert_eq!(
+        format!("{:.3}", e.causes[0].kind),
+        "value must be one of 'ééé…', 1"
+    );
+    let sch = compiler.compile("http://tmp.com/short.json", &mut schemas)?;
+    let e = schemas.validate(&instance["a"], sch).unwrap_err();
+    assert_eq!(
+        format!("{:.3}", e.causes[0].kind),
+        "'ééé…' is not valid short: \"éé…"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_redaction() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.enable_custom_error_messages();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({
+            "properties": {
+                "email": {"format": "email"},
+                "name": {"pattern": "^[a-z]+$", "errorMessage": "lowercase name required"},
+                "age": {"minimum": 18}
+            }
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+    let instance = json!({"email": "john.at.example.com", "name": "John", "age": 17});
+
+    let mut options = ValidationOptions::new();
+    options.enable_redaction();
+    let e = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    let msg = format!("{e:#}");
+    for secret in ["john.at", "John", "17"] {
+        assert!(!msg.contains(secret), "{msg}");
+        assert!(!format!("{e:?}").contains(secret), "{e:?}");
+    }
+    fn leaves<'a, 's, 'v>(e: &'a ValidationError<'s, 'v>, v: &mut Vec<&'a ErrorKind<'s, 'v>>) {
+        if e.causes.is_empty() {
+            v.push(&e.kind);
+        }
+        e.causes.iter().for_each(|e| leaves(e, v));
+    }
+    let mut kinds = vec![];
+    leaves(&e, &mut kinds);
+    let mut got: Vec<_> = kinds.iter().map(|k| k.to_string()).collect();
+    got.sort();
+    assert_eq!(
+        got,
+        [
+            "<string, 19 chars> is not valid email: <redacted>",
+            "lowercase name required",
+            "must be >=18, but got <number>",
+        ]
+    );
+    let age = kinds.iter().find(|k| k.to_string().contains("18")).unwrap();
+    let ErrorKind::Redacted { got, kind } = age else {
+        panic!("{age:?}");
+    };
+    assert_eq!(got, "<number>");
+    assert!(matches!(**kind, ErrorKind::Minimum { .. }));
+    assert_eq!(age.keyword_path().unwrap().to_string(), "minimum");
+
+    // not redacted by default
+    let e = schemas.validate(&instance, sch).unwrap_err();
+    assert!(format!("{e:#}").contains("'john.at.example.com'"));
+
+    // format error quoting the value
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.register_format_fn("sku", |v| match v.as_str() {
+        Some(s) if !s.starts_with("SKU-") => Err(format!("{s:?} has no SKU- prefix").into()),
+        _ => Ok(()),
+    });
+    compiler.add_resource("http://tmp.com/sku.json", json!({"format": "sku"}))?;
+    let sch = compiler.compile("http://tmp.com/sku.json", &mut schemas)?;
+    let instance = json!("secret-123");
+    let e = schemas.validate(&instance, sch).unwrap_err();
+    assert!(format!("{e:#}").contains("secret-123"));
+    let e = schemas.validate_with(&instance, sch, &options).unwrap_err();
+    for msg in [format!("{e:#}"), format!("{e:?}")] {
+        assert!(!msg.contains("secret-123"), "{msg}");
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "stacker")]
+fn test_deep_instance() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({"items": {"$ref": "#"}, "not": {"type": "object"}});
+    compiler.add_resource("http://tmp.com/schema.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+
+    let mut instance = json!(1);
+    for _ in 0..100_000 {
+        instance = serde_json::Value::Array(vec![instance]);
+    }
+    assert!(schemas.validate(&instance, sch).is_ok());
+    drop_deep(instance);
+
+    // errors deeper than 256 are collapsed
+    let schema = json!({
+        "$ref": "#/$defs/n",
+        "$defs": {"n": {"type": "array", "items": {"$ref": "#/$defs/n"}}}
+    });
+    compiler.add_resource("http://tmp.com/invalid.json", schema)?;
+    let sch = compiler.compile("http://tmp.com/invalid.json", &mut schemas)?;
+    let mut instance = json!(1);
+    for _ in 0..100_000 {
+        instance = serde_json::Value::Array(vec![instance]);
+    }
+    let e = schemas.validate(&instance, sch).unwrap_err();
+    let mut leaf = &e;
+    while let Some(cause) = leaf.causes.first() {
+        leaf = cause;
+    }
+    assert_eq!(leaf.instance_location.tokens.len(), 256);
+    let url = "http://tmp.com/invalid.json#/$defs/n/items";
+    assert_eq!(leaf.kind, ErrorKind::Schema { url });
+    assert!(format!("{e}").contains(&format!("validation failed with {url}")));
+    assert!(format!("{e:#}").contains(&format!("validation failed with {url}")));
+    assert!(!format!("{e:?}").is_empty());
+    assert!(serde_json::to_string(&e.detailed_output()).is_ok());
+    // uses clone_static
+    drop(boon::Error::from(e));
+    drop_deep(instance);
+    Ok(())
+}
+
+#[test]
+fn test_date_time_options() {
+    let opts = DateTimeOptions::default();
+    assert!(opts.check("1998-12-31T23:59:60Z").is_ok());
+    assert!(opts
+        .check("2022-07-08T00:14:07+01:00[Europe/Paris]")
+        .is_err());
+
+    let opts = DateTimeOptions {
+        leap_seconds: false,
+        extended: true,
+    };
+    let err = opts.check("1998-12-31T23:59:60Z").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid date-time: invalid time element: leap second not allowed"
+    );
+    for valid in [
+        "2022-07-08T00:14:07Z",
+        "2022-07-08T00:14:07+01:00[Europe/Paris]",
+        "2022-07-08T00:14:07+01:00[!Europe/Paris][u-ca=hebrew]",
+        "2022-07-08T00:14:07Z[+01:00]",
+        "2022-07-08T00:14:07Z[America/Argentina/Buenos_Aires]",
+    ] {
+        assert!(opts.check(valid).is_ok(), "{valid}");
+    }
+    for invalid in [
+        "2022-07-08T00:14:07Z[",
+        "2022-07-08T00:14:07Z[]",
+        "2022-07-08T00:14:07Z[Europe/../Paris]",
+        "2022-07-08T00:14:07Z[U-ca=hebrew]",
+        "2022-07-08T00:14:07Z[u-ca=]",
+        "2022-07-08T00:14:07Z[+25:00]",
+        "2022-07-08T00:14:07Z x",
+    ] {
+        assert!(opts.check(invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono() {
+    use boon::formats::chrono::*;
+    use chrono::Timelike;
+
+    let (time, offset) = parse_time("23:59:60.5Z").unwrap();
+    assert_eq!(time.hour(), 23);
+    assert_eq!(time.nanosecond(), 1_500_000_000);
+    assert_eq!(offset.local_minus_utc(), 0);
+    assert!(parse_time("23:59:60+01:00").is_err());
+
+    let dt = parse_iso_date_time("2024-02-29t10:30:00").unwrap();
+    assert_eq!(dt.to_string(), "2024-02-29 10:30:00");
+    assert!(parse_iso_date_time("2024-02-29T10:30:00Z").is_err());
+
+    let dt = parse_date_time("2024-02-29t10:30:00z").unwrap();
+    assert_eq!(dt.to_rfc3339(), "2024-02-29T10:30:00+00:00");
+}
+
+#[test]
+fn test_hostname_options() {
+    let opts = HostnameOptions::default();
+    assert!(opts.check("localhost").is_ok());
+    assert!(opts.check("my_service.default.svc").is_err());
+
+    let opts = HostnameOptions {
+        allow_underscore: true,
+        min_labels: 2,
+        max_label_len: 10,
+    };
+    assert!(opts.check("my_service.default.svc").is_ok());
+    assert!(opts.check("example.com.").is_ok());
+    let err = opts.check("localhost.").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid hostname: must have at least 2 labels"
+    );
+    let err = opts.check("averylonglabel.com").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid hostname: label must be 1 to 10 characters long"
+    );
+}
+
+#[cfg(feature = "axum")]
+#[test]
+fn test_web_validated() -> Result<(), Box<dyn Error>> {
+    use std::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use axum::{
+        body::{to_bytes, Body},
+        extract::{FromRequest, Request},
+        http::{header, StatusCode},
+        response::IntoResponse,
+    };
+    use boon::web::{RouteSchema, Validated};
+
+    // request bodies are in memory, so futures complete on first poll
+    fn block_on<F: Future>(f: F) -> F::Output {
+        let mut f = pin!(f);
+        match f.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(v) => v,
+            Poll::Pending => panic!("future is pending"),
+        }
+    }
+
+    #[derive(serde::Deserialize)]
+    struct User {
+        name: String,
+    }
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://tmp.com/schema.json",
+        json!({
+            "properties": {"name": {"type": "string", "minLength": 1}},
+            "required": ["name"]
+        }),
+    )?;
+    let sch = compiler.compile("http://tmp.com/schema.json", &mut schemas)?;
+    let route = RouteSchema::new(Arc::new(schemas), sch);
+    let request = |body: &'static str| {
+        let mut req = Request::new(Body::from(body));
+        req.extensions_mut().insert(route.clone());
+        req
+    };
+
+    let Validated(user) = block_on(Validated::<User>::from_request(
+        request(r#"{"name": "x"}"#),
+        &(),
+    ))
+    .map_err(|_| "expected valid")?;
+    assert_eq!(user.name, "x");
+
+    let Err(rejection) = block_on(Validated::<User>::from_request(
+        request(r#"{"name": ""}"#),
+        &(),
+    )) else {
+        panic!("expected rejection");
+    };
+    let resp = rejection.into_response();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        resp.headers()[header::CONTENT_TYPE],
+        "application/problem+json"
+    );
+    let body = block_on(to_bytes(resp.into_body(), usize::MAX))?;
+    let problem: serde_json::Value = serde_json::from_slice(&body)?;
+    assert_eq!(problem["status"], 422);
+    assert_eq!(problem["errors"][0]["pointer"], "/name");
+    assert_eq!(problem["errors"][0]["keyword"], "minLength");
+
+    let Err(rejection) = block_on(Validated::<User>::from_request(request("{"), &())) else {
+        panic!("expected rejection");
+    };
+    assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+
+    let req = Request::new(Body::from("{}"));
+    let Err(rejection) = block_on(Validated::<User>::from_request(req, &())) else {
+        panic!("expected rejection");
+    };
+    assert_eq!(
+        rejection.into_response().status(),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+    Ok(())
+}
+
+#[test]
+fn test_jtd() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "definitions": {
+            "point": {"properties": {"x": {"type": "float64"}, "y": {"type": "float64"}}}
+        },
+        "properties": {
+            "id": {"type": "int32"},
+            "at": {"type": "timestamp"},
+            "color": {"enum": ["red", "green"], "nullable": true},
+            "path": {"elements": {"ref": "point"}},
+            "labels": {"values": {"type": "string"}},
+            "shape": {
+                "discriminator": "kind",
+                "mapping": {
+                    "circle": {"properties": {"radius": {"type": "float32"}}},
+                    "square": {"properties": {"side": {"type": "uint16"}}}
+                }
+            }
+        },
+        "optionalProperties": {"extra": {}},
+        "additionalProperties": true
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::builder().dialect(Dialect::Jtd).build();
+    compiler.add_resource("http://a.com/s.jtd.json", schema)?;
+    let sch = compiler.compile("http://a.com/s.jtd.json", &mut schemas)?;
+
+    let valid = json!({
+        "id": 1,
+        "at": "1990-12-31T23:59:60Z",
+        "color": null,
+        "path": [{"x": 1, "y": 2.5}],
+        "labels": {"a": "b"},
+        "shape": {"kind": "circle", "radius": 1.5},
+        "unknown": 1
+    });
+    let result = schemas.validate(&valid, sch);
+    assert!(result.is_ok(), "{}", result.unwrap_err());
+
+    let invalid = [
+        ("/id", json!(2147483648_i64)),
+        ("/at", json!("yesterday")),
+        ("/color", json!("blue")),
+        ("/path/0", json!([{"x": 1}])),
+        ("/path/0", json!([{"x": 1, "y": 2, "z": 3}])),
+        ("/labels/a", json!({"a": 1})),
+        ("/shape/kind", json!({"kind": "triangle"})),
+        ("/shape/radius", json!({"kind": "circle", "radius": "x"})),
+        ("/shape", json!({"kind": "square", "side": 1, "radius": 1})),
+    ];
+    for (loc, v) in invalid {
+        let pname = loc.split('/').nth(1).unwrap();
+        let mut instance = valid.clone();
+        instance[pname] = v;
+        let err = schemas.validate(&instance, sch).unwrap_err();
+        let locs: Vec<_> = err
+            .basic_output()
+            .to_string()
+            .split("\"instanceLocation\":\"")
+            .skip(1)
+            .map(|s| s.split('"').next().unwrap().to_owned())
+            .collect();
+        assert!(locs.contains(&loc.to_owned()), "{loc}: {err:#}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_avro() -> Result<(), Box<dyn Error>> {
+    let avro = json!({
+        "type": "record",
+        "name": "Node",
+        "namespace": "com.example",
+        "fields": [
+            {"name": "id", "type": {"type": "string", "logicalType": "uuid"}},
+            {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "B"]}},
+            {"name": "hash", "type": {"type": "fixed", "name": "Hash", "size": 2}},
+            {"name": "attrs", "type": {"type": "map", "values": "long"}},
+            {"name": "next", "type": ["null", "Node"], "default": null},
+            {"name": "kinds", "type": {"type": "array", "items": "com.example.Kind"}}
+        ]
+    });
+    let valid = json!({
+        "id": "6ba7b810-9dad-11d1-80b4-00c04fd430c8",
+        "kind": "A",
+        "hash": "ab",
+        "attrs": {"x": 1},
+        "next": {"id": "6ba7b810-9dad-11d1-80b4-00c04fd430c8", "kind": "B", "hash": "cd", "attrs": {}, "kinds": []},
+        "kinds": ["A"]
+    });
+    let encoded = {
+        let mut v = valid.clone();
+        v["next"] = json!({"com.example.Node": v["next"].take()});
+        v
+    };
+
+    for (encoding, valid, invalid) in [
+        (avro::Encoding::Plain, &valid, &encoded),
+        (avro::Encoding::Avro, &encoded, &valid),
+    ] {
+        let schema = avro::to_json_schema(&avro, encoding)?;
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        compiler.enable_format_assertions();
+        let sch = compiler.compile_value("http://a.com/node.json", schema, &mut schemas)?;
+        let result = schemas.validate(valid, sch);
+        assert!(result.is_ok(), "{encoding:?}: {}", result.unwrap_err());
+        assert!(schemas.validate(invalid, sch).is_err(), "{encoding:?}");
+        for (pname, v) in [
+            ("id", json!("x")),
+            ("kind", json!("C")),
+            ("hash", json!("abc")),
+            ("attrs", json!({"x": 1.5})),
+            ("unknown", json!(1)),
+        ] {
+            let mut instance = valid.clone();
+            instance[pname] = v;
+            assert!(
+                schemas.validate(&instance, sch).is_err(),
+                "{encoding:?} {pname}"
+            );
+        }
+    }
+
+    let err = avro::to_json_schema(
+        &json!({"type": "array", "items": "Missing"}),
+        avro::Encoding::Plain,
+    )
+    .unwrap_err();
+    assert_eq!(err.ptr, "/items");
+    let err = avro::to_json_schema(&json!(["int", "int"]), avro::Encoding::Plain).unwrap_err();
+    assert_eq!(err.ptr, "/1");
+    Ok(())
+}
+
+#[test]
+fn test_json_schema_to_avro() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "type": "object",
+        "description": "a user",
+        "properties": {
+            "name": {"type": "string", "description": "full name"},
+            "age": {"type": "integer", "minimum": 0, "maximum": 200},
+            "score": {"type": ["number", "null"]},
+            "role": {"enum": ["admin", "guest"]},
+            "tags": {"type": "array", "items": {"type": "string"}},
+            "attrs": {"type": "object", "additionalProperties": {"type": "boolean"}},
+            "address": {"type": "object", "properties": {"city": {"type": "string"}}, "required": ["city"]}
+        },
+        "required": ["name", "age"]
+    });
+    let got = avro::from_json_schema(&schema, "User")?;
+    let want = json!({
+        "type": "record",
+        "name": "User",
+        "doc": "a user",
+        "fields": [
+            {"name": "address", "type": ["null", {"type": "record", "name": "UserAddress", "fields": [
+                {"name": "city", "type": "string"}
+            ]}], "default": null},
+            {"name": "age", "type": "int"},
+            {"name": "attrs", "type": ["null", {"type": "map", "values": "boolean"}], "default": null},
+            {"name": "name", "type": "string", "doc": "full name"},
+            {"name": "role", "type": ["null", {"type": "enum", "name": "UserRole", "symbols": ["admin", "guest"]}], "default": null},
+            {"name": "score", "type": ["null", "double"], "default": null},
+            {"name": "tags", "type": ["null", {"type": "array", "items": "string"}], "default": null}
+        ]
+    });
+    assert_eq!(got, want, "{got:#}");
+
+    // round trip
+    let back = avro::to_json_schema(&got, avro::Encoding::Plain)?;
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile_value("http://a.com/user.json", back, &mut schemas)?;
+    let instance = json!({"name": "x", "age": 1, "address": {"city": "y"}, "tags": ["a"]});
+    let result = schemas.validate(&instance, sch);
+    assert!(result.is_ok(), "{}", result.unwrap_err());
+
+    let err = avro::from_json_schema(
+        &json!({"properties": {"a": {"$ref": "#"}}, "type": "object"}),
+        "A",
+    )
+    .unwrap_err();
+    assert_eq!(err.ptr, "/properties/a/$ref");
+    let err = avro::from_json_schema(
+        &json!({"anyOf": [{"type": "string"}, {"type": "string"}]}),
+        "A",
+    )
+    .unwrap_err();
+    assert_eq!(err.ptr, "/anyOf/1");
+    Ok(())
+}
+
+#[test]
+fn test_validate_with_context() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/tree.json",
+        json!({
+            "$dynamicAnchor": "node",
+            "properties": {
+                "children": {"items": {"$dynamicRef": "#node"}}
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://a.com/strict-tree.json",
+        json!({
+            "$dynamicAnchor": "node",
+            "$ref": "tree.json",
+            "unevaluatedProperties": false
+        }),
+    )?;
+    compiler.add_resource(
+        "http://a.com/tree2019.json",
+        json!({
+            "$schema": "https://json-schema.org/draft/2019-09/schema",
+            "$recursiveAnchor": true,
+            "properties": {
+                "children": {"items": {"$recursiveRef": "#"}}
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://a.com/strict-tree2019.json",
+        json!({
+            "$schema": "https://json-schema.org/draft/2019-09/schema",
+            "$recursiveAnchor": true,
+            "$ref": "tree2019.json",
+            "unevaluatedProperties": false
+        }),
+    )?;
+    let tree = compiler.compile("http://a.com/tree.json", &mut schemas)?;
+    let strict = compiler.compile("http://a.com/strict-tree.json", &mut schemas)?;
+    let tree2019 = compiler.compile("http://a.com/tree2019.json", &mut schemas)?;
+    let strict2019 = compiler.compile("http://a.com/strict-tree2019.json", &mut schemas)?;
+
+    let options = ValidationOptions::default();
+    let v = json!({"x": 1, "children": [{"children": [{"y": 1}]}]});
+    for (tree, strict) in [(tree, strict), (tree2019, strict2019)] {
+        assert!(schemas.validate(&v, tree).is_ok());
+        assert!(schemas
+            .validate_value_with_context(&v, tree, &[], &options)
+            .is_ok());
+
+        // root is not validated by seeded schema, only nested nodes are
+        let err = schemas
+            .validate_value_with_context(&v, tree, &[strict], &options)
+            .unwrap_err();
+        assert_eq!(err.causes.len(), 1, "{err:#}");
+        let cause = &err.causes[0];
+        assert_eq!(cause.instance_location.to_string(), "/children/0");
+        let ErrorKind::Reference { url, .. } = cause.kind else {
+            panic!("{err:#}");
+        };
+        assert!(url.starts_with("http://a.com/strict-tree"), "{url}");
+
+        let v = json!({"children": [{"children": []}]});
+        assert!(schemas
+            .validate_value_with_context(&v, tree, &[strict], &options)
+            .is_ok());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "hooks")]
+#[test]
+fn test_keyword_hook() -> Result<(), Box<dyn Error>> {
+    use boon::{KeywordEval, KeywordHook};
+
+    #[derive(Default)]
+    struct Recorder {
+        before: Mutex<Vec<String>>,
+        after: Mutex<Vec<(String, bool)>>,
+    }
+
+    impl KeywordHook for Recorder {
+        fn before(&self, eval: &KeywordEval) {
+            let loc = format!(
+                "{}/{} at {}",
+                eval.schema_url, eval.keyword, eval.instance_location
+            );
+            self.before.lock().unwrap().push(loc);
+        }
+
+        fn after(&self, eval: &KeywordEval, valid: bool) {
+            let loc = format!(
+                "{}/{} at {}",
+                eval.schema_url, eval.keyword, eval.instance_location
+            );
+            self.after.lock().unwrap().push((loc, valid));
+        }
+    }
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/s.json",
+        json!({
+            "type": "object",
+            "properties": {
+                "a": {"minimum": 1},
+                "b": {"maxLength": 2}
+            },
+            "if": {"required": ["a"]},
+            "then": {"required": ["b"]},
+            "not": {"required": ["c"]}
+        }),
+    )?;
+    let sch = compiler.compile("http://a.com/s.json", &mut schemas)?;
+
+    let recorder = Arc::new(Recorder::default());
+    let mut options = ValidationOptions::new();
+    options.set_keyword_hook(recorder.clone());
+    assert!(schemas
+        .validate_with(&json!({"a": 0}), sch, &options)
+        .is_err());
+
+    let mut after = recorder.after.lock().unwrap().clone();
+    after.sort();
+    assert_eq!(
+        after,
+        [
+            ("http://a.com/s.json#/if at ".to_owned(), false),
+            ("http://a.com/s.json#/if/required at ".to_owned(), true),
+            ("http://a.com/s.json#/not at ".to_owned(), true),
+            ("http://a.com/s.json#/not/required at ".to_owned(), false),
+            ("http://a.com/s.json#/properties at ".to_owned(), false),
+            (
+                "http://a.com/s.json#/properties/a/minimum at /a".to_owned(),
+                false
+            ),
+            ("http://a.com/s.json#/then/required at ".to_owned(), false),
+            ("http://a.com/s.json#/type at ".to_owned(), true),
+        ]
+    );
+    let mut before = recorder.before.lock().unwrap().clone();
+    before.sort();
+    let want: Vec<_> = after.into_iter().map(|(loc, _)| loc).collect();
+    assert_eq!(before, want);
+
+    assert_eq!(
+        schemas.keyword_locations(sch),
+        [
+            "http://a.com/s.json#/if",
+            "http://a.com/s.json#/if/required",
+            "http://a.com/s.json#/not",
+            "http://a.com/s.json#/not/required",
+            "http://a.com/s.json#/properties",
+            "http://a.com/s.json#/properties/a/minimum",
+            "http://a.com/s.json#/properties/b/maxLength",
+            "http://a.com/s.json#/then/required",
+            "http://a.com/s.json#/type",
+        ]
+    );
+    Ok(())
+}